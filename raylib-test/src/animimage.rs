@@ -0,0 +1,130 @@
+#[cfg(test)]
+mod animimage_test {
+    use raylib::prelude::*;
+
+    fn solid(width: i32, height: i32, color: Color) -> Image {
+        let pixels = vec![color; (width * height) as usize];
+        Image::from_pixel_slice(width, height, &pixels).unwrap()
+    }
+
+    #[test]
+    fn test_animated_image_frames() {
+        let frames = [
+            solid(3, 2, Color::RED),
+            solid(3, 2, Color::GREEN),
+            solid(3, 2, Color::BLUE),
+        ];
+        let mut anim = AnimatedImage::from_frames(&frames, 100).unwrap();
+        anim.set_delay(1, 50);
+        assert_eq!(anim.frame_count(), 3);
+        assert_eq!(anim.delays(), &[100, 50, 100]);
+        assert_eq!(anim.duration(), 250);
+        assert!(anim.push_frame(&solid(2, 2, Color::RED), 10).is_err());
+
+        let frame = anim.frame(1).unwrap();
+        assert_eq!((frame.width(), frame.height()), (3, 2));
+        assert_eq!(frame.get(2, 1), Some(Color::GREEN));
+        assert!(anim.frame(3).is_none());
+        assert_eq!(anim.frame_bytes(2).unwrap()[..4], [0, 121, 241, 255]);
+        let delays: Vec<u32> = anim.frames().map(|f| f.delay).collect();
+        assert_eq!(delays, vec![100, 50, 100]);
+
+        // looping forever wraps around, a set number of loops stops on the last frame
+        assert_eq!(anim.frame_at(0), 0);
+        assert_eq!(anim.frame_at(120), 1);
+        assert_eq!(anim.frame_at(260), 0);
+        anim.set_loop_count(2);
+        assert_eq!(anim.frame_at(260), 0);
+        assert_eq!(anim.frame_at(600), 2);
+
+        let stack = anim.to_image().unwrap();
+        assert_eq!((stack.width(), stack.height()), (3, 6));
+        let split = AnimatedImage::from_image_stack(&stack, 3, 40).unwrap();
+        assert_eq!(split.frame(2).unwrap().get(0, 0), Some(Color::BLUE));
+        assert!(AnimatedImage::from_image_stack(&stack, 4, 40).is_err());
+    }
+
+    #[test]
+    fn test_gif_round_trip() {
+        let mut first: Vec<Color> = (0..64)
+            .map(|i| Color::new((i % 8) as u8 * 32, (i / 8) as u8 * 32, 128, 255))
+            .collect();
+        first[0] = Color::BLANK;
+        let frames = [
+            Image::from_pixel_slice(8, 8, &first).unwrap(),
+            solid(8, 8, Color::ORANGE),
+        ];
+        let mut anim = AnimatedImage::from_frames(&frames, 120).unwrap();
+        anim.set_delay(1, 33);
+        anim.set_loop_count(3);
+
+        let gif = anim.to_gif(&GifOptions::default()).unwrap();
+        assert!(gif.starts_with(b"GIF89a"));
+        let decoded = AnimatedImage::load_from_memory(&gif).unwrap();
+        assert_eq!((decoded.width(), decoded.height()), (8, 8));
+        assert_eq!(decoded.frame_count(), 2);
+        // delays round to hundredths of a second
+        assert_eq!(decoded.delays(), &[120, 30]);
+        assert_eq!(decoded.loop_count(), 3);
+        // few enough colors to keep them all
+        assert_eq!(decoded.frame(0).unwrap().to_vec(), first);
+        assert_eq!(decoded.frame(1).unwrap().get(5, 5), Some(Color::ORANGE));
+
+        anim.set_loop_count(1);
+        let gif = anim.to_gif(&GifOptions::default()).unwrap();
+        assert_eq!(
+            AnimatedImage::load_from_memory(&gif).unwrap().loop_count(),
+            1
+        );
+        assert!(AnimatedImage::load_from_memory(b"GIF89a").is_err());
+        assert!(AnimatedImage::load_from_memory(b"PNG").is_err());
+    }
+
+    #[test]
+    fn test_gif_pixel_limit() {
+        // a 65535 x 65535 canvas in a header of 13 bytes
+        let mut header = b"GIF89a".to_vec();
+        header.extend_from_slice(&[0xff, 0xff, 0xff, 0xff, 0, 0, 0, 0x3b]);
+        assert!(AnimatedImage::load_from_memory(&header).is_err());
+
+        let frames = [solid(8, 8, Color::RED), solid(8, 8, Color::BLUE)];
+        let anim = AnimatedImage::from_frames(&frames, 100).unwrap();
+        let gif = anim.to_gif(&GifOptions::default()).unwrap();
+        // the limit counts the pixels of every frame
+        assert!(AnimatedImage::load_from_memory_with_max_pixels(&gif, 127).is_err());
+        let decoded = AnimatedImage::load_from_memory_with_max_pixels(&gif, 128).unwrap();
+        assert_eq!(decoded.frame_count(), 2);
+    }
+
+    #[test]
+    fn test_gif_palette_reduction() {
+        // a gradient with more colors than fit in the palette, big enough to fill the LZW table
+        let pixels: Vec<Color> = (0..128 * 128)
+            .map(|i| {
+                let (x, y) = (i % 128, i / 128);
+                Color::new((x * 2) as u8, (y * 2) as u8, ((x + y) % 256) as u8, 255)
+            })
+            .collect();
+        let image = Image::from_pixel_slice(128, 128, &pixels).unwrap();
+        let anim = AnimatedImage::from_frames(&[image], 0).unwrap();
+        for &dither in &[false, true] {
+            let options = GifOptions {
+                max_colors: 16,
+                dither,
+            };
+            let decoded = AnimatedImage::load_from_memory(&anim.to_gif(&options).unwrap()).unwrap();
+            let frame = decoded.frame(0).unwrap().to_vec();
+            let mut colors: Vec<[u8; 3]> = frame.iter().map(|c| [c.r, c.g, c.b]).collect();
+            colors.sort_unstable();
+            colors.dedup();
+            assert!(colors.len() <= 16);
+            // close to the original on average
+            let error: u64 = frame
+                .iter()
+                .zip(&pixels)
+                .map(|(a, b)| (a.r as i64 - b.r as i64).unsigned_abs())
+                .sum();
+            assert!(error / (pixels.len() as u64) < 32);
+        }
+    }
+}
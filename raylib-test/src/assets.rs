@@ -0,0 +1,97 @@
+#[cfg(test)]
+mod assets_test {
+    use crate::tests::*;
+    use raylib::error::Error;
+    use raylib::prelude::*;
+    use std::time::{Duration, Instant};
+
+    /// Polls `handle` until it finishes, for assets that don't need `pump`.
+    fn wait<T>(handle: &AssetHandle<T>) -> Result<T, Error> {
+        let start = Instant::now();
+        loop {
+            if let Some(result) = handle.try_take() {
+                return result;
+            }
+            assert!(
+                start.elapsed() < Duration::from_secs(10),
+                "asset never loaded"
+            );
+            std::thread::sleep(Duration::from_millis(1));
+        }
+    }
+
+    #[test]
+    fn test_asset_loader_cpu() {
+        let mut loader = AssetLoader::new(2);
+        let image = loader.load_image("resources/billboard.png");
+        let missing = loader.load_image("resources/doesnt_exist.png");
+        let custom = loader.load_with(|| Ok(vec![1u8, 2, 3]));
+        let panics = loader.load_with(|| -> Result<u8, Error> { panic!("decoder bug") });
+
+        let image = wait(&image).expect("could not load image billboard");
+        assert!(image.width() > 0);
+        assert!(wait(&missing).is_err());
+        assert_eq!(wait(&custom).unwrap(), vec![1, 2, 3]);
+        assert!(wait(&panics).is_err());
+        // taken handles stay empty
+        assert!(custom.try_take().is_none());
+        assert!(loader.is_idle());
+    }
+
+    #[test]
+    fn test_mesh_data_from_obj() {
+        let quad = "v 0 0 0\nv 1 0 0\nv 1 1 0\nv 0 1 0\nvt 0 0\nvt 1 1\nf 1/1 2/2 3/1 -1/2\n";
+        let data = MeshData::from_obj(quad).unwrap();
+        assert_eq!(data.triangle_count(), 2);
+        assert_eq!(data.vertices[5], Vector3::new(0.0, 1.0, 0.0));
+        // flat normals face the side the corners wind around
+        assert_eq!(data.normals[0], Vector3::new(0.0, 0.0, 1.0));
+        // texture coordinates are flipped to raylib's top-down convention
+        assert_eq!(data.texcoords[0], Vector2::new(0.0, 1.0));
+        assert_eq!(data.texcoords[1], Vector2::new(1.0, 0.0));
+
+        assert!(MeshData::from_obj("v 0 0 0\nf 1 2 3\n").is_err());
+        assert!(MeshData::from_obj("v 0 zero 0\n").is_err());
+        assert!(MeshData::from_obj("# nothing\n").is_err());
+
+        let cube = std::fs::read_to_string("resources/cube.obj").unwrap();
+        let cube = MeshData::from_obj(&cube).unwrap();
+        assert_eq!(cube.triangle_count(), 12);
+        assert_eq!(cube.normals[0], Vector3::new(0.0, -1.0, 0.0));
+    }
+
+    ray_test!(test_asset_loader_upload);
+    fn test_asset_loader_upload(thread: &RaylibThread) {
+        let mut handle = TEST_HANDLE.write().unwrap();
+        let rl = handle.as_mut().unwrap();
+        let mut loader = AssetLoader::new(2);
+        let texture = loader.load_texture("resources/billboard.png");
+        let model = loader.load_model("resources/cube.obj");
+        // only OBJ models are read
+        let missing = loader.load_model("resources/missing.obj");
+        let not_obj = loader.load_model("resources/billboard.png");
+        let start = Instant::now();
+        while !loader.is_idle() {
+            assert!(
+                start.elapsed() < Duration::from_secs(10),
+                "assets never loaded"
+            );
+            loader.pump(rl, thread, Duration::from_millis(2));
+        }
+        let texture = texture
+            .try_take()
+            .unwrap()
+            .expect("could not upload texture");
+        assert!(texture.width() > 0);
+        let model = model.try_take().unwrap().expect("could not upload model");
+        assert_eq!(model.meshes().len(), 1);
+        assert!(missing.try_take().unwrap().is_err());
+        assert!(not_obj.try_take().unwrap().is_err());
+
+        // uploads that never happen fail once the loader is gone
+        let mut loader = AssetLoader::new(1);
+        let texture = loader.load_texture("resources/billboard.png");
+        drop(loader);
+        assert!(texture.try_take().unwrap().is_err());
+    }
+}
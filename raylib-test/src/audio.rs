@@ -12,6 +12,25 @@ mod audio_test {
         //w.export_wave("test_out/wave.wav");
     }
 
+    #[test]
+    fn test_wave_data() {
+        let data = std::thread::spawn(|| WaveData::load("resources/audio/wave.ogg"))
+            .join()
+            .unwrap()
+            .expect("wave decoding failed");
+        assert_eq!(
+            data.samples().len() as u32,
+            data.frame_count() * data.channels() * data.sample_size() / 8
+        );
+        if let Ok(audio) = RaylibAudio::init_audio_device() {
+            let wave = audio
+                .new_wave_from_data(&data)
+                .expect("wave from data failed");
+            assert_eq!(wave.frame_count(), data.frame_count());
+            assert_eq!(wave.channels(), data.channels());
+        }
+    }
+
     /*#[test]
     fn test_export_wave_as_code() {
         let w = Wave::load_wave("resources/audio/wave.ogg").expect("wave loading failed");
@@ -0,0 +1,159 @@
+#[cfg(test)]
+mod bmfont_test {
+    use crate::tests::*;
+    use raylib::prelude::*;
+
+    const TEXT: &str = r#"info face="Pixel Sans" size=-16 bold=1 italic=0 charset="" unicode=1 stretchH=100 smooth=0 aa=1 padding=1,2,3,4 spacing=1,1 outline=0
+common lineHeight=18 base=14 scaleW=64 scaleH=64 pages=2 packed=0 alphaChnl=0 redChnl=4 greenChnl=4 blueChnl=4
+page id=0 file="pixel_0.png"
+page id=1 file="pixel_1.png"
+chars count=3
+char id=32   x=0     y=0     width=0     height=0     xoffset=0     yoffset=14    xadvance=4     page=0  chnl=15
+char id=65   x=1     y=2     width=9     height=11    xoffset=-1    yoffset=3     xadvance=8     page=0  chnl=15
+char id=86   x=10    y=20    width=9     height=11    xoffset=0     yoffset=3     xadvance=8     page=1  chnl=15
+kernings count=1
+kerning first=65  second=86  amount=-2
+"#;
+
+    const XML: &str = r#"<?xml version="1.0"?>
+<font>
+  <info face="Pixel Sans" size="-16" bold="1" italic="0" charset="" unicode="1" stretchH="100" smooth="0" aa="1" padding="1,2,3,4" spacing="1,1" outline="0"/>
+  <common lineHeight="18" base="14" scaleW="64" scaleH="64" pages="2" packed="0" alphaChnl="0" redChnl="4" greenChnl="4" blueChnl="4"/>
+  <pages>
+    <page id="0" file="pixel_0.png" />
+    <page id="1" file="pixel_1.png" />
+  </pages>
+  <chars count="3">
+    <char id="32" x="0" y="0" width="0" height="0" xoffset="0" yoffset="14" xadvance="4" page="0" chnl="15" />
+    <char id="65" x="1" y="2" width="9" height="11" xoffset="-1" yoffset="3" xadvance="8" page="0" chnl="15" />
+    <char id="86" x="10" y="20" width="9" height="11" xoffset="0" yoffset="3" xadvance="8" page="1" chnl="15" />
+  </chars>
+  <kernings count="1">
+    <kerning first="65" second="86" amount="-2" />
+  </kernings>
+</font>
+"#;
+
+    #[test]
+    fn test_bmfont_parse_text() {
+        let font = BmFont::parse(TEXT.as_bytes()).unwrap();
+        assert_eq!(font.info.face, "Pixel Sans");
+        assert_eq!(font.info.size, -16);
+        assert!(font.info.bold && font.info.unicode && !font.info.smooth);
+        assert_eq!(font.info.padding, [1, 2, 3, 4]);
+        assert_eq!(font.common.line_height, 18);
+        assert_eq!(font.common.base, 14);
+        assert_eq!(font.pages, vec!["pixel_0.png", "pixel_1.png"]);
+        assert_eq!(font.chars.len(), 3);
+        assert_eq!(
+            font.glyph('A'),
+            Some(&BmChar {
+                id: 65,
+                x: 1,
+                y: 2,
+                width: 9,
+                height: 11,
+                xoffset: -1,
+                yoffset: 3,
+                xadvance: 8,
+                page: 0,
+                chnl: 15,
+            })
+        );
+        assert_eq!(font.glyph('V').unwrap().page, 1);
+        assert_eq!(font.kerning('A', 'V'), -2);
+        assert_eq!(font.kerning('V', 'A'), 0);
+    }
+
+    #[test]
+    fn test_bmfont_formats_agree() {
+        let text = BmFont::parse(TEXT.as_bytes()).unwrap();
+        assert_eq!(BmFontFormat::detect(XML.as_bytes()), BmFontFormat::Xml);
+        assert_eq!(BmFont::parse(XML.as_bytes()).unwrap(), text);
+
+        for format in [BmFontFormat::Text, BmFontFormat::Xml, BmFontFormat::Binary] {
+            let bytes = text.to_bytes(format);
+            assert_eq!(BmFontFormat::detect(&bytes), format);
+            assert_eq!(BmFont::parse(&bytes).unwrap(), text, "{:?}", format);
+        }
+    }
+
+    #[test]
+    fn test_bmfont_binary_layout() {
+        let font = BmFont::parse(TEXT.as_bytes()).unwrap();
+        let bytes = font.to_binary();
+        assert_eq!(&bytes[..4], b"BMF\x03");
+        // info block: type, size, then the font size as a signed 16 bit integer
+        assert_eq!(bytes[4], 1);
+        assert_eq!(i16::from_le_bytes([bytes[9], bytes[10]]), -16);
+        // bold is bit 3 counting from the most significant bit, unicode bit 1
+        assert_eq!(bytes[11], 0x10 | 0x40);
+
+        assert!(BmFont::parse_binary(&bytes[..bytes.len() - 3]).is_err());
+        let mut version_2 = bytes.clone();
+        version_2[3] = 2;
+        assert!(BmFont::parse_binary(&version_2).is_err());
+    }
+
+    #[test]
+    fn test_bmfont_errors() {
+        assert!(BmFont::parse(b"info face=\"x\"\n").is_err());
+        assert!(BmFont::parse_text("common lineHeight=abc").is_err());
+        let missing_page = "common lineHeight=10\nchar id=65 page=1";
+        assert!(BmFont::parse_text(missing_page).is_err());
+        let negative_size = "common lineHeight=10\npage id=0 file=\"a.png\"\nchar id=65 width=-4";
+        assert!(BmFont::parse_text(negative_size).is_err());
+        assert!(BmFont::parse_xml("<font><common lineHeight=\"10\"").is_err());
+    }
+
+    #[test]
+    fn test_bmfont_malformed_page_ids() {
+        let negative = "common lineHeight=10 pages=1\npage id=-1 file=\"a.png\"";
+        assert!(BmFont::parse_text(negative).is_err());
+        let huge = "common lineHeight=10 pages=1\npage id=2147483647 file=\"a.png\"";
+        assert!(BmFont::parse_text(huge).is_err());
+        let skipped = "common lineHeight=10 pages=1\npage id=5 file=\"a.png\"";
+        assert!(BmFont::parse_text(skipped).is_err());
+        let too_many = "common lineHeight=10 pages=70000";
+        assert!(BmFont::parse_text(too_many).is_err());
+        let xml = "<font><common lineHeight=\"10\" pages=\"1\"/><pages><page id=\"-3\" file=\"a.png\"/></pages></font>";
+        assert!(BmFont::parse_xml(xml).is_err());
+
+        let unlisted = "common lineHeight=10\npage id=0 file=\"a.png\"\npage id=1 file=\"b.png\"";
+        let font = BmFont::parse_text(unlisted).unwrap();
+        assert_eq!(font.pages, vec!["a.png".to_owned(), "b.png".to_owned()]);
+    }
+
+    ray_test!(test_bmfont_export_load);
+    fn test_bmfont_export_load(thread: &RaylibThread) {
+        let mut handle = TEST_HANDLE.write().unwrap();
+        let rl = handle.as_mut().unwrap();
+        let font = rl
+            .load_font_ex(thread, "resources/pixeloid.ttf", 32, None)
+            .expect("couldn't load font");
+        font.export_bmfont("test_out/pixeloid.fnt", BmFontFormat::Binary)
+            .expect("couldn't export font");
+
+        let descriptor = BmFont::load("test_out/pixeloid.fnt").unwrap();
+        assert_eq!(descriptor.pages, vec!["pixeloid_0.png"]);
+        assert_eq!(descriptor.chars.len(), font.chars().len());
+
+        let loaded = rl
+            .load_font_bmfont(thread, "test_out/pixeloid.fnt")
+            .expect("couldn't load bmfont");
+        assert_eq!(loaded.base_size(), 32);
+        assert_eq!(loaded.chars().len(), font.chars().len());
+        assert_eq!(
+            loaded.get_glyph_atlas_rec('A'),
+            font.get_glyph_atlas_rec('A')
+        );
+
+        // glyphs reaching past their page are rejected rather than read out of bounds
+        std::fs::write(
+            "test_out/outside.fnt",
+            "common lineHeight=10\npage id=0 file=\"pixeloid_0.png\"\nchar id=65 width=100000 height=8\n",
+        )
+        .unwrap();
+        assert!(rl.load_font_bmfont(thread, "test_out/outside.fnt").is_err());
+    }
+}
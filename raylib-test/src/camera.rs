@@ -0,0 +1,70 @@
+#[cfg(test)]
+mod camera_test {
+    use raylib::prelude::*;
+
+    #[test]
+    fn test_camera2d_controller_deadzone() {
+        let mut c = Camera2DController::new(800.0, 450.0);
+        c.set_follow_mode(FollowMode::Snap)
+            .set_deadzone(Vector2::new(50.0, 50.0));
+        c.update(Vector2::new(30.0, -20.0), 1.0 / 60.0);
+        assert_eq!(c.position(), Vector2::zero());
+        c.update(Vector2::new(80.0, 0.0), 1.0 / 60.0);
+        assert_eq!(c.position(), Vector2::new(30.0, 0.0));
+    }
+
+    #[test]
+    fn test_camera2d_controller_bounds() {
+        let mut c = Camera2DController::new(800.0, 400.0);
+        c.set_follow_mode(FollowMode::Snap)
+            .set_bounds(Some(Rectangle::new(0.0, 0.0, 2000.0, 1000.0)));
+        c.update(Vector2::new(-500.0, -500.0), 1.0 / 60.0);
+        assert_eq!(c.position(), Vector2::new(400.0, 200.0));
+
+        // Zooming in shrinks the visible area, so the camera can get closer to the edge.
+        c.set_zoom(2.0);
+        c.update(Vector2::new(-500.0, -500.0), 1.0 / 60.0);
+        assert_eq!(c.position(), Vector2::new(200.0, 100.0));
+
+        // Bounds smaller than the view center the camera on them.
+        c.set_zoom(0.1);
+        c.update(Vector2::new(-500.0, -500.0), 1.0 / 60.0);
+        assert_eq!(c.position(), Vector2::new(1000.0, 500.0));
+    }
+
+    #[test]
+    fn test_camera2d_controller_smoothing() {
+        for mode in [
+            FollowMode::Lerp { speed: 5.0 },
+            FollowMode::Spring {
+                stiffness: 60.0,
+                damping: 16.0,
+            },
+            FollowMode::CriticallyDamped { smooth_time: 0.2 },
+        ] {
+            let mut c = Camera2DController::new(800.0, 450.0);
+            c.set_follow_mode(mode);
+            let target = Vector2::new(100.0, 50.0);
+            c.update(target, 1.0 / 60.0);
+            assert!(c.position().x > 0.0 && c.position().x < 100.0);
+            for _ in 0..600 {
+                c.update(target, 1.0 / 60.0);
+            }
+            assert!(c.position().distance_to(target) < 0.5, "{:?}", mode);
+        }
+    }
+
+    #[test]
+    fn test_camera2d_controller_shake() {
+        let mut c = Camera2DController::new(800.0, 450.0);
+        let calm = c.camera();
+        assert_eq!(calm.offset, Vector2::new(400.0, 225.0));
+        c.add_trauma(2.0);
+        assert_eq!(c.trauma(), 1.0);
+        c.update(Vector2::zero(), 0.1);
+        assert!(c.trauma() < 1.0);
+        let shaken = c.camera();
+        assert!((shaken.offset.x - 400.0).abs() <= 16.0);
+        assert!((shaken.offset.y - 225.0).abs() <= 16.0);
+    }
+}
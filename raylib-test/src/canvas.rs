@@ -0,0 +1,131 @@
+#[cfg(test)]
+mod canvas_test {
+    use crate::tests::*;
+    use raylib::prelude::*;
+
+    fn draw_badge<C: RaylibCanvas>(c: &mut C) {
+        c.fill_rect_rounded(Rectangle::new(10.0, 10.0, 60.0, 60.0), 0.3, 0, Color::GOLD);
+        c.stroke_circle(Vector2::new(40.0, 40.0), 20.0, 3.0, Color::BROWN);
+        c.fill_poly(Vector2::new(40.0, 40.0), 5, 10.0, -90.0, Color::RED);
+        c.stroke_spline_catmull_rom(
+            &[
+                Vector2::new(0.0, 90.0),
+                Vector2::new(20.0, 80.0),
+                Vector2::new(60.0, 95.0),
+                Vector2::new(80.0, 80.0),
+            ],
+            2.0,
+            Color::BLUE,
+        );
+    }
+
+    #[test]
+    fn test_canvas_rect() {
+        let mut img = Image::gen_image_color(16, 16, Color::BLACK);
+        {
+            let mut c = img.canvas();
+            c.fill_rect(Rectangle::new(2.0, 2.0, 4.0, 4.0), Color::RED);
+            // half a pixel wide, so half covered
+            c.fill_rect(Rectangle::new(10.0, 2.0, 0.5, 1.0), Color::WHITE);
+        }
+        assert_eq!(img.get_color(2, 2), Color::RED);
+        assert_eq!(img.get_color(5, 5), Color::RED);
+        assert_eq!(img.get_color(6, 5), Color::BLACK);
+        assert_eq!(img.get_color(1, 2), Color::BLACK);
+        assert_eq!(img.get_color(10, 2), Color::new(128, 128, 128, 255));
+
+        img.canvas()
+            .set_antialias(false)
+            .fill_rect(Rectangle::new(10.0, 2.0, 0.5, 1.0), Color::GREEN);
+        assert_eq!(img.get_color(10, 2), Color::GREEN);
+    }
+
+    #[test]
+    fn test_canvas_clip_and_blend() {
+        let mut img = Image::gen_image_color(16, 16, Color::new(100, 0, 0, 255));
+        {
+            let mut c = img.canvas();
+            c.set_clip(Some(Rectangle::new(0.0, 0.0, 8.0, 16.0)))
+                .set_blend_mode(CanvasBlendMode::Additive);
+            c.fill_circle(Vector2::new(8.0, 8.0), 6.0, Color::new(100, 50, 0, 255));
+        }
+        assert_eq!(img.get_color(6, 8), Color::new(200, 50, 0, 255));
+        assert_eq!(img.get_color(9, 8), Color::new(100, 0, 0, 255));
+    }
+
+    #[test]
+    fn test_canvas_compressed() {
+        // 64 bytes, enough for 8x8 DXT1 but a quarter of what 8x8 RGBA would need
+        let mut img = Image::gen_image_color(4, 4, Color::BLACK);
+        img.width = 8;
+        img.height = 8;
+        img.format = PixelFormat::PIXELFORMAT_COMPRESSED_DXT1_RGB as i32;
+        let before = unsafe { std::slice::from_raw_parts(img.data as *const u8, 64) }.to_vec();
+        {
+            let mut c = img.canvas();
+            c.fill_background(Color::WHITE);
+            draw_badge(&mut c);
+        }
+        let after = unsafe { std::slice::from_raw_parts(img.data as *const u8, 64) };
+        assert_eq!(after, &before[..]);
+        assert_eq!(img.format(), PixelFormat::PIXELFORMAT_COMPRESSED_DXT1_RGB);
+    }
+
+    ray_test!(test_canvas_text);
+    fn test_canvas_text(_: &RaylibThread) {
+        // the window is open, so the default font is loaded
+        let mut img = Image::gen_image_color(64, 16, Color::BLACK);
+        img.canvas()
+            .fill_text("a\0b", Vector2::new(2.0, 2.0), 10, Color::WHITE);
+        let lit = img
+            .get_image_data()
+            .iter()
+            .filter(|&&c| c != Color::BLACK)
+            .count();
+        assert!(lit > 0);
+    }
+
+    #[test]
+    fn test_canvas_texture() {
+        let mut tex = Image::gen_image_color(2, 2, Color::RED);
+        tex.draw_pixel(1, 0, Color::BLUE);
+        let mut img = Image::gen_image_color(8, 8, Color::BLACK);
+        img.canvas().fill_texture_pro(
+            &tex,
+            Rectangle::new(0.0, 0.0, 2.0, 2.0),
+            Rectangle::new(0.0, 0.0, 8.0, 8.0),
+            Vector2::zero(),
+            0.0,
+            Color::WHITE,
+        );
+        assert_eq!(img.get_color(1, 1), Color::RED);
+        assert_eq!(img.get_color(6, 1), Color::BLUE);
+        assert_eq!(img.get_color(6, 6), Color::RED);
+
+        // a negative source width mirrors the texture
+        img.canvas().fill_texture_pro(
+            &tex,
+            Rectangle::new(0.0, 0.0, -2.0, 2.0),
+            Rectangle::new(0.0, 0.0, 8.0, 8.0),
+            Vector2::zero(),
+            0.0,
+            Color::WHITE,
+        );
+        assert_eq!(img.get_color(1, 1), Color::BLUE);
+    }
+
+    #[test]
+    fn test_canvas_shapes() {
+        let mut img = Image::gen_image_color(100, 100, Color::WHITE);
+        draw_badge(&mut img.canvas());
+        assert_eq!(img.get_color(40, 40), Color::RED);
+        assert_eq!(img.get_color(12, 40), Color::GOLD);
+        assert_eq!(img.get_color(11, 11), Color::WHITE);
+    }
+
+    ray_draw_test!(test_canvas_draw);
+    fn test_canvas_draw(d: &mut RaylibDrawHandle, _: &TestAssets) {
+        d.clear_background(Color::WHITE);
+        draw_badge(d);
+    }
+}
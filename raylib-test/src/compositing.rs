@@ -0,0 +1,125 @@
+#[cfg(test)]
+mod compositing_test {
+    use raylib::prelude::*;
+
+    fn solid(color: Color) -> Image {
+        Image::from_pixel_slice(2, 2, &[color; 4]).unwrap()
+    }
+
+    fn blended(dst: Color, src: Color, mode: LayerBlendMode) -> Color {
+        let mut image = solid(dst);
+        let rec = Rectangle::new(0.0, 0.0, 2.0, 2.0);
+        image.draw_blended(&solid(src), rec, rec, Color::WHITE, mode);
+        image.get_color(1, 1)
+    }
+
+    #[test]
+    fn test_blend_modes_opaque() {
+        let dst = Color::new(200, 100, 50, 255);
+        let src = Color::new(100, 200, 255, 255);
+        assert_eq!(blended(dst, src, LayerBlendMode::Normal), src);
+        assert_eq!(
+            blended(dst, src, LayerBlendMode::Multiply),
+            Color::new(78, 78, 50, 255)
+        );
+        assert_eq!(
+            blended(dst, src, LayerBlendMode::Screen),
+            Color::new(222, 222, 255, 255)
+        );
+        assert_eq!(
+            blended(dst, src, LayerBlendMode::Darken),
+            Color::new(100, 100, 50, 255)
+        );
+        assert_eq!(
+            blended(dst, src, LayerBlendMode::Lighten),
+            Color::new(200, 200, 255, 255)
+        );
+        assert_eq!(
+            blended(dst, src, LayerBlendMode::Additive),
+            Color::new(255, 255, 255, 255)
+        );
+        assert_eq!(
+            blended(dst, src, LayerBlendMode::Difference),
+            Color::new(100, 100, 205, 255)
+        );
+        assert_eq!(
+            blended(dst, src, LayerBlendMode::Overlay),
+            Color::new(188, 157, 100, 255)
+        );
+        assert_eq!(
+            blended(dst, src, LayerBlendMode::ColorDodge),
+            Color::new(255, 255, 255, 255)
+        );
+        assert_eq!(
+            blended(dst, src, LayerBlendMode::ColorBurn),
+            Color::new(115, 57, 50, 255)
+        );
+        // luminosity keeps the destination's hue, so gray in gives gray out
+        let gray = blended(Color::GRAY, src, LayerBlendMode::Luminosity);
+        assert!(gray.r == gray.g && gray.g == gray.b);
+        let colored = blended(Color::GRAY, src, LayerBlendMode::Color);
+        assert!(colored.b > colored.r);
+    }
+
+    #[test]
+    fn test_blend_alpha() {
+        let dst = Color::new(200, 100, 50, 255);
+        // transparent source changes nothing
+        assert_eq!(
+            blended(dst, Color::new(0, 0, 0, 0), LayerBlendMode::Multiply),
+            dst
+        );
+        // half transparent multiply mixes halfway between the backdrop and the product
+        assert_eq!(
+            blended(dst, Color::new(0, 0, 0, 128), LayerBlendMode::Multiply),
+            Color::new(100, 50, 25, 255)
+        );
+        // over a transparent backdrop the source shows unblended
+        assert_eq!(
+            blended(
+                Color::BLANK,
+                Color::new(10, 20, 30, 128),
+                LayerBlendMode::Difference
+            ),
+            Color::new(10, 20, 30, 128)
+        );
+    }
+
+    #[test]
+    fn test_blend_premultiplied_and_scaled() {
+        // 50% red over opaque blue, premultiplied
+        let mut image = solid(Color::new(0, 0, 255, 255));
+        let src = solid(Color::new(128, 0, 0, 128));
+        let rec = Rectangle::new(0.0, 0.0, 2.0, 2.0);
+        image.draw_blended_premultiplied(&src, rec, rec, Color::WHITE, LayerBlendMode::Normal);
+        assert_eq!(image.get_color(0, 0), Color::new(128, 0, 127, 255));
+
+        // 1x1 source stretched over part of the destination, tinted
+        let mut image = Image::from_pixel_slice(4, 4, &[Color::BLACK; 16]).unwrap();
+        let dot = Image::from_pixel_slice(1, 1, &[Color::WHITE]).unwrap();
+        image.draw_blended(
+            &dot,
+            Rectangle::new(0.0, 0.0, 1.0, 1.0),
+            Rectangle::new(1.0, 1.0, 2.0, 2.0),
+            Color::new(255, 0, 0, 255),
+            LayerBlendMode::Screen,
+        );
+        assert_eq!(image.get_color(2, 2), Color::new(255, 0, 0, 255));
+        assert_eq!(image.get_color(0, 0), Color::BLACK);
+        assert_eq!(image.get_color(3, 3), Color::BLACK);
+    }
+
+    #[test]
+    fn test_blend_compressed() {
+        // compressed images can't be converted to RGBA, so they're left alone
+        let mut compressed = Image::gen_image_color(8, 8, Color::BLACK);
+        compressed.format = PixelFormat::PIXELFORMAT_COMPRESSED_DXT1_RGB as i32;
+        let source = Image::gen_image_color(8, 8, Color::RED);
+        let rec = Rectangle::new(0.0, 0.0, 8.0, 8.0);
+        compressed.draw_blended(&source, rec, rec, Color::WHITE, LayerBlendMode::Multiply);
+        assert_eq!(
+            compressed.format(),
+            PixelFormat::PIXELFORMAT_COMPRESSED_DXT1_RGB
+        );
+    }
+}
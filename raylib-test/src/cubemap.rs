@@ -0,0 +1,157 @@
+#[cfg(test)]
+mod cubemap_test {
+    use raylib::prelude::*;
+
+    const FACE_COLORS: [Color; 6] = [
+        Color::RED,
+        Color::GREEN,
+        Color::BLUE,
+        Color::YELLOW,
+        Color::PURPLE,
+        Color::ORANGE,
+    ];
+
+    /// A panorama colored by the direction each pixel looks in.
+    fn direction_panorama(width: usize, height: usize) -> Image {
+        let mut pixels = Vec::new();
+        for y in 0..height {
+            let latitude = (0.5 - (y as f32 + 0.5) / height as f32) * std::f32::consts::PI;
+            for x in 0..width {
+                let longitude =
+                    ((x as f32 + 0.5) / width as f32 - 0.5) * 2.0 * std::f32::consts::PI;
+                let channel = |v: f32| ((v + 1.0) * 127.5) as u8;
+                pixels.push(Color::new(
+                    channel(latitude.cos() * longitude.cos()),
+                    channel(latitude.sin()),
+                    channel(latitude.cos() * longitude.sin()),
+                    255,
+                ));
+            }
+        }
+        Image::from_pixel_slice(width as i32, height as i32, &pixels).unwrap()
+    }
+
+    #[test]
+    fn test_cube_faces_layouts() {
+        let faces = FACE_COLORS.map(|c| Image::from_pixel_slice(4, 4, &[c; 16]).unwrap());
+        for &(layout, width, height) in &[
+            (CubemapLayout::CUBEMAP_LAYOUT_LINE_VERTICAL, 4, 24),
+            (CubemapLayout::CUBEMAP_LAYOUT_LINE_HORIZONTAL, 24, 4),
+            (CubemapLayout::CUBEMAP_LAYOUT_CROSS_THREE_BY_FOUR, 12, 16),
+            (CubemapLayout::CUBEMAP_LAYOUT_CROSS_FOUR_BY_THREE, 16, 12),
+        ] {
+            let cubemap = Image::from_cube_faces(&faces, layout).unwrap();
+            assert_eq!((cubemap.width(), cubemap.height()), (width, height));
+            for (&face, &color) in CubeFace::ALL.iter().zip(FACE_COLORS.iter()) {
+                let extracted = cubemap
+                    .cube_face(CubemapLayout::CUBEMAP_LAYOUT_AUTO_DETECT, face)
+                    .unwrap();
+                assert_eq!(extracted.get_color(3, 3), color);
+            }
+        }
+        // the cross puts +Z in the middle, with +X to its right
+        let cross =
+            Image::from_cube_faces(&faces, CubemapLayout::CUBEMAP_LAYOUT_CROSS_FOUR_BY_THREE)
+                .unwrap();
+        assert_eq!(cross.get_color(5, 5), Color::PURPLE);
+        assert_eq!(cross.get_color(9, 5), Color::RED);
+        assert_eq!(cross.get_color(0, 0), Color::BLANK);
+
+        assert!(Image::from_cube_faces(&faces, CubemapLayout::CUBEMAP_LAYOUT_AUTO_DETECT).is_err());
+        let mut uneven = FACE_COLORS.map(|c| Image::from_pixel_slice(4, 4, &[c; 16]).unwrap());
+        uneven[2] = Image::from_pixel_slice(2, 2, &[Color::BLUE; 4]).unwrap();
+        assert!(
+            Image::from_cube_faces(&uneven, CubemapLayout::CUBEMAP_LAYOUT_LINE_VERTICAL).is_err()
+        );
+        assert!(faces[0]
+            .cube_face(
+                CubemapLayout::CUBEMAP_LAYOUT_AUTO_DETECT,
+                CubeFace::PositiveX
+            )
+            .is_err());
+    }
+
+    #[test]
+    fn test_cube_face_directions() {
+        for &face in &CubeFace::ALL {
+            for &(u, v) in &[(0.0, 0.0), (0.5, -0.25), (-0.9, 0.9)] {
+                let (found, fu, fv) = CubeFace::from_direction(face.direction(u, v) * 3.0);
+                assert_eq!(found, face);
+                assert!((fu - u).abs() < 1e-5 && (fv - v).abs() < 1e-5);
+            }
+        }
+        assert_eq!(
+            CubeFace::from_direction(Vector3::new(0.0, 2.0, 0.0)).0,
+            CubeFace::PositiveY
+        );
+    }
+
+    #[test]
+    fn test_equirect_round_trip() {
+        let panorama = direction_panorama(128, 64);
+        let cubemap = panorama
+            .equirect_to_cubemap(32, CubemapLayout::CUBEMAP_LAYOUT_LINE_HORIZONTAL)
+            .unwrap();
+        assert_eq!((cubemap.width(), cubemap.height()), (192, 32));
+        // the center of each face looks along its axis
+        let up = cubemap
+            .cube_face(
+                CubemapLayout::CUBEMAP_LAYOUT_LINE_HORIZONTAL,
+                CubeFace::PositiveY,
+            )
+            .unwrap()
+            .get_color(16, 16);
+        assert!(up.g > 250 && (up.r as i32 - 127).abs() < 8);
+        let forward = cubemap
+            .cube_face(
+                CubemapLayout::CUBEMAP_LAYOUT_LINE_HORIZONTAL,
+                CubeFace::PositiveZ,
+            )
+            .unwrap()
+            .get_color(16, 16);
+        assert!(forward.b > 250 && (forward.g as i32 - 127).abs() < 8);
+
+        let back = cubemap
+            .cubemap_to_equirect(CubemapLayout::CUBEMAP_LAYOUT_AUTO_DETECT, 128, 64)
+            .unwrap();
+        let (a, b) = (
+            panorama.pixels::<Rgba8>().unwrap(),
+            back.pixels::<Rgba8>().unwrap(),
+        );
+        let error: i64 = a
+            .iter()
+            .zip(b.iter())
+            .map(|(a, b)| {
+                (a.r as i64 - b.r as i64).abs()
+                    + (a.g as i64 - b.g as i64).abs()
+                    + (a.b as i64 - b.b as i64).abs()
+            })
+            .sum();
+        assert!(error / (128 * 64 * 3) < 4);
+    }
+
+    #[test]
+    fn test_equirect_hdr() {
+        let sky = Rgba32f {
+            r: 12.5,
+            g: 4.0,
+            b: 0.5,
+            a: 1.0,
+        };
+        let panorama = Image::from_pixel_slice(16, 8, &[sky; 128]).unwrap();
+        let cubemap = panorama
+            .equirect_to_cubemap(4, CubemapLayout::CUBEMAP_LAYOUT_CROSS_THREE_BY_FOUR)
+            .unwrap();
+        assert_eq!(
+            cubemap.format(),
+            PixelFormat::PIXELFORMAT_UNCOMPRESSED_R32G32B32A32
+        );
+        let face = cubemap
+            .cube_face(
+                CubemapLayout::CUBEMAP_LAYOUT_CROSS_THREE_BY_FOUR,
+                CubeFace::NegativeY,
+            )
+            .unwrap();
+        assert_eq!(face.pixels::<Rgba32f>().unwrap()[5], sky);
+    }
+}
@@ -124,6 +124,57 @@ mod draw_test {
         d.draw_poly(Vector2::new(100.0, 100.0), 12, 20.0, 45.0, Color::RED);
     }
 
+    ray_draw_test!(test_blend_custom);
+    fn test_blend_custom(d: &mut RaylibDrawHandle, _: &TestAssets) {
+        d.clear_background(Color::GRAY);
+        {
+            let mut max = d.begin_blend_state(BlendState::MAX);
+            max.draw_rectangle(10, 10, 40, 40, Color::RED);
+            {
+                let mut min = max.begin_blend_mode_custom(
+                    BlendFactor::One,
+                    BlendFactor::One,
+                    BlendEquation::Min,
+                );
+                min.draw_rectangle(30, 30, 40, 40, Color::BLUE);
+            }
+            // back to max blending
+            max.draw_rectangle(50, 50, 40, 40, Color::GREEN);
+        }
+        let mut mask = d.begin_blend_mode_separate(
+            BlendFactor::Zero,
+            BlendFactor::SrcAlpha,
+            BlendFactor::Zero,
+            BlendFactor::SrcAlpha,
+            BlendEquation::Add,
+            BlendEquation::Add,
+        );
+        mask.draw_circle(120, 60, 20.0, Color::BLANK);
+    }
+
+    ray_draw_test!(test_rlgl);
+    fn test_rlgl(d: &mut RaylibDrawHandle, _: &TestAssets) {
+        d.clear_background(Color::WHITE);
+        let mut m = d.push_matrix();
+        m.translate(100.0, 100.0, 0.0)
+            .rotate(45.0, Vector3::new(0.0, 0.0, 1.0));
+        {
+            let mut tri = m.begin(PrimitiveMode::Triangles);
+            tri.color(Color::RED)
+                .vertex2(0.0, -20.0)
+                .vertex2(-20.0, 20.0)
+                .vertex2(20.0, 20.0);
+        }
+        {
+            let mut lines = m.begin(PrimitiveMode::Lines);
+            lines
+                .color(Color::BLUE)
+                .vertex2_v(Vector2::new(-30.0, 0.0))
+                .vertex2_v(Vector2::new(30.0, 0.0));
+        }
+        m.draw_circle(0, 0, 4.0, Color::BLACK);
+    }
+
     ray_draw_test!(test_spline);
     fn test_spline(d: &mut RaylibDrawHandle, _: &TestAssets) {
         d.draw_spline_linear(
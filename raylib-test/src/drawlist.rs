@@ -0,0 +1,101 @@
+#[cfg(test)]
+mod drawlist_test {
+    use crate::tests::*;
+    use raylib::prelude::*;
+
+    fn draw_hud(d: &mut impl RaylibDraw, hp: i32) {
+        d.draw_rectangle(10, 10, hp, 8, Color::RED);
+        d.draw_text(&format!("HP {}", hp), 10, 22, 10, Color::BLACK);
+    }
+
+    fn draw_scene(d: &mut (impl RaylibDraw + RaylibDraw3D)) {
+        d.draw_cube(Vector3::zero(), 1.0, 1.0, 1.0, Color::BLUE);
+        d.draw_grid(10, 1.0);
+    }
+
+    #[test]
+    fn test_drawlist_records_calls() {
+        let mut list = DrawList::new();
+        draw_hud(&mut list, 42);
+        assert_eq!(
+            list.commands(),
+            &[
+                DrawCommand::DrawRectangle {
+                    x: 10,
+                    y: 10,
+                    width: 42,
+                    height: 8,
+                    color: Color::RED,
+                },
+                DrawCommand::DrawText {
+                    text: "HP 42".to_owned(),
+                    x: 10,
+                    y: 22,
+                    font_size: 10,
+                    color: Color::BLACK,
+                },
+            ]
+        );
+
+        list.clear();
+        draw_scene(&mut list);
+        assert_eq!(list.len(), 2);
+        assert_eq!(
+            list.commands()[1],
+            DrawCommand::DrawGrid {
+                slices: 10,
+                spacing: 1.0
+            }
+        );
+    }
+
+    #[test]
+    fn test_drawlist_modes_and_threads() {
+        let camera = Camera2D {
+            offset: Vector2::new(400.0, 225.0),
+            target: Vector2::zero(),
+            rotation: 0.0,
+            zoom: 2.0,
+        };
+        let list = std::thread::spawn(move || {
+            let mut list = DrawList::new();
+            list.mode2D(camera, |l| {
+                l.scissor_mode(0, 0, 100, 100, |l| draw_hud(l, 5));
+            });
+            list
+        })
+        .join()
+        .unwrap();
+
+        assert_eq!(list.len(), 6);
+        assert_eq!(list.commands()[0], DrawCommand::BeginMode2D { camera });
+        assert_eq!(list.commands()[4], DrawCommand::EndScissorMode);
+        assert_eq!(list.commands()[5], DrawCommand::EndMode2D);
+
+        // the guard versions record the same commands instead of calling raylib
+        let mut guarded = DrawList::new();
+        {
+            let mut world = guarded.begin_mode2D(camera);
+            let mut clipped = world.begin_scissor_mode(0, 0, 100, 100);
+            draw_hud(&mut *clipped, 5);
+        }
+        assert_eq!(guarded, list);
+    }
+
+    ray_draw_test!(test_drawlist_replay);
+    fn test_drawlist_replay(d: &mut RaylibDrawHandle, _: &TestAssets) {
+        let mut list = DrawList::new();
+        list.clear_background(Color::WHITE);
+        draw_hud(&mut list, 80);
+        list.mode3D(
+            Camera3D::perspective(
+                Vector3::new(4.0, 4.0, 4.0),
+                Vector3::zero(),
+                Vector3::up(),
+                45.0,
+            ),
+            |l| draw_scene(l),
+        );
+        assert_eq!(list.replay(d), 0);
+    }
+}
@@ -0,0 +1,54 @@
+#[cfg(test)]
+mod dynamicfont_test {
+    use crate::tests::*;
+    use raylib::prelude::*;
+
+    ray_test!(test_dynamic_font_cache);
+    fn test_dynamic_font_cache(thread: &RaylibThread) {
+        let mut handle = TEST_HANDLE.write().unwrap();
+        let rl = handle.as_mut().unwrap();
+        let mut font = rl
+            .load_font_dynamic(thread, "resources/pixeloid.ttf", 32)
+            .expect("couldn't load dynamic font");
+        assert_eq!(font.glyph_count(), 95);
+        assert!(font.contains('A'));
+        assert!(!font.contains('é'));
+
+        assert_eq!(font.cache(thread, "café crème").unwrap(), 2);
+        assert!(font.contains('é') && font.contains('è'));
+        assert_eq!(font.cache(thread, "café crème").unwrap(), 0);
+        assert_eq!(font.glyph_count(), 97);
+        assert!(font.measure_text("café", 32.0, 1.0).x > font.measure_text("caf", 32.0, 1.0).x);
+
+        // enough codepoints to outgrow the first atlas
+        let (width, height) = font.atlas_size();
+        let many: String = ('\u{100}'..'\u{500}').collect();
+        font.cache(thread, &many).unwrap();
+        assert!(font.contains('\u{4FF}'));
+        let (grown_width, grown_height) = font.atlas_size();
+        assert!(grown_width * grown_height > width * height);
+
+        assert!(rl
+            .load_font_dynamic(thread, "resources/missing.ttf", 32)
+            .is_err());
+    }
+
+    ray_test!(test_dynamic_font_full_atlas);
+    fn test_dynamic_font_full_atlas(thread: &RaylibThread) {
+        let mut handle = TEST_HANDLE.write().unwrap();
+        let rl = handle.as_mut().unwrap();
+        let mut font = rl
+            .load_font_dynamic(thread, "resources/pixeloid.ttf", 300)
+            .expect("couldn't load dynamic font");
+        let count = font.glyph_count();
+        let size = font.atlas_size();
+
+        // a failed cache leaves the font as it was
+        let many: String = ('\u{100}'..'\u{500}').collect();
+        assert!(font.cache(thread, &many).is_err());
+        assert_eq!(font.glyph_count(), count);
+        assert_eq!(font.atlas_size(), size);
+        assert!(!font.contains('\u{100}'));
+        assert!(font.measure_text("A", 300.0, 1.0).x > 0.0);
+    }
+}
@@ -0,0 +1,60 @@
+#[cfg(test)]
+mod fontstack_test {
+    use crate::tests::*;
+    use raylib::prelude::*;
+
+    ray_draw_test!(test_fontstack_fallback);
+    fn test_fontstack_fallback(d: &mut RaylibDrawHandle, assets: &TestAssets) {
+        let mut fonts = FontStack::new(&assets.font);
+        fonts.push(&assets.font_ex);
+        assert_eq!(fonts.font_count(), 2);
+        assert_eq!(fonts.resolve('A'), (0, 'A'));
+        // neither font has CJK glyphs
+        assert_eq!(fonts.resolve('日'), (0, '?'));
+        fonts.set_replacement('*');
+        assert_eq!(fonts.resolve('日').1, '*');
+
+        // a stack of one font measures like the font itself
+        let single = FontStack::new(&assets.font);
+        let expected = assets.font.measure_text("Hello\nWorld", 20.0, 1.0);
+        let measured = single.measure_text("Hello\nWorld", 20.0, 1.0);
+        assert!((expected.x - measured.x).abs() < 0.01);
+        assert!((expected.y - measured.y).abs() < 0.01);
+
+        let layout = TextLayout::with_font_stack(&fonts, "Hello stacked fonts", 20.0, 1.0, None);
+        assert_eq!(layout.lines().len(), 1);
+        assert!(
+            (layout.size().x - fonts.measure_text("Hello stacked fonts", 20.0, 1.0).x).abs() < 0.01
+        );
+
+        d.clear_background(Color::WHITE);
+        d.draw_text_stack(
+            &fonts,
+            "Hello 日本",
+            Vector2::new(10.0, 10.0),
+            20.0,
+            1.0,
+            Color::BLACK,
+        );
+        d.draw_text_stack_pro(
+            &fonts,
+            "Rotated",
+            Vector2::new(100.0, 100.0),
+            Vector2::zero(),
+            45.0,
+            20.0,
+            1.0,
+            Color::BLACK,
+        );
+        d.draw_text_layout_stack(&fonts, &layout, Vector2::new(10.0, 200.0), Color::BLACK);
+
+        let mut rich = FontSet::new(&assets.font_ex);
+        rich.set_fallback(&fonts);
+        rich.size = 20.0;
+        d.draw_text_rich(
+            &rich,
+            "Fallback [b]日本[/b]",
+            Rectangle::new(10.0, 300.0, 300.0, 40.0),
+        );
+    }
+}
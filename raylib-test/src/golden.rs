@@ -0,0 +1,127 @@
+//! Golden-image checks: render a scene offscreen and compare it with a reference PNG
+//!
+//! References live in `resources/golden/<name>.png`. Running the tests with
+//! `RAYLIB_UPDATE_GOLDENS=1` writes them from the current output instead of comparing.
+//! When a comparison fails, the rendered frame and a heatmap of the differences are written
+//! to `test_out/golden/`.
+use raylib::prelude::*;
+
+pub const GOLDEN_DIR: &str = "resources/golden";
+pub const ARTIFACT_DIR: &str = "test_out/golden";
+pub const UPDATE_GOLDENS_VAR: &str = "RAYLIB_UPDATE_GOLDENS";
+
+/// How close a render has to be to its reference.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Tolerance {
+    /// Largest difference allowed in any channel of a matching pixel.
+    pub channel: u8,
+    /// Number of pixels allowed to differ by more than `channel`.
+    pub pixels: usize,
+}
+
+impl Tolerance {
+    pub const EXACT: Tolerance = Tolerance {
+        channel: 0,
+        pixels: 0,
+    };
+}
+
+impl Default for Tolerance {
+    /// Allows for rounding differences between GPUs, but no pixel that is really off.
+    fn default() -> Tolerance {
+        Tolerance {
+            channel: 2,
+            pixels: 0,
+        }
+    }
+}
+
+/// Renders `draw` into a `width` x `height` render texture and reads it back, top row first.
+pub fn render(
+    rl: &mut RaylibHandle,
+    thread: &RaylibThread,
+    width: u32,
+    height: u32,
+    draw: impl FnOnce(&mut RaylibTextureMode<RaylibHandle>),
+) -> Image {
+    let mut target = rl
+        .load_render_texture(thread, width, height)
+        .expect("could not create golden render texture");
+    {
+        let mut d = rl.begin_texture_mode(thread, &mut target);
+        draw(&mut d);
+    }
+    let mut image = target
+        .load_image()
+        .expect("could not read back golden render texture");
+    // render textures are stored bottom row first
+    image.flip_vertical();
+    image
+}
+
+/// Renders `draw` like [`render`] and checks the result against the golden image `name`.
+pub fn assert_golden(
+    rl: &mut RaylibHandle,
+    thread: &RaylibThread,
+    name: &str,
+    (width, height): (u32, u32),
+    tolerance: Tolerance,
+    draw: impl FnOnce(&mut RaylibTextureMode<RaylibHandle>),
+) {
+    let actual = render(rl, thread, width, height, draw);
+    check_golden(name, &actual, tolerance);
+}
+
+/// Compares `actual` with the golden image `name`, panicking with the error statistics and
+/// writing `<name>.actual.png` and `<name>.diff.png` artifacts if they differ.
+pub fn check_golden(name: &str, actual: &Image, tolerance: Tolerance) {
+    let golden = format!("{}/{}.png", GOLDEN_DIR, name);
+    if std::env::var_os(UPDATE_GOLDENS_VAR).is_some() {
+        std::fs::create_dir_all(GOLDEN_DIR).expect("could not create golden directory");
+        export(actual, &golden);
+        return;
+    }
+    let expected = Image::load_image(&golden).unwrap_or_else(|_| {
+        panic!(
+            "missing golden image {}, run with {}=1 to create it",
+            golden, UPDATE_GOLDENS_VAR
+        )
+    });
+    check_image(name, &expected, actual, tolerance);
+}
+
+/// Compares `actual` with `expected` like [`check_golden`], naming the artifacts after `name`.
+pub fn check_image(name: &str, expected: &Image, actual: &Image, tolerance: Tolerance) {
+    std::fs::create_dir_all(ARTIFACT_DIR).expect("could not create artifact directory");
+    let failure = match actual.diff_with_tolerance(expected, tolerance.channel) {
+        Ok(diff) if diff.mismatched <= tolerance.pixels => return,
+        Ok(diff) => {
+            export(
+                &diff.heatmap,
+                &format!("{}/{}.diff.png", ARTIFACT_DIR, name),
+            );
+            format!(
+                "{} pixels differ by more than {} (max error {:?}, mean error {:?})",
+                diff.mismatched, tolerance.channel, diff.max_error, diff.mean_error
+            )
+        }
+        Err(e) => e.to_string(),
+    };
+    export(actual, &format!("{}/{}.actual.png", ARTIFACT_DIR, name));
+    panic!(
+        "{} doesn't match its expected image: {}. Artifacts are in {}",
+        name, failure, ARTIFACT_DIR
+    );
+}
+
+/// Writes `image` to `path`, panicking if the file isn't there afterwards. Any old file is
+/// removed first, since exporting doesn't report failures.
+fn export(image: &Image, path: &str) {
+    let _ = std::fs::remove_file(path);
+    image.export_image(path);
+    assert!(
+        std::path::Path::new(path).exists(),
+        "could not write {}",
+        path
+    );
+}
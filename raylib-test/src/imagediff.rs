@@ -0,0 +1,39 @@
+#[cfg(test)]
+mod imagediff_test {
+    use raylib::prelude::*;
+
+    #[test]
+    fn test_diff() {
+        let gray = Color::new(100, 100, 100, 255);
+        let image = Image::from_pixel_slice(4, 3, &[gray; 12]).unwrap();
+        let mut pixels = vec![gray; 12];
+        pixels[5] = Color::new(110, 100, 97, 255);
+        pixels[11] = Color::new(102, 100, 100, 255);
+        let other = Image::from_pixel_slice(4, 3, &pixels).unwrap();
+
+        let diff = image.diff(&other).unwrap();
+        assert_eq!(diff.max_error, [10, 0, 3, 0]);
+        assert_eq!(diff.mean_error, [1.0, 0.0, 0.25, 0.0]);
+        assert_eq!(diff.mismatched, 2);
+        assert!(!diff.is_match());
+        let heatmap = diff.heatmap.pixels::<Rgba8>().unwrap();
+        assert_eq!(heatmap[5], Color::new(255, 10, 0, 255));
+        assert_eq!(heatmap[0], Color::new(25, 25, 25, 255));
+
+        assert_eq!(image.diff_with_tolerance(&other, 2).unwrap().mismatched, 1);
+        assert!(image.diff(&image).unwrap().is_match());
+        let taller = Image::from_pixel_slice(3, 4, &[gray; 12]).unwrap();
+        assert!(image.diff(&taller).is_err());
+    }
+
+    #[test]
+    fn test_diff_empty() {
+        let empty = Image::gen_image_color(0, 0, Color::BLACK);
+        let diff = empty
+            .diff(&Image::gen_image_color(0, 0, Color::WHITE))
+            .unwrap();
+        assert_eq!(diff.mismatched, 0);
+        assert!(diff.is_match());
+        assert_eq!(diff.heatmap.width(), 0);
+    }
+}
@@ -0,0 +1,152 @@
+#[cfg(test)]
+mod imageops_test {
+    use raylib::prelude::*;
+
+    /// A `width` x `height` image from rows of `#` (white) and `.` (black).
+    fn mask(rows: &[&str]) -> Image {
+        let pixels: Vec<Color> = rows
+            .iter()
+            .flat_map(|row| row.chars())
+            .map(|c| if c == '#' { Color::WHITE } else { Color::BLACK })
+            .collect();
+        Image::from_pixel_slice(rows[0].len() as i32, rows.len() as i32, &pixels).unwrap()
+    }
+
+    fn white_count(image: &Image) -> usize {
+        image
+            .pixels::<Rgba8>()
+            .unwrap()
+            .iter()
+            .filter(|c| c.r == 255)
+            .count()
+    }
+
+    #[test]
+    fn test_flood_fill() {
+        let mut image = mask(&["..#..", "..#..", "###..", "....."]);
+        assert_eq!(image.flood_fill(0, 0, Color::RED, 0), 4);
+        assert_eq!(image.get_color(1, 1), Color::RED);
+        assert_eq!(image.get_color(4, 0), Color::BLACK);
+        assert_eq!(image.flood_fill(-1, 0, Color::RED, 0), 0);
+
+        // tolerance reaches into similar colors
+        let mut image = Image::from_pixel_slice(
+            3,
+            1,
+            &[
+                Color::new(100, 100, 100, 255),
+                Color::new(110, 100, 100, 255),
+                Color::new(130, 100, 100, 255),
+            ],
+        )
+        .unwrap();
+        assert_eq!(image.flood_fill(0, 0, Color::BLUE, 10), 2);
+        assert_eq!(image.get_color(2, 0), Color::new(130, 100, 100, 255));
+
+        // compressed images can't be converted to RGBA, so they're left alone
+        let mut compressed = Image::gen_image_color(8, 8, Color::BLACK);
+        compressed.format = PixelFormat::PIXELFORMAT_COMPRESSED_DXT1_RGB as i32;
+        assert_eq!(compressed.flood_fill(0, 0, Color::RED, 0), 0);
+        compressed.morph_close(1);
+        compressed.sharpen(1.0);
+        assert_eq!(
+            compressed.format(),
+            PixelFormat::PIXELFORMAT_COMPRESSED_DXT1_RGB
+        );
+    }
+
+    #[test]
+    fn test_morphology() {
+        let mut image = mask(&[".....", ".###.", ".###.", ".###.", "....#"]);
+        image.morph_open(1);
+        // the lone pixel is gone, the square survives
+        assert_eq!(white_count(&image), 9);
+        assert_eq!(image.get_color(4, 4), Color::BLACK);
+
+        let mut image = mask(&["#.#", "...", "..."]);
+        image.dilate(1);
+        assert_eq!(white_count(&image), 6);
+        // outside the image doesn't count, so the edge row survives
+        image.erode(1);
+        assert_eq!(white_count(&image), 3);
+
+        let mut image = mask(&["#####", "##.##", "#####"]);
+        image.morph_close(1);
+        assert_eq!(white_count(&image), 15);
+    }
+
+    #[test]
+    fn test_connected_components() {
+        let image = mask(&["##..#", "#...#", "...#.", "....."]);
+        let four = image.connected_components(Connectivity::Four, |c| c.r > 0);
+        assert_eq!(four.components.len(), 3);
+        assert_eq!(four.label_at(1, 0), four.label_at(0, 1));
+        assert_ne!(four.label_at(0, 0), four.label_at(3, 2));
+        assert_eq!(four.label_at(3, 3), 0);
+        let first = four.component(four.label_at(0, 0)).unwrap();
+        assert_eq!(first.area, 3);
+        assert_eq!(first.bounds, Rectangle::new(0.0, 0.0, 2.0, 2.0));
+        let right = four.component(four.label_at(4, 0)).unwrap();
+        assert_eq!(right.centroid, Vector2::new(4.5, 1.0));
+
+        let eight = image.connected_components(Connectivity::Eight, |c| c.r > 0);
+        assert_eq!(eight.components.len(), 2);
+    }
+
+    #[test]
+    fn test_histogram_and_tones() {
+        let pixels: Vec<Color> = (0..4).map(|i| Color::new(i * 10, 50, 200, 255)).collect();
+        let mut image = Image::from_pixel_slice(2, 2, &pixels).unwrap();
+        let histogram = image.histogram();
+        assert_eq!(histogram.total(), 4);
+        assert_eq!(histogram.red[10], 1);
+        assert_eq!(histogram.green[50], 4);
+        assert_eq!(histogram.alpha[255], 4);
+
+        image.equalize_histogram();
+        let reds: Vec<u8> = image
+            .pixels::<Rgba8>()
+            .unwrap()
+            .iter()
+            .map(|c| c.r)
+            .collect();
+        assert_eq!(reds, vec![0, 85, 170, 255]);
+
+        let mut image = Image::from_pixel_slice(1, 1, &[Color::new(64, 128, 192, 80)]).unwrap();
+        image.levels(64, 192, 1.0, 0, 255);
+        assert_eq!(image.get_color(0, 0), Color::new(0, 128, 255, 80));
+        image.gamma(2.0);
+        assert_eq!(image.get_color(0, 0).g, 181);
+        image.curves(&[(0, 255), (255, 0)]);
+        assert_eq!(image.get_color(0, 0), Color::new(255, 74, 0, 80));
+    }
+
+    #[test]
+    fn test_quantize() {
+        let mut pixels = vec![Color::new(250, 0, 0, 255); 8];
+        pixels.extend(vec![Color::new(240, 10, 0, 255); 8]);
+        pixels.extend(vec![Color::new(0, 0, 250, 255); 16]);
+        let mut image = Image::from_pixel_slice(8, 4, &pixels).unwrap();
+        let palette = image.quantize(2, QuantizeMethod::MedianCut);
+        assert_eq!(palette.len(), 2);
+        assert!(palette.contains(&Color::new(245, 5, 0, 255)));
+        assert!(palette.contains(&Color::new(0, 0, 250, 255)));
+        assert_eq!(image.get_color(0, 0), Color::new(245, 5, 0, 255));
+
+        let image = Image::from_pixel_slice(8, 4, &pixels).unwrap();
+        let palette = image.quantize_palette(8, QuantizeMethod::KMeans { iterations: 4 });
+        assert_eq!(palette.len(), 3);
+    }
+
+    #[test]
+    fn test_sharpen() {
+        let mut image = mask(&["..##", "..##"]);
+        let mut sharpened = Image::from_pixel_slice(2, 2, &[Color::GRAY; 4]).unwrap();
+        sharpened.sharpen(1.0);
+        assert_eq!(sharpened.get_color(0, 0), Color::GRAY);
+        image.unsharp_mask(1.0, 1.0, 0);
+        // the edge keeps its extremes, the flat parts stay put
+        assert_eq!(image.get_color(0, 0), Color::BLACK);
+        assert_eq!(image.get_color(3, 0), Color::WHITE);
+    }
+}
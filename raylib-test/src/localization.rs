@@ -0,0 +1,220 @@
+#[cfg(test)]
+mod localization_test {
+    use crate::tests::*;
+    use raylib::prelude::*;
+
+    const EN: &str = r#"
+# Main menu
+-brand = Raylib Quest
+title = Welcome to { -brand }!
+greeting = Hello, { $name }.
+emails =
+    { $count ->
+        [0] You have no new emails.
+        [one] You have one new email.
+       *[other] You have { $count } new emails.
+    }
+login = Log in
+    .tooltip = Sign in with your { -brand } account
+"#;
+
+    const RU: &str = r#"
+title = Добро пожаловать в { -brand }!
+-brand = Raylib Квест
+emails = { $count ->
+    [one] { $count } письмо
+    [few] { $count } письма
+   *[many] { $count } писем
+}
+"#;
+
+    const PO: &str = r#"
+msgid ""
+msgstr ""
+"Content-Type: text/plain; charset=UTF-8\n"
+"Plural-Forms: nplurals=3; plural=(n%10==1 && n%100!=11 ? 0 : n%10>=2 && n%10<=4 && (n%100<10 || n%100>=20) ? 1 : 2);\n"
+
+#: src/main.rs:10
+msgid "Open"
+msgstr "Открыть"
+
+msgctxt "door"
+msgid "Open"
+msgstr "Открыта"
+
+msgid "Hello, {name}!"
+msgstr ""
+"Привет, "
+"{name}!"
+
+msgid "{count} apple"
+msgid_plural "{count} apples"
+msgstr[0] "{count} яблоко"
+msgstr[1] "{count} яблока"
+msgstr[2] "{count} яблок"
+
+msgid "Untranslated"
+msgstr ""
+"#;
+
+    fn fluent() -> Localization {
+        let mut l10n = Localization::new("en");
+        l10n.add_ftl("en", EN).unwrap();
+        l10n.add_ftl("ru", RU).unwrap();
+        l10n
+    }
+
+    #[test]
+    fn test_localization_ftl() {
+        let l10n = fluent();
+        assert_eq!(l10n.get("title"), "Welcome to Raylib Quest!");
+        assert_eq!(
+            l10n.format("greeting", &[("name", "Ada".into())]),
+            "Hello, Ada."
+        );
+        assert_eq!(
+            l10n.get("login.tooltip"),
+            "Sign in with your Raylib Quest account"
+        );
+        assert_eq!(
+            l10n.format("emails", &[("count", 0.into())]),
+            "You have no new emails."
+        );
+        assert_eq!(
+            l10n.format("emails", &[("count", 1.into())]),
+            "You have one new email."
+        );
+        assert_eq!(
+            l10n.format("emails", &[("count", 7.into())]),
+            "You have 7 new emails."
+        );
+        // missing arguments choose the default variant and stay visible
+        assert_eq!(l10n.get("greeting"), "Hello, {name}.");
+        assert_eq!(l10n.get("missing-key"), "missing-key");
+        assert_eq!(l10n.try_format("missing-key", &[]), None);
+        assert!(Localization::new("en")
+            .add_ftl("en", "no equals sign")
+            .is_err());
+        assert!(Localization::new("en")
+            .add_ftl("en", "a = { $n ->\n    [one] x\n    }")
+            .is_err());
+    }
+
+    #[test]
+    fn test_localization_switch_and_fallback() {
+        let mut l10n = fluent();
+        assert!(l10n.set_locale("de").is_err());
+        l10n.set_locale("ru").unwrap();
+        assert_eq!(l10n.locale(), "ru");
+        assert_eq!(l10n.locales(), vec!["en", "ru"]);
+        assert_eq!(l10n.get("title"), "Добро пожаловать в Raylib Квест!");
+        assert_eq!(l10n.format("emails", &[("count", 1.into())]), "1 письмо");
+        assert_eq!(l10n.format("emails", &[("count", 3.into())]), "3 письма");
+        assert_eq!(l10n.format("emails", &[("count", 11.into())]), "11 писем");
+        assert_eq!(l10n.format("emails", &[("count", 21.into())]), "21 письмо");
+        // not translated, so English
+        assert_eq!(l10n.get("login"), "Log in");
+        l10n.set_fallback(None);
+        assert_eq!(l10n.get("login"), "login");
+    }
+
+    #[test]
+    fn test_localization_po() {
+        let mut l10n = Localization::new("ru");
+        l10n.add_po("ru", PO).unwrap();
+        assert_eq!(l10n.get("Open"), "Открыть");
+        assert_eq!(l10n.format_in_context("door", "Open", &[]), "Открыта");
+        assert_eq!(
+            l10n.format("Hello, {name}!", &[("name", "Ада".into())]),
+            "Привет, Ада!"
+        );
+        for (count, text) in [
+            (1, "1 яблоко"),
+            (2, "2 яблока"),
+            (5, "5 яблок"),
+            (22, "22 яблока"),
+        ]
+        .iter()
+        {
+            assert_eq!(
+                l10n.format("{count} apple", &[("count", (*count).into())]),
+                *text
+            );
+        }
+        // untranslated entries fall through to the key
+        assert_eq!(l10n.get("Untranslated"), "Untranslated");
+        assert!(l10n.add_po("ru", "msgid \"broken").is_err());
+    }
+
+    #[test]
+    fn test_localization_po_deep_plural_forms() {
+        let header = |formula: String| {
+            format!(
+                "msgid \"\"\nmsgstr \"\"\n\"Plural-Forms: nplurals=2; plural={};\\n\"\n",
+                formula
+            )
+        };
+        let mut l10n = Localization::new("en");
+        let nested = format!("{}n != 1{}", "(".repeat(100_000), ")".repeat(100_000));
+        assert!(l10n.add_po("en", &header(nested)).is_err());
+        let chained = format!("{}n", "n + ".repeat(100_000));
+        assert!(l10n.add_po("en", &header(chained)).is_err());
+        let shallow = format!("{}n != 1{}", "(".repeat(8), ")".repeat(8));
+        l10n.add_po("en", &header(shallow)).unwrap();
+    }
+
+    #[test]
+    fn test_localization_ftl_deep_selects() {
+        let nested = |levels: usize| {
+            format!(
+                "deep = {}x{}\n",
+                "{ $n -> *[other] ".repeat(levels),
+                "}".repeat(levels)
+            )
+        };
+        let mut l10n = Localization::new("en");
+        assert!(l10n.add_ftl("en", &nested(100_000)).is_err());
+        l10n.add_ftl("en", &nested(8)).unwrap();
+        assert_eq!(l10n.format("deep", &[("n", 2.0.into())]), "x");
+    }
+
+    #[test]
+    fn test_plural_categories() {
+        use PluralCategory::*;
+        assert_eq!(PluralCategory::of("en-US", 1.0), One);
+        assert_eq!(PluralCategory::of("en", 0.0), Other);
+        assert_eq!(PluralCategory::of("fr", 0.0), One);
+        assert_eq!(PluralCategory::of("ru_RU", 111.0), Many);
+        assert_eq!(PluralCategory::of("pl", 22.0), Few);
+        assert_eq!(PluralCategory::of("pl", 21.0), Many);
+        assert_eq!(PluralCategory::of("ar", 0.0), Zero);
+        assert_eq!(PluralCategory::of("ar", 2.0), Two);
+        assert_eq!(PluralCategory::of("ja", 1.0), Other);
+        assert_eq!(PluralCategory::of("en", 1.5), Other);
+    }
+
+    #[test]
+    fn test_localization_codepoints() {
+        let l10n = fluent();
+        let glyphs = l10n.codepoints("ru").unwrap();
+        assert!(glyphs.contains('Д') && glyphs.contains('ь') && glyphs.contains('7'));
+        // only the text of the tables, not their syntax
+        assert!(!glyphs.contains('$') && !glyphs.contains('{'));
+        let mut sorted: Vec<char> = glyphs.chars().collect();
+        sorted.dedup();
+        assert_eq!(sorted.len(), glyphs.chars().count());
+        assert!(l10n.codepoints("de").is_none());
+    }
+
+    ray_test!(test_localization_font);
+    fn test_localization_font(thread: &RaylibThread) {
+        let mut handle = TEST_HANDLE.write().unwrap();
+        let rl = handle.as_mut().unwrap();
+        let l10n = fluent();
+        let glyphs = l10n.codepoints("en").unwrap();
+        let font = rl
+            .load_font_ex(thread, "resources/pixeloid.ttf", 32, Some(&glyphs))
+            .expect("couldn't load font");
+        assert_eq!(font.glyphCount as usize, glyphs.chars().count());
+    }
+}
@@ -46,4 +46,89 @@ mod model_test {
 
         world.draw_model(&model, zero, 1.0, Color::RED);
     }
+
+    ray_test!(test_mesh_builder);
+    fn test_mesh_builder(thread: &RaylibThread) {
+        let mut handle = TEST_HANDLE.write().unwrap();
+        let rl = handle.as_mut().unwrap();
+
+        let positions = [
+            Vector3::new(0.0, 0.0, 0.0),
+            Vector3::new(1.0, 0.0, 0.0),
+            Vector3::new(0.0, 1.0, 0.0),
+            Vector3::new(1.0, 1.0, 0.0),
+        ];
+        let normals = [Vector3::new(0.0, 0.0, 1.0); 4];
+        let texcoords = [
+            Vector2::new(0.0, 0.0),
+            Vector2::new(1.0, 0.0),
+            Vector2::new(0.0, 1.0),
+            Vector2::new(1.0, 1.0),
+        ];
+        let colors = [Color::WHITE; 4];
+        let tangents = [Vector4::new(1.0, 0.0, 0.0, 1.0); 4];
+        let mut mesh = MeshBuilder::new(&positions)
+            .normals(&normals)
+            .texcoords(&texcoords)
+            .texcoords2(&texcoords)
+            .colors(&colors)
+            .tangents(&tangents)
+            .indices(&[0, 1, 2, 2, 1, 3])
+            .bones(&[[0, 0, 0, 0]; 4], &[[1.0, 0.0, 0.0, 0.0]; 4])
+            .dynamic()
+            .build(thread)
+            .expect("mesh built");
+        assert_eq!(mesh.vertexCount, 4);
+        assert_eq!(mesh.triangleCount, 2);
+        assert_eq!(mesh.indicies(), &[0, 1, 2, 2, 1, 3]);
+
+        mesh.update_positions(thread, 3, &[Vector3::new(2.0, 2.0, 0.0)])
+            .expect("positions updated");
+        assert_eq!(mesh.vertices()[3], Vector3::new(2.0, 2.0, 0.0));
+        mesh.update_colors(thread, 0, &[Color::RED; 4])
+            .expect("colors updated");
+        assert_eq!(mesh.colors()[0], Color::RED);
+        assert!(mesh.update_texcoords(thread, 2, &texcoords).is_err());
+
+        let bounds = mesh.get_mesh_bounding_box();
+        assert_eq!(bounds.max, Vector3::new(2.0, 2.0, 0.0));
+        let model = rl
+            .load_model_from_mesh(thread, unsafe { mesh.make_weak() })
+            .unwrap();
+        let camera = Camera3D::perspective(
+            Vector3::new(0.0, 0.0, 5.0),
+            Vector3::zero(),
+            Vector3::up(),
+            45.0,
+        );
+        let mut d = rl.begin_drawing(thread);
+        let mut world = d.begin_mode3D(camera);
+        world.draw_model(&model, Vector3::zero(), 1.0, Color::WHITE);
+    }
+
+    ray_test!(test_mesh_builder_errors);
+    fn test_mesh_builder_errors(thread: &RaylibThread) {
+        let positions = [Vector3::zero(); 3];
+        let mut mesh = MeshBuilder::new(&positions)
+            .build(thread)
+            .expect("mesh built");
+        // neither colors nor normals were given
+        assert!(mesh.update_colors(thread, 0, &[Color::RED]).is_err());
+        assert!(mesh.update_normals(thread, 0, &[Vector3::up()]).is_err());
+
+        assert!(MeshBuilder::new(&[]).build(thread).is_err());
+        assert!(MeshBuilder::new(&positions[..2]).build(thread).is_err());
+        assert!(MeshBuilder::new(&positions)
+            .normals(&[Vector3::up(); 2])
+            .build(thread)
+            .is_err());
+        assert!(MeshBuilder::new(&positions)
+            .indices(&[0, 1, 3])
+            .build(thread)
+            .is_err());
+        assert!(MeshBuilder::new(&positions)
+            .indices(&[0, 1])
+            .build(thread)
+            .is_err());
+    }
 }
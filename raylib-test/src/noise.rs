@@ -0,0 +1,133 @@
+#[cfg(test)]
+mod noise_test {
+    use raylib::prelude::*;
+
+    fn grid() -> impl Iterator<Item = (f32, f32)> {
+        let ys = (0..40).map(|j| j as f32 * 0.219 - 4.0);
+        (0..40)
+            .map(|i| i as f32 * 0.173 - 3.0)
+            .flat_map(move |x| ys.clone().map(move |y| (x, y)))
+    }
+
+    #[test]
+    fn test_noise_range_and_seeds() {
+        let sources: [Box<dyn Noise>; 2] =
+            [Box::new(SimplexNoise::new(1)), Box::new(ValueNoise::new(1))];
+        for noise in &sources {
+            let (mut lo, mut hi) = (f32::MAX, f32::MIN);
+            for (x, y) in grid() {
+                for &v in &[
+                    noise.sample2(x, y),
+                    noise.sample3(x, y, x - y),
+                    noise.sample4(x, y, y * 0.5, x * 0.7),
+                ] {
+                    assert!(v.abs() <= 1.05, "{} out of range", v);
+                    lo = lo.min(v);
+                    hi = hi.max(v);
+                }
+                // continuous: a tiny step makes a tiny change
+                assert!((noise.sample2(x, y) - noise.sample2(x + 0.001, y)).abs() < 0.05);
+            }
+            assert!(hi - lo > 1.0);
+        }
+
+        let (a, b) = (SimplexNoise::new(3), SimplexNoise::new(4));
+        assert_eq!(
+            a.sample3(1.3, 2.7, -0.4),
+            SimplexNoise::new(3).sample3(1.3, 2.7, -0.4)
+        );
+        assert!(grid().any(|(x, y)| a.sample2(x, y) != b.sample2(x, y)));
+    }
+
+    #[test]
+    fn test_worley_noise() {
+        let f1 = WorleyNoise::new(9);
+        let f2 = f1.with_distance(WorleyDistance::F2);
+        let cracks = f1.with_distance(WorleyDistance::F2MinusF1);
+        for (x, y) in grid() {
+            let (d1, d2) = (f1.sample2(x, y), f2.sample2(x, y));
+            assert!(d1 >= 0.0 && d1 <= d2);
+            assert!((cracks.sample2(x, y) - (d2 - d1)).abs() < 1e-5);
+            assert!(f1.sample3(x, y, 0.5) >= 0.0);
+            assert!(f1.sample4(x, y, 0.5, 1.5) >= 0.0);
+        }
+        // without jitter the feature points sit at the cell centers
+        let regular = WorleyNoise::new(9).with_jitter(0.0);
+        assert!(regular.sample2(2.5, -1.5) < 1e-6);
+        assert!((regular.sample2(2.0, -1.5) - 0.5).abs() < 1e-6);
+        assert!(regular.sample3(0.5, 0.5, 0.5) < 1e-6);
+    }
+
+    #[test]
+    fn test_fractal_warp_and_tiling() {
+        let layers: [Box<dyn Noise>; 4] = [
+            Box::new(SimplexNoise::new(5).fbm(6)),
+            Box::new(SimplexNoise::new(5).ridged(4).with_gain(0.6)),
+            Box::new(ValueNoise::new(5).billow(3).with_lacunarity(2.5)),
+            Box::new(SimplexNoise::new(5).fbm(3).warped(ValueNoise::new(6), 0.8)),
+        ];
+        for noise in &layers {
+            for (x, y) in grid() {
+                assert!(noise.sample2(x, y).abs() <= 1.05);
+                assert!(noise.sample3(x, y, 0.3).abs() <= 1.05);
+            }
+            // a tiled pattern matches itself one period over, in both directions
+            for &(x, y) in &[(0.0, 0.0), (1.7, 3.2), (5.5, 0.4)] {
+                let v = noise.sample_tiled(x, y, 8.0, 6.0);
+                assert!((v - noise.sample_tiled(x + 8.0, y, 8.0, 6.0)).abs() < 1e-3);
+                assert!((v - noise.sample_tiled(x, y - 6.0, 8.0, 6.0)).abs() < 1e-3);
+            }
+        }
+        // warping changes the pattern
+        let plain = SimplexNoise::new(5);
+        let warped = plain.warped(SimplexNoise::new(6), 1.0);
+        assert!(grid().any(|(x, y)| (plain.sample2(x, y) - warped.sample2(x, y)).abs() > 0.1));
+    }
+
+    #[test]
+    fn test_heightmap_images() {
+        let mut heights = Heightmap::from_noise_tiled(&SimplexNoise::new(2).fbm(4), 32, 16, 0.125);
+        assert_eq!((heights.width(), heights.height()), (32, 16));
+        let (lo, hi) = heights.range().unwrap();
+        assert!(lo < hi);
+        heights.normalize();
+        assert_eq!(heights.range(), Some((0.0, 1.0)));
+        assert!(heights.get(32, 0).is_none());
+
+        let gray = heights.to_image().unwrap();
+        assert_eq!(
+            gray.format(),
+            PixelFormat::PIXELFORMAT_UNCOMPRESSED_GRAYSCALE
+        );
+        let (lowest, _) = heights
+            .data()
+            .iter()
+            .enumerate()
+            .fold(
+                (0, f32::MAX),
+                |best, (i, &v)| if v < best.1 { (i, v) } else { best },
+            );
+        assert_eq!(gray.pixels::<Gray8>().unwrap()[lowest], Gray8(0));
+
+        let floats = heights.to_image_f32().unwrap();
+        assert_eq!(floats.pixels::<R32f>().unwrap()[7], R32f(heights.data()[7]));
+
+        let ramp =
+            ColorRamp::new(&[(1.0, Color::WHITE), (0.0, Color::BLACK)]).stop(0.5, Color::RED);
+        assert_eq!(ramp.sample(-1.0), Color::BLACK);
+        assert_eq!(ramp.sample(0.5), Color::RED);
+        assert_eq!(ramp.sample(0.75), Color::new(243, 148, 155, 255));
+        assert_eq!(ramp.sample(2.0), Color::WHITE);
+        assert_eq!(ColorRamp::default().sample(0.5), Color::BLANK);
+        let colored = heights.to_image_ramp(&ramp).unwrap();
+        assert_eq!(colored.pixels::<Rgba8>().unwrap()[lowest], Color::BLACK);
+
+        let mut flat = Heightmap::from_fn(3, 2, |x, y| (x + y) as f32);
+        assert_eq!(flat.get(2, 1), Some(3.0));
+        flat.set(2, 1, -1.0);
+        assert_eq!(flat.range(), Some((-1.0, 2.0)));
+        let mut flat = Heightmap::new(4, 4);
+        flat.normalize();
+        assert!(flat.data().iter().all(|&v| v == 0.0));
+    }
+}
@@ -0,0 +1,102 @@
+#[cfg(test)]
+mod pixels_test {
+    use raylib::prelude::*;
+
+    #[test]
+    fn test_pixels_typed_access() {
+        let mut image = Image::gen_image_color(4, 3, Color::new(10, 20, 30, 255));
+        assert_eq!(image.pixels::<Rgba8>().unwrap().len(), 12);
+        for pixel in image.pixels_mut::<Rgba8>().unwrap() {
+            pixel.r = 200;
+        }
+        assert_eq!(image.get_color(3, 2), Color::new(200, 20, 30, 255));
+        // wrong format is an error instead of reinterpreting the bytes
+        assert!(image.pixels::<Gray8>().is_err());
+        assert!(image.pixels::<Rgba32f>().is_err());
+
+        image.set_format(PixelFormat::PIXELFORMAT_UNCOMPRESSED_GRAYSCALE);
+        let gray = image.pixels::<Gray8>().unwrap();
+        assert_eq!(gray.len(), 12);
+        assert_eq!(gray[0].to_color(), image.get_color(0, 0));
+
+        image.set_format(PixelFormat::PIXELFORMAT_UNCOMPRESSED_R32G32B32A32);
+        let hdr = image.pixels::<Rgba32f>().unwrap();
+        assert_eq!(hdr[5].a, 1.0);
+    }
+
+    #[test]
+    fn test_pixel_conversions() {
+        let color = Color::new(255, 128, 0, 255);
+        assert_eq!(
+            Rgb565::from_color(color).to_color(),
+            Color::new(255, 129, 0, 255)
+        );
+        assert_eq!(Rgba4444::from_color(Color::WHITE).0, 0xffff);
+        assert_eq!(Rgba5551::from_color(Color::BLANK).to_color().a, 0);
+        assert_eq!(Rgb8::from_color(color).to_color(), color);
+        assert_eq!(R16f::new(0.5).value(), 0.5);
+        assert_eq!(R16f::new(-2.0).0, 0xc000);
+        assert_eq!(
+            Rgba16f::new(1.0, 0.25, 0.0, 1.0).to_array(),
+            [1.0, 0.25, 0.0, 1.0]
+        );
+        assert_eq!(Rgba32f::from_color(color).to_color(), color);
+    }
+
+    #[test]
+    fn test_image_view() {
+        let mut image = Image::gen_image_color(8, 6, Color::BLACK);
+        {
+            let mut view = image
+                .view_mut::<Rgba8>(Rectangle::new(2.0, 1.0, 4.0, 3.0))
+                .unwrap();
+            assert_eq!((view.width(), view.height(), view.stride()), (4, 3, 8));
+            view.fill(Color::RED);
+            view.set(0, 0, Color::GREEN);
+            let mut inner = view.view_mut(Rectangle::new(3.0, 2.0, 1.0, 1.0)).unwrap();
+            inner.set(0, 0, Color::BLUE);
+        }
+        assert_eq!(image.get_color(1, 1), Color::BLACK);
+        assert_eq!(image.get_color(2, 1), Color::GREEN);
+        assert_eq!(image.get_color(5, 3), Color::BLUE);
+        assert_eq!(image.get_color(6, 3), Color::BLACK);
+
+        let view = image
+            .view::<Rgba8>(Rectangle::new(2.0, 1.0, 4.0, 3.0))
+            .unwrap();
+        assert_eq!(view.row(1), &[Color::RED; 4]);
+        assert_eq!(view.rows().count(), 3);
+        assert_eq!(view.get(4, 0), None);
+        assert_eq!(view.to_vec().len(), 12);
+        assert!(image
+            .view::<Rgba8>(Rectangle::new(6.0, 0.0, 4.0, 1.0))
+            .is_err());
+        assert!(image
+            .view::<Gray8>(Rectangle::new(0.0, 0.0, 1.0, 1.0))
+            .is_err());
+    }
+
+    #[test]
+    fn test_image_from_pixels() {
+        let pixels = vec![Color::new(1, 2, 3, 4); 6];
+        let mut image = Image::from_pixel_slice(3, 2, &pixels).unwrap();
+        assert_eq!(image.get_color(2, 1), Color::new(1, 2, 3, 4));
+        assert_eq!(image.as_bytes()[..4], [1, 2, 3, 4]);
+        assert!(Image::from_pixel_slice(4, 2, &pixels).is_err());
+
+        // raylib owns the data, so it can reallocate it
+        image.resize(6, 4);
+        assert_eq!(image.as_bytes().len(), 6 * 4 * 4);
+        image.gen_mipmaps();
+        assert_eq!(image.as_bytes().len(), (24 + 6 + 1) * 4);
+
+        let gray = Image::from_pixels(
+            2,
+            2,
+            PixelFormat::PIXELFORMAT_UNCOMPRESSED_GRAYSCALE,
+            &[0, 64, 128, 255],
+        )
+        .unwrap();
+        assert_eq!(gray.pixels::<Gray8>().unwrap()[3], Gray8(255));
+    }
+}
@@ -0,0 +1,108 @@
+#[cfg(test)]
+mod richtext_test {
+    use crate::tests::*;
+    use raylib::prelude::*;
+
+    const BASE: RichStyle = RichStyle {
+        color: Color::BLACK,
+        size: 10.0,
+        bold: false,
+        italic: false,
+        effect: TextEffect::None,
+    };
+
+    #[test]
+    fn test_rich_text_parse() {
+        let text = RichText::parse(
+            "Hi [color=red]big [size=20][b]world[/size][/color] [icon=coin]!",
+            BASE,
+        );
+        let red = RichStyle {
+            color: Color::RED,
+            ..BASE
+        };
+        assert_eq!(
+            text.spans(),
+            &[
+                RichSpan::Text {
+                    text: "Hi ".to_owned(),
+                    style: BASE,
+                },
+                RichSpan::Text {
+                    text: "big ".to_owned(),
+                    style: red,
+                },
+                RichSpan::Text {
+                    text: "world".to_owned(),
+                    style: RichStyle {
+                        size: 20.0,
+                        bold: true,
+                        ..red
+                    },
+                },
+                RichSpan::Text {
+                    text: " ".to_owned(),
+                    style: BASE,
+                },
+                RichSpan::Icon {
+                    name: "coin".to_owned(),
+                    style: BASE,
+                },
+                RichSpan::Text {
+                    text: "!".to_owned(),
+                    style: BASE,
+                },
+            ]
+        );
+        assert_eq!(text.plain_text(), "Hi big world !");
+    }
+
+    #[test]
+    fn test_rich_text_literal_tags() {
+        let text = RichText::parse("[[b] [x] [/i] [size=big] [wave=2]~[/wave]", BASE);
+        assert_eq!(text.plain_text(), "[b] [x] [/i] [size=big] ~");
+        assert_eq!(
+            text.spans()[1],
+            RichSpan::Text {
+                text: "~".to_owned(),
+                style: RichStyle {
+                    effect: TextEffect::Wave { amplitude: 2.0 },
+                    ..BASE
+                },
+            }
+        );
+        let hex = RichText::parse("[color=#10203040]a", BASE);
+        match &hex.spans()[0] {
+            RichSpan::Text { style, .. } => assert_eq!(style.color, Color::new(16, 32, 48, 64)),
+            span => panic!("unexpected span {:?}", span),
+        }
+    }
+
+    ray_draw_test!(test_draw_text_rich);
+    fn test_draw_text_rich(d: &mut RaylibDrawHandle, assets: &TestAssets) {
+        d.clear_background(Color::WHITE);
+        let mut fonts = FontSet::new(&assets.font);
+        fonts.size = 20.0;
+        fonts.spacing = 2.0;
+
+        // "aaa b" fits on a line, "aaa bbb" doesn't
+        let width = assets.font.measure_text("aaa b", 20.0, 2.0).x;
+        let bounds = Rectangle::new(10.0, 10.0, width, 200.0);
+        let layout = RichText::parse("aaa [color=red]bbb[/color]", fonts.base_style())
+            .layout(&fonts, bounds);
+        assert_eq!(layout.lines, 2);
+        assert_eq!(layout.glyphs.len(), 6);
+        let b = layout.glyphs[3];
+        assert_eq!(b.kind, RichGlyphKind::Char('b'));
+        assert_eq!(b.position.x, bounds.x);
+        assert!(b.position.y >= bounds.y + 20.0);
+        assert_eq!(b.style.color, Color::RED);
+
+        fonts.align = TextAlign::Center;
+        d.draw_text_rich(
+            &fonts,
+            "[wave]Rich[/wave] [b]text[/b]\n[shake=2][color=#e03030]shaky[/color][/shake] [size=40]big[/size]",
+            Rectangle::new(100.0, 100.0, 300.0, 200.0),
+        );
+    }
+}
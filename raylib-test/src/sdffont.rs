@@ -0,0 +1,58 @@
+#[cfg(test)]
+mod sdffont_test {
+    use crate::tests::*;
+    use raylib::prelude::*;
+
+    ray_test!(test_font_load_sdf);
+    fn test_font_load_sdf(thread: &RaylibThread) {
+        let mut handle = TEST_HANDLE.write().unwrap();
+        let rl = handle.as_mut().unwrap();
+        let font = rl
+            .load_font_sdf(thread, "resources/pixeloid.ttf", 48, None)
+            .expect("couldn't load sdf font");
+        assert_eq!(font.base_size(), 48);
+        assert_eq!(font.chars().len(), 95);
+        assert!(font.shader().is_shader_valid());
+        assert!(font.measure_text("Hello", 96.0, 0.0).x > font.measure_text("Hello", 48.0, 0.0).x);
+
+        let font = rl
+            .load_font_sdf(thread, "resources/pixeloid.ttf", 32, Some("abcabc"))
+            .expect("couldn't load sdf font");
+        assert_eq!(font.chars().len(), 3);
+
+        assert!(rl
+            .load_font_sdf(thread, "resources/missing.ttf", 32, None)
+            .is_err());
+    }
+
+    ray_test!(test_draw_text_sdf);
+    fn test_draw_text_sdf(thread: &RaylibThread) {
+        let mut handle = TEST_HANDLE.write().unwrap();
+        let rl = handle.as_mut().unwrap();
+        let font = rl
+            .load_font_sdf(thread, "resources/pixeloid.ttf", 48, None)
+            .expect("couldn't load sdf font");
+        let style = SdfTextStyle {
+            outline_width: 2.0,
+            outline_color: Color::WHITE,
+            shadow: Some(SdfShadow {
+                offset: Vector2::new(4.0, 4.0),
+                color: Color::BLACK,
+                blur: 3.0,
+            }),
+            ..SdfTextStyle::default()
+        };
+        let mut d = rl.begin_drawing(thread);
+        d.clear_background(Color::GRAY);
+        // NULs are skipped instead of panicking
+        d.draw_text_sdf(
+            &font,
+            "Sha\0dow",
+            Vector2::new(10.0, 10.0),
+            64.0,
+            1.0,
+            Color::RED,
+            &style,
+        );
+    }
+}
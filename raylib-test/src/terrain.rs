@@ -0,0 +1,235 @@
+#[cfg(test)]
+mod terrain_test {
+    use crate::tests::*;
+    use raylib::prelude::*;
+
+    /// Rolling hills with a sample every unit, 64 by 32 units and 10 high.
+    fn hills() -> Terrain {
+        let heights = Heightmap::from_fn(65, 33, |x, z| {
+            0.5 + 0.25 * (x as f32 * 0.3).sin() + 0.25 * (z as f32 * 0.2).cos()
+        });
+        Terrain::new(heights, Vector3::new(64.0, 10.0, 32.0)).unwrap()
+    }
+
+    #[test]
+    fn test_terrain_chunks_and_lod() {
+        let terrain = hills().with_chunk_cells(16).with_lod(3, 20.0);
+        assert_eq!(terrain.chunks().len(), 8);
+        let last = &terrain.chunks()[7];
+        assert_eq!((last.column(), last.row()), (3, 1));
+        assert_eq!(last.bounds().max.x, 64.0);
+        // the lowest chunk reaches a skirt below the lowest point
+        let lowest = terrain
+            .chunks()
+            .iter()
+            .map(|c| c.bounds().min.y)
+            .fold(f32::MAX, f32::min);
+        assert!((lowest - (terrain.bounds().min.y - terrain.skirt_depth())).abs() < 1e-5);
+
+        // 16x16 quads and a 16 quad skirt along each edge
+        let full = terrain.chunk_mesh(5, 0).unwrap();
+        assert_eq!(full.triangle_count(), 16 * 16 * 2 + 4 * 16 * 2);
+        let coarse = terrain.chunk_mesh(5, 2).unwrap();
+        assert_eq!(coarse.triangle_count(), 4 * 4 * 2 + 4 * 4 * 2);
+        assert!(terrain.chunk_mesh(5, 3).is_none());
+        assert!(terrain.chunk_mesh(8, 0).is_none());
+
+        for mesh in &[full, coarse] {
+            for triangle in mesh.vertices.chunks(3) {
+                let normal = (triangle[1] - triangle[0]).cross(triangle[2] - triangle[0]);
+                let center = (triangle[0] + triangle[1] + triangle[2]) / 3.0;
+                let chunk = terrain.chunks()[5].bounds();
+                let middle = (chunk.min + chunk.max) / 2.0;
+                if normal.y.abs() > 1e-6 {
+                    // the surface faces up and lies on the heights
+                    assert!(normal.y > 0.0);
+                    for v in triangle {
+                        assert!((terrain.height_at(v.x, v.z).unwrap() - v.y).abs() < 1e-4);
+                    }
+                } else {
+                    // skirts face out of the chunk
+                    let out = Vector3::new(center.x - middle.x, 0.0, center.z - middle.z);
+                    assert!(normal.dot(out) > 0.0);
+                }
+            }
+        }
+
+        // uneven chunk sizes end on the last sample
+        let uneven = hills().with_chunk_cells(20);
+        assert_eq!(uneven.chunks().len(), 4 * 2);
+        let edge = uneven.chunk_mesh(3, 0).unwrap();
+        assert_eq!(edge.triangle_count(), 4 * 20 * 2 + (4 + 20) * 2 * 2);
+
+        assert_eq!(terrain.lod_for_distance(0.0), 0);
+        assert_eq!(terrain.lod_for_distance(19.0), 0);
+        assert_eq!(terrain.lod_for_distance(25.0), 1);
+        assert_eq!(terrain.lod_for_distance(45.0), 2);
+        assert_eq!(terrain.lod_for_distance(5000.0), 2);
+    }
+
+    #[test]
+    fn test_terrain_queries() {
+        // a ramp rising half a unit per unit along x, offset from the origin
+        let ramp = Heightmap::from_fn(9, 9, |x, _| x as f32 / 8.0);
+        let terrain = Terrain::new(ramp, Vector3::new(8.0, 4.0, 8.0))
+            .unwrap()
+            .with_position(Vector3::new(-4.0, 1.0, 0.0));
+        assert_eq!(terrain.height_at(0.0, 3.0), Some(3.0));
+        assert!((terrain.height_at(-0.7, 7.9).unwrap() - 2.65).abs() < 1e-5);
+        assert_eq!(terrain.height_at(4.5, 3.0), None);
+        let normal = terrain.normal_at(1.0, 1.0).unwrap();
+        let expected = Vector3::new(-0.5, 1.0, 0.0).normalized();
+        assert!((normal - expected).length() < 1e-5);
+
+        let down = Ray::new(Vector3::new(-0.7, 10.0, 5.1), Vector3::new(0.0, -1.0, 0.0));
+        let hit = terrain.get_ray_collision(down);
+        assert!(hit.hit);
+        assert!((hit.point.y - 2.65).abs() < 1e-4);
+        assert!((hit.distance - 7.35).abs() < 1e-4);
+        assert!((hit.normal - expected).length() < 1e-5);
+
+        let up = Ray::new(Vector3::new(-0.7, 10.0, 5.1), Vector3::new(0.0, 1.0, 0.0));
+        assert!(!terrain.get_ray_collision(up).hit);
+        let beside = Ray::new(Vector3::new(9.0, 2.0, 5.0), Vector3::new(0.0, -1.0, 0.0));
+        assert!(!terrain.get_ray_collision(beside).hit);
+
+        // hits on bumpy ground land on the surface, from any direction and side
+        let terrain = hills();
+        for &(from, to) in &[
+            (
+                Vector3::new(-10.0, 30.0, -5.0),
+                Vector3::new(40.0, 0.0, 20.0),
+            ),
+            (Vector3::new(70.0, 12.0, 40.0), Vector3::new(10.0, 0.0, 3.0)),
+            (
+                Vector3::new(31.5, 1.0, 15.5),
+                Vector3::new(33.0, 12.0, 16.0),
+            ),
+            (
+                Vector3::new(20.0, 25.0, 10.0),
+                Vector3::new(20.0, -5.0, 10.0),
+            ),
+        ] {
+            let hit = terrain.get_ray_collision(Ray::new(from, to - from));
+            assert!(hit.hit, "{:?} to {:?} missed", from, to);
+            let ground = terrain.height_at(hit.point.x, hit.point.z).unwrap();
+            assert!((ground - hit.point.y).abs() < 1e-3);
+            assert!((hit.distance - from.distance_to(hit.point)).abs() < 1e-3);
+        }
+    }
+
+    #[test]
+    fn test_terrain_culling() {
+        let camera = Camera3D::perspective(
+            Vector3::new(0.0, 10.0, 10.0),
+            Vector3::zero(),
+            Vector3::up(),
+            45.0,
+        );
+        let frustum = Frustum::from_camera(&camera, 16.0 / 9.0);
+        assert!(frustum.contains_point(Vector3::zero()));
+        assert!(!frustum.contains_point(Vector3::new(0.0, 20.0, 30.0)));
+        assert!(!frustum.contains_point(Vector3::new(40.0, 0.0, 0.0)));
+        let near = BoundingBox::new(Vector3::new(-1.0, -1.0, -1.0), Vector3::one());
+        assert!(frustum.intersects_box(&near));
+        let behind = BoundingBox::new(Vector3::new(-1.0, 0.0, 20.0), Vector3::new(1.0, 2.0, 22.0));
+        assert!(!frustum.intersects_box(&behind));
+
+        // looking at one corner of the terrain leaves the rest out
+        let terrain = hills().with_chunk_cells(8).with_lod(2, 10.0);
+        let corner = Camera3D::perspective(
+            Vector3::new(-6.0, 12.0, -6.0),
+            Vector3::zero(),
+            Vector3::up(),
+            30.0,
+        );
+        let visible = terrain.visible_chunks(&Frustum::from_camera(&corner, 1.0), corner.position);
+        assert!(visible.contains(&(0, 0)));
+        assert!(visible.len() < terrain.chunks().len() / 2);
+        let overview = Camera3D::perspective(
+            Vector3::new(32.0, 200.0, 16.0),
+            Vector3::new(32.0, 0.0, 16.001),
+            Vector3::up(),
+            60.0,
+        );
+        let visible =
+            terrain.visible_chunks(&Frustum::from_camera(&overview, 1.0), overview.position);
+        assert_eq!(visible.len(), terrain.chunks().len());
+        assert!(visible.iter().all(|&(_, lod)| lod == 1));
+    }
+
+    #[test]
+    fn test_terrain_from_image_and_splat_map() {
+        let pixels: Vec<Color> = (0..16)
+            .map(|i| {
+                let v = (i % 4) as u8 * 85;
+                Color::new(v, v, v, 255)
+            })
+            .collect();
+        let image = Image::from_pixel_slice(4, 4, &pixels).unwrap();
+        let terrain = Terrain::from_image(&image, Vector3::new(3.0, 6.0, 3.0)).unwrap();
+        assert_eq!(terrain.height_at(3.0, 1.0), Some(6.0));
+        assert_eq!(terrain.height_at(1.0, 2.0), Some(2.0));
+        assert!(
+            Terrain::from_image(&Image::gen_image_color(1, 4, Color::WHITE), Vector3::one())
+                .is_err()
+        );
+
+        let splat = terrain
+            .splat_map(6, 2, |height, normal| {
+                assert!(normal.y > 0.0);
+                [1.0 - height, height, 0.0, 0.0]
+            })
+            .unwrap();
+        let splat = splat.pixels::<Rgba8>().unwrap();
+        assert_eq!(splat[0], Color::new(234, 21, 0, 0));
+        assert_eq!(splat[11], Color::new(21, 234, 0, 0));
+    }
+
+    ray_test!(test_terrain_draw);
+    fn test_terrain_draw(thread: &RaylibThread) {
+        let mut handle = TEST_HANDLE.write().unwrap();
+        let rl = handle.as_mut().unwrap();
+        let mut terrain = hills().with_chunk_cells(16);
+        terrain.load_meshes(thread).unwrap();
+        for (index, chunk) in terrain.chunks().iter().enumerate() {
+            assert_eq!(chunk.meshes().len(), terrain.lod_levels());
+            let coarsest = terrain.chunk_mesh(index, 3).unwrap();
+            assert_eq!(
+                chunk.meshes()[3].vertexCount as usize,
+                coarsest.vertices.len()
+            );
+        }
+
+        let splat = terrain
+            .splat_map(64, 32, |h, _| [1.0 - h, h, 0.0, 0.0])
+            .unwrap();
+        let splat = rl.load_texture_from_image(thread, &splat).unwrap();
+        let layers: Vec<Texture2D> = [Color::GREEN, Color::BROWN, Color::GRAY, Color::WHITE]
+            .iter()
+            .map(|&c| {
+                let image = Image::gen_image_color(8, 8, c);
+                rl.load_texture_from_image(thread, &image).unwrap()
+            })
+            .collect();
+        let mut material = rl
+            .load_terrain_splat_material(
+                thread,
+                &splat,
+                [&layers[0], &layers[1], &layers[2], &layers[3]],
+                8.0,
+            )
+            .expect("could not load splat material");
+        material.set_tiling(4.0);
+
+        let camera = Camera3D::perspective(
+            Vector3::new(32.0, 30.0, 60.0),
+            Vector3::new(32.0, 0.0, 16.0),
+            Vector3::up(),
+            45.0,
+        );
+        let mut d = rl.begin_drawing(thread);
+        let mut world = d.begin_mode3D(camera);
+        assert!(terrain.draw(&mut world, &camera, 640.0 / 480.0, &material) > 0);
+    }
+}
@@ -20,6 +20,30 @@ mod text_test {
             .expect("couldn't load font");
     }
 
+    ray_test!(test_font_load_non_ascii_chars);
+    fn test_font_load_non_ascii_chars(thread: &RaylibThread) {
+        // 4 codepoints in 9 bytes, the glyph count has to follow the codepoints
+        const CHARS: &str = "aé€ж";
+        let mut handle = TEST_HANDLE.write().unwrap();
+        let rl = handle.as_mut().unwrap();
+        let f = rl
+            .load_font_ex(thread, "resources/pixeloid.ttf", 32, Some(CHARS))
+            .expect("couldn't load font");
+        assert_eq!(f.chars().len(), 4);
+
+        let data = std::fs::read("resources/pixeloid.ttf").expect("couldn't read font");
+        let f = rl
+            .load_font_from_memory(thread, ".ttf", &data, 32, Some(CHARS))
+            .expect("couldn't load font from memory");
+        assert_eq!(f.chars().len(), 4);
+
+        let glyphs = rl
+            .load_font_data(&data, 32, Some(CHARS), 0)
+            .expect("couldn't load font data");
+        assert_eq!(glyphs.len(), 4);
+        assert_eq!(glyphs[3].value, 'ж' as i32);
+    }
+
     ray_test!(test_font_export);
     fn test_font_export(thread: &RaylibThread) {
         let mut handle = TEST_HANDLE.write().unwrap();
@@ -0,0 +1,153 @@
+#[cfg(test)]
+mod textlayout_test {
+    use crate::tests::*;
+    use raylib::prelude::*;
+
+    /// A font with 10 pixel wide glyphs for ASCII and the ellipsis, without any GPU resources.
+    struct MonoFont {
+        font: ffi::Font,
+        _glyphs: Vec<ffi::GlyphInfo>,
+    }
+
+    impl AsRef<ffi::Font> for MonoFont {
+        fn as_ref(&self) -> &ffi::Font {
+            &self.font
+        }
+    }
+
+    impl AsMut<ffi::Font> for MonoFont {
+        fn as_mut(&mut self) -> &mut ffi::Font {
+            &mut self.font
+        }
+    }
+
+    impl RaylibFont for MonoFont {}
+
+    fn mono_font() -> MonoFont {
+        let mut glyphs: Vec<ffi::GlyphInfo> = (' '..='~')
+            .chain(std::iter::once('\u{2026}'))
+            .map(|c| ffi::GlyphInfo {
+                value: c as i32,
+                offsetX: 0,
+                offsetY: 0,
+                advanceX: 10,
+                image: unsafe { std::mem::zeroed() },
+            })
+            .collect();
+        let font = ffi::Font {
+            baseSize: 10,
+            glyphCount: glyphs.len() as i32,
+            glyphPadding: 0,
+            texture: unsafe { std::mem::zeroed() },
+            recs: std::ptr::null_mut(),
+            glyphs: glyphs.as_mut_ptr(),
+        };
+        MonoFont {
+            font,
+            _glyphs: glyphs,
+        }
+    }
+
+    fn line_text<'a>(layout: &'a TextLayout, line: usize) -> &'a str {
+        &layout.text()[layout.lines()[line].text.clone()]
+    }
+
+    #[test]
+    fn test_text_layout_wrap_and_align() {
+        let font = mono_font();
+        let mut layout = TextLayout::new(&font, "hello world foo\nbar", 10.0, 0.0, Some(110.0));
+        layout.set_line_spacing(2.0);
+        assert_eq!(layout.lines().len(), 3);
+        assert_eq!(line_text(&layout, 0), "hello world ");
+        assert_eq!(line_text(&layout, 1), "foo");
+        assert_eq!(line_text(&layout, 2), "bar");
+        assert_eq!(layout.lines()[0].width, 110.0);
+        assert!(!layout.lines()[0].ends_paragraph);
+        assert!(layout.lines()[1].ends_paragraph);
+        assert_eq!(layout.size(), Vector2::new(110.0, 34.0));
+
+        layout.set_align(TextAlign::Center);
+        assert_eq!(layout.lines()[1].position, Vector2::new(40.0, 12.0));
+        layout.set_align(TextAlign::Right);
+        assert_eq!(
+            layout.glyphs()[layout.lines()[2].glyphs.start].position.x,
+            80.0
+        );
+    }
+
+    #[test]
+    fn test_text_layout_justify_and_ellipsis() {
+        let font = mono_font();
+        let mut layout = TextLayout::new(&font, "aa bb cc", 10.0, 0.0, Some(70.0));
+        layout.set_align(TextAlign::Justify);
+        assert_eq!(line_text(&layout, 0), "aa bb ");
+        // the single inner space takes the 20 spare pixels
+        assert_eq!(layout.glyphs()[3].position.x, 50.0);
+        assert_eq!(layout.lines()[1].position.x, 0.0);
+
+        let mut layout = TextLayout::new(&font, "aaaa bbbb cccc", 10.0, 0.0, Some(40.0));
+        layout.set_max_lines(Some(1));
+        assert!(layout.is_truncated());
+        let text: String = layout.glyphs().iter().map(|g| g.codepoint).collect();
+        assert_eq!(text, "aaa\u{2026}");
+        assert_eq!(layout.glyphs()[3].offset, 3);
+    }
+
+    #[test]
+    fn test_text_layout_breaks() {
+        let font = mono_font();
+        let layout = TextLayout::new(&font, "well-known (ok)!", 10.0, 0.0, Some(60.0));
+        assert_eq!(line_text(&layout, 0), "well-");
+        assert_eq!(line_text(&layout, 1), "known ");
+        assert_eq!(line_text(&layout, 2), "(ok)!");
+
+        // missing glyphs measure like '?', and CJK text breaks between characters
+        let layout = TextLayout::new(&font, "日本語のテキスト", 10.0, 0.0, Some(30.0));
+        assert_eq!(line_text(&layout, 0), "日本語");
+        assert_eq!(line_text(&layout, 1), "のテキ");
+        assert_eq!(line_text(&layout, 2), "スト");
+        // but a line can't start with closing punctuation
+        let layout = TextLayout::new(&font, "日本語。", 10.0, 0.0, Some(30.0));
+        assert_eq!(line_text(&layout, 0), "日本");
+        assert_eq!(line_text(&layout, 1), "語。");
+    }
+
+    #[test]
+    fn test_text_layout_carets() {
+        let font = mono_font();
+        let mut layout = TextLayout::new(&font, "hello world foo\nbar", 10.0, 0.0, Some(110.0));
+        layout.set_line_spacing(2.0);
+        assert_eq!(layout.point_from_caret(0), Vector2::zero());
+        assert_eq!(layout.point_from_caret(12), Vector2::new(0.0, 12.0));
+        assert_eq!(layout.point_from_caret(15), Vector2::new(30.0, 12.0));
+        assert_eq!(layout.point_from_caret(19), Vector2::new(30.0, 24.0));
+        assert_eq!(layout.line_of_caret(17), 2);
+
+        assert_eq!(layout.caret_from_point(Vector2::new(14.0, 13.0)), 13);
+        assert_eq!(layout.caret_from_point(Vector2::new(16.0, 13.0)), 14);
+        assert_eq!(layout.caret_from_point(Vector2::new(500.0, 13.0)), 15);
+        // past the end of a wrapped line stays before its trailing space
+        assert_eq!(layout.caret_from_point(Vector2::new(500.0, 0.0)), 11);
+        assert_eq!(layout.caret_from_point(Vector2::new(-5.0, 500.0)), 16);
+    }
+
+    ray_draw_test!(test_draw_text_layout);
+    fn test_draw_text_layout(d: &mut RaylibDrawHandle, assets: &TestAssets) {
+        d.clear_background(Color::WHITE);
+        let mut layout = TextLayout::new(
+            &assets.font,
+            "The quick brown fox jumps over the lazy dog, twice over.",
+            20.0,
+            2.0,
+            Some(200.0),
+        );
+        layout.set_align(TextAlign::Justify).set_max_lines(Some(3));
+        d.draw_rectangle_lines(20, 20, 200, layout.size().y as i32, Color::GRAY);
+        d.draw_text_layout(
+            &assets.font,
+            &layout,
+            Vector2::new(20.0, 20.0),
+            Color::BLACK,
+        );
+    }
+}
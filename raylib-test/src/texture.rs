@@ -36,6 +36,161 @@ mod texture_test {
             .expect("render texture created");
     }
 
+    ray_test!(test_render_texture_builder);
+    fn test_render_texture_builder(thread: &RaylibThread) {
+        const GBUFFER: &str = "#version 330
+in vec2 fragTexCoord;
+layout(location = 0) out vec4 albedo;
+layout(location = 1) out vec4 position;
+void main() {
+    albedo = vec4(0.0, 1.0, 0.0, 1.0);
+    position = vec4(fragTexCoord, 4.0, 1.0);
+}";
+        let mut handle = TEST_HANDLE.write().unwrap();
+        let rl = handle.as_mut().unwrap();
+        let mut gbuffer = RenderTextureBuilder::new(64, 32)
+            .color_attachments(&[
+                PixelFormat::PIXELFORMAT_UNCOMPRESSED_R8G8B8A8,
+                PixelFormat::PIXELFORMAT_UNCOMPRESSED_R32G32B32A32,
+            ])
+            .depth(DepthAttachment::Texture)
+            .build(thread)
+            .expect("g-buffer created");
+        assert_eq!(gbuffer.color_count(), 2);
+        assert_eq!((gbuffer.width(), gbuffer.height()), (64, 32));
+        assert!(gbuffer.depth_texture().is_some());
+        assert!(!gbuffer.is_multisampled());
+
+        let mut shader = rl.load_shader_from_memory(thread, None, Some(GBUFFER));
+        {
+            let mut d = rl.begin_drawing(thread);
+            let mut t = d.begin_texture_mode(thread, &mut gbuffer);
+            let mut s = t.begin_shader_mode(&mut shader);
+            s.draw_rectangle(0, 0, 64, 32, Color::WHITE);
+        }
+        let mut albedo = gbuffer.color_texture(0).unwrap().load_image().unwrap();
+        assert_eq!(albedo.get_color(10, 10), Color::GREEN);
+        let position = gbuffer.color_texture(1).unwrap().load_image().unwrap();
+        assert_eq!(
+            position.format(),
+            PixelFormat::PIXELFORMAT_UNCOMPRESSED_R32G32B32A32
+        );
+
+        let shadow = RenderTextureBuilder::new(128, 128)
+            .no_color()
+            .depth(DepthAttachment::Texture)
+            .build(thread)
+            .expect("shadow map created");
+        assert_eq!(shadow.color_count(), 0);
+        assert_eq!(shadow.depth_texture().unwrap().width(), 128);
+
+        assert!(RenderTextureBuilder::new(0, 8).build(thread).is_err());
+        assert!(RenderTextureBuilder::new(8, 8)
+            .no_color()
+            .build(thread)
+            .is_err());
+        assert!(RenderTextureBuilder::new(8, 8)
+            .color_format(PixelFormat::PIXELFORMAT_COMPRESSED_DXT1_RGB)
+            .build(thread)
+            .is_err());
+        assert!(RenderTextureBuilder::new(8, 8)
+            .depth(DepthAttachment::Texture)
+            .stencil()
+            .build(thread)
+            .is_err());
+    }
+
+    ray_test!(test_render_texture_msaa);
+    fn test_render_texture_msaa(thread: &RaylibThread) {
+        let mut handle = TEST_HANDLE.write().unwrap();
+        let rl = handle.as_mut().unwrap();
+        let mut target = RenderTextureBuilder::new(32, 32)
+            .color_format(PixelFormat::PIXELFORMAT_UNCOMPRESSED_R16G16B16A16)
+            .stencil()
+            .msaa(4)
+            .build(thread)
+            .expect("multisampled render texture created");
+        assert!(target.is_multisampled());
+        assert!(target.has_stencil());
+        assert_eq!(target.samples(), 4);
+        {
+            let mut d = rl.begin_drawing(thread);
+            let mut t = d.begin_texture_mode(thread, &mut target);
+            t.clear_background(Color::BLUE);
+        }
+        target.resolve(thread);
+        let mut image = target.color_texture(0).unwrap().load_image().unwrap();
+        image.set_format(PixelFormat::PIXELFORMAT_UNCOMPRESSED_R8G8B8A8);
+        assert_eq!(image.get_color(16, 16), Color::BLUE);
+    }
+
+    ray_test!(test_golden_shapes);
+    fn test_golden_shapes(thread: &RaylibThread) {
+        use crate::golden::*;
+        let mut handle = TEST_HANDLE.write().unwrap();
+        let rl = handle.as_mut().unwrap();
+        let scene = |d: &mut RaylibTextureMode<RaylibHandle>| {
+            d.clear_background(Color::RAYWHITE);
+            d.draw_rectangle(8, 8, 24, 16, Color::RED);
+            d.draw_rectangle(40, 24, 16, 16, Color::BLUE);
+        };
+        assert_golden(rl, thread, "shapes", (64, 48), Tolerance::default(), scene);
+
+        // a wrong render fails and leaves the artifacts behind, unless it would become the
+        // new golden image
+        if std::env::var_os(UPDATE_GOLDENS_VAR).is_some() {
+            return;
+        }
+        // the artifacts get their own name so they aren't mistaken for a real failure
+        let expected = Image::load_image(&format!("{}/shapes.png", GOLDEN_DIR)).unwrap();
+        let wrong = render(rl, thread, 64, 48, |d| d.clear_background(Color::RAYWHITE));
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            check_image("shapes_negative", &expected, &wrong, Tolerance::EXACT)
+        }));
+        assert!(result.is_err());
+        assert!(std::path::Path::new("test_out/golden/shapes_negative.diff.png").exists());
+        assert!(std::path::Path::new("test_out/golden/shapes_negative.actual.png").exists());
+    }
+
+    ray_test!(test_post_process_stack);
+    fn test_post_process_stack(thread: &RaylibThread) {
+        const INVERT: &str = "#version 330
+in vec2 fragTexCoord;
+uniform sampler2D texture0;
+uniform vec2 resolution;
+out vec4 finalColor;
+void main() {
+    vec4 c = texture(texture0, fragTexCoord);
+    finalColor = vec4(1.0 - c.rgb, c.a);
+}";
+        let mut handle = TEST_HANDLE.write().unwrap();
+        let rl = handle.as_mut().unwrap();
+        let mut stack = PostProcessStack::new(rl, thread, 64, 64).expect("stack created");
+        let invert = rl.load_shader_from_memory(thread, None, Some(INVERT));
+        stack.add_pass("invert", invert);
+        let invert = rl.load_shader_from_memory(thread, None, Some(INVERT));
+        stack.add_pass("invert_again", invert).set_enabled(false);
+
+        let mut target = rl.load_render_texture(thread, 64, 64).unwrap();
+        {
+            let mut d = rl.begin_drawing(thread);
+            {
+                let mut s = d.begin_texture_mode(thread, stack.scene_mut());
+                s.clear_background(Color::WHITE);
+            }
+            stack.apply(&mut d, thread);
+            stack.present_to(&mut d, thread, &mut target);
+        }
+        let mut img = target.load_image().unwrap();
+        assert_eq!(img.get_color(32, 32), Color::BLACK);
+
+        assert!(stack.pass_mut("invert_again").unwrap().toggle());
+        stack.resize(rl, thread, 32, 32).unwrap();
+        assert_eq!((stack.width(), stack.height()), (32, 32));
+        assert!(stack.remove_pass("invert").is_some());
+        assert_eq!(stack.passes().len(), 1);
+    }
+
     #[test]
     fn test_image_manipulations() {
         // Just checking that nothing segfaults. Not ensuring they work as expected.
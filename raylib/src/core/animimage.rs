@@ -0,0 +1,852 @@
+//! Animated images with per-frame delays, decoded from and encoded to GIF
+//!
+//! [`Image::load_image_anim`] stacks the frames of a GIF into one tall image and keeps
+//! nothing but their count. [`AnimatedImage`] keeps every frame with how long it's shown and
+//! how many times the animation plays, and can write the frames back out as a GIF with a
+//! palette chosen per frame. Decoding and encoding are done in Rust, so neither needs a
+//! window.
+//!
+//! GIF stores delays in hundredths of a second, so delays round to 10 milliseconds when
+//! saved.
+//!
+//! ```no_run
+//! use raylib::prelude::*;
+//!
+//! let (mut rl, thread) = raylib::init().size(800, 450).build();
+//! let anim = AnimatedImage::load("scarfy.gif").unwrap();
+//! let mut texture = rl
+//!     .load_texture_from_image(&thread, &anim.frame_image(0).unwrap())
+//!     .unwrap();
+//! let mut shown = 0;
+//! while !rl.window_should_close() {
+//!     let frame = anim.frame_at((rl.get_time() * 1000.0) as u32);
+//!     if frame != shown {
+//!         texture.update_texture(anim.frame_bytes(frame).unwrap()).unwrap();
+//!         shown = frame;
+//!     }
+//!     let mut d = rl.begin_drawing(&thread);
+//!     d.draw_texture(&texture, 0, 0, Color::WHITE);
+//! }
+//! ```
+use crate::core::color::Color;
+use crate::core::imageops::{color_counts, median_cut, nearest};
+use crate::core::pixels::{ImageView, ImageViewMut, Rgba8};
+use crate::core::texture::Image;
+use crate::error::{error, Error};
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Largest number of codes a GIF LZW table holds.
+const LZW_TABLE_SIZE: usize = 4096;
+
+/// Most pixels [`AnimatedImage::load_from_memory`] decodes, counting every frame: 256 MiB of
+/// RGBA. A GIF's header can ask for a 65535 x 65535 canvas and each frame is a full copy of it,
+/// so a small file could otherwise take all the memory there is.
+pub const ANIMATED_IMAGE_MAX_PIXELS: usize = 1 << 26;
+
+/// Settings for [`AnimatedImage::to_gif`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GifOptions {
+    /// Colors per frame, between 2 and 256. Frames with transparent pixels use one of them
+    /// for transparency.
+    pub max_colors: usize,
+    /// Spreads the error of frames with more colors than `max_colors` over their neighbours
+    /// (Floyd-Steinberg) instead of mapping each pixel to the nearest palette color.
+    pub dither: bool,
+}
+
+impl Default for GifOptions {
+    fn default() -> Self {
+        GifOptions {
+            max_colors: 256,
+            dither: false,
+        }
+    }
+}
+
+/// One frame of an [`AnimatedImage`].
+#[derive(Debug, Clone, Copy)]
+pub struct AnimationFrame<'a> {
+    pub index: usize,
+    pub pixels: ImageView<'a, Rgba8>,
+    /// How long the frame is shown, in milliseconds.
+    pub delay: u32,
+}
+
+/// Frames of the same size, each shown for its own delay.
+///
+/// Frames are stored as RGBA8 one below the other, the same layout
+/// [`Image::load_image_anim`] uses.
+#[derive(Debug, Clone)]
+pub struct AnimatedImage {
+    width: usize,
+    height: usize,
+    pixels: Vec<Color>,
+    delays: Vec<u32>,
+    loop_count: u16,
+}
+
+impl AnimatedImage {
+    /// An animation of `width` x `height` frames with no frames yet, looping forever.
+    pub fn new(width: usize, height: usize) -> AnimatedImage {
+        AnimatedImage {
+            width,
+            height,
+            pixels: Vec::new(),
+            delays: Vec::new(),
+            loop_count: 0,
+        }
+    }
+
+    /// An animation of `frames`, each shown for `delay` milliseconds. The frames must all
+    /// have the size of the first.
+    pub fn from_frames(frames: &[Image], delay: u32) -> Result<AnimatedImage, Error> {
+        let first = frames
+            .first()
+            .ok_or(error!("An animation needs at least one frame."))?;
+        let mut anim = AnimatedImage::new(
+            first.width().max(0) as usize,
+            first.height().max(0) as usize,
+        );
+        for frame in frames {
+            anim.push_frame(frame, delay)?;
+        }
+        Ok(anim)
+    }
+
+    /// Splits `image` into `frame_count` frames stacked vertically, as returned by
+    /// [`Image::load_image_anim`], each shown for `delay` milliseconds.
+    pub fn from_image_stack(
+        image: &Image,
+        frame_count: usize,
+        delay: u32,
+    ) -> Result<AnimatedImage, Error> {
+        let height = image.height().max(0) as usize;
+        if frame_count == 0 || !height.is_multiple_of(frame_count) {
+            return Err(error!("Image height is not a multiple of the frame count."));
+        }
+        let mut anim = AnimatedImage::new(image.width().max(0) as usize, height / frame_count);
+        anim.pixels = image.rgba().into_owned();
+        anim.delays = vec![delay; frame_count];
+        Ok(anim)
+    }
+
+    /// Reads and decodes the GIF at `filename`.
+    pub fn load(filename: impl AsRef<Path>) -> Result<AnimatedImage, Error> {
+        let filename = filename.as_ref();
+        let data = std::fs::read(filename).map_err(|_| {
+            error!(
+                "Error loading animated image. Check if the file exists",
+                filename
+            )
+        })?;
+        AnimatedImage::load_from_memory(&data)
+    }
+
+    /// Decodes a GIF held in memory. Fails if the frames have more than
+    /// [`ANIMATED_IMAGE_MAX_PIXELS`] pixels in all.
+    pub fn load_from_memory(data: &[u8]) -> Result<AnimatedImage, Error> {
+        decode_gif(data, ANIMATED_IMAGE_MAX_PIXELS)
+    }
+
+    /// Like [`AnimatedImage::load_from_memory`], with a limit of `max_pixels` pixels for all
+    /// frames together instead of [`ANIMATED_IMAGE_MAX_PIXELS`].
+    pub fn load_from_memory_with_max_pixels(
+        data: &[u8],
+        max_pixels: usize,
+    ) -> Result<AnimatedImage, Error> {
+        decode_gif(data, max_pixels)
+    }
+
+    /// Encodes the animation as a GIF and writes it to `filename`.
+    pub fn save(&self, filename: impl AsRef<Path>, options: &GifOptions) -> Result<(), Error> {
+        let filename = filename.as_ref();
+        let data = self.to_gif(options)?;
+        std::fs::write(filename, data).map_err(|_| error!("Error saving animated image.", filename))
+    }
+
+    /// Encodes the animation as a GIF.
+    pub fn to_gif(&self, options: &GifOptions) -> Result<Vec<u8>, Error> {
+        encode_gif(self, options)
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn frame_count(&self) -> usize {
+        self.delays.len()
+    }
+
+    /// How many times the animation plays, 0 for forever.
+    pub fn loop_count(&self) -> u16 {
+        self.loop_count
+    }
+
+    pub fn set_loop_count(&mut self, loop_count: u16) {
+        self.loop_count = loop_count;
+    }
+
+    /// How long each frame is shown, in milliseconds.
+    pub fn delays(&self) -> &[u32] {
+        &self.delays
+    }
+
+    pub fn delay(&self, index: usize) -> Option<u32> {
+        self.delays.get(index).copied()
+    }
+
+    pub fn set_delay(&mut self, index: usize, delay: u32) {
+        if let Some(d) = self.delays.get_mut(index) {
+            *d = delay;
+        }
+    }
+
+    /// How long one play through the frames takes, in milliseconds.
+    pub fn duration(&self) -> u64 {
+        self.delays.iter().map(|&d| d as u64).sum()
+    }
+
+    /// Adds `image` as the last frame, shown for `delay` milliseconds.
+    pub fn push_frame(&mut self, image: &Image, delay: u32) -> Result<(), Error> {
+        if image.width() as usize != self.width || image.height() as usize != self.height {
+            return Err(error!("Frame size doesn't match the animation."));
+        }
+        self.pixels.extend_from_slice(&image.rgba());
+        self.delays.push(delay);
+        Ok(())
+    }
+
+    /// Removes every frame after the first `count`.
+    pub fn truncate(&mut self, count: usize) {
+        self.delays.truncate(count);
+        self.pixels.truncate(count * self.frame_len());
+    }
+
+    fn frame_len(&self) -> usize {
+        self.width * self.height
+    }
+
+    fn frame_pixels(&self, index: usize) -> Option<&[Color]> {
+        if index >= self.frame_count() {
+            return None;
+        }
+        let len = self.frame_len();
+        Some(&self.pixels[index * len..(index + 1) * len])
+    }
+
+    /// The pixels of frame `index`.
+    pub fn frame(&self, index: usize) -> Option<ImageView<'_, Rgba8>> {
+        let pixels = self.frame_pixels(index)?;
+        ImageView::new(pixels, self.width, self.height, self.width).ok()
+    }
+
+    /// The pixels of frame `index`, to change them.
+    pub fn frame_mut(&mut self, index: usize) -> Option<ImageViewMut<'_, Rgba8>> {
+        if index >= self.frame_count() {
+            return None;
+        }
+        let (width, height, len) = (self.width, self.height, self.frame_len());
+        let pixels = &mut self.pixels[index * len..(index + 1) * len];
+        ImageViewMut::new(pixels, width, height, width).ok()
+    }
+
+    /// The RGBA8 bytes of frame `index`, ready for
+    /// [`update_texture`](crate::core::texture::RaylibTexture2D::update_texture).
+    pub fn frame_bytes(&self, index: usize) -> Option<&[u8]> {
+        let pixels = self.frame_pixels(index)?;
+        // `Color` is four bytes with no padding
+        Some(unsafe {
+            std::slice::from_raw_parts(pixels.as_ptr() as *const u8, std::mem::size_of_val(pixels))
+        })
+    }
+
+    /// Copies frame `index` into a new image.
+    pub fn frame_image(&self, index: usize) -> Result<Image, Error> {
+        let pixels = self
+            .frame_pixels(index)
+            .ok_or(error!("Frame index out of range."))?;
+        Image::from_pixel_slice(self.width as i32, self.height as i32, pixels)
+    }
+
+    /// Every frame in order.
+    pub fn frames(&self) -> impl Iterator<Item = AnimationFrame<'_>> {
+        (0..self.frame_count()).filter_map(move |index| {
+            Some(AnimationFrame {
+                index,
+                pixels: self.frame(index)?,
+                delay: self.delays[index],
+            })
+        })
+    }
+
+    /// The frame shown `time` milliseconds after the animation started. Once the last loop
+    /// has played, that's the last frame.
+    pub fn frame_at(&self, time: u32) -> usize {
+        let duration = self.duration();
+        if duration == 0 {
+            return 0;
+        }
+        let time = time as u64;
+        if self.loop_count != 0 && time >= duration * self.loop_count as u64 {
+            return self.frame_count() - 1;
+        }
+        let mut time = time % duration;
+        for (index, &delay) in self.delays.iter().enumerate() {
+            if time < delay as u64 {
+                return index;
+            }
+            time -= delay as u64;
+        }
+        self.frame_count() - 1
+    }
+
+    /// All frames stacked vertically in one image, as [`Image::load_image_anim`] returns
+    /// them.
+    pub fn to_image(&self) -> Result<Image, Error> {
+        Image::from_pixel_slice(
+            self.width as i32,
+            (self.height * self.frame_count()) as i32,
+            &self.pixels,
+        )
+    }
+}
+
+/// What a graphic control extension says about the frame after it.
+#[derive(Debug, Clone, Copy, Default)]
+struct GraphicsControl {
+    disposal: u8,
+    delay: u32,
+    transparent: Option<u8>,
+}
+
+struct GifReader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> GifReader<'a> {
+    fn bytes(&mut self, len: usize) -> Result<&'a [u8], Error> {
+        let bytes = self
+            .data
+            .get(self.pos..self.pos + len)
+            .ok_or(error!("GIF data ends unexpectedly."))?;
+        self.pos += len;
+        Ok(bytes)
+    }
+
+    fn u8(&mut self) -> Result<u8, Error> {
+        Ok(self.bytes(1)?[0])
+    }
+
+    fn u16(&mut self) -> Result<u16, Error> {
+        let bytes = self.bytes(2)?;
+        Ok(u16::from_le_bytes([bytes[0], bytes[1]]))
+    }
+
+    /// A color table of `2^(size + 1)` entries.
+    fn palette(&mut self, size: u8) -> Result<Vec<Color>, Error> {
+        let bytes = self.bytes(3 << (size + 1))?;
+        Ok(bytes
+            .chunks(3)
+            .map(|c| Color::new(c[0], c[1], c[2], 255))
+            .collect())
+    }
+
+    /// Data sub-blocks up to the empty one, joined.
+    fn sub_blocks(&mut self) -> Result<Vec<u8>, Error> {
+        let mut data = Vec::new();
+        loop {
+            let len = self.u8()? as usize;
+            if len == 0 {
+                return Ok(data);
+            }
+            data.extend_from_slice(self.bytes(len)?);
+        }
+    }
+}
+
+/// Row of the `n`th row stored in an interlaced image of `height` rows.
+fn interlaced_row(mut n: usize, height: usize) -> usize {
+    for &(start, step) in &[(0, 8), (4, 8), (2, 4), (1, 2)] {
+        let rows = if height > start {
+            (height - start).div_ceil(step)
+        } else {
+            0
+        };
+        if n < rows {
+            return start + n * step;
+        }
+        n -= rows;
+    }
+    height
+}
+
+fn decode_gif(data: &[u8], max_pixels: usize) -> Result<AnimatedImage, Error> {
+    if !data.starts_with(b"GIF87a") && !data.starts_with(b"GIF89a") {
+        return Err(error!("Data is not a GIF."));
+    }
+    let mut reader = GifReader { data, pos: 6 };
+    let width = reader.u16()? as usize;
+    let height = reader.u16()? as usize;
+    let flags = reader.u8()?;
+    // background color and pixel aspect ratio, which players ignore
+    reader.bytes(2)?;
+    let global = if flags & 0x80 != 0 {
+        Some(reader.palette(flags & 7)?)
+    } else {
+        None
+    };
+    if width == 0 || height == 0 {
+        return Err(error!("GIF has no pixels."));
+    }
+    let too_large = || error!("GIF is larger than the pixel limit.");
+    // checked before allocating anything the size of the canvas
+    if width * height > max_pixels {
+        return Err(too_large());
+    }
+
+    let mut anim = AnimatedImage::new(width, height);
+    // without a looping extension the animation plays once
+    anim.loop_count = 1;
+    let mut canvas = vec![Color::BLANK; width * height];
+    let mut control = GraphicsControl::default();
+    // a missing trailer after the last frame is common enough to accept
+    while reader.pos < data.len() {
+        match reader.u8()? {
+            0x21 => {
+                let label = reader.u8()?;
+                let block = reader.sub_blocks()?;
+                match label {
+                    0xf9 if block.len() >= 4 => {
+                        control = GraphicsControl {
+                            disposal: (block[0] >> 2) & 7,
+                            delay: u16::from_le_bytes([block[1], block[2]]) as u32 * 10,
+                            transparent: if block[0] & 1 != 0 {
+                                Some(block[3])
+                            } else {
+                                None
+                            },
+                        };
+                    }
+                    0xff if block.len() >= 14
+                        && (block.starts_with(b"NETSCAPE2.0")
+                            || block.starts_with(b"ANIMEXTS1.0"))
+                        && block[11] == 1 =>
+                    {
+                        // the extension counts repeats after the first play
+                        anim.loop_count = match u16::from_le_bytes([block[12], block[13]]) {
+                            0 => 0,
+                            repeats => repeats.saturating_add(1),
+                        };
+                    }
+                    _ => {}
+                }
+            }
+            0x2c => {
+                let left = reader.u16()? as usize;
+                let top = reader.u16()? as usize;
+                let frame_width = reader.u16()? as usize;
+                let frame_height = reader.u16()? as usize;
+                let flags = reader.u8()?;
+                let local = if flags & 0x80 != 0 {
+                    Some(reader.palette(flags & 7)?)
+                } else {
+                    None
+                };
+                if frame_width * frame_height > max_pixels
+                    || anim.pixels.len() + width * height > max_pixels
+                {
+                    return Err(too_large());
+                }
+                let min_code_size = reader.u8()?;
+                let lzw = reader.sub_blocks()?;
+                let palette = local
+                    .as_ref()
+                    .or(global.as_ref())
+                    .ok_or(error!("GIF frame has no color table."))?;
+                let indices = lzw_decode(&lzw, min_code_size, frame_width * frame_height)?;
+
+                let previous = if control.disposal == 3 {
+                    Some(canvas.clone())
+                } else {
+                    None
+                };
+                for (i, &index) in indices.iter().enumerate() {
+                    let row = i / frame_width;
+                    let row = if flags & 0x40 != 0 {
+                        interlaced_row(row, frame_height)
+                    } else {
+                        row
+                    };
+                    let (x, y) = (left + i % frame_width, top + row);
+                    if x >= width || y >= height || Some(index) == control.transparent {
+                        continue;
+                    }
+                    if let Some(&color) = palette.get(index as usize) {
+                        canvas[y * width + x] = color;
+                    }
+                }
+                anim.pixels.extend_from_slice(&canvas);
+                anim.delays.push(control.delay);
+
+                match (control.disposal, previous) {
+                    (2, _) => {
+                        for y in top..(top + frame_height).min(height) {
+                            for x in left..(left + frame_width).min(width) {
+                                canvas[y * width + x] = Color::BLANK;
+                            }
+                        }
+                    }
+                    (3, Some(previous)) => canvas = previous,
+                    _ => {}
+                }
+                control = GraphicsControl::default();
+            }
+            0x3b => break,
+            _ => return Err(error!("GIF data is corrupted.")),
+        }
+    }
+    if anim.delays.is_empty() {
+        return Err(error!("GIF has no frames."));
+    }
+    Ok(anim)
+}
+
+/// Decompresses GIF LZW data into at most `len` color indices.
+fn lzw_decode(data: &[u8], min_code_size: u8, len: usize) -> Result<Vec<u8>, Error> {
+    if !(1..=8).contains(&min_code_size) {
+        return Err(error!("GIF data is corrupted."));
+    }
+    let clear = 1usize << min_code_size;
+    let end = clear + 1;
+    let mut prefix = vec![0u16; LZW_TABLE_SIZE];
+    let mut suffix = vec![0u8; LZW_TABLE_SIZE];
+    let mut lengths = vec![0u16; LZW_TABLE_SIZE];
+    for code in 0..clear {
+        suffix[code] = code as u8;
+        lengths[code] = 1;
+    }
+
+    // writes the string for `code` and returns its first index
+    let write = |code: usize, out: &mut Vec<u8>, prefix: &[u16], suffix: &[u8], len: u16| {
+        let start = out.len();
+        out.resize(start + len as usize, 0);
+        let mut code = code;
+        for i in (0..len as usize).rev() {
+            out[start + i] = suffix[code];
+            code = prefix[code] as usize;
+        }
+        out[start]
+    };
+
+    let mut out = Vec::with_capacity(len);
+    let mut size = min_code_size as u32 + 1;
+    let mut next = end + 1;
+    let mut previous: Option<usize> = None;
+    let (mut bits, mut count) = (0u32, 0u32);
+    let mut bytes = data.iter();
+    while out.len() < len {
+        while count < size {
+            match bytes.next() {
+                Some(&byte) => {
+                    bits |= (byte as u32) << count;
+                    count += 8;
+                }
+                None => return Ok(out),
+            }
+        }
+        let code = (bits & ((1 << size) - 1)) as usize;
+        bits >>= size;
+        count -= size;
+
+        if code == clear {
+            size = min_code_size as u32 + 1;
+            next = end + 1;
+            previous = None;
+            continue;
+        }
+        if code == end {
+            break;
+        }
+        let previous_code = match previous {
+            Some(previous_code) => previous_code,
+            None if code < clear => {
+                out.push(code as u8);
+                previous = Some(code);
+                continue;
+            }
+            None => return Err(error!("GIF data is corrupted.")),
+        };
+        let first = if code < next {
+            write(code, &mut out, &prefix, &suffix, lengths[code])
+        } else if code == next {
+            let first = write(
+                previous_code,
+                &mut out,
+                &prefix,
+                &suffix,
+                lengths[previous_code],
+            );
+            out.push(first);
+            first
+        } else {
+            return Err(error!("GIF data is corrupted."));
+        };
+        if next < LZW_TABLE_SIZE {
+            prefix[next] = previous_code as u16;
+            suffix[next] = first;
+            lengths[next] = lengths[previous_code] + 1;
+            next += 1;
+            if next == 1 << size && size < 12 {
+                size += 1;
+            }
+        }
+        previous = Some(code);
+    }
+    out.truncate(len);
+    Ok(out)
+}
+
+/// Packs variable-width codes least significant bit first.
+struct BitWriter {
+    out: Vec<u8>,
+    bits: u32,
+    count: u32,
+}
+
+impl BitWriter {
+    fn write(&mut self, code: usize, size: u32) {
+        self.bits |= (code as u32) << self.count;
+        self.count += size;
+        while self.count >= 8 {
+            self.out.push(self.bits as u8);
+            self.bits >>= 8;
+            self.count -= 8;
+        }
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        if self.count > 0 {
+            self.out.push(self.bits as u8);
+        }
+        self.out
+    }
+}
+
+/// Compresses color indices below `2^min_code_size` with GIF LZW.
+fn lzw_encode(indices: &[u8], min_code_size: u8) -> Vec<u8> {
+    let clear = 1usize << min_code_size;
+    let end = clear + 1;
+    let mut table: HashMap<(usize, u8), usize> = HashMap::new();
+    let mut writer = BitWriter {
+        out: Vec::new(),
+        bits: 0,
+        count: 0,
+    };
+    let mut size = min_code_size as u32 + 1;
+    let mut next = end + 1;
+    writer.write(clear, size);
+
+    let mut current: Option<usize> = None;
+    for &index in indices {
+        let code = match current {
+            None => {
+                current = Some(index as usize);
+                continue;
+            }
+            Some(code) => code,
+        };
+        if let Some(&longer) = table.get(&(code, index)) {
+            current = Some(longer);
+            continue;
+        }
+        writer.write(code, size);
+        table.insert((code, index), next);
+        next += 1;
+        // the decoder adds each code one step later, so it widens when reading the next one
+        if next > 1 << size && size < 12 {
+            size += 1;
+        }
+        if next == LZW_TABLE_SIZE {
+            writer.write(clear, size);
+            table.clear();
+            size = min_code_size as u32 + 1;
+            next = end + 1;
+        }
+        current = Some(index as usize);
+    }
+    if let Some(code) = current {
+        writer.write(code, size);
+        // the decoder adds one last code on reading that one
+        if next == 1 << size && size < 12 {
+            size += 1;
+        }
+    }
+    writer.write(end, size);
+    writer.finish()
+}
+
+/// A frame's palette, its pixels as palette indices and the index used for transparency.
+fn index_frame(
+    pixels: &[Color],
+    width: usize,
+    options: &GifOptions,
+) -> (Vec<Color>, Vec<u8>, Option<u8>) {
+    let max_colors = options.max_colors.clamp(2, 256);
+    let has_transparency = pixels.iter().any(|c| c.a < 128);
+    let slots = if has_transparency {
+        max_colors - 1
+    } else {
+        max_colors
+    };
+    let opaque: Vec<Color> = pixels
+        .iter()
+        .filter(|c| c.a >= 128)
+        .map(|&c| Color::new(c.r, c.g, c.b, 255))
+        .collect();
+    let counts = color_counts(&opaque);
+    let exact = counts.len() <= slots;
+    let palette: Vec<Color> = if exact {
+        counts.into_iter().map(|(c, _)| c).collect()
+    } else {
+        median_cut(counts, slots)
+    };
+    let transparent = if has_transparency {
+        Some(palette.len() as u8)
+    } else {
+        None
+    };
+
+    let mut indices = Vec::with_capacity(pixels.len());
+    if options.dither && !exact {
+        let mut errors = vec![[0f32; 3]; pixels.len() + width + 1];
+        for (i, &c) in pixels.iter().enumerate() {
+            if c.a < 128 {
+                indices.push(transparent.unwrap_or(0));
+                continue;
+            }
+            let error = errors[i];
+            let wanted = [
+                c.r as f32 + error[0],
+                c.g as f32 + error[1],
+                c.b as f32 + error[2],
+            ];
+            let clamped = |v: f32| v.round().clamp(0.0, 255.0) as u8;
+            let index = nearest(
+                &palette,
+                Color::new(
+                    clamped(wanted[0]),
+                    clamped(wanted[1]),
+                    clamped(wanted[2]),
+                    255,
+                ),
+            );
+            indices.push(index as u8);
+            let chosen = palette[index];
+            let diff = [
+                wanted[0] - chosen.r as f32,
+                wanted[1] - chosen.g as f32,
+                wanted[2] - chosen.b as f32,
+            ];
+            let x = i % width;
+            let mut spread = |j: usize, weight: f32| {
+                for (e, d) in errors[j].iter_mut().zip(diff.iter()) {
+                    *e += d * weight;
+                }
+            };
+            if x + 1 < width {
+                spread(i + 1, 7.0 / 16.0);
+                spread(i + width + 1, 1.0 / 16.0);
+            }
+            if x > 0 {
+                spread(i + width - 1, 3.0 / 16.0);
+            }
+            spread(i + width, 5.0 / 16.0);
+        }
+    } else {
+        let mut cache: HashMap<[u8; 3], u8> = HashMap::new();
+        for &c in pixels {
+            if c.a < 128 {
+                indices.push(transparent.unwrap_or(0));
+                continue;
+            }
+            let index = *cache
+                .entry([c.r, c.g, c.b])
+                .or_insert_with(|| nearest(&palette, Color::new(c.r, c.g, c.b, 255)) as u8);
+            indices.push(index);
+        }
+    }
+    (palette, indices, transparent)
+}
+
+fn encode_gif(anim: &AnimatedImage, options: &GifOptions) -> Result<Vec<u8>, Error> {
+    if anim.frame_count() == 0 {
+        return Err(error!("An animation needs at least one frame."));
+    }
+    if anim.width == 0
+        || anim.height == 0
+        || anim.width > u16::MAX as usize
+        || anim.height > u16::MAX as usize
+    {
+        return Err(error!("Animation size doesn't fit in a GIF."));
+    }
+    let (width, height) = (anim.width as u16, anim.height as u16);
+
+    let mut out = b"GIF89a".to_vec();
+    out.extend_from_slice(&width.to_le_bytes());
+    out.extend_from_slice(&height.to_le_bytes());
+    // no global color table, every frame has its own
+    out.extend_from_slice(&[0, 0, 0]);
+    if anim.loop_count != 1 {
+        let repeats = anim.loop_count.saturating_sub(1);
+        out.extend_from_slice(b"\x21\xff\x0bNETSCAPE2.0\x03\x01");
+        out.extend_from_slice(&repeats.to_le_bytes());
+        out.push(0);
+    }
+
+    // frames cover the whole canvas, but transparent pixels would show the frame before
+    let disposal = if anim.pixels.iter().any(|c| c.a < 128) {
+        2
+    } else {
+        1
+    };
+    for (index, &delay) in anim.delays.iter().enumerate() {
+        let pixels = anim.frame_pixels(index).unwrap_or(&[]);
+        let (palette, indices, transparent) = index_frame(pixels, anim.width, options);
+
+        let centiseconds = ((delay as u64 + 5) / 10).min(u16::MAX as u64) as u16;
+        out.extend_from_slice(&[0x21, 0xf9, 4, disposal << 2 | transparent.is_some() as u8]);
+        out.extend_from_slice(&centiseconds.to_le_bytes());
+        out.extend_from_slice(&[transparent.unwrap_or(0), 0]);
+
+        let entries = palette.len() + transparent.is_some() as usize;
+        let mut bits = 1;
+        while 1 << bits < entries {
+            bits += 1;
+        }
+        out.push(0x2c);
+        out.extend_from_slice(&[0, 0, 0, 0]);
+        out.extend_from_slice(&width.to_le_bytes());
+        out.extend_from_slice(&height.to_le_bytes());
+        out.push(0x80 | (bits - 1) as u8);
+        for i in 0..1 << bits {
+            let c = palette.get(i).copied().unwrap_or(Color::BLACK);
+            out.extend_from_slice(&[c.r, c.g, c.b]);
+        }
+
+        let min_code_size = bits.max(2) as u8;
+        out.push(min_code_size);
+        for block in lzw_encode(&indices, min_code_size).chunks(255) {
+            out.push(block.len() as u8);
+            out.extend_from_slice(block);
+        }
+        out.push(0);
+    }
+    out.push(0x3b);
+    Ok(out)
+}
@@ -0,0 +1,514 @@
+//! Decoding assets on worker threads, with GPU uploads spread over frames
+//!
+//! Loading through [`RaylibHandle`] decodes files on the main thread, so a big texture or
+//! model stalls the frame it's loaded in. [`AssetLoader`] decodes images, waves and models
+//! on a pool of threads and hands back an [`AssetHandle`] right away. Assets that live in
+//! CPU memory are ready as soon as they're decoded. Textures and models also need the GPU,
+//! which only the main thread can use, so they're uploaded by [`AssetLoader::pump`], a few
+//! at a time within a budget each frame.
+//!
+//! Files are read with raylib's loaders, so custom file callbacks set with
+//! `SetLoadFileDataCallback` must be safe to call from any thread. Models are limited to
+//! Wavefront OBJ files, which are parsed in Rust after raylib reads them.
+//!
+//! ```no_run
+//! use raylib::prelude::*;
+//! use std::time::Duration;
+//!
+//! let (mut rl, thread) = raylib::init().size(800, 450).build();
+//! let mut loader = AssetLoader::new(4);
+//! let level = loader.load_texture("level.png");
+//! let ship = loader.load_model("ship.obj");
+//! let mut texture = None;
+//! while !rl.window_should_close() {
+//!     loader.pump(&mut rl, &thread, Duration::from_millis(4));
+//!     if let Some(loaded) = level.try_take() {
+//!         texture = Some(loaded.unwrap());
+//!     }
+//!     let mut d = rl.begin_drawing(&thread);
+//!     d.clear_background(Color::BLACK);
+//!     match &texture {
+//!         Some(texture) => d.draw_texture(texture, 0, 0, Color::WHITE),
+//!         None => d.draw_text("Loading...", 10, 10, 20, Color::WHITE),
+//!     }
+//! }
+//! ```
+use crate::core::audio::WaveData;
+use crate::core::file::load_file_data;
+use crate::core::math::{Vector2, Vector3};
+use crate::core::models::{Mesh, MeshBuilder, Model};
+use crate::core::texture::{Image, Texture2D};
+use crate::core::{RaylibHandle, RaylibThread};
+use crate::error::{error, Error};
+use std::any::Any;
+use std::collections::{HashMap, VecDeque};
+use std::future::Future;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::path::Path;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Waker};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+/// Work for a worker thread. The flag is set when the loader is being dropped and the job
+/// should give up instead of decoding.
+type Job = Box<dyn FnOnce(bool) + Send>;
+
+/// A decoded asset on its way back to the main thread.
+type Decoded = Result<Box<dyn Any + Send>, Error>;
+
+/// Finishes an asset on the main thread, given the handle to upload with, or `None` if the
+/// loader is being dropped.
+type Finisher = Box<dyn FnOnce(Decoded, Option<(&mut RaylibHandle, &RaylibThread)>)>;
+
+enum Slot<T> {
+    Pending(Option<Waker>),
+    Ready(Result<T, Error>),
+    Taken,
+}
+
+/// An asset that is still loading, or has loaded.
+///
+/// Check on it each frame with [`try_take`](AssetHandle::try_take), or `.await` it. Handles
+/// for textures and models only finish while [`AssetLoader::pump`] is being called.
+pub struct AssetHandle<T> {
+    slot: Arc<Mutex<Slot<T>>>,
+}
+
+impl<T> AssetHandle<T> {
+    fn new() -> Self {
+        AssetHandle {
+            slot: Arc::new(Mutex::new(Slot::Pending(None))),
+        }
+    }
+
+    fn complete(&self, result: Result<T, Error>) {
+        let mut slot = self.slot.lock().unwrap_or_else(|e| e.into_inner());
+        if let Slot::Pending(Some(waker)) = std::mem::replace(&mut *slot, Slot::Ready(result)) {
+            waker.wake();
+        }
+    }
+
+    /// Whether the asset has finished loading, successfully or not.
+    pub fn is_ready(&self) -> bool {
+        matches!(
+            *self.slot.lock().unwrap_or_else(|e| e.into_inner()),
+            Slot::Ready(_)
+        )
+    }
+
+    /// Takes the asset if it has finished loading. Returns `None` while it's loading, and
+    /// after it has been taken.
+    pub fn try_take(&self) -> Option<Result<T, Error>> {
+        let mut slot = self.slot.lock().unwrap_or_else(|e| e.into_inner());
+        match std::mem::replace(&mut *slot, Slot::Taken) {
+            Slot::Ready(result) => Some(result),
+            other => {
+                *slot = other;
+                None
+            }
+        }
+    }
+}
+
+impl<T> Clone for AssetHandle<T> {
+    fn clone(&self) -> Self {
+        AssetHandle {
+            slot: Arc::clone(&self.slot),
+        }
+    }
+}
+
+impl<T> std::fmt::Debug for AssetHandle<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AssetHandle")
+            .field("ready", &self.is_ready())
+            .finish()
+    }
+}
+
+impl<T> Future for AssetHandle<T> {
+    type Output = Result<T, Error>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut slot = self.slot.lock().unwrap_or_else(|e| e.into_inner());
+        match std::mem::replace(&mut *slot, Slot::Taken) {
+            Slot::Ready(result) => Poll::Ready(result),
+            Slot::Pending(_) => {
+                *slot = Slot::Pending(Some(cx.waker().clone()));
+                Poll::Pending
+            }
+            Slot::Taken => Poll::Ready(Err(error!("Asset was already taken."))),
+        }
+    }
+}
+
+/// Triangles decoded from a model file, one vertex per corner, ready to upload as a mesh.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MeshData {
+    pub vertices: Vec<Vector3>,
+    pub normals: Vec<Vector3>,
+    pub texcoords: Vec<Vector2>,
+}
+
+impl MeshData {
+    /// Parses a Wavefront OBJ file. Faces with more than three corners are split into fans,
+    /// and faces without normals get flat ones. Materials and groups are ignored, so the
+    /// whole file becomes one mesh.
+    pub fn from_obj(source: &str) -> Result<MeshData, Error> {
+        let mut positions = Vec::new();
+        let mut texcoords = Vec::new();
+        let mut normals = Vec::new();
+        let mut data = MeshData::default();
+
+        for line in source.lines() {
+            let mut parts = line.split_whitespace();
+            match parts.next() {
+                Some("v") => match obj_numbers(parts)?[..] {
+                    [x, y, z, ..] => positions.push(Vector3::new(x, y, z)),
+                    _ => return Err(error!("OBJ vertex needs three coordinates.")),
+                },
+                Some("vt") => match obj_numbers(parts)?[..] {
+                    // OBJ puts v = 0 at the bottom, raylib at the top
+                    [u, v, ..] => texcoords.push(Vector2::new(u, 1.0 - v)),
+                    [u] => texcoords.push(Vector2::new(u, 1.0)),
+                    _ => return Err(error!("OBJ texture coordinate needs a value.")),
+                },
+                Some("vn") => match obj_numbers(parts)?[..] {
+                    [x, y, z, ..] => normals.push(Vector3::new(x, y, z)),
+                    _ => return Err(error!("OBJ normal needs three coordinates.")),
+                },
+                Some("f") => {
+                    let corners = parts
+                        .map(|corner| {
+                            let mut indices = corner.split('/');
+                            let position = obj_index(indices.next(), positions.len())?
+                                .ok_or(error!("OBJ face corner needs a vertex."))?;
+                            let texcoord = obj_index(indices.next(), texcoords.len())?;
+                            let normal = obj_index(indices.next(), normals.len())?;
+                            Ok((position, texcoord, normal))
+                        })
+                        .collect::<Result<Vec<_>, Error>>()?;
+                    if corners.len() < 3 {
+                        return Err(error!("OBJ face needs at least three corners."));
+                    }
+                    for i in 1..corners.len() - 1 {
+                        let triangle = [corners[0], corners[i], corners[i + 1]];
+                        let [a, b, c] = triangle.map(|(p, _, _)| positions[p]);
+                        let flat = (b - a).cross(c - a).normalized();
+                        for &(p, t, n) in &triangle {
+                            data.vertices.push(positions[p]);
+                            data.texcoords
+                                .push(t.map_or(Vector2::new(0.0, 0.0), |t| texcoords[t]));
+                            data.normals.push(n.map_or(flat, |n| normals[n]));
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+        if data.vertices.is_empty() {
+            return Err(error!("OBJ data has no faces."));
+        }
+        Ok(data)
+    }
+
+    pub fn triangle_count(&self) -> usize {
+        self.vertices.len() / 3
+    }
+
+    /// Uploads the triangles as the only mesh of a new model with the default material.
+    pub fn load_model(&self, rl: &mut RaylibHandle, thread: &RaylibThread) -> Result<Model, Error> {
+        let mesh = self.load_mesh(thread)?;
+        unsafe { rl.load_model_from_mesh(thread, mesh.make_weak()) }
+    }
+
+    /// Uploads the triangles as a mesh.
+    pub fn load_mesh(&self, thread: &RaylibThread) -> Result<Mesh, Error> {
+        if self.vertices.is_empty()
+            || !self.vertices.len().is_multiple_of(3)
+            || self.normals.len() != self.vertices.len()
+            || self.texcoords.len() != self.vertices.len()
+        {
+            return Err(error!(
+                "Mesh data needs whole triangles with a normal and texture coordinate per vertex."
+            ));
+        }
+        MeshBuilder::new(&self.vertices)
+            .normals(&self.normals)
+            .texcoords(&self.texcoords)
+            .build(thread)
+    }
+}
+
+fn obj_numbers(parts: std::str::SplitWhitespace<'_>) -> Result<Vec<f32>, Error> {
+    parts
+        .map(|p| p.parse::<f32>())
+        .collect::<Result<_, _>>()
+        .map_err(|_| error!("Invalid number in OBJ data."))
+}
+
+/// Index into a list of `len` items from one part of an OBJ face corner. Indices count from
+/// 1, and negative ones count back from the end.
+fn obj_index(part: Option<&str>, len: usize) -> Result<Option<usize>, Error> {
+    let part = match part {
+        Some(part) if !part.is_empty() => part,
+        _ => return Ok(None),
+    };
+    let index: i64 = part
+        .parse()
+        .map_err(|_| error!("Invalid index in OBJ data."))?;
+    let index = if index < 0 {
+        len as i64 + index
+    } else {
+        index - 1
+    };
+    if index < 0 || index >= len as i64 {
+        return Err(error!("OBJ face refers to a missing vertex."));
+    }
+    Ok(Some(index as usize))
+}
+
+/// Decodes assets on worker threads and uploads the ones the GPU needs on the main thread.
+///
+/// Images and waves are decoded by raylib, so they can be in any format it reads. Models are
+/// parsed in Rust and must be Wavefront OBJ files, see [`AssetLoader::load_model`].
+///
+/// Dropping the loader skips jobs that haven't started, waits for the ones that have, and
+/// fails their handles.
+pub struct AssetLoader {
+    jobs: Option<Sender<Job>>,
+    workers: Vec<JoinHandle<()>>,
+    cancelled: Arc<AtomicBool>,
+    /// Jobs whose result goes straight to the handle, queued or running.
+    in_flight: Arc<AtomicUsize>,
+    decoded_sender: Sender<(u64, Decoded)>,
+    decoded: Receiver<(u64, Decoded)>,
+    uploads: VecDeque<(u64, Decoded)>,
+    finishers: HashMap<u64, Finisher>,
+    next_id: u64,
+}
+
+impl AssetLoader {
+    /// A loader with `threads` worker threads, at least one.
+    pub fn new(threads: usize) -> AssetLoader {
+        let (jobs, receiver) = channel::<Job>();
+        let receiver = Arc::new(Mutex::new(receiver));
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let workers = (0..threads.max(1))
+            .map(|i| {
+                let receiver = Arc::clone(&receiver);
+                let cancelled = Arc::clone(&cancelled);
+                std::thread::Builder::new()
+                    .name(format!("raylib-assets-{}", i))
+                    .spawn(move || loop {
+                        let job = match receiver.lock() {
+                            Ok(receiver) => receiver.recv(),
+                            Err(_) => return,
+                        };
+                        match job {
+                            Ok(job) => job(cancelled.load(Ordering::Acquire)),
+                            Err(_) => return,
+                        }
+                    })
+                    .expect("failed to spawn asset loader thread")
+            })
+            .collect();
+        let (decoded_sender, decoded) = channel();
+        AssetLoader {
+            jobs: Some(jobs),
+            workers,
+            cancelled,
+            in_flight: Arc::new(AtomicUsize::new(0)),
+            decoded_sender,
+            decoded,
+            uploads: VecDeque::new(),
+            finishers: HashMap::new(),
+            next_id: 0,
+        }
+    }
+
+    fn submit(&self, job: Job) {
+        if let Some(jobs) = &self.jobs {
+            // workers only stop once the sender is gone, so this can't fail
+            let _ = jobs.send(job);
+        }
+    }
+
+    /// Runs `decode` on a worker thread, finishing the handle with its result.
+    pub fn load_with<T, D>(&mut self, decode: D) -> AssetHandle<T>
+    where
+        T: Send + 'static,
+        D: FnOnce() -> Result<T, Error> + Send + 'static,
+    {
+        let handle = AssetHandle::new();
+        let done = handle.clone();
+        let in_flight = Arc::clone(&self.in_flight);
+        in_flight.fetch_add(1, Ordering::AcqRel);
+        self.submit(Box::new(move |cancelled| {
+            let result = if cancelled {
+                Err(error!("Asset loader was dropped."))
+            } else {
+                run_decode(decode)
+            };
+            done.complete(result);
+            in_flight.fetch_sub(1, Ordering::AcqRel);
+        }));
+        handle
+    }
+
+    /// Runs `decode` on a worker thread, then `upload` on the main thread during
+    /// [`pump`](AssetLoader::pump), finishing the handle with the result.
+    pub fn load_with_upload<T, U, D, F>(&mut self, decode: D, upload: F) -> AssetHandle<U>
+    where
+        T: Send + 'static,
+        U: 'static,
+        D: FnOnce() -> Result<T, Error> + Send + 'static,
+        F: FnOnce(&mut RaylibHandle, &RaylibThread, T) -> Result<U, Error> + 'static,
+    {
+        let id = self.next_id;
+        self.next_id += 1;
+        let handle = AssetHandle::new();
+        let done = handle.clone();
+        self.finishers.insert(
+            id,
+            Box::new(
+                move |decoded: Decoded, target: Option<(&mut RaylibHandle, &RaylibThread)>| {
+                    let result = decoded.and_then(|decoded| {
+                        let decoded = *decoded
+                            .downcast::<T>()
+                            .map_err(|_| error!("Decoded asset has the wrong type."))?;
+                        match target {
+                            Some((rl, thread)) => upload(rl, thread, decoded),
+                            None => Err(error!("Asset loader was dropped.")),
+                        }
+                    });
+                    done.complete(result);
+                },
+            ),
+        );
+        let sender = self.decoded_sender.clone();
+        self.submit(Box::new(move |cancelled| {
+            let result = if cancelled {
+                Err(error!("Asset loader was dropped."))
+            } else {
+                run_decode(decode).map(|decoded| Box::new(decoded) as Box<dyn Any + Send>)
+            };
+            let _ = sender.send((id, result));
+        }));
+        handle
+    }
+
+    /// Decodes the image at `filename`.
+    pub fn load_image(&mut self, filename: impl AsRef<Path>) -> AssetHandle<Image> {
+        let filename = filename.as_ref().to_owned();
+        self.load_with(move || Image::load_image(&filename.to_string_lossy()))
+    }
+
+    /// Decodes the audio file at `filename`.
+    ///
+    /// Decoding doesn't need the audio device, so this yields owned [`WaveData`]. Turn it
+    /// into a wave on the main thread with
+    /// [`RaylibAudio::new_wave_from_data`](crate::core::audio::RaylibAudio::new_wave_from_data).
+    pub fn load_wave(&mut self, filename: impl AsRef<Path>) -> AssetHandle<WaveData> {
+        let filename = filename.as_ref().to_owned();
+        self.load_with(move || WaveData::load(&filename.to_string_lossy()))
+    }
+
+    /// Decodes the image at `filename` and uploads it as a texture.
+    pub fn load_texture(&mut self, filename: impl AsRef<Path>) -> AssetHandle<Texture2D> {
+        let filename = filename.as_ref().to_owned();
+        self.load_with_upload(
+            move || Image::load_image(&filename.to_string_lossy()),
+            |rl, thread, image| rl.load_texture_from_image(thread, &image),
+        )
+    }
+
+    /// Decodes the OBJ file at `filename` and uploads it as a model with one mesh and the
+    /// default material. See [`MeshData::from_obj`] for what's read.
+    ///
+    /// Only OBJ files are supported. Load other formats with [`RaylibHandle::load_model`] on
+    /// the main thread.
+    pub fn load_model(&mut self, filename: impl AsRef<Path>) -> AssetHandle<Model> {
+        let filename = filename.as_ref().to_owned();
+        self.load_with_upload(
+            move || {
+                let source = load_file_data(&filename)
+                    .and_then(|data| String::from_utf8(data).ok())
+                    .ok_or_else(|| {
+                        error!(
+                            "Error loading model. Check if the file exists and is an OBJ file",
+                            filename.clone()
+                        )
+                    })?;
+                MeshData::from_obj(&source)
+            },
+            |rl, thread, data| data.load_model(rl, thread),
+        )
+    }
+
+    /// Uploads decoded textures and models until `budget` has passed, and returns how many
+    /// were finished. At least one is finished if any is waiting, so loading can't stall on
+    /// a budget that's too small.
+    pub fn pump(
+        &mut self,
+        rl: &mut RaylibHandle,
+        thread: &RaylibThread,
+        budget: Duration,
+    ) -> usize {
+        self.uploads.extend(self.decoded.try_iter());
+        let start = Instant::now();
+        let mut finished = 0;
+        while let Some((id, decoded)) = self.uploads.pop_front() {
+            if let Some(finish) = self.finishers.remove(&id) {
+                finish(decoded, Some((&mut *rl, thread)));
+                finished += 1;
+            }
+            if start.elapsed() >= budget {
+                break;
+            }
+        }
+        finished
+    }
+
+    /// How many assets haven't finished loading.
+    pub fn pending(&self) -> usize {
+        self.in_flight.load(Ordering::Acquire) + self.finishers.len()
+    }
+
+    /// Whether every asset has finished loading.
+    pub fn is_idle(&self) -> bool {
+        self.pending() == 0
+    }
+}
+
+impl Drop for AssetLoader {
+    fn drop(&mut self) {
+        self.cancelled.store(true, Ordering::Release);
+        self.jobs = None;
+        for worker in self.workers.drain(..) {
+            let _ = worker.join();
+        }
+        for (_, finish) in self.finishers.drain() {
+            finish(Err(error!("Asset loader was dropped.")), None);
+        }
+    }
+}
+
+impl std::fmt::Debug for AssetLoader {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AssetLoader")
+            .field("threads", &self.workers.len())
+            .field("pending", &self.pending())
+            .finish()
+    }
+}
+
+/// Runs `decode`, turning a panic into an error so the handle still finishes.
+fn run_decode<T>(decode: impl FnOnce() -> Result<T, Error>) -> Result<T, Error> {
+    catch_unwind(AssertUnwindSafe(decode))
+        .unwrap_or_else(|_| Err(error!("Asset decoding panicked.")))
+}
@@ -17,6 +17,19 @@ make_thin_wrapper_lifetime!(
     ffi::UnloadAudioStream
 );
 
+/// Wave samples decoded without the audio device.
+///
+/// Unlike [`Wave`], this owns a plain copy of the samples and can be decoded on any thread.
+/// Turn it into a [`Wave`] on the main thread with [`RaylibAudio::new_wave_from_data`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct WaveData {
+    samples: Vec<u8>,
+    frame_count: u32,
+    sample_rate: u32,
+    sample_size: u32,
+    channels: u32,
+}
+
 pub struct WaveSamples(*mut f32, usize);
 
 impl AsRef<[f32]> for WaveSamples {
@@ -120,6 +133,28 @@ impl RaylibAudio {
         Ok(Wave(w, self))
     }
 
+    /// Copies decoded wave data into a wave played by this audio device.
+    pub fn new_wave_from_data<'aud>(&'aud self, data: &WaveData) -> Result<Wave<'aud>, Error> {
+        if data.samples.is_empty() {
+            return Err(error!("Wave data is empty"));
+        }
+        let buffer = unsafe { ffi::MemAlloc(data.samples.len() as u32) } as *mut u8;
+        if buffer.is_null() {
+            return Err(error!("failed to allocate wave samples"));
+        }
+        unsafe { std::ptr::copy_nonoverlapping(data.samples.as_ptr(), buffer, data.samples.len()) };
+        Ok(Wave(
+            ffi::Wave {
+                frameCount: data.frame_count,
+                sampleRate: data.sample_rate,
+                sampleSize: data.sample_size,
+                channels: data.channels,
+                data: buffer as *mut std::os::raw::c_void,
+            },
+            self,
+        ))
+    }
+
     /// Load wave from memory buffer, fileType refers to extension: i.e. '.wav'
     #[inline]
     pub fn new_wave_from_memory<'aud>(
@@ -190,6 +225,44 @@ impl<'aud> Drop for RaylibAudio {
     }
 }
 
+impl WaveData {
+    /// Decodes the wave at `filename` into RAM.
+    pub fn load(filename: &str) -> Result<WaveData, Error> {
+        let c_filename = CString::new(filename).unwrap();
+        let w = unsafe { ffi::LoadWave(c_filename.as_ptr()) };
+        if w.data.is_null() {
+            return Err(error!("Cannot load wave {}", filename));
+        }
+        let len = w.frameCount as usize * w.channels as usize * (w.sampleSize as usize / 8);
+        let samples = unsafe { std::slice::from_raw_parts(w.data as *const u8, len).to_vec() };
+        unsafe { ffi::UnloadWave(w) };
+        Ok(WaveData {
+            samples,
+            frame_count: w.frameCount,
+            sample_rate: w.sampleRate,
+            sample_size: w.sampleSize,
+            channels: w.channels,
+        })
+    }
+
+    /// The raw sample bytes, interleaved by channel.
+    pub fn samples(&self) -> &[u8] {
+        &self.samples
+    }
+    pub fn frame_count(&self) -> u32 {
+        self.frame_count
+    }
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+    pub fn sample_size(&self) -> u32 {
+        self.sample_size
+    }
+    pub fn channels(&self) -> u32 {
+        self.channels
+    }
+}
+
 impl<'aud> Wave<'aud> {
     pub fn frame_count(&self) -> u32 {
         self.0.frameCount
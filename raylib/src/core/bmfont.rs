@@ -0,0 +1,1061 @@
+//! AngelCode BMFont (.fnt) descriptors, as written by BMFont, Hiero and most bitmap font tools
+//!
+//! [`BmFont`] reads and writes the text, XML and binary variants of the format without touching
+//! the GPU. [`RaylibHandle::load_font_bmfont`] turns a descriptor and its pages into a [`Font`],
+//! and [`RaylibFont::export_bmfont`] goes the other way.
+//!
+//! ```no_run
+//! use raylib::prelude::*;
+//!
+//! let (mut rl, thread) = raylib::init().size(800, 450).build();
+//! let font = rl.load_font_bmfont(&thread, "fonts/pixel.fnt").unwrap();
+//! let kerning = BmFont::load("fonts/pixel.fnt").unwrap().kerning('A', 'V');
+//! font.export_bmfont("out/pixel.fnt", BmFontFormat::Xml).unwrap();
+//! ```
+use crate::core::color::Color;
+use crate::core::text::{Font, RaylibFont};
+use crate::core::texture::Image;
+use crate::core::{RaylibHandle, RaylibThread};
+use crate::error::{error, Error};
+use crate::ffi;
+use crate::math::Rectangle;
+use std::borrow::Cow;
+use std::fmt::Write;
+use std::path::Path;
+
+/// The three encodings of a BMFont descriptor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum BmFontFormat {
+    /// Lines of `tag key=value` pairs.
+    #[default]
+    Text,
+    Xml,
+    /// Version 3 of the binary format.
+    Binary,
+}
+
+impl BmFontFormat {
+    /// Guesses the format of a descriptor from its first bytes.
+    pub fn detect(data: &[u8]) -> BmFontFormat {
+        if data.starts_with(b"BMF") {
+            return BmFontFormat::Binary;
+        }
+        let start = data
+            .iter()
+            .position(|b| !b.is_ascii_whitespace())
+            .unwrap_or(data.len());
+        // skip a UTF-8 byte order mark
+        let data = &data[start..];
+        let data = data.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(data);
+        if data.starts_with(b"<") {
+            BmFontFormat::Xml
+        } else {
+            BmFontFormat::Text
+        }
+    }
+}
+
+/// How the font was rendered, the `info` block.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct BmFontInfo {
+    pub face: String,
+    /// Font size in pixels. Tools write it negative when the size matches the cell height.
+    pub size: i32,
+    pub bold: bool,
+    pub italic: bool,
+    /// Name of the OEM charset, empty for unicode fonts.
+    pub charset: String,
+    pub unicode: bool,
+    /// Font height stretch in percent.
+    pub stretch_h: i32,
+    pub smooth: bool,
+    /// Supersampling level, 1 for none.
+    pub aa: i32,
+    /// Padding of each glyph: up, right, down, left.
+    pub padding: [i32; 4],
+    /// Spacing between glyphs in the pages: horizontal, vertical.
+    pub spacing: [i32; 2],
+    pub outline: i32,
+}
+
+/// Metrics shared by every glyph, the `common` block.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct BmFontCommon {
+    /// Distance in pixels between each line of text.
+    pub line_height: i32,
+    /// Distance in pixels from the top of a line to the baseline.
+    pub base: i32,
+    /// Width of the pages.
+    pub scale_w: i32,
+    /// Height of the pages.
+    pub scale_h: i32,
+    /// Whether monochrome glyphs are packed in each channel of the pages.
+    pub packed: bool,
+    pub alpha_chnl: i32,
+    pub red_chnl: i32,
+    pub green_chnl: i32,
+    pub blue_chnl: i32,
+}
+
+/// A glyph and where it is in the pages.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct BmChar {
+    pub id: u32,
+    pub x: i32,
+    pub y: i32,
+    pub width: i32,
+    pub height: i32,
+    pub xoffset: i32,
+    pub yoffset: i32,
+    pub xadvance: i32,
+    pub page: u32,
+    /// Channels of the page holding the glyph, 15 for all of them.
+    pub chnl: i32,
+}
+
+/// Extra horizontal offset between two glyphs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct BmKerning {
+    pub first: u32,
+    pub second: u32,
+    pub amount: i32,
+}
+
+/// A parsed BMFont descriptor.
+///
+/// Fields map one to one to the format, so a descriptor written back keeps everything it was
+/// read with, in any of the three formats.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct BmFont {
+    pub info: BmFontInfo,
+    pub common: BmFontCommon,
+    /// File names of the page images, relative to the descriptor. The index is the page id.
+    pub pages: Vec<String>,
+    pub chars: Vec<BmChar>,
+    pub kernings: Vec<BmKerning>,
+}
+
+impl BmFont {
+    /// Reads and parses the descriptor at `filename`, whatever its format.
+    pub fn load(filename: impl AsRef<Path>) -> Result<BmFont, Error> {
+        let filename = filename.as_ref();
+        let data = std::fs::read(filename).map_err(|_| {
+            error!(
+                "Error loading BMFont. Check if the file exists and if it's the right type",
+                filename,
+            )
+        })?;
+        BmFont::parse(&data)
+    }
+
+    /// Parses a descriptor, detecting its format with [`BmFontFormat::detect`].
+    pub fn parse(data: &[u8]) -> Result<BmFont, Error> {
+        match BmFontFormat::detect(data) {
+            BmFontFormat::Binary => BmFont::parse_binary(data),
+            format => {
+                let text = std::str::from_utf8(data)
+                    .map_err(|_| error!("Error parsing BMFont: the file is not valid UTF-8."))?;
+                match format {
+                    BmFontFormat::Xml => BmFont::parse_xml(text),
+                    _ => BmFont::parse_text(text),
+                }
+            }
+        }
+    }
+
+    /// Parses the text format.
+    pub fn parse_text(text: &str) -> Result<BmFont, Error> {
+        let mut font = BmFont::default();
+        for line in text.lines() {
+            let line = line.trim();
+            let (tag, rest) = line.split_at(line.find(char::is_whitespace).unwrap_or(line.len()));
+            if !tag.is_empty() {
+                font.add_element(tag, &parse_attributes(rest, false))?;
+            }
+        }
+        font.validate()
+    }
+
+    /// Parses the XML format.
+    pub fn parse_xml(text: &str) -> Result<BmFont, Error> {
+        let mut font = BmFont::default();
+        let mut rest = text;
+        while let Some(start) = rest.find('<') {
+            let end = rest[start..]
+                .find('>')
+                .ok_or(error!("Error parsing BMFont: unterminated XML element."))?;
+            let element = &rest[start + 1..start + end];
+            rest = &rest[start + end + 1..];
+            // declarations, comments and closing tags carry nothing
+            if element.starts_with(['?', '!', '/']) {
+                continue;
+            }
+            let element = element.trim_end_matches('/').trim();
+            let (tag, attributes) =
+                element.split_at(element.find(char::is_whitespace).unwrap_or(element.len()));
+            font.add_element(tag, &parse_attributes(attributes, true))?;
+        }
+        font.validate()
+    }
+
+    /// Parses version 3 of the binary format.
+    pub fn parse_binary(data: &[u8]) -> Result<BmFont, Error> {
+        let mut reader = Reader { data, pos: 0 };
+        if reader.bytes(3)? != b"BMF" {
+            return Err(error!("Error parsing BMFont: missing BMF header."));
+        }
+        let version = reader.u8()?;
+        if version != 3 {
+            return Err(error!(Cow::Owned(format!(
+                "Error parsing BMFont: unsupported binary version {}, only version 3 is supported.",
+                version
+            ))));
+        }
+
+        let mut font = BmFont::default();
+        while reader.pos < data.len() {
+            let kind = reader.u8()?;
+            let size = reader.u32()? as usize;
+            let mut block = Reader {
+                data: reader.bytes(size)?,
+                pos: 0,
+            };
+            match kind {
+                1 => {
+                    let info = &mut font.info;
+                    info.size = block.i16()? as i32;
+                    // bit 0 of the format is the most significant one
+                    let flags = block.u8()?;
+                    info.smooth = flags & 0x80 != 0;
+                    info.unicode = flags & 0x40 != 0;
+                    info.italic = flags & 0x20 != 0;
+                    info.bold = flags & 0x10 != 0;
+                    info.charset = match block.u8()? {
+                        0 => String::new(),
+                        charset => charset.to_string(),
+                    };
+                    info.stretch_h = block.u16()? as i32;
+                    info.aa = block.u8()? as i32;
+                    for padding in info.padding.iter_mut() {
+                        *padding = block.u8()? as i32;
+                    }
+                    for spacing in info.spacing.iter_mut() {
+                        *spacing = block.u8()? as i32;
+                    }
+                    info.outline = block.u8()? as i32;
+                    info.face = block.c_str()?;
+                }
+                2 => {
+                    let common = &mut font.common;
+                    common.line_height = block.u16()? as i32;
+                    common.base = block.u16()? as i32;
+                    common.scale_w = block.u16()? as i32;
+                    common.scale_h = block.u16()? as i32;
+                    let pages = block.u16()? as usize;
+                    common.packed = block.u8()? & 0x01 != 0;
+                    common.alpha_chnl = block.u8()? as i32;
+                    common.red_chnl = block.u8()? as i32;
+                    common.green_chnl = block.u8()? as i32;
+                    common.blue_chnl = block.u8()? as i32;
+                    font.pages.resize(pages, String::new());
+                }
+                3 => {
+                    font.pages.clear();
+                    while block.pos < block.data.len() {
+                        font.pages.push(block.c_str()?);
+                    }
+                }
+                4 => {
+                    while block.pos < block.data.len() {
+                        font.chars.push(BmChar {
+                            id: block.u32()?,
+                            x: block.u16()? as i32,
+                            y: block.u16()? as i32,
+                            width: block.u16()? as i32,
+                            height: block.u16()? as i32,
+                            xoffset: block.i16()? as i32,
+                            yoffset: block.i16()? as i32,
+                            xadvance: block.i16()? as i32,
+                            page: block.u8()? as u32,
+                            chnl: block.u8()? as i32,
+                        });
+                    }
+                }
+                5 => {
+                    while block.pos < block.data.len() {
+                        font.kernings.push(BmKerning {
+                            first: block.u32()?,
+                            second: block.u32()?,
+                            amount: block.i16()? as i32,
+                        });
+                    }
+                }
+                // unknown blocks are skipped, like BMFont readers are told to
+                _ => {}
+            }
+        }
+        font.validate()
+    }
+
+    /// Encodes the descriptor in `format`.
+    pub fn to_bytes(&self, format: BmFontFormat) -> Vec<u8> {
+        match format {
+            BmFontFormat::Text => self.to_text().into_bytes(),
+            BmFontFormat::Xml => self.to_xml().into_bytes(),
+            BmFontFormat::Binary => self.to_binary(),
+        }
+    }
+
+    /// Writes the descriptor to `filename` in `format`.
+    pub fn save(&self, filename: impl AsRef<Path>, format: BmFontFormat) -> Result<(), Error> {
+        let filename = filename.as_ref();
+        std::fs::write(filename, self.to_bytes(format))
+            .map_err(|_| error!("Error saving BMFont.", filename))
+    }
+
+    /// The descriptor in the text format.
+    pub fn to_text(&self) -> String {
+        let mut out = String::new();
+        for (tag, attributes) in self.elements() {
+            out.push_str(tag);
+            for (key, value) in attributes {
+                let value = match value {
+                    Value::Int(value) => value.to_string(),
+                    Value::Str(value) => format!("\"{}\"", value),
+                    Value::List(values) => join(&values),
+                };
+                write!(out, " {}={}", key, value).unwrap();
+            }
+            out.push('\n');
+        }
+        out
+    }
+
+    /// The descriptor in the XML format.
+    pub fn to_xml(&self) -> String {
+        let mut out = String::from("<?xml version=\"1.0\"?>\n<font>\n");
+        let mut open: Option<&str> = None;
+        for (tag, attributes) in self.elements() {
+            // the counts of the text format become the containers of the XML one
+            let container = match tag {
+                "page" => Some("pages"),
+                "char" => Some("chars"),
+                "kerning" => Some("kernings"),
+                _ => None,
+            };
+            if open.is_some() && open != container {
+                writeln!(out, "  </{}>", open.take().unwrap()).unwrap();
+            }
+            if tag == "page" && open.is_none() {
+                out.push_str("  <pages>\n");
+                open = Some("pages");
+            }
+            let indent = if open.is_some() { "    " } else { "  " };
+            out.push_str(indent);
+            out.push('<');
+            out.push_str(tag);
+            for (key, value) in attributes {
+                let value = match value {
+                    Value::Int(value) => value.to_string(),
+                    Value::Str(value) => escape_xml(&value),
+                    Value::List(values) => join(&values),
+                };
+                write!(out, " {}=\"{}\"", key, value).unwrap();
+            }
+            if tag == "chars" || tag == "kernings" {
+                out.push_str(">\n");
+                open = Some(tag);
+            } else {
+                out.push_str("/>\n");
+            }
+        }
+        if let Some(open) = open {
+            writeln!(out, "  </{}>", open).unwrap();
+        }
+        out.push_str("</font>\n");
+        out
+    }
+
+    /// The descriptor in version 3 of the binary format.
+    pub fn to_binary(&self) -> Vec<u8> {
+        let mut out = b"BMF\x03".to_vec();
+
+        let info = &self.info;
+        let mut block = Vec::new();
+        block.extend_from_slice(&(info.size as i16).to_le_bytes());
+        let flags = (info.smooth as u8) << 7
+            | (info.unicode as u8) << 6
+            | (info.italic as u8) << 5
+            | (info.bold as u8) << 4;
+        block.push(flags);
+        block.push(info.charset.parse().unwrap_or(0));
+        block.extend_from_slice(&(info.stretch_h as u16).to_le_bytes());
+        block.push(info.aa as u8);
+        block.extend(info.padding.iter().map(|&padding| padding as u8));
+        block.extend(info.spacing.iter().map(|&spacing| spacing as u8));
+        block.push(info.outline as u8);
+        push_c_str(&mut block, &info.face);
+        push_block(&mut out, 1, &block);
+
+        let common = &self.common;
+        let mut block = Vec::new();
+        for value in [
+            common.line_height,
+            common.base,
+            common.scale_w,
+            common.scale_h,
+            self.pages.len() as i32,
+        ] {
+            block.extend_from_slice(&(value as u16).to_le_bytes());
+        }
+        block.push(common.packed as u8);
+        for channel in [
+            common.alpha_chnl,
+            common.red_chnl,
+            common.green_chnl,
+            common.blue_chnl,
+        ] {
+            block.push(channel as u8);
+        }
+        push_block(&mut out, 2, &block);
+
+        let mut block = Vec::new();
+        for page in &self.pages {
+            push_c_str(&mut block, page);
+        }
+        push_block(&mut out, 3, &block);
+
+        let mut block = Vec::new();
+        for c in &self.chars {
+            block.extend_from_slice(&c.id.to_le_bytes());
+            for value in [c.x, c.y, c.width, c.height] {
+                block.extend_from_slice(&(value as u16).to_le_bytes());
+            }
+            for value in [c.xoffset, c.yoffset, c.xadvance] {
+                block.extend_from_slice(&(value as i16).to_le_bytes());
+            }
+            block.push(c.page as u8);
+            block.push(c.chnl as u8);
+        }
+        push_block(&mut out, 4, &block);
+
+        if !self.kernings.is_empty() {
+            let mut block = Vec::new();
+            for kerning in &self.kernings {
+                block.extend_from_slice(&kerning.first.to_le_bytes());
+                block.extend_from_slice(&kerning.second.to_le_bytes());
+                block.extend_from_slice(&(kerning.amount as i16).to_le_bytes());
+            }
+            push_block(&mut out, 5, &block);
+        }
+        out
+    }
+
+    /// The glyph for `codepoint`, if the font has one.
+    pub fn glyph(&self, codepoint: char) -> Option<&BmChar> {
+        self.chars.iter().find(|c| c.id == codepoint as u32)
+    }
+
+    /// Extra horizontal offset in pixels between `first` and `second`, 0 if the font has no
+    /// kerning pair for them. raylib fonts ignore kerning, so apply it yourself if you need it.
+    pub fn kerning(&self, first: char, second: char) -> i32 {
+        self.kernings
+            .iter()
+            .find(|kerning| kerning.first == first as u32 && kerning.second == second as u32)
+            .map_or(0, |kerning| kerning.amount)
+    }
+
+    /// Describes the atlas and glyphs of `font` as a single page descriptor, with `page` as
+    /// the file name of the atlas.
+    pub fn from_font(font: &(impl RaylibFont + ?Sized), face: &str, page: &str) -> BmFont {
+        let raw = font.as_ref();
+        let recs: &[ffi::Rectangle] = if raw.recs.is_null() {
+            &[]
+        } else {
+            unsafe { std::slice::from_raw_parts(raw.recs, raw.glyphCount as usize) }
+        };
+        let (width, height) = atlas_size(raw, recs);
+        let chars = font
+            .chars()
+            .iter()
+            .zip(recs)
+            .map(|(glyph, rec)| BmChar {
+                id: glyph.value as u32,
+                x: rec.x as i32,
+                y: rec.y as i32,
+                width: rec.width as i32,
+                height: rec.height as i32,
+                xoffset: glyph.offsetX,
+                yoffset: glyph.offsetY,
+                xadvance: if glyph.advanceX == 0 {
+                    rec.width as i32
+                } else {
+                    glyph.advanceX
+                },
+                page: 0,
+                chnl: 15,
+            })
+            .collect();
+        BmFont {
+            info: BmFontInfo {
+                face: face.to_owned(),
+                size: font.base_size(),
+                unicode: true,
+                stretch_h: 100,
+                smooth: true,
+                aa: 1,
+                spacing: [1, 1],
+                ..Default::default()
+            },
+            common: BmFontCommon {
+                line_height: font.base_size(),
+                base: baseline(font),
+                scale_w: width,
+                scale_h: height,
+                ..Default::default()
+            },
+            pages: vec![page.to_owned()],
+            chars,
+            kernings: Vec::new(),
+        }
+    }
+
+    fn add_element(&mut self, tag: &str, attributes: &[(String, String)]) -> Result<(), Error> {
+        let get = |key: &str| {
+            attributes
+                .iter()
+                .find(|(k, _)| k == key)
+                .map(|(_, value)| value.as_str())
+        };
+        let int = |key: &str| -> Result<i32, Error> {
+            get(key).map_or(Ok(0), |value| {
+                value
+                    .trim()
+                    .parse()
+                    .map_err(|_| invalid_value(tag, key, value))
+            })
+        };
+        let list = |key: &str, values: &mut [i32]| -> Result<(), Error> {
+            if let Some(value) = get(key) {
+                for (slot, item) in values.iter_mut().zip(value.split(',')) {
+                    *slot = item
+                        .trim()
+                        .parse()
+                        .map_err(|_| invalid_value(tag, key, value))?;
+                }
+            }
+            Ok(())
+        };
+        match tag {
+            "info" => {
+                let info = &mut self.info;
+                info.face = get("face").unwrap_or_default().to_owned();
+                info.size = int("size")?;
+                info.bold = int("bold")? != 0;
+                info.italic = int("italic")? != 0;
+                info.charset = get("charset").unwrap_or_default().to_owned();
+                info.unicode = int("unicode")? != 0;
+                info.stretch_h = int("stretchH")?;
+                info.smooth = int("smooth")? != 0;
+                info.aa = int("aa")?;
+                list("padding", &mut info.padding)?;
+                list("spacing", &mut info.spacing)?;
+                info.outline = int("outline")?;
+            }
+            "common" => {
+                let common = &mut self.common;
+                common.line_height = int("lineHeight")?;
+                common.base = int("base")?;
+                common.scale_w = int("scaleW")?;
+                common.scale_h = int("scaleH")?;
+                common.packed = int("packed")? != 0;
+                common.alpha_chnl = int("alphaChnl")?;
+                common.red_chnl = int("redChnl")?;
+                common.green_chnl = int("greenChnl")?;
+                common.blue_chnl = int("blueChnl")?;
+                // The binary format stores the page count in 16 bits.
+                let pages = int("pages")?;
+                if !(0..=u16::MAX as i32).contains(&pages) {
+                    return Err(invalid_value(
+                        tag,
+                        "pages",
+                        get("pages").unwrap_or_default(),
+                    ));
+                }
+                if self.pages.len() < pages as usize {
+                    self.pages.resize(pages as usize, String::new());
+                }
+            }
+            "page" => {
+                // Pages are declared in `common` or listed in order, so an id past the end is
+                // malformed rather than a reason to allocate up to it.
+                let id = int("id")?;
+                if id < 0 || id as usize > self.pages.len() {
+                    return Err(invalid_value(tag, "id", get("id").unwrap_or_default()));
+                }
+                let id = id as usize;
+                if self.pages.len() == id {
+                    self.pages.push(String::new());
+                }
+                self.pages[id] = get("file").unwrap_or_default().to_owned();
+            }
+            "char" => self.chars.push(BmChar {
+                id: int("id")? as u32,
+                x: int("x")?,
+                y: int("y")?,
+                width: int("width")?,
+                height: int("height")?,
+                xoffset: int("xoffset")?,
+                yoffset: int("yoffset")?,
+                xadvance: int("xadvance")?,
+                page: int("page")? as u32,
+                chnl: int("chnl")?,
+            }),
+            "kerning" => self.kernings.push(BmKerning {
+                first: int("first")? as u32,
+                second: int("second")? as u32,
+                amount: int("amount")?,
+            }),
+            // counts and containers are implied by the elements themselves
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn validate(self) -> Result<BmFont, Error> {
+        if self.common.line_height <= 0 {
+            return Err(error!("Error parsing BMFont: missing common block."));
+        }
+        if let Some(c) = self
+            .chars
+            .iter()
+            .find(|c| c.page as usize >= self.pages.len())
+        {
+            return Err(error!(Cow::Owned(format!(
+                "Error parsing BMFont: char {} is on page {}, but the font has {} pages.",
+                c.id,
+                c.page,
+                self.pages.len()
+            ))));
+        }
+        if let Some(c) = self
+            .chars
+            .iter()
+            .find(|c| c.x < 0 || c.y < 0 || c.width < 0 || c.height < 0)
+        {
+            return Err(error!(Cow::Owned(format!(
+                "Error parsing BMFont: char {} has a negative position or size.",
+                c.id
+            ))));
+        }
+        Ok(self)
+    }
+
+    /// Checks that every glyph lies inside its page, so it can be cut out of the loaded pages.
+    fn check_bounds(&self, pages: &[Image]) -> Result<(), Error> {
+        for c in &self.chars {
+            let page = &pages[c.page as usize];
+            let empty = c.width == 0 || c.height == 0;
+            if !empty
+                && (c.x as i64 + c.width as i64 > page.width as i64
+                    || c.y as i64 + c.height as i64 > page.height as i64)
+            {
+                return Err(error!(Cow::Owned(format!(
+                    "Error loading BMFont: char {} lies outside its {}x{} page.",
+                    c.id, page.width, page.height
+                ))));
+            }
+        }
+        Ok(())
+    }
+
+    /// Every element of the descriptor, in the order of the text format.
+    fn elements(&self) -> Vec<(&'static str, Vec<(&'static str, Value)>)> {
+        let info = &self.info;
+        let common = &self.common;
+        let mut elements = vec![
+            (
+                "info",
+                vec![
+                    ("face", Value::Str(info.face.clone())),
+                    ("size", Value::Int(info.size)),
+                    ("bold", Value::Int(info.bold as i32)),
+                    ("italic", Value::Int(info.italic as i32)),
+                    ("charset", Value::Str(info.charset.clone())),
+                    ("unicode", Value::Int(info.unicode as i32)),
+                    ("stretchH", Value::Int(info.stretch_h)),
+                    ("smooth", Value::Int(info.smooth as i32)),
+                    ("aa", Value::Int(info.aa)),
+                    ("padding", Value::List(info.padding.to_vec())),
+                    ("spacing", Value::List(info.spacing.to_vec())),
+                    ("outline", Value::Int(info.outline)),
+                ],
+            ),
+            (
+                "common",
+                vec![
+                    ("lineHeight", Value::Int(common.line_height)),
+                    ("base", Value::Int(common.base)),
+                    ("scaleW", Value::Int(common.scale_w)),
+                    ("scaleH", Value::Int(common.scale_h)),
+                    ("pages", Value::Int(self.pages.len() as i32)),
+                    ("packed", Value::Int(common.packed as i32)),
+                    ("alphaChnl", Value::Int(common.alpha_chnl)),
+                    ("redChnl", Value::Int(common.red_chnl)),
+                    ("greenChnl", Value::Int(common.green_chnl)),
+                    ("blueChnl", Value::Int(common.blue_chnl)),
+                ],
+            ),
+        ];
+        for (id, file) in self.pages.iter().enumerate() {
+            elements.push((
+                "page",
+                vec![
+                    ("id", Value::Int(id as i32)),
+                    ("file", Value::Str(file.clone())),
+                ],
+            ));
+        }
+        elements.push((
+            "chars",
+            vec![("count", Value::Int(self.chars.len() as i32))],
+        ));
+        for c in &self.chars {
+            elements.push((
+                "char",
+                vec![
+                    ("id", Value::Int(c.id as i32)),
+                    ("x", Value::Int(c.x)),
+                    ("y", Value::Int(c.y)),
+                    ("width", Value::Int(c.width)),
+                    ("height", Value::Int(c.height)),
+                    ("xoffset", Value::Int(c.xoffset)),
+                    ("yoffset", Value::Int(c.yoffset)),
+                    ("xadvance", Value::Int(c.xadvance)),
+                    ("page", Value::Int(c.page as i32)),
+                    ("chnl", Value::Int(c.chnl)),
+                ],
+            ));
+        }
+        if !self.kernings.is_empty() {
+            elements.push((
+                "kernings",
+                vec![("count", Value::Int(self.kernings.len() as i32))],
+            ));
+            for kerning in &self.kernings {
+                elements.push((
+                    "kerning",
+                    vec![
+                        ("first", Value::Int(kerning.first as i32)),
+                        ("second", Value::Int(kerning.second as i32)),
+                        ("amount", Value::Int(kerning.amount)),
+                    ],
+                ));
+            }
+        }
+        elements
+    }
+
+    /// Glyph data for a raylib font whose atlas is `pages` stacked vertically, top to bottom.
+    fn glyphs(
+        &self,
+        atlas: &Image,
+        page_offsets: &[i32],
+    ) -> (Vec<ffi::GlyphInfo>, Vec<ffi::Rectangle>) {
+        self.chars
+            .iter()
+            .map(|c| {
+                let rec = ffi::Rectangle {
+                    x: c.x as f32,
+                    y: (c.y + page_offsets[c.page as usize]) as f32,
+                    width: c.width as f32,
+                    height: c.height as f32,
+                };
+                let image = if c.width > 0 && c.height > 0 {
+                    unsafe { ffi::ImageFromImage(atlas.0, rec) }
+                } else {
+                    // raylib expects every glyph to have an image, even blank ones
+                    unsafe { ffi::GenImageColor(1, 1, Color::BLANK.into()) }
+                };
+                let glyph = ffi::GlyphInfo {
+                    value: c.id as i32,
+                    offsetX: c.xoffset,
+                    offsetY: c.yoffset,
+                    advanceX: c.xadvance,
+                    image,
+                };
+                (glyph, rec)
+            })
+            .unzip()
+    }
+}
+
+impl RaylibHandle {
+    /// Loads a BMFont descriptor and its pages into a font. Pages are stacked into a single
+    /// atlas, and grayscale pages are used as their own alpha, like raylib does.
+    pub fn load_font_bmfont(
+        &mut self,
+        thread: &RaylibThread,
+        filename: impl AsRef<Path>,
+    ) -> Result<Font, Error> {
+        let filename = filename.as_ref();
+        let descriptor = BmFont::load(filename)?;
+        let directory = filename.parent().unwrap_or_else(|| Path::new(""));
+
+        let mut pages = Vec::with_capacity(descriptor.pages.len());
+        for page in &descriptor.pages {
+            let path = directory.join(page);
+            let mut image = Image::load_image(&path.to_string_lossy())
+                .map_err(|_| error!("Error loading BMFont page.", path))?;
+            if image.format() == ffi::PixelFormat::PIXELFORMAT_UNCOMPRESSED_GRAYSCALE {
+                let mask = image.clone();
+                image.alpha_mask(&mask);
+            }
+            image.set_format(ffi::PixelFormat::PIXELFORMAT_UNCOMPRESSED_R8G8B8A8);
+            pages.push(image);
+        }
+        descriptor.check_bounds(&pages)?;
+
+        let width = pages.iter().map(|page| page.width).max().unwrap_or(0);
+        let height: i32 = pages.iter().map(|page| page.height).sum();
+        let mut atlas = Image::gen_image_color(width.max(1), height.max(1), Color::BLANK);
+        let mut page_offsets = Vec::with_capacity(pages.len());
+        let mut y = 0;
+        for page in &pages {
+            let rec = Rectangle::new(0.0, 0.0, page.width as f32, page.height as f32);
+            atlas.draw(
+                page,
+                rec,
+                Rectangle::new(0.0, y as f32, rec.width, rec.height),
+                Color::WHITE,
+            );
+            page_offsets.push(y);
+            y += page.height;
+        }
+
+        let texture = self.load_texture_from_image(thread, &atlas)?;
+        let (glyphs, recs) = descriptor.glyphs(&atlas, &page_offsets);
+        let mut font = unsafe { Font(std::mem::zeroed()) };
+        font.0.baseSize = descriptor.common.line_height;
+        font.set_chars(&glyphs);
+        font.set_recs(&recs);
+        font.set_texture(texture);
+        Ok(font)
+    }
+}
+
+/// Writes `font` as a BMFont descriptor at `filename` and its atlas as a PNG next to it.
+pub(crate) fn export_font(
+    font: &(impl RaylibFont + ?Sized),
+    filename: &Path,
+    format: BmFontFormat,
+) -> Result<(), Error> {
+    let stem = filename
+        .file_stem()
+        .map_or(Cow::Borrowed("font"), |stem| stem.to_string_lossy());
+    let page = format!("{}_0.png", stem);
+    let face = stem.into_owned();
+    let descriptor = BmFont::from_font(font, &face, &page);
+
+    let atlas = atlas_image(font, &descriptor);
+    let page_path = filename.with_file_name(&page);
+    let c_page = std::ffi::CString::new(page_path.to_string_lossy().as_bytes()).unwrap();
+    if !unsafe { ffi::ExportImage(atlas.0, c_page.as_ptr()) } {
+        return Err(error!("Error exporting BMFont page.", page_path));
+    }
+    descriptor.save(filename, format)
+}
+
+/// Rebuilds the atlas of `font` from its glyph images, so exporting needs no GPU.
+fn atlas_image(font: &(impl RaylibFont + ?Sized), descriptor: &BmFont) -> Image {
+    let mut atlas = Image::gen_image_color(
+        descriptor.common.scale_w.max(1),
+        descriptor.common.scale_h.max(1),
+        Color::BLANK,
+    );
+    for (glyph, c) in font.chars().iter().zip(&descriptor.chars) {
+        let image = &glyph.image;
+        if image.data.is_null() || image.width <= 0 || image.height <= 0 {
+            continue;
+        }
+        // glyphs of generated atlases are coverage only, drawn white like raylib's atlas
+        let image = if image.format == ffi::PixelFormat::PIXELFORMAT_UNCOMPRESSED_GRAYSCALE as i32 {
+            let mut white = Image::gen_image_color(image.width, image.height, Color::WHITE);
+            unsafe { ffi::ImageAlphaMask(&mut white.0, *image) };
+            white
+        } else {
+            Image(unsafe { ffi::ImageCopy(*image) })
+        };
+        let width = image.width.min(c.width) as f32;
+        let height = image.height.min(c.height) as f32;
+        atlas.draw(
+            &image,
+            Rectangle::new(0.0, 0.0, width, height),
+            Rectangle::new(c.x as f32, c.y as f32, width, height),
+            Color::WHITE,
+        );
+    }
+    atlas
+}
+
+/// Size of the atlas of `font`, from its texture or, without one, from its glyph rectangles.
+fn atlas_size(font: &ffi::Font, recs: &[ffi::Rectangle]) -> (i32, i32) {
+    if font.texture.width > 0 && font.texture.height > 0 {
+        return (font.texture.width, font.texture.height);
+    }
+    recs.iter().fold((0, 0), |(width, height), rec| {
+        (
+            width.max((rec.x + rec.width).ceil() as i32),
+            height.max((rec.y + rec.height).ceil() as i32),
+        )
+    })
+}
+
+/// Distance from the top of a line to the baseline, from the bottom of the tallest capital.
+fn baseline(font: &(impl RaylibFont + ?Sized)) -> i32 {
+    let raw = font.as_ref();
+    let recs = if raw.recs.is_null() {
+        return font.base_size();
+    } else {
+        unsafe { std::slice::from_raw_parts(raw.recs, raw.glyphCount as usize) }
+    };
+    font.chars()
+        .iter()
+        .zip(recs)
+        .filter(|(glyph, _)| (b'A' as i32..=b'Z' as i32).contains(&glyph.value))
+        .map(|(glyph, rec)| glyph.offsetY + rec.height as i32)
+        .max()
+        .unwrap_or_else(|| font.base_size())
+}
+
+enum Value {
+    Int(i32),
+    Str(String),
+    List(Vec<i32>),
+}
+
+fn join(values: &[i32]) -> String {
+    values
+        .iter()
+        .map(|value| value.to_string())
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+fn invalid_value(tag: &str, key: &str, value: &str) -> Error {
+    error!(Cow::Owned(format!(
+        "Error parsing BMFont: invalid {} {} value \"{}\".",
+        tag, key, value
+    )))
+}
+
+/// Splits `key=value` pairs, with optionally quoted values.
+fn parse_attributes(text: &str, xml: bool) -> Vec<(String, String)> {
+    let mut attributes = Vec::new();
+    let mut rest = text.trim_start();
+    while !rest.is_empty() {
+        let key_end = rest
+            .find(|c: char| c == '=' || c.is_whitespace())
+            .unwrap_or(rest.len());
+        let key = &rest[..key_end];
+        rest = rest[key_end..].trim_start();
+        let value = match rest.strip_prefix('=') {
+            Some(after) => {
+                let after = after.trim_start();
+                let (value, remaining) = match after.chars().next() {
+                    Some(quote @ ('"' | '\'')) => {
+                        let inner = &after[1..];
+                        let end = inner.find(quote).unwrap_or(inner.len());
+                        (&inner[..end], inner.get(end + 1..).unwrap_or(""))
+                    }
+                    _ => {
+                        let end = after.find(char::is_whitespace).unwrap_or(after.len());
+                        (&after[..end], &after[end..])
+                    }
+                };
+                rest = remaining.trim_start();
+                value
+            }
+            None => "",
+        };
+        if !key.is_empty() {
+            let value = if xml {
+                unescape_xml(value)
+            } else {
+                value.to_owned()
+            };
+            attributes.push((key.to_owned(), value));
+        }
+    }
+    attributes
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('"', "&quot;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+fn unescape_xml(text: &str) -> String {
+    text.replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&amp;", "&")
+}
+
+fn push_c_str(out: &mut Vec<u8>, text: &str) {
+    out.extend_from_slice(text.as_bytes());
+    out.push(0);
+}
+
+fn push_block(out: &mut Vec<u8>, kind: u8, block: &[u8]) {
+    out.push(kind);
+    out.extend_from_slice(&(block.len() as u32).to_le_bytes());
+    out.extend_from_slice(block);
+}
+
+/// Little endian reader for the binary format.
+struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn bytes(&mut self, len: usize) -> Result<&'a [u8], Error> {
+        let bytes = self.data.get(self.pos..self.pos + len).ok_or(error!(
+            "Error parsing BMFont: unexpected end of binary data."
+        ))?;
+        self.pos += len;
+        Ok(bytes)
+    }
+
+    fn u8(&mut self) -> Result<u8, Error> {
+        Ok(self.bytes(1)?[0])
+    }
+
+    fn u16(&mut self) -> Result<u16, Error> {
+        let bytes = self.bytes(2)?;
+        Ok(u16::from_le_bytes([bytes[0], bytes[1]]))
+    }
+
+    fn i16(&mut self) -> Result<i16, Error> {
+        Ok(self.u16()? as i16)
+    }
+
+    fn u32(&mut self) -> Result<u32, Error> {
+        let bytes = self.bytes(4)?;
+        Ok(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    fn c_str(&mut self) -> Result<String, Error> {
+        let rest = &self.data[self.pos..];
+        let len = rest
+            .iter()
+            .position(|&b| b == 0)
+            .ok_or(error!("Error parsing BMFont: unterminated string."))?;
+        self.pos += len + 1;
+        Ok(String::from_utf8_lossy(&rest[..len]).into_owned())
+    }
+}
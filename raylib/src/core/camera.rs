@@ -1,10 +1,13 @@
 //! Utility code for using Raylib [`Camera3D`] and [`Camera2D`]
 use raylib_sys::CameraMode;
 
-use crate::core::math::{Vector2, Vector3};
+use crate::core::math::{Rectangle, Vector2, Vector3};
 use crate::core::RaylibHandle;
 use crate::ffi;
 
+#[cfg(feature = "with_serde")]
+use serde::{Deserialize, Serialize};
+
 #[repr(C)]
 #[derive(Debug, Copy, Clone)]
 pub struct Camera3D {
@@ -41,7 +44,8 @@ impl Into<ffi::Camera3D> for &Camera3D {
 }
 
 #[repr(C)]
-#[derive(Debug, Copy, Clone, Default)]
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "with_serde", derive(Serialize, Deserialize))]
 pub struct Camera2D {
     pub offset: Vector2,
     pub target: Vector2,
@@ -115,3 +119,361 @@ impl RaylibHandle {
         }
     }
 }
+
+/// How a [`Camera2DController`] chases its focus point.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum FollowMode {
+    /// Jump straight to the focus point every update.
+    Snap,
+    /// Exponential smoothing. Higher `speed` closes the gap faster, independent of frame rate.
+    Lerp { speed: f32 },
+    /// Damped spring. Low `damping` relative to `stiffness` gives a bouncy follow.
+    Spring { stiffness: f32, damping: f32 },
+    /// Critically damped smoothing that reaches the focus in roughly `smooth_time` seconds without overshooting.
+    CriticallyDamped { smooth_time: f32 },
+}
+
+impl Default for FollowMode {
+    fn default() -> Self {
+        FollowMode::Lerp { speed: 8.0 }
+    }
+}
+
+/// Drives a [`Camera2D`] that follows a moving point.
+///
+/// Supports smoothing ([`FollowMode`]), a deadzone, look-ahead, clamping to world bounds,
+/// zooming around the cursor and trauma-based screen shake. Call [`Camera2DController::update`]
+/// once per frame and pass [`Camera2DController::camera`] to `begin_mode2D`.
+///
+/// ```no_run
+/// use raylib::prelude::*;
+///
+/// let (mut rl, thread) = raylib::init().size(800, 450).build();
+/// let mut controller = Camera2DController::new(800.0, 450.0);
+/// controller
+///     .set_follow_mode(FollowMode::CriticallyDamped { smooth_time: 0.25 })
+///     .set_deadzone(Vector2::new(40.0, 30.0))
+///     .set_bounds(Some(Rectangle::new(0.0, 0.0, 2000.0, 600.0)));
+///
+/// let player = Vector2::new(400.0, 280.0);
+/// while !rl.window_should_close() {
+///     controller.update(player, rl.get_frame_time());
+///     let mut d = rl.begin_drawing(&thread);
+///     let mut d2 = d.begin_mode2D(controller.camera());
+///     d2.draw_circle_v(player, 10.0, Color::RED);
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Camera2DController {
+    viewport: Vector2,
+    position: Vector2,
+    velocity: Vector2,
+    zoom: f32,
+    rotation: f32,
+    follow: FollowMode,
+    deadzone: Vector2,
+    look_ahead: f32,
+    max_look_ahead: f32,
+    look_ahead_offset: Vector2,
+    last_focus: Option<Vector2>,
+    bounds: Option<Rectangle>,
+    min_zoom: f32,
+    max_zoom: f32,
+    trauma: f32,
+    trauma_decay: f32,
+    max_shake_offset: Vector2,
+    max_shake_angle: f32,
+    shake_frequency: f32,
+    shake_seed: u32,
+    time: f32,
+}
+
+impl Camera2DController {
+    /// Creates a controller for a viewport of the given size, centered on the world origin.
+    pub fn new(viewport_width: f32, viewport_height: f32) -> Camera2DController {
+        Camera2DController {
+            viewport: Vector2::new(viewport_width, viewport_height),
+            position: Vector2::zero(),
+            velocity: Vector2::zero(),
+            zoom: 1.0,
+            rotation: 0.0,
+            follow: FollowMode::default(),
+            deadzone: Vector2::zero(),
+            look_ahead: 0.0,
+            max_look_ahead: f32::INFINITY,
+            look_ahead_offset: Vector2::zero(),
+            last_focus: None,
+            bounds: None,
+            min_zoom: 0.1,
+            max_zoom: 10.0,
+            trauma: 0.0,
+            trauma_decay: 1.0,
+            max_shake_offset: Vector2::new(16.0, 16.0),
+            max_shake_angle: 3.0,
+            shake_frequency: 15.0,
+            shake_seed: 0,
+            time: 0.0,
+        }
+    }
+
+    /// Sets how the camera chases its focus.
+    pub fn set_follow_mode(&mut self, mode: FollowMode) -> &mut Self {
+        self.follow = mode;
+        self
+    }
+
+    /// Sets the deadzone half extents in world units. The focus can move this far from the camera center before the camera follows.
+    pub fn set_deadzone(&mut self, half_extents: Vector2) -> &mut Self {
+        self.deadzone = Vector2::new(half_extents.x.abs(), half_extents.y.abs());
+        self
+    }
+
+    /// Leads the camera in the direction the focus is moving.
+    /// `seconds` is how far ahead to predict, `max_distance` caps the offset in world units.
+    pub fn set_look_ahead(&mut self, seconds: f32, max_distance: f32) -> &mut Self {
+        self.look_ahead = seconds.max(0.0);
+        self.max_look_ahead = max_distance.max(0.0);
+        self
+    }
+
+    /// Keeps the visible area inside `bounds` (world units). Pass `None` to disable clamping.
+    pub fn set_bounds(&mut self, bounds: Option<Rectangle>) -> &mut Self {
+        self.bounds = bounds;
+        self
+    }
+
+    /// Sets the allowed zoom range. The current zoom is clamped into it.
+    pub fn set_zoom_limits(&mut self, min_zoom: f32, max_zoom: f32) -> &mut Self {
+        self.min_zoom = min_zoom.max(f32::EPSILON);
+        self.max_zoom = max_zoom.max(self.min_zoom);
+        self.zoom = self.zoom.clamp(self.min_zoom, self.max_zoom);
+        self
+    }
+
+    /// Configures screen shake.
+    /// `max_offset` is in screen pixels, `max_angle` in degrees, `frequency` in noise samples per second
+    /// and `decay` is how much trauma is removed per second.
+    pub fn set_shake(
+        &mut self,
+        max_offset: Vector2,
+        max_angle: f32,
+        frequency: f32,
+        decay: f32,
+    ) -> &mut Self {
+        self.max_shake_offset = max_offset;
+        self.max_shake_angle = max_angle;
+        self.shake_frequency = frequency;
+        self.trauma_decay = decay.max(0.0);
+        self
+    }
+
+    /// Seeds the shake noise so different controllers don't shake in sync.
+    pub fn set_shake_seed(&mut self, seed: u32) -> &mut Self {
+        self.shake_seed = seed;
+        self
+    }
+
+    /// Updates the viewport size, e.g. after the window was resized.
+    pub fn set_viewport(&mut self, width: f32, height: f32) -> &mut Self {
+        self.viewport = Vector2::new(width, height);
+        self.position = self.clamp_to_bounds(self.position);
+        self
+    }
+
+    /// Sets the base rotation of the camera in degrees.
+    pub fn set_rotation(&mut self, degrees: f32) -> &mut Self {
+        self.rotation = degrees;
+        self
+    }
+
+    /// Sets the zoom, clamped to the zoom limits.
+    pub fn set_zoom(&mut self, zoom: f32) -> &mut Self {
+        self.zoom = zoom.clamp(self.min_zoom, self.max_zoom);
+        self.position = self.clamp_to_bounds(self.position);
+        self
+    }
+
+    /// Moves the camera center to `position` immediately, dropping any velocity.
+    pub fn teleport(&mut self, position: Vector2) -> &mut Self {
+        self.position = self.clamp_to_bounds(position);
+        self.velocity = Vector2::zero();
+        self.look_ahead_offset = Vector2::zero();
+        self.last_focus = None;
+        self
+    }
+
+    /// World position at the center of the view, without shake.
+    pub fn position(&self) -> Vector2 {
+        self.position
+    }
+
+    pub fn zoom(&self) -> f32 {
+        self.zoom
+    }
+
+    /// Current trauma in `0.0..=1.0`.
+    pub fn trauma(&self) -> f32 {
+        self.trauma
+    }
+
+    /// Adds trauma, which drives shake intensity. Trauma is clamped to `1.0` and decays over time.
+    pub fn add_trauma(&mut self, amount: f32) {
+        self.trauma = (self.trauma + amount).clamp(0.0, 1.0);
+    }
+
+    /// Advances the controller by `dt` seconds towards `focus` (world units).
+    pub fn update(&mut self, focus: Vector2, dt: f32) {
+        if dt <= 0.0 {
+            return;
+        }
+        self.time += dt;
+        self.trauma = (self.trauma - self.trauma_decay * dt).max(0.0);
+
+        let focus_velocity = match self.last_focus {
+            Some(last) => (focus - last) / dt,
+            None => Vector2::zero(),
+        };
+        self.last_focus = Some(focus);
+
+        if self.look_ahead > 0.0 {
+            let mut lead = focus_velocity * self.look_ahead;
+            let len = lead.length();
+            if len > self.max_look_ahead {
+                lead = lead * (self.max_look_ahead / len);
+            }
+            // Smooth the lead so jittery input doesn't throw the camera around.
+            let t = 1.0 - (-4.0 * dt).exp();
+            self.look_ahead_offset = self.look_ahead_offset.lerp(lead, t);
+        } else {
+            self.look_ahead_offset = Vector2::zero();
+        }
+
+        let desired = self.apply_deadzone(focus + self.look_ahead_offset);
+        let desired = self.clamp_to_bounds(desired);
+
+        match self.follow {
+            FollowMode::Snap => {
+                self.velocity = (desired - self.position) / dt;
+                self.position = desired;
+            }
+            FollowMode::Lerp { speed } => {
+                let t = 1.0 - (-speed.max(0.0) * dt).exp();
+                let next = self.position.lerp(desired, t);
+                self.velocity = (next - self.position) / dt;
+                self.position = next;
+            }
+            FollowMode::Spring { stiffness, damping } => {
+                let accel = (desired - self.position) * stiffness - self.velocity * damping;
+                self.velocity += accel * dt;
+                self.position += self.velocity * dt;
+            }
+            FollowMode::CriticallyDamped { smooth_time } => {
+                // Game Programming Gems 4, "Critically Damped Ease-In/Ease-Out Smoothing".
+                let omega = 2.0 / smooth_time.max(1e-4);
+                let x = omega * dt;
+                let exp = 1.0 / (1.0 + x + 0.48 * x * x + 0.235 * x * x * x);
+                let change = self.position - desired;
+                let temp = (self.velocity + change * omega) * dt;
+                self.velocity = (self.velocity - temp * omega) * exp;
+                self.position = desired + (change + temp) * exp;
+            }
+        }
+
+        self.position = self.clamp_to_bounds(self.position);
+    }
+
+    /// Zooms by `factor` while keeping the world point under `cursor` (screen space) fixed.
+    pub fn zoom_at(&mut self, rl: &RaylibHandle, cursor: Vector2, factor: f32) {
+        let before = rl.get_screen_to_world2D(cursor, self.base_camera());
+        self.zoom = (self.zoom * factor).clamp(self.min_zoom, self.max_zoom);
+        let after = rl.get_screen_to_world2D(cursor, self.base_camera());
+        self.position += before - after;
+        self.position = self.clamp_to_bounds(self.position);
+    }
+
+    /// The camera to pass to `begin_mode2D`, including shake.
+    pub fn camera(&self) -> Camera2D {
+        let mut camera = self.base_camera();
+        if self.trauma > 0.0 {
+            let shake = self.trauma * self.trauma;
+            let t = self.time * self.shake_frequency;
+            let seed = self.shake_seed;
+            camera.offset.x += self.max_shake_offset.x * shake * shake_noise(seed, t);
+            camera.offset.y += self.max_shake_offset.y * shake * shake_noise(seed ^ 0x9e37_79b9, t);
+            camera.rotation += self.max_shake_angle * shake * shake_noise(seed ^ 0x85eb_ca6b, t);
+        }
+        camera
+    }
+
+    fn base_camera(&self) -> Camera2D {
+        Camera2D {
+            offset: self.viewport * 0.5,
+            target: self.position,
+            rotation: self.rotation,
+            zoom: self.zoom,
+        }
+    }
+
+    fn apply_deadzone(&self, focus: Vector2) -> Vector2 {
+        let delta = focus - self.position;
+        let push = |d: f32, half: f32| {
+            if d > half {
+                d - half
+            } else if d < -half {
+                d + half
+            } else {
+                0.0
+            }
+        };
+        self.position
+            + Vector2::new(
+                push(delta.x, self.deadzone.x),
+                push(delta.y, self.deadzone.y),
+            )
+    }
+
+    fn clamp_to_bounds(&self, center: Vector2) -> Vector2 {
+        let bounds = match self.bounds {
+            Some(b) => b,
+            None => return center,
+        };
+        // Half of the visible area in world units. Rotation widens the visible area to its bounding box.
+        let (sin, cos) = self.rotation.to_radians().sin_cos();
+        let half_w = self.viewport.x * 0.5 / self.zoom;
+        let half_h = self.viewport.y * 0.5 / self.zoom;
+        let half = Vector2::new(
+            half_w * cos.abs() + half_h * sin.abs(),
+            half_w * sin.abs() + half_h * cos.abs(),
+        );
+        let clamp_axis = |c: f32, min: f32, size: f32, half: f32| {
+            if size <= half * 2.0 {
+                min + size * 0.5
+            } else {
+                c.clamp(min + half, min + size - half)
+            }
+        };
+        Vector2::new(
+            clamp_axis(center.x, bounds.x, bounds.width, half.x),
+            clamp_axis(center.y, bounds.y, bounds.height, half.y),
+        )
+    }
+}
+
+/// Smooth 1D value noise in `-1.0..=1.0`, used for camera shake.
+fn shake_noise(seed: u32, t: f32) -> f32 {
+    fn hash(seed: u32, i: i32) -> f32 {
+        let mut h = seed ^ (i as u32).wrapping_mul(0x2710_05a5);
+        h ^= h >> 15;
+        h = h.wrapping_mul(0x2c1b_3c6d);
+        h ^= h >> 12;
+        h = h.wrapping_mul(0x297a_2d39);
+        h ^= h >> 15;
+        (h as f32 / u32::MAX as f32) * 2.0 - 1.0
+    }
+    let i = t.floor();
+    let f = t - i;
+    let u = f * f * (3.0 - 2.0 * f);
+    let a = hash(seed, i as i32);
+    let b = hash(seed, i as i32 + 1);
+    a + (b - a) * u
+}
@@ -0,0 +1,1415 @@
+//! A 2D drawing API shared by GPU draw handles and CPU [`Image`]s
+//!
+//! [`RaylibCanvas`] is implemented for everything that implements [`RaylibDraw`], where each
+//! call forwards to the matching raylib draw function, and for [`ImageCanvas`], which rasterizes
+//! on the CPU with anti-aliasing, blend modes and a clipping rectangle. The same drawing code
+//! can then render to the screen, or into an image without a window or GPU.
+//!
+//! ```no_run
+//! use raylib::prelude::*;
+//!
+//! fn badge<C: RaylibCanvas>(c: &mut C) {
+//!     c.fill_rect_rounded(Rectangle::new(4.0, 4.0, 56.0, 56.0), 0.3, 0, Color::GOLD);
+//!     c.stroke_circle(Vector2::new(32.0, 32.0), 18.0, 3.0, Color::BROWN);
+//! }
+//!
+//! let mut thumbnail = Image::gen_image_color(64, 64, Color::BLANK);
+//! badge(&mut thumbnail.canvas());
+//! thumbnail.export_image("badge.png");
+//! ```
+use crate::consts::PixelFormat;
+use crate::core::color::Color;
+use crate::core::drawing::RaylibDraw;
+use crate::core::math::{Rectangle, Vector2};
+use crate::core::texture::{Image, WeakTexture2D};
+use crate::ffi;
+use std::f32::consts::{PI, TAU};
+use std::ffi::CString;
+
+/// Number of line segments each spline segment is flattened into, same as raylib.
+const SPLINE_SEGMENT_DIVISIONS: usize = 24;
+
+/// 2D drawing onto either the GPU or an [`ImageCanvas`].
+///
+/// Angles are in degrees. Methods mirror [`RaylibDraw`], but are named `fill_*`/`stroke_*` so
+/// both traits can be in scope at once.
+pub trait RaylibCanvas {
+    /// What [`RaylibCanvas::fill_texture_pro`] draws from: a GPU texture or a CPU image.
+    type Texture;
+
+    /// Fills the whole canvas (the clipping rectangle on an [`ImageCanvas`]) with `color`.
+    fn fill_background(&mut self, color: impl Into<ffi::Color>);
+
+    /// Draws a single pixel.
+    fn plot(&mut self, position: impl Into<ffi::Vector2>, color: impl Into<ffi::Color>);
+
+    /// Draws a line with thickness.
+    fn stroke_line(
+        &mut self,
+        start: impl Into<ffi::Vector2>,
+        end: impl Into<ffi::Vector2>,
+        thick: f32,
+        color: impl Into<ffi::Color>,
+    );
+
+    /// Draws connected lines through `points`.
+    fn stroke_polyline(&mut self, points: &[Vector2], thick: f32, color: impl Into<ffi::Color>);
+
+    /// Draws a B-Spline through `points`, minimum 4 points.
+    fn stroke_spline_basis(&mut self, points: &[Vector2], thick: f32, color: impl Into<ffi::Color>);
+
+    /// Draws a Catmull-Rom spline through `points`, minimum 4 points.
+    fn stroke_spline_catmull_rom(
+        &mut self,
+        points: &[Vector2],
+        thick: f32,
+        color: impl Into<ffi::Color>,
+    );
+
+    /// Draws quadratic Bezier curves, minimum 3 points: `[p1, c2, p3, c4, p5, ...]`.
+    fn stroke_spline_bezier_quadratic(
+        &mut self,
+        points: &[Vector2],
+        thick: f32,
+        color: impl Into<ffi::Color>,
+    );
+
+    /// Draws cubic Bezier curves, minimum 4 points: `[p1, c2, c3, p4, c5, c6, p7, ...]`.
+    fn stroke_spline_bezier_cubic(
+        &mut self,
+        points: &[Vector2],
+        thick: f32,
+        color: impl Into<ffi::Color>,
+    );
+
+    /// Fills a rectangle.
+    fn fill_rect(&mut self, rec: impl Into<ffi::Rectangle>, color: impl Into<ffi::Color>);
+
+    /// Fills a rectangle rotated by `rotation` around `origin`, which is relative to `rec`.
+    fn fill_rect_pro(
+        &mut self,
+        rec: impl Into<ffi::Rectangle>,
+        origin: impl Into<ffi::Vector2>,
+        rotation: f32,
+        color: impl Into<ffi::Color>,
+    );
+
+    /// Fills a rectangle with a color per corner, counter-clockwise from the top-left.
+    fn fill_rect_gradient(
+        &mut self,
+        rec: impl Into<ffi::Rectangle>,
+        top_left: impl Into<ffi::Color>,
+        bottom_left: impl Into<ffi::Color>,
+        bottom_right: impl Into<ffi::Color>,
+        top_right: impl Into<ffi::Color>,
+    );
+
+    /// Fills a rectangle with a vertical gradient.
+    fn fill_rect_gradient_v(
+        &mut self,
+        rec: impl Into<ffi::Rectangle>,
+        top: impl Into<ffi::Color>,
+        bottom: impl Into<ffi::Color>,
+    ) {
+        let (top, bottom) = (to_color(top), to_color(bottom));
+        self.fill_rect_gradient(rec, top, bottom, bottom, top)
+    }
+
+    /// Fills a rectangle with a horizontal gradient.
+    fn fill_rect_gradient_h(
+        &mut self,
+        rec: impl Into<ffi::Rectangle>,
+        left: impl Into<ffi::Color>,
+        right: impl Into<ffi::Color>,
+    ) {
+        let (left, right) = (to_color(left), to_color(right));
+        self.fill_rect_gradient(rec, left, left, right, right)
+    }
+
+    /// Draws a rectangle outline `thick` pixels wide, inside `rec`.
+    fn stroke_rect(
+        &mut self,
+        rec: impl Into<ffi::Rectangle>,
+        thick: f32,
+        color: impl Into<ffi::Color>,
+    );
+
+    /// Fills a rectangle with rounded corners. `roundness` goes from 0 (square) to 1 and
+    /// `segments` below 4 picks a count based on the radius.
+    fn fill_rect_rounded(
+        &mut self,
+        rec: impl Into<ffi::Rectangle>,
+        roundness: f32,
+        segments: i32,
+        color: impl Into<ffi::Color>,
+    );
+
+    /// Draws the outline of a rounded rectangle `thick` pixels wide, outside `rec`.
+    fn stroke_rect_rounded(
+        &mut self,
+        rec: impl Into<ffi::Rectangle>,
+        roundness: f32,
+        segments: i32,
+        thick: f32,
+        color: impl Into<ffi::Color>,
+    );
+
+    /// Fills a circle.
+    fn fill_circle(
+        &mut self,
+        center: impl Into<ffi::Vector2>,
+        radius: f32,
+        color: impl Into<ffi::Color>,
+    );
+
+    /// Fills a circle going from `inner` at the center to `outer` at the edge.
+    fn fill_circle_gradient(
+        &mut self,
+        center: impl Into<ffi::Vector2>,
+        radius: f32,
+        inner: impl Into<ffi::Color>,
+        outer: impl Into<ffi::Color>,
+    );
+
+    /// Draws a circle outline `thick` pixels wide, centered on `radius`.
+    fn stroke_circle(
+        &mut self,
+        center: impl Into<ffi::Vector2>,
+        radius: f32,
+        thick: f32,
+        color: impl Into<ffi::Color>,
+    ) {
+        let half = thick * 0.5;
+        self.fill_ring(center, radius - half, radius + half, 0.0, 360.0, 0, color)
+    }
+
+    /// Fills an ellipse.
+    fn fill_ellipse(
+        &mut self,
+        center: impl Into<ffi::Vector2>,
+        radius_h: f32,
+        radius_v: f32,
+        color: impl Into<ffi::Color>,
+    );
+
+    /// Fills a ring or a piece of one. `segments` below 4 picks a count based on the radius.
+    fn fill_ring(
+        &mut self,
+        center: impl Into<ffi::Vector2>,
+        inner_radius: f32,
+        outer_radius: f32,
+        start_angle: f32,
+        end_angle: f32,
+        segments: i32,
+        color: impl Into<ffi::Color>,
+    );
+
+    /// Fills a triangle. Unlike [`RaylibDraw::draw_triangle`], any vertex order works.
+    fn fill_triangle(
+        &mut self,
+        v1: impl Into<ffi::Vector2>,
+        v2: impl Into<ffi::Vector2>,
+        v3: impl Into<ffi::Vector2>,
+        color: impl Into<ffi::Color>,
+    );
+
+    /// Fills a regular polygon.
+    fn fill_poly(
+        &mut self,
+        center: impl Into<ffi::Vector2>,
+        sides: i32,
+        radius: f32,
+        rotation: f32,
+        color: impl Into<ffi::Color>,
+    );
+
+    /// Draws a regular polygon outline `thick` pixels wide, inside `radius`.
+    fn stroke_poly(
+        &mut self,
+        center: impl Into<ffi::Vector2>,
+        sides: i32,
+        radius: f32,
+        rotation: f32,
+        thick: f32,
+        color: impl Into<ffi::Color>,
+    );
+
+    /// Draws the `source` part of `texture` into `dest`, rotated by `rotation` around `origin`.
+    /// A negative source width or height flips the texture.
+    fn fill_texture_pro(
+        &mut self,
+        texture: &Self::Texture,
+        source: impl Into<ffi::Rectangle>,
+        dest: impl Into<ffi::Rectangle>,
+        origin: impl Into<ffi::Vector2>,
+        rotation: f32,
+        tint: impl Into<ffi::Color>,
+    );
+
+    /// Draws text with the default font. NUL characters in `text` are skipped.
+    /// On an [`ImageCanvas`] this needs the window to be initialized, since that loads the font;
+    /// before that nothing is drawn.
+    fn fill_text(
+        &mut self,
+        text: &str,
+        position: impl Into<ffi::Vector2>,
+        font_size: i32,
+        color: impl Into<ffi::Color>,
+    );
+}
+
+impl<D: RaylibDraw> RaylibCanvas for D {
+    type Texture = ffi::Texture2D;
+
+    fn fill_background(&mut self, color: impl Into<ffi::Color>) {
+        self.clear_background(color)
+    }
+
+    fn plot(&mut self, position: impl Into<ffi::Vector2>, color: impl Into<ffi::Color>) {
+        self.draw_pixel_v(position, color)
+    }
+
+    fn stroke_line(
+        &mut self,
+        start: impl Into<ffi::Vector2>,
+        end: impl Into<ffi::Vector2>,
+        thick: f32,
+        color: impl Into<ffi::Color>,
+    ) {
+        self.draw_line_ex(start, end, thick, color)
+    }
+
+    fn stroke_polyline(&mut self, points: &[Vector2], thick: f32, color: impl Into<ffi::Color>) {
+        self.draw_spline_linear(points, thick, color)
+    }
+
+    fn stroke_spline_basis(
+        &mut self,
+        points: &[Vector2],
+        thick: f32,
+        color: impl Into<ffi::Color>,
+    ) {
+        self.draw_spline_basis(points, thick, color)
+    }
+
+    fn stroke_spline_catmull_rom(
+        &mut self,
+        points: &[Vector2],
+        thick: f32,
+        color: impl Into<ffi::Color>,
+    ) {
+        self.draw_spline_catmull_rom(points, thick, color)
+    }
+
+    fn stroke_spline_bezier_quadratic(
+        &mut self,
+        points: &[Vector2],
+        thick: f32,
+        color: impl Into<ffi::Color>,
+    ) {
+        self.draw_spline_bezier_quadratic(points, thick, color)
+    }
+
+    fn stroke_spline_bezier_cubic(
+        &mut self,
+        points: &[Vector2],
+        thick: f32,
+        color: impl Into<ffi::Color>,
+    ) {
+        self.draw_spline_bezier_cubic(points, thick, color)
+    }
+
+    fn fill_rect(&mut self, rec: impl Into<ffi::Rectangle>, color: impl Into<ffi::Color>) {
+        self.draw_rectangle_rec(rec, color)
+    }
+
+    fn fill_rect_pro(
+        &mut self,
+        rec: impl Into<ffi::Rectangle>,
+        origin: impl Into<ffi::Vector2>,
+        rotation: f32,
+        color: impl Into<ffi::Color>,
+    ) {
+        self.draw_rectangle_pro(rec, origin, rotation, color)
+    }
+
+    fn fill_rect_gradient(
+        &mut self,
+        rec: impl Into<ffi::Rectangle>,
+        top_left: impl Into<ffi::Color>,
+        bottom_left: impl Into<ffi::Color>,
+        bottom_right: impl Into<ffi::Color>,
+        top_right: impl Into<ffi::Color>,
+    ) {
+        self.draw_rectangle_gradient_ex(rec, top_left, bottom_left, bottom_right, top_right)
+    }
+
+    fn stroke_rect(
+        &mut self,
+        rec: impl Into<ffi::Rectangle>,
+        thick: f32,
+        color: impl Into<ffi::Color>,
+    ) {
+        self.draw_rectangle_lines_ex(rec, thick, color)
+    }
+
+    fn fill_rect_rounded(
+        &mut self,
+        rec: impl Into<ffi::Rectangle>,
+        roundness: f32,
+        segments: i32,
+        color: impl Into<ffi::Color>,
+    ) {
+        self.draw_rectangle_rounded(rec, roundness, segments, color)
+    }
+
+    fn stroke_rect_rounded(
+        &mut self,
+        rec: impl Into<ffi::Rectangle>,
+        roundness: f32,
+        segments: i32,
+        thick: f32,
+        color: impl Into<ffi::Color>,
+    ) {
+        self.draw_rectangle_rounded_lines_ex(rec, roundness, segments, thick, color)
+    }
+
+    fn fill_circle(
+        &mut self,
+        center: impl Into<ffi::Vector2>,
+        radius: f32,
+        color: impl Into<ffi::Color>,
+    ) {
+        self.draw_circle_v(center, radius, color)
+    }
+
+    fn fill_circle_gradient(
+        &mut self,
+        center: impl Into<ffi::Vector2>,
+        radius: f32,
+        inner: impl Into<ffi::Color>,
+        outer: impl Into<ffi::Color>,
+    ) {
+        let center = to_v2(center);
+        self.draw_circle_gradient(center.x as i32, center.y as i32, radius, inner, outer)
+    }
+
+    fn fill_ellipse(
+        &mut self,
+        center: impl Into<ffi::Vector2>,
+        radius_h: f32,
+        radius_v: f32,
+        color: impl Into<ffi::Color>,
+    ) {
+        let center = to_v2(center);
+        self.draw_ellipse(center.x as i32, center.y as i32, radius_h, radius_v, color)
+    }
+
+    fn fill_ring(
+        &mut self,
+        center: impl Into<ffi::Vector2>,
+        inner_radius: f32,
+        outer_radius: f32,
+        start_angle: f32,
+        end_angle: f32,
+        segments: i32,
+        color: impl Into<ffi::Color>,
+    ) {
+        self.draw_ring(
+            center,
+            inner_radius,
+            outer_radius,
+            start_angle,
+            end_angle,
+            segments,
+            color,
+        )
+    }
+
+    fn fill_triangle(
+        &mut self,
+        v1: impl Into<ffi::Vector2>,
+        v2: impl Into<ffi::Vector2>,
+        v3: impl Into<ffi::Vector2>,
+        color: impl Into<ffi::Color>,
+    ) {
+        let (v1, v2, v3) = (to_v2(v1), to_v2(v2), to_v2(v3));
+        // raylib only draws counter-clockwise triangles
+        if cross(v1, v2, v3) > 0.0 {
+            self.draw_triangle(v1, v3, v2, color)
+        } else {
+            self.draw_triangle(v1, v2, v3, color)
+        }
+    }
+
+    fn fill_poly(
+        &mut self,
+        center: impl Into<ffi::Vector2>,
+        sides: i32,
+        radius: f32,
+        rotation: f32,
+        color: impl Into<ffi::Color>,
+    ) {
+        self.draw_poly(center, sides, radius, rotation, color)
+    }
+
+    fn stroke_poly(
+        &mut self,
+        center: impl Into<ffi::Vector2>,
+        sides: i32,
+        radius: f32,
+        rotation: f32,
+        thick: f32,
+        color: impl Into<ffi::Color>,
+    ) {
+        self.draw_poly_lines_ex(to_v2(center), sides, radius, rotation, thick, color)
+    }
+
+    fn fill_texture_pro(
+        &mut self,
+        texture: &ffi::Texture2D,
+        source: impl Into<ffi::Rectangle>,
+        dest: impl Into<ffi::Rectangle>,
+        origin: impl Into<ffi::Vector2>,
+        rotation: f32,
+        tint: impl Into<ffi::Color>,
+    ) {
+        self.draw_texture_pro(
+            WeakTexture2D(*texture),
+            source,
+            dest,
+            origin,
+            rotation,
+            tint,
+        )
+    }
+
+    fn fill_text(
+        &mut self,
+        text: &str,
+        position: impl Into<ffi::Vector2>,
+        font_size: i32,
+        color: impl Into<ffi::Color>,
+    ) {
+        let position = to_v2(position);
+        let text = text.replace('\0', "");
+        self.draw_text(
+            &text,
+            position.x as i32,
+            position.y as i32,
+            font_size,
+            color,
+        )
+    }
+}
+
+/// How an [`ImageCanvas`] combines what is drawn with the pixels already in the image.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+pub enum CanvasBlendMode {
+    /// Regular alpha blending
+    #[default]
+    Alpha,
+    /// Adds the color, weighted by its alpha
+    Additive,
+    /// Multiplies the existing color, weighted by the alpha of the new one
+    Multiplied,
+    /// Overwrites the existing pixel, alpha included. Only anti-aliased edges are blended.
+    Replace,
+}
+
+/// An [`Image`] prepared for drawing with [`RaylibCanvas`] on the CPU.
+///
+/// Created by [`Image::canvas`], which converts the image to
+/// [`PIXELFORMAT_UNCOMPRESSED_R8G8B8A8`](PixelFormat::PIXELFORMAT_UNCOMPRESSED_R8G8B8A8).
+/// Images that can't be converted, like compressed ones, are left unchanged by every drawing
+/// function. Anti-aliasing is on, blending is [`CanvasBlendMode::Alpha`] and nothing is clipped by default.
+pub struct ImageCanvas<'a> {
+    image: &'a mut Image,
+    antialias: bool,
+    blend_mode: CanvasBlendMode,
+    clip: Option<Rectangle>,
+    /// Whether the image is RGBA and can be drawn to.
+    writable: bool,
+}
+
+impl Image {
+    /// Gets an [`ImageCanvas`] for drawing into this image with [`RaylibCanvas`].
+    pub fn canvas(&mut self) -> ImageCanvas<'_> {
+        let writable = self.rgba_mut().is_some();
+        ImageCanvas {
+            image: self,
+            antialias: true,
+            blend_mode: CanvasBlendMode::Alpha,
+            clip: None,
+            writable,
+        }
+    }
+}
+
+impl<'a> ImageCanvas<'a> {
+    /// Enables or disables anti-aliased edges.
+    pub fn set_antialias(&mut self, antialias: bool) -> &mut Self {
+        self.antialias = antialias;
+        self
+    }
+
+    pub fn antialias(&self) -> bool {
+        self.antialias
+    }
+
+    pub fn set_blend_mode(&mut self, blend_mode: CanvasBlendMode) -> &mut Self {
+        self.blend_mode = blend_mode;
+        self
+    }
+
+    pub fn blend_mode(&self) -> CanvasBlendMode {
+        self.blend_mode
+    }
+
+    /// Restricts drawing to `clip`, or to the whole image with `None`.
+    pub fn set_clip(&mut self, clip: Option<Rectangle>) -> &mut Self {
+        self.clip = clip;
+        self
+    }
+
+    pub fn clip(&self) -> Option<Rectangle> {
+        self.clip
+    }
+
+    pub fn image(&self) -> &Image {
+        self.image
+    }
+
+    /// Pixel bounds that can be drawn to, as `(x0, y0, x1, y1)` with exclusive ends. Empty if
+    /// the image isn't RGBA.
+    fn bounds(&self) -> (i32, i32, i32, i32) {
+        if !self.writable {
+            return (0, 0, 0, 0);
+        }
+        let (w, h) = (self.image.width(), self.image.height());
+        match self.clip {
+            None => (0, 0, w, h),
+            Some(c) => (
+                (c.x.ceil() as i32).clamp(0, w),
+                (c.y.ceil() as i32).clamp(0, h),
+                ((c.x + c.width).floor() as i32).clamp(0, w),
+                ((c.y + c.height).floor() as i32).clamp(0, h),
+            ),
+        }
+    }
+
+    fn pixels(&mut self) -> &mut [Color] {
+        match self.image.rgba_mut() {
+            Some((pixels, _, _)) => pixels,
+            None => &mut [],
+        }
+    }
+
+    /// Fills the union of `polygons`, coloring each covered pixel with `shade(x, y)`,
+    /// where `(x, y)` is the center of the pixel.
+    fn fill_polygons(&mut self, polygons: &[Vec<Vector2>], shade: impl Fn(f32, f32) -> Color) {
+        let (bx0, by0, bx1, by1) = self.bounds();
+        let (mut min, mut max) = (
+            Vector2::new(f32::MAX, f32::MAX),
+            Vector2::new(f32::MIN, f32::MIN),
+        );
+        for p in polygons.iter().flatten() {
+            if !p.x.is_finite() || !p.y.is_finite() {
+                return;
+            }
+            min = Vector2::new(min.x.min(p.x), min.y.min(p.y));
+            max = Vector2::new(max.x.max(p.x), max.y.max(p.y));
+        }
+        let x0 = (min.x.floor() as i32).max(bx0);
+        let y0 = (min.y.floor() as i32).max(by0);
+        let x1 = (max.x.ceil() as i32).min(bx1);
+        let y1 = (max.y.ceil() as i32).min(by1);
+        if x0 >= x1 || y0 >= y1 {
+            return;
+        }
+
+        let mut coverage = Coverage::new(x0, y0, (x1 - x0) as usize, (y1 - y0) as usize);
+        for polygon in polygons {
+            coverage.add_polygon(polygon);
+        }
+
+        let (antialias, blend_mode, width) = (self.antialias, self.blend_mode, self.image.width());
+        let pixels = self.pixels();
+        coverage.for_each(|x, y, cov| {
+            let cov = match antialias {
+                true => cov,
+                false if cov >= 0.5 => 1.0,
+                false => return,
+            };
+            let src = shade(x as f32 + 0.5, y as f32 + 0.5);
+            let dst = &mut pixels[(y * width + x) as usize];
+            *dst = blend(*dst, src, cov, blend_mode);
+        });
+    }
+
+    fn fill_solid(&mut self, polygons: &[Vec<Vector2>], color: impl Into<ffi::Color>) {
+        let color = to_color(color);
+        self.fill_polygons(polygons, |_, _| color);
+    }
+
+    fn stroke(&mut self, points: &[Vector2], thick: f32, color: impl Into<ffi::Color>) {
+        let polygons = stroke_polygons(points, thick);
+        self.fill_solid(&polygons, color);
+    }
+}
+
+impl<'a> RaylibCanvas for ImageCanvas<'a> {
+    type Texture = ffi::Image;
+
+    fn fill_background(&mut self, color: impl Into<ffi::Color>) {
+        let color = to_color(color);
+        let (x0, y0, x1, y1) = self.bounds();
+        let x1 = x1.max(x0);
+        let width = self.image.width();
+        let pixels = self.pixels();
+        for y in y0..y1 {
+            let row = (y * width) as usize;
+            pixels[row + x0 as usize..row + x1 as usize].fill(color);
+        }
+    }
+
+    fn plot(&mut self, position: impl Into<ffi::Vector2>, color: impl Into<ffi::Color>) {
+        let p = to_v2(position);
+        let (x, y) = (p.x.floor(), p.y.floor());
+        self.fill_solid(&[rect_polygon(x, y, 1.0, 1.0)], color);
+    }
+
+    fn stroke_line(
+        &mut self,
+        start: impl Into<ffi::Vector2>,
+        end: impl Into<ffi::Vector2>,
+        thick: f32,
+        color: impl Into<ffi::Color>,
+    ) {
+        let polygon = line_polygon(to_v2(start), to_v2(end), thick);
+        self.fill_solid(&[polygon], color);
+    }
+
+    fn stroke_polyline(&mut self, points: &[Vector2], thick: f32, color: impl Into<ffi::Color>) {
+        self.stroke(points, thick, color);
+    }
+
+    fn stroke_spline_basis(
+        &mut self,
+        points: &[Vector2],
+        thick: f32,
+        color: impl Into<ffi::Color>,
+    ) {
+        if points.len() < 4 {
+            return;
+        }
+        let mut path = Vec::new();
+        for p in points.windows(4) {
+            flatten(&mut path, |t| {
+                let t2 = t * t;
+                let t3 = t2 * t;
+                (p[0] * (1.0 - t).powi(3)
+                    + p[1] * (3.0 * t3 - 6.0 * t2 + 4.0)
+                    + p[2] * (-3.0 * t3 + 3.0 * t2 + 3.0 * t + 1.0)
+                    + p[3] * t3)
+                    / 6.0
+            });
+        }
+        self.stroke(&path, thick, color);
+    }
+
+    fn stroke_spline_catmull_rom(
+        &mut self,
+        points: &[Vector2],
+        thick: f32,
+        color: impl Into<ffi::Color>,
+    ) {
+        if points.len() < 4 {
+            return;
+        }
+        let mut path = Vec::new();
+        for p in points.windows(4) {
+            flatten(&mut path, |t| {
+                let t2 = t * t;
+                let t3 = t2 * t;
+                (p[1] * 2.0
+                    + (p[2] - p[0]) * t
+                    + (p[0] * 2.0 - p[1] * 5.0 + p[2] * 4.0 - p[3]) * t2
+                    + (p[1] * 3.0 - p[0] - p[2] * 3.0 + p[3]) * t3)
+                    * 0.5
+            });
+        }
+        self.stroke(&path, thick, color);
+    }
+
+    fn stroke_spline_bezier_quadratic(
+        &mut self,
+        points: &[Vector2],
+        thick: f32,
+        color: impl Into<ffi::Color>,
+    ) {
+        let mut path = Vec::new();
+        let mut i = 0;
+        while i + 2 < points.len() {
+            let p = &points[i..i + 3];
+            flatten(&mut path, |t| {
+                let u = 1.0 - t;
+                p[0] * (u * u) + p[1] * (2.0 * u * t) + p[2] * (t * t)
+            });
+            i += 2;
+        }
+        self.stroke(&path, thick, color);
+    }
+
+    fn stroke_spline_bezier_cubic(
+        &mut self,
+        points: &[Vector2],
+        thick: f32,
+        color: impl Into<ffi::Color>,
+    ) {
+        let mut path = Vec::new();
+        let mut i = 0;
+        while i + 3 < points.len() {
+            let p = &points[i..i + 4];
+            flatten(&mut path, |t| {
+                let u = 1.0 - t;
+                p[0] * (u * u * u)
+                    + p[1] * (3.0 * u * u * t)
+                    + p[2] * (3.0 * u * t * t)
+                    + p[3] * (t * t * t)
+            });
+            i += 3;
+        }
+        self.stroke(&path, thick, color);
+    }
+
+    fn fill_rect(&mut self, rec: impl Into<ffi::Rectangle>, color: impl Into<ffi::Color>) {
+        let r = to_rect(rec);
+        self.fill_solid(&[rect_polygon(r.x, r.y, r.width, r.height)], color);
+    }
+
+    fn fill_rect_pro(
+        &mut self,
+        rec: impl Into<ffi::Rectangle>,
+        origin: impl Into<ffi::Vector2>,
+        rotation: f32,
+        color: impl Into<ffi::Color>,
+    ) {
+        let quad = rotated_rect(to_rect(rec), to_v2(origin), rotation);
+        self.fill_solid(&[quad.to_vec()], color);
+    }
+
+    fn fill_rect_gradient(
+        &mut self,
+        rec: impl Into<ffi::Rectangle>,
+        top_left: impl Into<ffi::Color>,
+        bottom_left: impl Into<ffi::Color>,
+        bottom_right: impl Into<ffi::Color>,
+        top_right: impl Into<ffi::Color>,
+    ) {
+        let r = to_rect(rec);
+        let tl = to_color(top_left);
+        let bl = to_color(bottom_left);
+        let br = to_color(bottom_right);
+        let tr = to_color(top_right);
+        self.fill_polygons(&[rect_polygon(r.x, r.y, r.width, r.height)], |x, y| {
+            let u = ((x - r.x) / r.width).clamp(0.0, 1.0);
+            let v = ((y - r.y) / r.height).clamp(0.0, 1.0);
+            lerp_color(lerp_color(tl, tr, u), lerp_color(bl, br, u), v)
+        });
+    }
+
+    fn stroke_rect(
+        &mut self,
+        rec: impl Into<ffi::Rectangle>,
+        thick: f32,
+        color: impl Into<ffi::Color>,
+    ) {
+        let r = to_rect(rec);
+        let t = thick.min(r.width * 0.5).min(r.height * 0.5);
+        let outer = rect_polygon(r.x, r.y, r.width, r.height);
+        let inner = rect_polygon(r.x + t, r.y + t, r.width - 2.0 * t, r.height - 2.0 * t);
+        self.fill_solid(&[with_hole(outer, inner)], color);
+    }
+
+    fn fill_rect_rounded(
+        &mut self,
+        rec: impl Into<ffi::Rectangle>,
+        roundness: f32,
+        segments: i32,
+        color: impl Into<ffi::Color>,
+    ) {
+        let r = to_rect(rec);
+        let radius = rounded_radius(&r, roundness);
+        self.fill_solid(&[rounded_rect_polygon(&r, radius, segments)], color);
+    }
+
+    fn stroke_rect_rounded(
+        &mut self,
+        rec: impl Into<ffi::Rectangle>,
+        roundness: f32,
+        segments: i32,
+        thick: f32,
+        color: impl Into<ffi::Color>,
+    ) {
+        let r = to_rect(rec);
+        let radius = rounded_radius(&r, roundness);
+        let outer_rec = Rectangle::new(
+            r.x - thick,
+            r.y - thick,
+            r.width + 2.0 * thick,
+            r.height + 2.0 * thick,
+        );
+        let outer = rounded_rect_polygon(&outer_rec, radius + thick, segments);
+        let inner = rounded_rect_polygon(&r, radius, segments);
+        self.fill_solid(&[with_hole(outer, inner)], color);
+    }
+
+    fn fill_circle(
+        &mut self,
+        center: impl Into<ffi::Vector2>,
+        radius: f32,
+        color: impl Into<ffi::Color>,
+    ) {
+        let polygon = ellipse_polygon(to_v2(center), radius, radius);
+        self.fill_solid(&[polygon], color);
+    }
+
+    fn fill_circle_gradient(
+        &mut self,
+        center: impl Into<ffi::Vector2>,
+        radius: f32,
+        inner: impl Into<ffi::Color>,
+        outer: impl Into<ffi::Color>,
+    ) {
+        let center = to_v2(center);
+        let inner = to_color(inner);
+        let outer = to_color(outer);
+        let polygon = ellipse_polygon(center, radius, radius);
+        self.fill_polygons(&[polygon], |x, y| {
+            let t = (Vector2::new(x, y).distance_to(center) / radius).min(1.0);
+            lerp_color(inner, outer, t)
+        });
+    }
+
+    fn fill_ellipse(
+        &mut self,
+        center: impl Into<ffi::Vector2>,
+        radius_h: f32,
+        radius_v: f32,
+        color: impl Into<ffi::Color>,
+    ) {
+        let polygon = ellipse_polygon(to_v2(center), radius_h, radius_v);
+        self.fill_solid(&[polygon], color);
+    }
+
+    fn fill_ring(
+        &mut self,
+        center: impl Into<ffi::Vector2>,
+        inner_radius: f32,
+        outer_radius: f32,
+        start_angle: f32,
+        end_angle: f32,
+        segments: i32,
+        color: impl Into<ffi::Color>,
+    ) {
+        let center = to_v2(center);
+        let inner_radius = inner_radius.max(0.0);
+        let outer_radius = outer_radius.max(inner_radius);
+        let sweep = (end_angle - start_angle).to_radians();
+        let segments = if segments >= 4 {
+            segments as usize
+        } else {
+            arc_segments(outer_radius, sweep.abs())
+        };
+        let start = start_angle.to_radians();
+        let arc = |radius: f32, i: usize| {
+            let a = start + sweep * i as f32 / segments as f32;
+            center + Vector2::new(a.cos(), a.sin()) * radius
+        };
+        let mut polygon: Vec<Vector2> = (0..=segments).map(|i| arc(outer_radius, i)).collect();
+        polygon.extend((0..=segments).rev().map(|i| arc(inner_radius, i)));
+        self.fill_solid(&[polygon], color);
+    }
+
+    fn fill_triangle(
+        &mut self,
+        v1: impl Into<ffi::Vector2>,
+        v2: impl Into<ffi::Vector2>,
+        v3: impl Into<ffi::Vector2>,
+        color: impl Into<ffi::Color>,
+    ) {
+        let polygon = vec![to_v2(v1), to_v2(v2), to_v2(v3)];
+        self.fill_solid(&[polygon], color);
+    }
+
+    fn fill_poly(
+        &mut self,
+        center: impl Into<ffi::Vector2>,
+        sides: i32,
+        radius: f32,
+        rotation: f32,
+        color: impl Into<ffi::Color>,
+    ) {
+        let polygon = regular_polygon(to_v2(center), sides, radius, rotation);
+        self.fill_solid(&[polygon], color);
+    }
+
+    fn stroke_poly(
+        &mut self,
+        center: impl Into<ffi::Vector2>,
+        sides: i32,
+        radius: f32,
+        rotation: f32,
+        thick: f32,
+        color: impl Into<ffi::Color>,
+    ) {
+        let center = to_v2(center);
+        let sides = sides.max(3);
+        let inner_radius = (radius - thick * (PI / sides as f32).cos()).max(0.0);
+        let outer = regular_polygon(center, sides, radius, rotation);
+        let inner = regular_polygon(center, sides, inner_radius, rotation);
+        self.fill_solid(&[with_hole(outer, inner)], color);
+    }
+
+    fn fill_texture_pro(
+        &mut self,
+        texture: &ffi::Image,
+        source: impl Into<ffi::Rectangle>,
+        dest: impl Into<ffi::Rectangle>,
+        origin: impl Into<ffi::Vector2>,
+        rotation: f32,
+        tint: impl Into<ffi::Color>,
+    ) {
+        let source = to_rect(source);
+        let dest = to_rect(dest);
+        let origin = to_v2(origin);
+        let tint = to_color(tint);
+        if dest.width <= 0.0 || dest.height <= 0.0 || texture.data.is_null() {
+            return;
+        }
+        let (flip_x, flip_y) = (source.width < 0.0, source.height < 0.0);
+        let (src_w, src_h) = (source.width.abs(), source.height.abs());
+        let sampler = Sampler::new(texture);
+        let (sin, cos) = (-rotation).to_radians().sin_cos();
+
+        let quad = rotated_rect(dest, origin, rotation);
+        self.fill_polygons(&[quad.to_vec()], |x, y| {
+            // back into the unrotated destination rectangle
+            let (dx, dy) = (x - dest.x, y - dest.y);
+            let lx = dx * cos - dy * sin + origin.x;
+            let ly = dx * sin + dy * cos + origin.y;
+            let mut u = (lx / dest.width).clamp(0.0, 1.0);
+            let mut v = (ly / dest.height).clamp(0.0, 1.0);
+            if flip_x {
+                u = 1.0 - u;
+            }
+            if flip_y {
+                v = 1.0 - v;
+            }
+            let texel = sampler.get(source.x + u * src_w, source.y + v * src_h);
+            modulate(texel, tint)
+        });
+    }
+
+    fn fill_text(
+        &mut self,
+        text: &str,
+        position: impl Into<ffi::Vector2>,
+        font_size: i32,
+        color: impl Into<ffi::Color>,
+    ) {
+        // the default font is loaded by InitWindow, ImageText would read its missing glyphs
+        if unsafe { ffi::GetFontDefault() }.glyphs.is_null() {
+            return;
+        }
+        let position = to_v2(position);
+        let c_text = CString::new(text.replace('\0', "")).unwrap();
+        let rendered = Image(unsafe { ffi::ImageText(c_text.as_ptr(), font_size, color.into()) });
+        let (w, h) = (rendered.width() as f32, rendered.height() as f32);
+        // text is already drawn in its color, only the coverage is blended in
+        self.fill_texture_pro(
+            &rendered.0,
+            Rectangle::new(0.0, 0.0, w, h),
+            Rectangle::new(position.x.floor(), position.y.floor(), w, h),
+            Vector2::zero(),
+            0.0,
+            Color::WHITE,
+        );
+    }
+}
+
+/// Nearest-neighbour texel lookup into any image format.
+struct Sampler<'a> {
+    image: &'a ffi::Image,
+    rgba: Option<&'a [Color]>,
+}
+
+impl<'a> Sampler<'a> {
+    fn new(image: &'a ffi::Image) -> Self {
+        let rgba = if image.format == PixelFormat::PIXELFORMAT_UNCOMPRESSED_R8G8B8A8 as i32 {
+            Some(unsafe {
+                std::slice::from_raw_parts(
+                    image.data as *const Color,
+                    (image.width * image.height) as usize,
+                )
+            })
+        } else {
+            None
+        };
+        Sampler { image, rgba }
+    }
+
+    fn get(&self, x: f32, y: f32) -> Color {
+        let x = (x.floor() as i32).clamp(0, self.image.width - 1);
+        let y = (y.floor() as i32).clamp(0, self.image.height - 1);
+        match self.rgba {
+            Some(pixels) => pixels[(y * self.image.width + x) as usize],
+            None => unsafe { ffi::GetImageColor(*self.image, x, y) }.into(),
+        }
+    }
+}
+
+/// Signed-area coverage accumulation over a window of the image.
+///
+/// Every edge adds the area it covers to the cells it crosses, with a sign depending on its
+/// direction; a running sum along each row then gives the exact coverage of each pixel.
+/// Polygons are normalized to the same orientation, so overlapping ones add up (and are clamped)
+/// while a hole traced the opposite way inside one polygon cancels out.
+struct Coverage {
+    x0: i32,
+    y0: i32,
+    width: usize,
+    height: usize,
+    stride: usize,
+    acc: Vec<f32>,
+}
+
+impl Coverage {
+    fn new(x0: i32, y0: i32, width: usize, height: usize) -> Self {
+        // two extra cells per row catch the spill-over of edges on the right border
+        let stride = width + 2;
+        Coverage {
+            x0,
+            y0,
+            width,
+            height,
+            stride,
+            acc: vec![0.0; stride * height],
+        }
+    }
+
+    fn add_polygon(&mut self, polygon: &[Vector2]) {
+        if polygon.len() < 3 {
+            return;
+        }
+        let area: f32 = polygon
+            .iter()
+            .zip(polygon.iter().cycle().skip(1))
+            .map(|(a, b)| a.x * b.y - b.x * a.y)
+            .sum();
+        let n = polygon.len();
+        for i in 0..n {
+            let (a, b) = (polygon[i], polygon[(i + 1) % n]);
+            if area >= 0.0 {
+                self.add_line(a, b);
+            } else {
+                self.add_line(b, a);
+            }
+        }
+    }
+
+    /// Adds an edge, clipping it horizontally: the parts outside the window are pushed onto
+    /// its left or right border, where they still count for the pixels in between.
+    fn add_line(&mut self, a: Vector2, b: Vector2) {
+        let a = Vector2::new(a.x - self.x0 as f32, a.y - self.y0 as f32);
+        let b = Vector2::new(b.x - self.x0 as f32, b.y - self.y0 as f32);
+        let w = self.width as f32;
+
+        let mut ts = [0.0, 1.0, 1.0, 1.0];
+        let mut n = 1;
+        for edge in [0.0, w] {
+            if (a.x - edge) * (b.x - edge) < 0.0 {
+                ts[n] = (edge - a.x) / (b.x - a.x);
+                n += 1;
+            }
+        }
+        ts[n] = 1.0;
+        ts[1..n].sort_by(|l, r| l.partial_cmp(r).unwrap());
+
+        for i in 0..n {
+            let (t0, t1) = (ts[i], ts[i + 1]);
+            let p = a.lerp(b, t0);
+            let q = a.lerp(b, t1);
+            self.raster_line(
+                Vector2::new(p.x.clamp(0.0, w), p.y),
+                Vector2::new(q.x.clamp(0.0, w), q.y),
+            );
+        }
+    }
+
+    fn raster_line(&mut self, p0: Vector2, p1: Vector2) {
+        if (p0.y - p1.y).abs() <= f32::EPSILON {
+            return;
+        }
+        let (dir, p0, p1) = if p0.y < p1.y {
+            (1.0, p0, p1)
+        } else {
+            (-1.0, p1, p0)
+        };
+        let dxdy = (p1.x - p0.x) / (p1.y - p0.y);
+        let w = self.width as f32;
+        let mut x = p0.x;
+        if p0.y < 0.0 {
+            x = (x - p0.y * dxdy).clamp(0.0, w);
+        }
+        let y_start = p0.y.max(0.0) as usize;
+        let y_end = self.height.min(p1.y.ceil().max(0.0) as usize);
+        for y in y_start..y_end {
+            let row = y * self.stride;
+            let dy = ((y + 1) as f32).min(p1.y) - (y as f32).max(p0.y);
+            let x_next = (x + dxdy * dy).clamp(0.0, w);
+            let d = dy * dir;
+            let (xa, xb) = if x < x_next { (x, x_next) } else { (x_next, x) };
+            let xa_floor = xa.floor();
+            let xai = xa_floor as usize;
+            let xb_ceil = xb.ceil();
+            let xbi = xb_ceil as usize;
+            if xbi <= xai + 1 {
+                // the edge stays within one cell
+                let xmf = 0.5 * (x + x_next) - xa_floor;
+                self.acc[row + xai] += d - d * xmf;
+                self.acc[row + xai + 1] += d * xmf;
+            } else {
+                let s = (xb - xa).recip();
+                let xaf = xa - xa_floor;
+                let a0 = 0.5 * s * (1.0 - xaf) * (1.0 - xaf);
+                let xbf = xb - xb_ceil + 1.0;
+                let am = 0.5 * s * xbf * xbf;
+                self.acc[row + xai] += d * a0;
+                if xbi == xai + 2 {
+                    self.acc[row + xai + 1] += d * (1.0 - a0 - am);
+                } else {
+                    let a1 = s * (1.5 - xaf);
+                    self.acc[row + xai + 1] += d * (a1 - a0);
+                    for xi in xai + 2..xbi - 1 {
+                        self.acc[row + xi] += d * s;
+                    }
+                    let a2 = a1 + (xbi - xai - 3) as f32 * s;
+                    self.acc[row + xbi - 1] += d * (1.0 - a2 - am);
+                }
+                self.acc[row + xbi] += d * am;
+            }
+            x = x_next;
+        }
+    }
+
+    /// Calls `f(x, y, coverage)` in image coordinates for every pixel with some coverage.
+    fn for_each(&self, mut f: impl FnMut(i32, i32, f32)) {
+        for y in 0..self.height {
+            let row = &self.acc[y * self.stride..y * self.stride + self.width];
+            let mut sum = 0.0;
+            for (x, a) in row.iter().enumerate() {
+                sum += a;
+                let cov = sum.abs().min(1.0);
+                if cov > 1.0 / 512.0 {
+                    f(self.x0 + x as i32, self.y0 + y as i32, cov);
+                }
+            }
+        }
+    }
+}
+
+fn blend(dst: Color, src: Color, coverage: f32, mode: CanvasBlendMode) -> Color {
+    let to_u8 = |v: f32| (v * 255.0).round().clamp(0.0, 255.0) as u8;
+    let norm = |c: Color| {
+        [
+            c.r as f32 / 255.0,
+            c.g as f32 / 255.0,
+            c.b as f32 / 255.0,
+            c.a as f32 / 255.0,
+        ]
+    };
+    let (d, s) = (norm(dst), norm(src));
+    let sa = s[3] * coverage;
+    let out = match mode {
+        CanvasBlendMode::Alpha => {
+            let a = sa + d[3] * (1.0 - sa);
+            if a <= 0.0 {
+                [0.0; 4]
+            } else {
+                let mix = |i: usize| (s[i] * sa + d[i] * d[3] * (1.0 - sa)) / a;
+                [mix(0), mix(1), mix(2), a]
+            }
+        }
+        CanvasBlendMode::Additive => {
+            let add = |i: usize| d[i] + s[i] * sa;
+            [add(0), add(1), add(2), sa + d[3] * (1.0 - sa)]
+        }
+        CanvasBlendMode::Multiplied => {
+            let mul = |i: usize| d[i] * (s[i] * sa + 1.0 - sa);
+            [mul(0), mul(1), mul(2), d[3]]
+        }
+        CanvasBlendMode::Replace => {
+            let mix = |i: usize| d[i] + (s[i] - d[i]) * coverage;
+            [mix(0), mix(1), mix(2), mix(3)]
+        }
+    };
+    Color::new(to_u8(out[0]), to_u8(out[1]), to_u8(out[2]), to_u8(out[3]))
+}
+
+fn lerp_color(a: Color, b: Color, t: f32) -> Color {
+    let l = |x: u8, y: u8| (x as f32 + (y as f32 - x as f32) * t).round() as u8;
+    Color::new(l(a.r, b.r), l(a.g, b.g), l(a.b, b.b), l(a.a, b.a))
+}
+
+fn modulate(a: Color, b: Color) -> Color {
+    let m = |x: u8, y: u8| ((x as u32 * y as u32 + 127) / 255) as u8;
+    Color::new(m(a.r, b.r), m(a.g, b.g), m(a.b, b.b), m(a.a, b.a))
+}
+
+fn to_color(color: impl Into<ffi::Color>) -> Color {
+    let color: ffi::Color = color.into();
+    color.into()
+}
+
+fn to_v2(v: impl Into<ffi::Vector2>) -> Vector2 {
+    let v: ffi::Vector2 = v.into();
+    v.into()
+}
+
+fn to_rect(r: impl Into<ffi::Rectangle>) -> Rectangle {
+    let r: ffi::Rectangle = r.into();
+    r.into()
+}
+
+fn cross(a: Vector2, b: Vector2, c: Vector2) -> f32 {
+    (b.x - a.x) * (c.y - a.y) - (b.y - a.y) * (c.x - a.x)
+}
+
+/// Segments for an arc of `sweep` radians, keeping each segment around two pixels long.
+fn arc_segments(radius: f32, sweep: f32) -> usize {
+    ((radius * sweep / 2.0).ceil() as usize).clamp(4, 1024)
+}
+
+fn rect_polygon(x: f32, y: f32, width: f32, height: f32) -> Vec<Vector2> {
+    vec![
+        Vector2::new(x, y),
+        Vector2::new(x, y + height),
+        Vector2::new(x + width, y + height),
+        Vector2::new(x + width, y),
+    ]
+}
+
+/// Corners of `rec` rotated by `rotation` degrees around `origin`, like `DrawRectanglePro`.
+fn rotated_rect(rec: Rectangle, origin: Vector2, rotation: f32) -> [Vector2; 4] {
+    let (sin, cos) = rotation.to_radians().sin_cos();
+    let corner = |lx: f32, ly: f32| {
+        let (dx, dy) = (lx - origin.x, ly - origin.y);
+        Vector2::new(rec.x + dx * cos - dy * sin, rec.y + dx * sin + dy * cos)
+    };
+    [
+        corner(0.0, 0.0),
+        corner(0.0, rec.height),
+        corner(rec.width, rec.height),
+        corner(rec.width, 0.0),
+    ]
+}
+
+fn rounded_radius(rec: &Rectangle, roundness: f32) -> f32 {
+    rec.width.min(rec.height) * roundness.clamp(0.0, 1.0) * 0.5
+}
+
+fn rounded_rect_polygon(rec: &Rectangle, radius: f32, segments: i32) -> Vec<Vector2> {
+    if radius <= 0.0 {
+        return rect_polygon(rec.x, rec.y, rec.width, rec.height);
+    }
+    let segments = if segments >= 4 {
+        segments as usize
+    } else {
+        arc_segments(radius, PI * 0.5)
+    };
+    let corners = [
+        (rec.x + rec.width - radius, rec.y + radius, -0.5 * PI),
+        (rec.x + rec.width - radius, rec.y + rec.height - radius, 0.0),
+        (rec.x + radius, rec.y + rec.height - radius, 0.5 * PI),
+        (rec.x + radius, rec.y + radius, PI),
+    ];
+    let mut polygon = Vec::with_capacity(4 * (segments + 1));
+    for (cx, cy, start) in corners {
+        for i in 0..=segments {
+            let a = start + 0.5 * PI * i as f32 / segments as f32;
+            polygon.push(Vector2::new(cx + a.cos() * radius, cy + a.sin() * radius));
+        }
+    }
+    polygon
+}
+
+fn ellipse_polygon(center: Vector2, radius_h: f32, radius_v: f32) -> Vec<Vector2> {
+    let segments = arc_segments(radius_h.max(radius_v), TAU);
+    (0..segments)
+        .map(|i| {
+            let a = TAU * i as f32 / segments as f32;
+            Vector2::new(center.x + a.cos() * radius_h, center.y + a.sin() * radius_v)
+        })
+        .collect()
+}
+
+fn regular_polygon(center: Vector2, sides: i32, radius: f32, rotation: f32) -> Vec<Vector2> {
+    let sides = sides.max(3);
+    let start = rotation.to_radians();
+    (0..sides)
+        .map(|i| {
+            let a = start + TAU * i as f32 / sides as f32;
+            center + Vector2::new(a.cos(), a.sin()) * radius
+        })
+        .collect()
+}
+
+/// Joins `outer` and `inner` into one polygon, with `inner` traced backwards so it cuts a hole.
+/// The seam between the two is walked in both directions and doesn't show.
+fn with_hole(mut outer: Vec<Vector2>, inner: Vec<Vector2>) -> Vec<Vector2> {
+    let (first_outer, first_inner) = (outer[0], inner[0]);
+    outer.push(first_outer);
+    outer.push(first_inner);
+    outer.extend(inner.into_iter().rev());
+    outer
+}
+
+fn line_polygon(start: Vector2, end: Vector2, thick: f32) -> Vec<Vector2> {
+    let delta = end - start;
+    let length = delta.length();
+    if length <= 0.0 {
+        return Vec::new();
+    }
+    let half = thick.max(1.0) * 0.5;
+    let normal = Vector2::new(-delta.y, delta.x) / length * half;
+    vec![start + normal, end + normal, end - normal, start - normal]
+}
+
+/// Quads for every segment of `points`, plus round joins between them.
+fn stroke_polygons(points: &[Vector2], thick: f32) -> Vec<Vec<Vector2>> {
+    let mut polygons: Vec<Vec<Vector2>> = points
+        .windows(2)
+        .map(|p| line_polygon(p[0], p[1], thick))
+        .collect();
+    if thick > 2.0 && points.len() > 2 {
+        let radius = thick * 0.5;
+        polygons.extend(
+            points[1..points.len() - 1]
+                .iter()
+                .map(|&p| ellipse_polygon(p, radius, radius)),
+        );
+    }
+    polygons
+}
+
+/// Appends `curve(t)` for `t` in `(0, 1]`, and `curve(0)` too if `path` is empty.
+fn flatten(path: &mut Vec<Vector2>, curve: impl Fn(f32) -> Vector2) {
+    if path.is_empty() {
+        path.push(curve(0.0));
+    }
+    for i in 1..=SPLINE_SEGMENT_DIVISIONS {
+        path.push(curve(i as f32 / SPLINE_SEGMENT_DIVISIONS as f32));
+    }
+}
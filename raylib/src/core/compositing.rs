@@ -0,0 +1,339 @@
+//! Layer-style blend modes for compositing images on the CPU
+//!
+//! [`Image::draw`] only alpha blends. [`Image::draw_blended`] composites with the blend
+//! modes of image editors, following the W3C Compositing and Blending specification: the
+//! source is blended with the destination where both are opaque, and laid over it with
+//! regular alpha blending elsewhere.
+//!
+//! ```no_run
+//! use raylib::prelude::*;
+//!
+//! let mut sprite = Image::load_image("knight.png").unwrap();
+//! let shading = Image::load_image("knight_shading.png").unwrap();
+//! let rec = Rectangle::new(0.0, 0.0, sprite.width() as f32, sprite.height() as f32);
+//! sprite.draw_blended(&shading, rec, rec, Color::WHITE, LayerBlendMode::Multiply);
+//! ```
+use crate::core::color::Color;
+use crate::core::math::Rectangle;
+use crate::core::texture::Image;
+use crate::ffi;
+
+/// How [`Image::draw_blended`] combines source and destination colors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum LayerBlendMode {
+    /// The source color
+    #[default]
+    Normal,
+    /// Darkens: destination times source
+    Multiply,
+    /// Lightens: the inverse of multiplying the inverses
+    Screen,
+    /// Multiply or screen depending on the destination, increasing contrast
+    Overlay,
+    /// The darker of the two, per channel
+    Darken,
+    /// The lighter of the two, per channel
+    Lighten,
+    /// Brightens the destination to reflect the source
+    ColorDodge,
+    /// Darkens the destination to reflect the source
+    ColorBurn,
+    /// Multiply or screen depending on the source, like a harsh spotlight
+    HardLight,
+    /// A softer version of hard light
+    SoftLight,
+    /// Destination plus source, clamped
+    Additive,
+    /// The absolute difference of the two
+    Difference,
+    /// Like difference, with lower contrast
+    Exclusion,
+    /// Hue of the source, saturation and luminosity of the destination
+    Hue,
+    /// Saturation of the source, hue and luminosity of the destination
+    Saturation,
+    /// Hue and saturation of the source, luminosity of the destination
+    Color,
+    /// Luminosity of the source, hue and saturation of the destination
+    Luminosity,
+}
+
+type Rgb = [f32; 3];
+
+fn lum(c: Rgb) -> f32 {
+    0.3 * c[0] + 0.59 * c[1] + 0.11 * c[2]
+}
+
+fn clip_color(c: Rgb) -> Rgb {
+    let l = lum(c);
+    let n = c[0].min(c[1]).min(c[2]);
+    let x = c[0].max(c[1]).max(c[2]);
+    let mut out = c;
+    for v in out.iter_mut() {
+        if n < 0.0 {
+            *v = l + (*v - l) * l / (l - n);
+        }
+        if x > 1.0 {
+            *v = l + (*v - l) * (1.0 - l) / (x - l);
+        }
+    }
+    out
+}
+
+fn set_lum(c: Rgb, l: f32) -> Rgb {
+    let d = l - lum(c);
+    clip_color([c[0] + d, c[1] + d, c[2] + d])
+}
+
+fn sat(c: Rgb) -> f32 {
+    c[0].max(c[1]).max(c[2]) - c[0].min(c[1]).min(c[2])
+}
+
+fn set_sat(c: Rgb, s: f32) -> Rgb {
+    let (min, max) = (c[0].min(c[1]).min(c[2]), c[0].max(c[1]).max(c[2]));
+    if max > min {
+        [
+            (c[0] - min) * s / (max - min),
+            (c[1] - min) * s / (max - min),
+            (c[2] - min) * s / (max - min),
+        ]
+    } else {
+        [0.0; 3]
+    }
+}
+
+/// The blend function `B(backdrop, source)` of `mode`, on straight colors in `0..=1`.
+fn blend(mode: LayerBlendMode, b: Rgb, s: Rgb) -> Rgb {
+    let separable = |f: fn(f32, f32) -> f32| [f(b[0], s[0]), f(b[1], s[1]), f(b[2], s[2])];
+    fn hard_light(b: f32, s: f32) -> f32 {
+        if s <= 0.5 {
+            b * 2.0 * s
+        } else {
+            let s = 2.0 * s - 1.0;
+            b + s - b * s
+        }
+    }
+    match mode {
+        LayerBlendMode::Normal => s,
+        LayerBlendMode::Multiply => separable(|b, s| b * s),
+        LayerBlendMode::Screen => separable(|b, s| b + s - b * s),
+        LayerBlendMode::Overlay => separable(|b, s| hard_light(s, b)),
+        LayerBlendMode::Darken => separable(f32::min),
+        LayerBlendMode::Lighten => separable(f32::max),
+        LayerBlendMode::ColorDodge => separable(|b, s| {
+            if b <= 0.0 {
+                0.0
+            } else if s >= 1.0 {
+                1.0
+            } else {
+                (b / (1.0 - s)).min(1.0)
+            }
+        }),
+        LayerBlendMode::ColorBurn => separable(|b, s| {
+            if b >= 1.0 {
+                1.0
+            } else if s <= 0.0 {
+                0.0
+            } else {
+                1.0 - ((1.0 - b) / s).min(1.0)
+            }
+        }),
+        LayerBlendMode::HardLight => separable(hard_light),
+        LayerBlendMode::SoftLight => separable(|b, s| {
+            if s <= 0.5 {
+                b - (1.0 - 2.0 * s) * b * (1.0 - b)
+            } else {
+                let d = if b <= 0.25 {
+                    ((16.0 * b - 12.0) * b + 4.0) * b
+                } else {
+                    b.sqrt()
+                };
+                b + (2.0 * s - 1.0) * (d - b)
+            }
+        }),
+        LayerBlendMode::Additive => separable(|b, s| (b + s).min(1.0)),
+        LayerBlendMode::Difference => separable(|b, s| (b - s).abs()),
+        LayerBlendMode::Exclusion => separable(|b, s| b + s - 2.0 * b * s),
+        LayerBlendMode::Hue => set_lum(set_sat(s, sat(b)), lum(b)),
+        LayerBlendMode::Saturation => set_lum(set_sat(b, sat(s)), lum(b)),
+        LayerBlendMode::Color => set_lum(s, lum(b)),
+        LayerBlendMode::Luminosity => set_lum(b, lum(s)),
+    }
+}
+
+/// Composites straight `source` over straight `backdrop`, both as RGBA in `0..=1`, and
+/// returns the premultiplied result.
+fn composite(mode: LayerBlendMode, backdrop: [f32; 4], source: [f32; 4]) -> [f32; 4] {
+    let (ab, a_s) = (backdrop[3], source[3]);
+    let b = [backdrop[0], backdrop[1], backdrop[2]];
+    let s = [source[0], source[1], source[2]];
+    let blended = blend(mode, b, s);
+    let mut out = [0.0; 4];
+    for i in 0..3 {
+        // where the backdrop is transparent the source shows unblended
+        let mixed = (1.0 - ab) * s[i] + ab * blended[i];
+        out[i] = a_s * mixed + ab * b[i] * (1.0 - a_s);
+    }
+    out[3] = a_s + ab * (1.0 - a_s);
+    out
+}
+
+fn to_unit(c: Color) -> [f32; 4] {
+    [
+        c.r as f32 / 255.0,
+        c.g as f32 / 255.0,
+        c.b as f32 / 255.0,
+        c.a as f32 / 255.0,
+    ]
+}
+
+fn to_color(c: [f32; 4]) -> Color {
+    let u = |v: f32| (v * 255.0).round().clamp(0.0, 255.0) as u8;
+    Color::new(u(c[0]), u(c[1]), u(c[2]), u(c[3]))
+}
+
+fn unpremultiply(c: [f32; 4]) -> [f32; 4] {
+    if c[3] <= 0.0 {
+        [0.0; 4]
+    } else {
+        [c[0] / c[3], c[1] / c[3], c[2] / c[3], c[3]]
+    }
+}
+
+fn premultiply(c: [f32; 4]) -> [f32; 4] {
+    [c[0] * c[3], c[1] * c[3], c[2] * c[3], c[3]]
+}
+
+/// Bilinear lookup of the premultiplied source at `(u, v)`, in source pixels, clamped to
+/// the pixels of `bounds` so nothing outside the source rectangle bleeds in.
+fn sample(
+    pixels: &[[f32; 4]],
+    width: usize,
+    bounds: (usize, usize, usize, usize),
+    u: f32,
+    v: f32,
+) -> [f32; 4] {
+    let (x0, y0, x1, y1) = bounds;
+    let fx = (u - 0.5).clamp(x0 as f32, (x1 - 1) as f32);
+    let fy = (v - 0.5).clamp(y0 as f32, (y1 - 1) as f32);
+    let (ix, iy) = (fx.floor() as usize, fy.floor() as usize);
+    let (tx, ty) = (fx - ix as f32, fy - iy as f32);
+    let (nx, ny) = ((ix + 1).min(x1 - 1), (iy + 1).min(y1 - 1));
+    let p = |x: usize, y: usize| pixels[y * width + x];
+    let (a, b, c, d) = (p(ix, iy), p(nx, iy), p(ix, ny), p(nx, ny));
+    let mut out = [0.0; 4];
+    for i in 0..4 {
+        let top = a[i] + (b[i] - a[i]) * tx;
+        let bottom = c[i] + (d[i] - c[i]) * tx;
+        out[i] = top + (bottom - top) * ty;
+    }
+    out
+}
+
+impl Image {
+    /// Draws the `src_rec` part of `src` into `dst_rec`, scaled with bilinear filtering,
+    /// tinted, and composited with `mode`. Both images have straight alpha.
+    ///
+    /// Converts this image to
+    /// [`PIXELFORMAT_UNCOMPRESSED_R8G8B8A8`](crate::consts::PixelFormat::PIXELFORMAT_UNCOMPRESSED_R8G8B8A8)
+    /// if needed, and does nothing if it can't be converted, like a compressed image.
+    pub fn draw_blended(
+        &mut self,
+        src: &Image,
+        src_rec: Rectangle,
+        dst_rec: Rectangle,
+        tint: impl Into<ffi::Color>,
+        mode: LayerBlendMode,
+    ) {
+        self.composite(src, src_rec, dst_rec, tint.into().into(), mode, false);
+    }
+
+    /// Like [`Image::draw_blended`], for images whose colors are premultiplied by their
+    /// alpha, such as after [`Image::alpha_premultiply`]. The result stays premultiplied.
+    pub fn draw_blended_premultiplied(
+        &mut self,
+        src: &Image,
+        src_rec: Rectangle,
+        dst_rec: Rectangle,
+        tint: impl Into<ffi::Color>,
+        mode: LayerBlendMode,
+    ) {
+        self.composite(src, src_rec, dst_rec, tint.into().into(), mode, true);
+    }
+
+    fn composite(
+        &mut self,
+        src: &Image,
+        src_rec: Rectangle,
+        dst_rec: Rectangle,
+        tint: Color,
+        mode: LayerBlendMode,
+        premultiplied: bool,
+    ) {
+        let (src_width, src_height) = (src.width().max(0) as usize, src.height().max(0) as usize);
+        // source rectangle clipped to the source image, in whole pixels
+        let sx0 = src_rec.x.max(0.0).floor() as usize;
+        let sy0 = src_rec.y.max(0.0).floor() as usize;
+        let sx1 = ((src_rec.x + src_rec.width).ceil().max(0.0) as usize).min(src_width);
+        let sy1 = ((src_rec.y + src_rec.height).ceil().max(0.0) as usize).min(src_height);
+        if sx1 <= sx0 || sy1 <= sy0 || dst_rec.width <= 0.0 || dst_rec.height <= 0.0 {
+            return;
+        }
+
+        // premultiplied and tinted source, so filtering doesn't darken edges
+        let tint = to_unit(tint);
+        let source: Vec<[f32; 4]> = src
+            .rgba()
+            .iter()
+            .map(|&c| {
+                let c = to_unit(c);
+                let c = if premultiplied { c } else { premultiply(c) };
+                let a = tint[3];
+                [
+                    c[0] * tint[0] * a,
+                    c[1] * tint[1] * a,
+                    c[2] * tint[2] * a,
+                    c[3] * a,
+                ]
+            })
+            .collect();
+        let scale_x = src_rec.width / dst_rec.width;
+        let scale_y = src_rec.height / dst_rec.height;
+
+        let (pixels, width, height) = match self.rgba_mut() {
+            Some(rgba) => rgba,
+            None => return,
+        };
+        let dx0 = dst_rec.x.max(0.0).floor() as usize;
+        let dy0 = dst_rec.y.max(0.0).floor() as usize;
+        let dx1 = ((dst_rec.x + dst_rec.width).ceil().max(0.0) as usize).min(width);
+        let dy1 = ((dst_rec.y + dst_rec.height).ceil().max(0.0) as usize).min(height);
+        for y in dy0..dy1 {
+            let center_y = y as f32 + 0.5;
+            if center_y < dst_rec.y || center_y >= dst_rec.y + dst_rec.height {
+                continue;
+            }
+            let v = src_rec.y + (center_y - dst_rec.y) * scale_y;
+            for x in dx0..dx1 {
+                let center_x = x as f32 + 0.5;
+                if center_x < dst_rec.x || center_x >= dst_rec.x + dst_rec.width {
+                    continue;
+                }
+                let u = src_rec.x + (center_x - dst_rec.x) * scale_x;
+                let s = sample(&source, src_width, (sx0, sy0, sx1, sy1), u, v);
+                if s[3] <= 0.0 {
+                    continue;
+                }
+                let pixel = &mut pixels[y * width + x];
+                let d = to_unit(*pixel);
+                let d = if premultiplied { unpremultiply(d) } else { d };
+                let out = composite(mode, d, unpremultiply(s));
+                *pixel = to_color(if premultiplied {
+                    out
+                } else {
+                    unpremultiply(out)
+                });
+            }
+        }
+    }
+}
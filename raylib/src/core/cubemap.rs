@@ -0,0 +1,393 @@
+//! Building cubemap images on the CPU, from six faces or from an equirectangular panorama
+//!
+//! [`RaylibHandle::load_texture_cubemap`](crate::core::RaylibHandle::load_texture_cubemap)
+//! wants one image with the faces already laid out as a line or a cross. These functions make
+//! that image from separate faces or from a panorama, and turn a cubemap back into a panorama
+//! to preview it. Faces are placed where raylib looks for them, in OpenGL's cubemap
+//! orientation.
+//!
+//! Images in a float format stay in floats, as
+//! [`PIXELFORMAT_UNCOMPRESSED_R32G32B32A32`](PixelFormat::PIXELFORMAT_UNCOMPRESSED_R32G32B32A32),
+//! so HDR panoramas keep their range. Anything else becomes
+//! [`PIXELFORMAT_UNCOMPRESSED_R8G8B8A8`](PixelFormat::PIXELFORMAT_UNCOMPRESSED_R8G8B8A8).
+//!
+//! ```no_run
+//! use raylib::prelude::*;
+//!
+//! let (mut rl, thread) = raylib::init().size(800, 450).build();
+//! let panorama = Image::load_image("daytime.hdr").unwrap();
+//! let cross = panorama
+//!     .equirect_to_cubemap(512, CubemapLayout::CUBEMAP_LAYOUT_CROSS_FOUR_BY_THREE)
+//!     .unwrap();
+//! let skybox = rl
+//!     .load_texture_cubemap(&thread, &cross, CubemapLayout::CUBEMAP_LAYOUT_CROSS_FOUR_BY_THREE)
+//!     .unwrap();
+//! ```
+use crate::consts::{CubemapLayout, PixelFormat};
+use crate::core::color::Color;
+use crate::core::math::Vector3;
+use crate::core::pixels::Rgba32f;
+use crate::core::texture::Image;
+use crate::error::{error, Error};
+use std::convert::TryInto;
+use std::f32::consts::PI;
+
+/// A face of a cubemap, in the order raylib and OpenGL store them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CubeFace {
+    PositiveX,
+    NegativeX,
+    PositiveY,
+    NegativeY,
+    PositiveZ,
+    NegativeZ,
+}
+
+impl CubeFace {
+    pub const ALL: [CubeFace; 6] = [
+        CubeFace::PositiveX,
+        CubeFace::NegativeX,
+        CubeFace::PositiveY,
+        CubeFace::NegativeY,
+        CubeFace::PositiveZ,
+        CubeFace::NegativeZ,
+    ];
+
+    /// Direction from the center of the cube through the point `(u, v)` of this face, where
+    /// both run from -1 to 1, left to right and top to bottom. Not normalized.
+    pub fn direction(self, u: f32, v: f32) -> Vector3 {
+        match self {
+            CubeFace::PositiveX => Vector3::new(1.0, -v, -u),
+            CubeFace::NegativeX => Vector3::new(-1.0, -v, u),
+            CubeFace::PositiveY => Vector3::new(u, 1.0, v),
+            CubeFace::NegativeY => Vector3::new(u, -1.0, -v),
+            CubeFace::PositiveZ => Vector3::new(u, -v, 1.0),
+            CubeFace::NegativeZ => Vector3::new(-u, -v, -1.0),
+        }
+    }
+
+    /// The face `direction` points at and the `(u, v)` it crosses that face at, the inverse
+    /// of [`direction`](CubeFace::direction).
+    pub fn from_direction(direction: Vector3) -> (CubeFace, f32, f32) {
+        let Vector3 { x, y, z } = direction;
+        let (ax, ay, az) = (x.abs(), y.abs(), z.abs());
+        if ax >= ay && ax >= az && ax > 0.0 {
+            if x > 0.0 {
+                (CubeFace::PositiveX, -z / ax, -y / ax)
+            } else {
+                (CubeFace::NegativeX, z / ax, -y / ax)
+            }
+        } else if ay >= az && ay > 0.0 {
+            if y > 0.0 {
+                (CubeFace::PositiveY, x / ay, z / ay)
+            } else {
+                (CubeFace::NegativeY, x / ay, -z / ay)
+            }
+        } else if z < 0.0 {
+            (CubeFace::NegativeZ, -x / az, -y / az)
+        } else if z > 0.0 {
+            (CubeFace::PositiveZ, x / az, -y / az)
+        } else {
+            // a zero vector points nowhere in particular
+            (CubeFace::PositiveZ, 0.0, 0.0)
+        }
+    }
+}
+
+/// Faces across and down in `layout`.
+fn layout_grid(layout: CubemapLayout) -> Result<(usize, usize), Error> {
+    match layout {
+        CubemapLayout::CUBEMAP_LAYOUT_LINE_VERTICAL => Ok((1, 6)),
+        CubemapLayout::CUBEMAP_LAYOUT_LINE_HORIZONTAL => Ok((6, 1)),
+        CubemapLayout::CUBEMAP_LAYOUT_CROSS_THREE_BY_FOUR => Ok((3, 4)),
+        CubemapLayout::CUBEMAP_LAYOUT_CROSS_FOUR_BY_THREE => Ok((4, 3)),
+        _ => Err(error!("Choose a cubemap layout to build.")),
+    }
+}
+
+/// Column and row of each face in `layout`, in [`CubeFace::ALL`] order.
+fn face_cells(layout: CubemapLayout) -> [(usize, usize); 6] {
+    match layout {
+        CubemapLayout::CUBEMAP_LAYOUT_LINE_HORIZONTAL => {
+            [(0, 0), (1, 0), (2, 0), (3, 0), (4, 0), (5, 0)]
+        }
+        CubemapLayout::CUBEMAP_LAYOUT_CROSS_THREE_BY_FOUR => {
+            [(1, 1), (1, 3), (1, 0), (1, 2), (0, 1), (2, 1)]
+        }
+        CubemapLayout::CUBEMAP_LAYOUT_CROSS_FOUR_BY_THREE => {
+            [(2, 1), (0, 1), (1, 0), (1, 2), (1, 1), (3, 1)]
+        }
+        _ => [(0, 0), (0, 1), (0, 2), (0, 3), (0, 4), (0, 5)],
+    }
+}
+
+/// The layout and face size of a cubemap image, guessing the layout from the image's shape
+/// for [`CUBEMAP_LAYOUT_AUTO_DETECT`](CubemapLayout::CUBEMAP_LAYOUT_AUTO_DETECT) the way
+/// raylib does.
+fn detect_layout(
+    width: usize,
+    height: usize,
+    layout: CubemapLayout,
+) -> Result<(CubemapLayout, usize), Error> {
+    let layout = match layout {
+        CubemapLayout::CUBEMAP_LAYOUT_AUTO_DETECT => {
+            if width > height && width / 6 == height {
+                CubemapLayout::CUBEMAP_LAYOUT_LINE_HORIZONTAL
+            } else if height > width && height / 6 == width {
+                CubemapLayout::CUBEMAP_LAYOUT_LINE_VERTICAL
+            } else if width > height && width / 4 == height / 3 {
+                CubemapLayout::CUBEMAP_LAYOUT_CROSS_FOUR_BY_THREE
+            } else if height > width && width / 3 == height / 4 {
+                CubemapLayout::CUBEMAP_LAYOUT_CROSS_THREE_BY_FOUR
+            } else {
+                return Err(error!("Image size doesn't match a cubemap layout."));
+            }
+        }
+        layout => layout,
+    };
+    let (columns, rows) = layout_grid(layout)?;
+    let size = width / columns;
+    if size == 0 || size * columns != width || size * rows != height {
+        return Err(error!("Image size doesn't match the cubemap layout."));
+    }
+    Ok((layout, size))
+}
+
+fn is_float_format(format: PixelFormat) -> bool {
+    matches!(
+        format,
+        PixelFormat::PIXELFORMAT_UNCOMPRESSED_R32
+            | PixelFormat::PIXELFORMAT_UNCOMPRESSED_R32G32B32
+            | PixelFormat::PIXELFORMAT_UNCOMPRESSED_R32G32B32A32
+            | PixelFormat::PIXELFORMAT_UNCOMPRESSED_R16
+            | PixelFormat::PIXELFORMAT_UNCOMPRESSED_R16G16B16
+            | PixelFormat::PIXELFORMAT_UNCOMPRESSED_R16G16B16A16
+    )
+}
+
+/// An image's pixels as floats, 0 to 1 for 8-bit formats, with whether it was in floats.
+struct Texels {
+    width: usize,
+    height: usize,
+    pixels: Vec<[f32; 4]>,
+    hdr: bool,
+}
+
+impl Texels {
+    fn new(image: &Image) -> Result<Texels, Error> {
+        let (width, height) = (
+            image.width().max(0) as usize,
+            image.height().max(0) as usize,
+        );
+        let hdr = is_float_format(image.format());
+        let pixels = if !hdr {
+            image
+                .rgba()
+                .iter()
+                .map(|c| {
+                    [
+                        c.r as f32 / 255.0,
+                        c.g as f32 / 255.0,
+                        c.b as f32 / 255.0,
+                        c.a as f32 / 255.0,
+                    ]
+                })
+                .collect()
+        } else if image.format() == PixelFormat::PIXELFORMAT_UNCOMPRESSED_R32G32B32A32 {
+            to_arrays(image.pixels::<Rgba32f>()?)
+        } else {
+            let mut copy = image.clone();
+            copy.set_format(PixelFormat::PIXELFORMAT_UNCOMPRESSED_R32G32B32A32);
+            to_arrays(copy.pixels::<Rgba32f>()?)
+        };
+        Ok(Texels {
+            width,
+            height,
+            pixels,
+            hdr,
+        })
+    }
+
+    fn get(&self, x: usize, y: usize) -> [f32; 4] {
+        self.pixels[y * self.width + x]
+    }
+
+    /// Bilinear sample at pixel coordinates of the `width` x `height` rectangle at
+    /// `(left, top)`, clamped to it, or wrapped around horizontally if `wrap` is set.
+    fn sample(
+        &self,
+        (left, top, width, height): (usize, usize, usize, usize),
+        x: f32,
+        y: f32,
+        wrap: bool,
+    ) -> [f32; 4] {
+        let column = |x: isize| {
+            if wrap {
+                x.rem_euclid(width as isize) as usize
+            } else {
+                x.clamp(0, width as isize - 1) as usize
+            }
+        };
+        let row = |y: isize| y.clamp(0, height as isize - 1) as usize;
+        let (x, y) = (x - 0.5, y - 0.5);
+        let (x0, y0) = (x.floor(), y.floor());
+        let (fx, fy) = (x - x0, y - y0);
+        let (x0, y0) = (x0 as isize, y0 as isize);
+        let (x0, x1) = (left + column(x0), left + column(x0 + 1));
+        let (y0, y1) = (top + row(y0), top + row(y0 + 1));
+        let (a, b, c, d) = (
+            self.get(x0, y0),
+            self.get(x1, y0),
+            self.get(x0, y1),
+            self.get(x1, y1),
+        );
+        let mut out = [0.0; 4];
+        for i in 0..4 {
+            let top = a[i] + (b[i] - a[i]) * fx;
+            let bottom = c[i] + (d[i] - c[i]) * fx;
+            out[i] = top + (bottom - top) * fy;
+        }
+        out
+    }
+}
+
+fn to_arrays(pixels: &[Rgba32f]) -> Vec<[f32; 4]> {
+    pixels.iter().map(|p| [p.r, p.g, p.b, p.a]).collect()
+}
+
+/// An image of `pixels`, in floats if `hdr` is set and in RGBA8 otherwise.
+fn image_from_texels(
+    width: usize,
+    height: usize,
+    pixels: &[[f32; 4]],
+    hdr: bool,
+) -> Result<Image, Error> {
+    let (width, height) = (
+        width
+            .try_into()
+            .map_err(|_| error!("Cubemap image is too large."))?,
+        height
+            .try_into()
+            .map_err(|_| error!("Cubemap image is too large."))?,
+    );
+    if hdr {
+        let pixels: Vec<Rgba32f> = pixels
+            .iter()
+            .map(|&[r, g, b, a]| Rgba32f { r, g, b, a })
+            .collect();
+        Image::from_pixel_slice(width, height, &pixels)
+    } else {
+        let byte = |v: f32| (v * 255.0).round().clamp(0.0, 255.0) as u8;
+        let pixels: Vec<Color> = pixels
+            .iter()
+            .map(|&[r, g, b, a]| Color::new(byte(r), byte(g), byte(b), byte(a)))
+            .collect();
+        Image::from_pixel_slice(width, height, &pixels)
+    }
+}
+
+impl Image {
+    /// Lays out six square faces of the same size, in [`CubeFace::ALL`] order, as one image
+    /// in `layout`. Unused cells of a cross are left transparent.
+    pub fn from_cube_faces(faces: &[Image; 6], layout: CubemapLayout) -> Result<Image, Error> {
+        let (columns, rows) = layout_grid(layout)?;
+        let faces = faces
+            .iter()
+            .map(Texels::new)
+            .collect::<Result<Vec<_>, Error>>()?;
+        let size = faces[0].width;
+        if size == 0 || faces.iter().any(|f| f.width != size || f.height != size) {
+            return Err(error!("Cubemap faces must be squares of the same size."));
+        }
+        let hdr = faces.iter().any(|f| f.hdr);
+        let width = columns * size;
+        let mut pixels = vec![[0.0; 4]; width * rows * size];
+        for (face, &(column, row)) in faces.iter().zip(face_cells(layout).iter()) {
+            for y in 0..size {
+                let start = (row * size + y) * width + column * size;
+                pixels[start..start + size].copy_from_slice(&face.pixels[y * size..(y + 1) * size]);
+            }
+        }
+        image_from_texels(width, rows * size, &pixels, hdr)
+    }
+
+    /// Copies one face out of a cubemap image laid out as `layout`.
+    pub fn cube_face(&self, layout: CubemapLayout, face: CubeFace) -> Result<Image, Error> {
+        let texels = Texels::new(self)?;
+        let (layout, size) = detect_layout(texels.width, texels.height, layout)?;
+        let (column, row) = face_cells(layout)[face as usize];
+        let mut pixels = Vec::with_capacity(size * size);
+        for y in 0..size {
+            let start = (row * size + y) * texels.width + column * size;
+            pixels.extend_from_slice(&texels.pixels[start..start + size]);
+        }
+        image_from_texels(size, size, &pixels, texels.hdr)
+    }
+
+    /// Projects an equirectangular panorama onto a cube with `face_size` pixel faces, laid
+    /// out as `layout`. The panorama's top row is straight up, its center looks along +X and
+    /// its right half turns toward +Z.
+    pub fn equirect_to_cubemap(
+        &self,
+        face_size: usize,
+        layout: CubemapLayout,
+    ) -> Result<Image, Error> {
+        let (columns, rows) = layout_grid(layout)?;
+        let texels = Texels::new(self)?;
+        if texels.width == 0 || texels.height == 0 || face_size == 0 {
+            return Err(error!("Panorama and faces can't be empty."));
+        }
+        let whole = (0, 0, texels.width, texels.height);
+        let width = columns * face_size;
+        let mut pixels = vec![[0.0; 4]; width * rows * face_size];
+        for (&face, &(column, row)) in CubeFace::ALL.iter().zip(face_cells(layout).iter()) {
+            for y in 0..face_size {
+                let v = (y as f32 + 0.5) / face_size as f32 * 2.0 - 1.0;
+                for x in 0..face_size {
+                    let u = (x as f32 + 0.5) / face_size as f32 * 2.0 - 1.0;
+                    let dir = face.direction(u, v).normalized();
+                    let longitude = dir.z.atan2(dir.x);
+                    let latitude = dir.y.clamp(-1.0, 1.0).asin();
+                    let px = (0.5 + longitude / (2.0 * PI)) * texels.width as f32;
+                    let py = (0.5 - latitude / PI) * texels.height as f32;
+                    pixels[(row * face_size + y) * width + column * face_size + x] =
+                        texels.sample(whole, px, py, true);
+                }
+            }
+        }
+        image_from_texels(width, rows * face_size, &pixels, texels.hdr)
+    }
+
+    /// Unwraps a cubemap image laid out as `layout` into a `width` x `height`
+    /// equirectangular panorama, the inverse of
+    /// [`equirect_to_cubemap`](Image::equirect_to_cubemap).
+    pub fn cubemap_to_equirect(
+        &self,
+        layout: CubemapLayout,
+        width: usize,
+        height: usize,
+    ) -> Result<Image, Error> {
+        let texels = Texels::new(self)?;
+        let (layout, size) = detect_layout(texels.width, texels.height, layout)?;
+        let cells = face_cells(layout);
+        let mut pixels = Vec::with_capacity(width * height);
+        for y in 0..height {
+            let latitude = (0.5 - (y as f32 + 0.5) / height as f32) * PI;
+            for x in 0..width {
+                let longitude = ((x as f32 + 0.5) / width as f32 - 0.5) * 2.0 * PI;
+                let dir = Vector3::new(
+                    latitude.cos() * longitude.cos(),
+                    latitude.sin(),
+                    latitude.cos() * longitude.sin(),
+                );
+                let (face, u, v) = CubeFace::from_direction(dir);
+                let (column, row) = cells[face as usize];
+                let rect = (column * size, row * size, size, size);
+                let fx = (u + 1.0) / 2.0 * size as f32;
+                let fy = (v + 1.0) / 2.0 * size as f32;
+                pixels.push(texels.sample(rect, fx, fy, false));
+            }
+        }
+        image_from_texels(width, height, &pixels, texels.hdr)
+    }
+}
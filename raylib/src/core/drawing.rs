@@ -6,6 +6,7 @@ use crate::core::camera::Camera3D;
 use crate::core::math::Ray;
 use crate::core::math::{Vector2, Vector3};
 
+use crate::core::rlgl::track_mode3d;
 use crate::core::texture::Texture2D;
 use crate::core::vr::VrStereoConfig;
 use crate::core::{RaylibHandle, RaylibThread};
@@ -78,6 +79,18 @@ impl<'a> std::ops::DerefMut for RaylibDrawHandle<'a> {
     }
 }
 impl<'a> RaylibDraw for RaylibDrawHandle<'a> {}
+impl<'a> sealed::Sealed for RaylibDrawHandle<'a> {}
+
+pub(crate) mod sealed {
+    pub trait Sealed {}
+}
+
+/// The draw handles of this crate, which only exist while a frame or texture mode is being
+/// drawn: [`RaylibDrawHandle`], [`RaylibTextureMode`] and the scoped mode guards.
+/// [`DrawList::replay`](crate::core::drawlist::DrawList::replay) takes one as proof that raylib
+/// is ready to draw.
+pub trait RaylibDrawImmediate: RaylibDraw + sealed::Sealed {}
+impl<D: RaylibDraw + sealed::Sealed> RaylibDrawImmediate for D {}
 
 // Texture2D Stuff
 
@@ -133,6 +146,7 @@ where
 impl<'a> RaylibTextureModeExt for RaylibDrawHandle<'a> {}
 impl RaylibTextureModeExt for RaylibHandle {}
 impl<'a, T> RaylibDraw for RaylibTextureMode<'a, T> {}
+impl<'a, T> sealed::Sealed for RaylibTextureMode<'a, T> {}
 
 // VR Stuff
 
@@ -178,6 +192,7 @@ where
 
 impl<D: RaylibDraw> RaylibVRModeExt for D {}
 impl<'a, T> RaylibDraw for RaylibVRMode<'a, T> {}
+impl<'a, T> sealed::Sealed for RaylibVRMode<'a, T> {}
 
 // 2D Mode
 
@@ -233,6 +248,7 @@ where
 
 impl<D: RaylibDraw> RaylibMode2DExt for D {}
 impl<'a, T> RaylibDraw for RaylibMode2D<'a, T> {}
+impl<'a, T> sealed::Sealed for RaylibMode2D<'a, T> {}
 
 // 3D Mode
 
@@ -240,6 +256,7 @@ pub struct RaylibMode3D<'a, T>(&'a mut T);
 impl<'a, T> Drop for RaylibMode3D<'a, T> {
     fn drop(&mut self) {
         unsafe { ffi::EndMode3D() }
+        track_mode3d(false);
     }
 }
 impl<'a, T> std::ops::Deref for RaylibMode3D<'a, T> {
@@ -267,6 +284,7 @@ where
         unsafe {
             ffi::BeginMode3D(camera.into());
         }
+        track_mode3d(true);
         RaylibMode3D(self)
     }
 
@@ -279,15 +297,18 @@ where
         unsafe {
             ffi::BeginMode3D(camera.into());
         }
+        track_mode3d(true);
         func(RaylibMode3D(self), camera);
         unsafe {
             ffi::EndMode3D();
         }
+        track_mode3d(false);
     }
 }
 
 impl<D: RaylibDraw> RaylibMode3DExt for D {}
 impl<'a, T> RaylibDraw for RaylibMode3D<'a, T> {}
+impl<'a, T> sealed::Sealed for RaylibMode3D<'a, T> {}
 impl<'a, T> RaylibDraw3D for RaylibMode3D<'a, T> {}
 
 // shader Mode
@@ -336,14 +357,164 @@ where
 
 impl<D: RaylibDraw> RaylibShaderModeExt for D {}
 impl<'a, T> RaylibDraw for RaylibShaderMode<'a, T> {}
+impl<'a, T> sealed::Sealed for RaylibShaderMode<'a, T> {}
 impl<'a, T> RaylibDraw3D for RaylibShaderMode<'a, T> {}
 
 // Blend Mode
 
-pub struct RaylibBlendMode<'a, T>(&'a mut T);
+/// Source/destination factor for custom blending, mirroring the GL blend factors exposed by rlgl.
+#[repr(i32)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum BlendFactor {
+    Zero = ffi::RL_ZERO as i32,
+    One = ffi::RL_ONE as i32,
+    SrcColor = ffi::RL_SRC_COLOR as i32,
+    OneMinusSrcColor = ffi::RL_ONE_MINUS_SRC_COLOR as i32,
+    SrcAlpha = ffi::RL_SRC_ALPHA as i32,
+    OneMinusSrcAlpha = ffi::RL_ONE_MINUS_SRC_ALPHA as i32,
+    DstAlpha = ffi::RL_DST_ALPHA as i32,
+    OneMinusDstAlpha = ffi::RL_ONE_MINUS_DST_ALPHA as i32,
+    DstColor = ffi::RL_DST_COLOR as i32,
+    OneMinusDstColor = ffi::RL_ONE_MINUS_DST_COLOR as i32,
+    SrcAlphaSaturate = ffi::RL_SRC_ALPHA_SATURATE as i32,
+}
+
+/// How the weighted source and destination are combined in custom blending.
+#[repr(i32)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum BlendEquation {
+    /// `src * src_factor + dst * dst_factor`
+    Add = ffi::RL_FUNC_ADD as i32,
+    /// `src * src_factor - dst * dst_factor`
+    Subtract = ffi::RL_FUNC_SUBTRACT as i32,
+    /// `dst * dst_factor - src * src_factor`
+    ReverseSubtract = ffi::RL_FUNC_REVERSE_SUBTRACT as i32,
+    /// `min(src, dst)`, factors are ignored
+    Min = ffi::RL_MIN as i32,
+    /// `max(src, dst)`, factors are ignored
+    Max = ffi::RL_MAX as i32,
+}
+
+/// A complete blending setup: one of raylib's presets, or custom factors and equations.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum BlendState {
+    Preset(crate::consts::BlendMode),
+    Custom {
+        src: BlendFactor,
+        dst: BlendFactor,
+        equation: BlendEquation,
+    },
+    CustomSeparate {
+        src_rgb: BlendFactor,
+        dst_rgb: BlendFactor,
+        src_alpha: BlendFactor,
+        dst_alpha: BlendFactor,
+        equation_rgb: BlendEquation,
+        equation_alpha: BlendEquation,
+    },
+}
+
+impl Default for BlendState {
+    /// raylib's default, [`BlendMode::BLEND_ALPHA`](crate::consts::BlendMode::BLEND_ALPHA).
+    fn default() -> Self {
+        BlendState::Preset(crate::consts::BlendMode::BLEND_ALPHA)
+    }
+}
+
+impl BlendState {
+    /// Keeps the per-channel minimum of source and destination.
+    pub const MIN: BlendState = BlendState::Custom {
+        src: BlendFactor::One,
+        dst: BlendFactor::One,
+        equation: BlendEquation::Min,
+    };
+    /// Keeps the per-channel maximum of source and destination.
+    pub const MAX: BlendState = BlendState::Custom {
+        src: BlendFactor::One,
+        dst: BlendFactor::One,
+        equation: BlendEquation::Max,
+    };
+    /// Alpha blending for colors that are already multiplied by their alpha.
+    pub const PREMULTIPLIED: BlendState = BlendState::Custom {
+        src: BlendFactor::One,
+        dst: BlendFactor::OneMinusSrcAlpha,
+        equation: BlendEquation::Add,
+    };
+    /// Additive blending for premultiplied light sources, e.g. accumulating lights into a light map.
+    pub const PREMULTIPLIED_ADD: BlendState = BlendState::Custom {
+        src: BlendFactor::One,
+        dst: BlendFactor::One,
+        equation: BlendEquation::Add,
+    };
+    /// Scales what is already drawn by the alpha of what gets drawn, leaving color untouched.
+    /// Drawing a shape with alpha 0 erases the area it covers, alpha 255 keeps it.
+    pub const MASK: BlendState = BlendState::CustomSeparate {
+        src_rgb: BlendFactor::Zero,
+        dst_rgb: BlendFactor::SrcAlpha,
+        src_alpha: BlendFactor::Zero,
+        dst_alpha: BlendFactor::SrcAlpha,
+        equation_rgb: BlendEquation::Add,
+        equation_alpha: BlendEquation::Add,
+    };
+
+    fn apply(self) {
+        use crate::consts::BlendMode;
+        unsafe {
+            match self {
+                BlendState::Preset(mode) => ffi::BeginBlendMode((mode as u32) as i32),
+                BlendState::Custom { src, dst, equation } => {
+                    ffi::rlSetBlendFactors(src as i32, dst as i32, equation as i32);
+                    ffi::BeginBlendMode((BlendMode::BLEND_CUSTOM as u32) as i32)
+                }
+                BlendState::CustomSeparate {
+                    src_rgb,
+                    dst_rgb,
+                    src_alpha,
+                    dst_alpha,
+                    equation_rgb,
+                    equation_alpha,
+                } => {
+                    ffi::rlSetBlendFactorsSeparate(
+                        src_rgb as i32,
+                        dst_rgb as i32,
+                        src_alpha as i32,
+                        dst_alpha as i32,
+                        equation_rgb as i32,
+                        equation_alpha as i32,
+                    );
+                    ffi::BeginBlendMode((BlendMode::BLEND_CUSTOM_SEPARATE as u32) as i32)
+                }
+            }
+        }
+    }
+}
+
+impl From<crate::consts::BlendMode> for BlendState {
+    fn from(mode: crate::consts::BlendMode) -> Self {
+        BlendState::Preset(mode)
+    }
+}
+
+thread_local! {
+    // raylib can't be queried for the active blend factors, so the guards keep track of them
+    static BLEND_STATE: std::cell::Cell<BlendState> = std::cell::Cell::new(BlendState::default());
+}
+
+fn push_blend_state(state: BlendState) -> BlendState {
+    state.apply();
+    BLEND_STATE.with(|s| s.replace(state))
+}
+
+/// Restores the blend state that was active before it was created when dropped.
+pub struct RaylibBlendMode<'a, T>(&'a mut T, BlendState);
 impl<'a, T> Drop for RaylibBlendMode<'a, T> {
     fn drop(&mut self) {
-        unsafe { ffi::EndBlendMode() }
+        BLEND_STATE.with(|s| s.set(self.1));
+        if self.1 == BlendState::default() {
+            unsafe { ffi::EndBlendMode() }
+        } else {
+            self.1.apply();
+        }
     }
 }
 impl<'a, T> std::ops::Deref for RaylibBlendMode<'a, T> {
@@ -364,11 +535,11 @@ where
     Self: Sized,
 {
     /// Begin blending mode (alpha, additive, multiplied, subtract, custom).
-    /// Prefer using the closure version, [RaylibBlendModeExt::begin_blend_mode]. This version returns a handle that calls [raylib_sys::EndBlendMode] at the end of the scope and is provided as a fallback incase you run into issues with closures(such as lifetime or performance reasons)
+    /// Prefer using the closure version, [RaylibBlendModeExt::begin_blend_mode]. This version returns a handle that restores the previous blend mode at the end of the scope and is provided as a fallback incase you run into issues with closures(such as lifetime or performance reasons)
     #[must_use]
     fn begin_blend_mode(&mut self, blend_mode: crate::consts::BlendMode) -> RaylibBlendMode<Self> {
-        unsafe { ffi::BeginBlendMode((blend_mode as u32) as i32) }
-        RaylibBlendMode(self)
+        let previous = push_blend_state(blend_mode.into());
+        RaylibBlendMode(self, previous)
     }
 
     fn start_blend_mode(
@@ -376,13 +547,63 @@ where
         blend_mode: crate::consts::BlendMode,
         mut func: impl FnMut(RaylibBlendMode<Self>),
     ) {
-        unsafe { ffi::BeginBlendMode((blend_mode as u32) as i32) }
-        func(RaylibBlendMode(self));
+        let previous = push_blend_state(blend_mode.into());
+        func(RaylibBlendMode(self, previous));
+    }
+
+    /// Begin blending with custom factors and equation, applied to color and alpha alike.
+    #[must_use]
+    fn begin_blend_mode_custom(
+        &mut self,
+        src: BlendFactor,
+        dst: BlendFactor,
+        equation: BlendEquation,
+    ) -> RaylibBlendMode<Self> {
+        self.begin_blend_state(BlendState::Custom { src, dst, equation })
+    }
+
+    /// Begin blending with separate custom factors and equations for the color and alpha channels.
+    #[must_use]
+    fn begin_blend_mode_separate(
+        &mut self,
+        src_rgb: BlendFactor,
+        dst_rgb: BlendFactor,
+        src_alpha: BlendFactor,
+        dst_alpha: BlendFactor,
+        equation_rgb: BlendEquation,
+        equation_alpha: BlendEquation,
+    ) -> RaylibBlendMode<Self> {
+        self.begin_blend_state(BlendState::CustomSeparate {
+            src_rgb,
+            dst_rgb,
+            src_alpha,
+            dst_alpha,
+            equation_rgb,
+            equation_alpha,
+        })
+    }
+
+    /// Begin blending with any [`BlendState`], e.g. [`BlendState::MAX`].
+    /// Prefer using the closure version, [RaylibBlendModeExt::start_blend_state].
+    #[must_use]
+    fn begin_blend_state(&mut self, state: impl Into<BlendState>) -> RaylibBlendMode<Self> {
+        let previous = push_blend_state(state.into());
+        RaylibBlendMode(self, previous)
+    }
+
+    fn start_blend_state(
+        &mut self,
+        state: impl Into<BlendState>,
+        mut func: impl FnMut(RaylibBlendMode<Self>),
+    ) {
+        let previous = push_blend_state(state.into());
+        func(RaylibBlendMode(self, previous));
     }
 }
 
 impl<D: RaylibDraw> RaylibBlendModeExt for D {}
 impl<'a, T> RaylibDraw for RaylibBlendMode<'a, T> {}
+impl<'a, T> sealed::Sealed for RaylibBlendMode<'a, T> {}
 impl<'a, T> RaylibDraw3D for RaylibBlendMode<'a, T> {}
 
 // Scissor Mode stuff
@@ -439,6 +660,7 @@ where
 
 impl<D: RaylibDraw> RaylibScissorModeExt for D {}
 impl<'a, T> RaylibDraw for RaylibScissorMode<'a, T> {}
+impl<'a, T> sealed::Sealed for RaylibScissorMode<'a, T> {}
 impl<'a, T: RaylibDraw3D> RaylibDraw3D for RaylibScissorMode<'a, T> {}
 
 // Actual drawing functions
@@ -0,0 +1,3480 @@
+//! Recording draw calls into a [`DrawList`] instead of sending them to the GPU
+//!
+//! [`DrawList`] implements [`RaylibDraw`] and [`RaylibDraw3D`], so drawing code that is generic
+//! over those traits can be run against it without a window. Every call is stored as a
+//! [`DrawCommand`], which can be inspected in plain `cargo test`, built on another thread,
+//! serialized with the `with_serde` feature and replayed on the main thread later.
+//!
+//! ```
+//! use raylib::prelude::*;
+//!
+//! fn draw_player(d: &mut impl RaylibDraw, pos: Vector2) {
+//!     d.draw_circle_v(pos, 8.0, Color::RED);
+//! }
+//!
+//! let mut list = DrawList::new();
+//! draw_player(&mut list, Vector2::new(10.0, 20.0));
+//! assert_eq!(
+//!     list.commands(),
+//!     &[DrawCommand::DrawCircleV {
+//!         center: Vector2::new(10.0, 20.0),
+//!         radius: 8.0,
+//!         color: Color::RED,
+//!     }]
+//! );
+//! ```
+//!
+//! 2D, 3D and scissor modes are recorded by [`DrawList::begin_mode2D`],
+//! [`DrawList::begin_mode3D`] and [`DrawList::begin_scissor_mode`], which take the place of the
+//! `*Ext` trait methods of the same names, or by their closure versions [`DrawList::mode2D`],
+//! [`DrawList::mode3D`] and [`DrawList::scissor_mode`]. The other extensions (shader and blend
+//! modes, rlgl, raygui, ...) are not recorded: they call raylib directly, so only use them on
+//! a real draw handle.
+//!
+//! ```
+//! use raylib::prelude::*;
+//!
+//! let mut list = DrawList::new();
+//! {
+//!     let mut world = list.begin_mode2D(Camera2D::default());
+//!     world.draw_circle(0, 0, 4.0, Color::RED);
+//! }
+//! assert_eq!(list.commands()[2], DrawCommand::EndMode2D);
+//! ```
+use std::collections::HashMap;
+use std::ffi::CString;
+use std::marker::PhantomData;
+
+use crate::core::camera::{Camera2D, Camera3D};
+use crate::core::color::Color;
+use crate::core::drawing::{RaylibDraw, RaylibDraw3D, RaylibDrawImmediate};
+use crate::core::math::{BoundingBox, Matrix, Ray, Rectangle, Vector2, Vector3};
+use crate::core::models::WeakMaterial;
+use crate::core::rlgl::track_mode3d;
+use crate::ffi;
+
+#[cfg(feature = "with_serde")]
+use serde::{Deserialize, Serialize};
+
+/// A texture as recorded in a [`DrawCommand`]. Textures live on the GPU, so a copy of the
+/// handle is all that is needed to draw them again, as long as the texture isn't unloaded first.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "with_serde", derive(Serialize, Deserialize))]
+pub struct TextureRef {
+    pub id: u32,
+    pub width: i32,
+    pub height: i32,
+    pub mipmaps: i32,
+    pub format: i32,
+}
+
+impl From<&ffi::Texture2D> for TextureRef {
+    fn from(t: &ffi::Texture2D) -> TextureRef {
+        TextureRef {
+            id: t.id,
+            width: t.width,
+            height: t.height,
+            mipmaps: t.mipmaps,
+            format: t.format,
+        }
+    }
+}
+
+impl From<TextureRef> for ffi::Texture2D {
+    fn from(t: TextureRef) -> ffi::Texture2D {
+        ffi::Texture2D {
+            id: t.id,
+            width: t.width,
+            height: t.height,
+            mipmaps: t.mipmaps,
+            format: t.format,
+        }
+    }
+}
+
+/// A font as recorded in a [`DrawCommand`], identified by the id of its atlas texture.
+/// Fonts are resolved through [`DrawResources`] on replay.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "with_serde", derive(Serialize, Deserialize))]
+pub struct FontRef(pub u32);
+
+impl FontRef {
+    pub fn of(font: &ffi::Font) -> FontRef {
+        FontRef(font.texture.id)
+    }
+}
+
+/// A mesh as recorded in a [`DrawCommand`], identified by its vertex array id.
+/// Meshes are resolved through [`DrawResources`] on replay.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "with_serde", derive(Serialize, Deserialize))]
+pub struct MeshRef(pub u32);
+
+impl MeshRef {
+    pub fn of(mesh: &ffi::Mesh) -> MeshRef {
+        MeshRef(mesh.vaoId)
+    }
+}
+
+/// A model as recorded in a [`DrawCommand`], identified by the vertex array id of its first mesh.
+/// Models are resolved through [`DrawResources`] on replay.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "with_serde", derive(Serialize, Deserialize))]
+pub struct ModelRef(pub u32);
+
+impl ModelRef {
+    pub fn of(model: &ffi::Model) -> ModelRef {
+        if model.meshCount > 0 && !model.meshes.is_null() {
+            ModelRef(unsafe { (*model.meshes).vaoId })
+        } else {
+            ModelRef(0)
+        }
+    }
+}
+
+/// A material as recorded in a [`DrawCommand`], identified by its shader and albedo texture.
+/// Materials are resolved through [`DrawResources`] on replay.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "with_serde", derive(Serialize, Deserialize))]
+pub struct MaterialRef {
+    pub shader: u32,
+    pub texture: u32,
+}
+
+impl MaterialRef {
+    pub fn of(material: &ffi::Material) -> MaterialRef {
+        let texture = if material.maps.is_null() {
+            0
+        } else {
+            unsafe { (*material.maps).texture.id }
+        };
+        MaterialRef {
+            shader: material.shader.id,
+            texture,
+        }
+    }
+}
+
+/// A [`Camera3D`] as recorded in a [`DrawCommand`]. `projection` is a raw `CameraProjection`.
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "with_serde", derive(Serialize, Deserialize))]
+pub struct RecordedCamera3D {
+    pub position: Vector3,
+    pub target: Vector3,
+    pub up: Vector3,
+    pub fovy: f32,
+    pub projection: i32,
+}
+
+impl From<ffi::Camera3D> for RecordedCamera3D {
+    fn from(c: ffi::Camera3D) -> RecordedCamera3D {
+        RecordedCamera3D {
+            position: c.position.into(),
+            target: c.target.into(),
+            up: c.up.into(),
+            fovy: c.fovy,
+            projection: c.projection,
+        }
+    }
+}
+
+impl From<RecordedCamera3D> for ffi::Camera3D {
+    fn from(c: RecordedCamera3D) -> ffi::Camera3D {
+        ffi::Camera3D {
+            position: c.position.into(),
+            target: c.target.into(),
+            up: c.up.into(),
+            fovy: c.fovy,
+            projection: c.projection,
+        }
+    }
+}
+
+/// An n-patch layout as recorded in a [`DrawCommand`]. `layout` is a raw `NPatchLayout`.
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "with_serde", derive(Serialize, Deserialize))]
+pub struct RecordedNPatchInfo {
+    pub source: Rectangle,
+    pub left: i32,
+    pub top: i32,
+    pub right: i32,
+    pub bottom: i32,
+    pub layout: i32,
+}
+
+impl From<ffi::NPatchInfo> for RecordedNPatchInfo {
+    fn from(n: ffi::NPatchInfo) -> RecordedNPatchInfo {
+        RecordedNPatchInfo {
+            source: n.source.into(),
+            left: n.left,
+            top: n.top,
+            right: n.right,
+            bottom: n.bottom,
+            layout: n.layout,
+        }
+    }
+}
+
+impl From<RecordedNPatchInfo> for ffi::NPatchInfo {
+    fn from(n: RecordedNPatchInfo) -> ffi::NPatchInfo {
+        ffi::NPatchInfo {
+            source: n.source.into(),
+            left: n.left,
+            top: n.top,
+            right: n.right,
+            bottom: n.bottom,
+            layout: n.layout,
+        }
+    }
+}
+
+/// One recorded draw call. Variants are named after the raylib function they replay with.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "with_serde", derive(Serialize, Deserialize))]
+pub enum DrawCommand {
+    BeginMode2D {
+        camera: Camera2D,
+    },
+    EndMode2D,
+    BeginMode3D {
+        camera: RecordedCamera3D,
+    },
+    EndMode3D,
+    BeginScissorMode {
+        x: i32,
+        y: i32,
+        width: i32,
+        height: i32,
+    },
+    EndScissorMode,
+
+    ClearBackground {
+        color: Color,
+    },
+    SetShapesTexture {
+        texture: TextureRef,
+        source: Rectangle,
+    },
+    DrawPixel {
+        x: i32,
+        y: i32,
+        color: Color,
+    },
+    DrawPixelV {
+        position: Vector2,
+        color: Color,
+    },
+    DrawLine {
+        start_pos_x: i32,
+        start_pos_y: i32,
+        end_pos_x: i32,
+        end_pos_y: i32,
+        color: Color,
+    },
+    DrawLineV {
+        start_pos: Vector2,
+        end_pos: Vector2,
+        color: Color,
+    },
+    DrawLineEx {
+        start_pos: Vector2,
+        end_pos: Vector2,
+        thick: f32,
+        color: Color,
+    },
+    DrawLineBezier {
+        start_pos: Vector2,
+        end_pos: Vector2,
+        thick: f32,
+        color: Color,
+    },
+    DrawLineStrip {
+        points: Vec<Vector2>,
+        color: Color,
+    },
+    DrawCircle {
+        center_x: i32,
+        center_y: i32,
+        radius: f32,
+        color: Color,
+    },
+    DrawCircleSector {
+        center: Vector2,
+        radius: f32,
+        start_angle: f32,
+        end_angle: f32,
+        segments: i32,
+        color: Color,
+    },
+    DrawCircleSectorLines {
+        center: Vector2,
+        radius: f32,
+        start_angle: f32,
+        end_angle: f32,
+        segments: i32,
+        color: Color,
+    },
+    DrawCircleGradient {
+        center_x: i32,
+        center_y: i32,
+        radius: f32,
+        color1: Color,
+        color2: Color,
+    },
+    DrawCircleV {
+        center: Vector2,
+        radius: f32,
+        color: Color,
+    },
+    DrawCircleLines {
+        center_x: i32,
+        center_y: i32,
+        radius: f32,
+        color: Color,
+    },
+    DrawCircleLinesV {
+        center: Vector2,
+        radius: f32,
+        color: Color,
+    },
+    DrawEllipse {
+        center_x: i32,
+        center_y: i32,
+        radius_h: f32,
+        radius_v: f32,
+        color: Color,
+    },
+    DrawEllipseLines {
+        center_x: i32,
+        center_y: i32,
+        radius_h: f32,
+        radius_v: f32,
+        color: Color,
+    },
+    DrawRing {
+        center: Vector2,
+        inner_radius: f32,
+        outer_radius: f32,
+        start_angle: f32,
+        end_angle: f32,
+        segments: i32,
+        color: Color,
+    },
+    DrawRingLines {
+        center: Vector2,
+        inner_radius: f32,
+        outer_radius: f32,
+        start_angle: f32,
+        end_angle: f32,
+        segments: i32,
+        color: Color,
+    },
+    DrawRectangle {
+        x: i32,
+        y: i32,
+        width: i32,
+        height: i32,
+        color: Color,
+    },
+    DrawRectangleV {
+        position: Vector2,
+        size: Vector2,
+        color: Color,
+    },
+    DrawRectangleRec {
+        rec: Rectangle,
+        color: Color,
+    },
+    DrawRectanglePro {
+        rec: Rectangle,
+        origin: Vector2,
+        rotation: f32,
+        color: Color,
+    },
+    DrawRectangleGradientV {
+        x: i32,
+        y: i32,
+        width: i32,
+        height: i32,
+        color1: Color,
+        color2: Color,
+    },
+    DrawRectangleGradientH {
+        x: i32,
+        y: i32,
+        width: i32,
+        height: i32,
+        color1: Color,
+        color2: Color,
+    },
+    DrawRectangleGradientEx {
+        rec: Rectangle,
+        col1: Color,
+        col2: Color,
+        col3: Color,
+        col4: Color,
+    },
+    DrawRectangleLines {
+        x: i32,
+        y: i32,
+        width: i32,
+        height: i32,
+        color: Color,
+    },
+    DrawRectangleLinesEx {
+        rec: Rectangle,
+        line_thick: f32,
+        color: Color,
+    },
+    DrawRectangleRounded {
+        rec: Rectangle,
+        roundness: f32,
+        segments: i32,
+        color: Color,
+    },
+    DrawRectangleRoundedLines {
+        rec: Rectangle,
+        roundness: f32,
+        segments: i32,
+        color: Color,
+    },
+    DrawRectangleRoundedLinesEx {
+        rec: Rectangle,
+        roundness: f32,
+        segments: i32,
+        line_thickness: f32,
+        color: Color,
+    },
+    DrawTriangle {
+        v1: Vector2,
+        v2: Vector2,
+        v3: Vector2,
+        color: Color,
+    },
+    DrawTriangleLines {
+        v1: Vector2,
+        v2: Vector2,
+        v3: Vector2,
+        color: Color,
+    },
+    DrawTriangleFan {
+        points: Vec<Vector2>,
+        color: Color,
+    },
+    DrawTriangleStrip {
+        points: Vec<Vector2>,
+        color: Color,
+    },
+    DrawPoly {
+        center: Vector2,
+        sides: i32,
+        radius: f32,
+        rotation: f32,
+        color: Color,
+    },
+    DrawPolyLines {
+        center: Vector2,
+        sides: i32,
+        radius: f32,
+        rotation: f32,
+        color: Color,
+    },
+    DrawPolyLinesEx {
+        center: Vector2,
+        sides: i32,
+        radius: f32,
+        rotation: f32,
+        line_thick: f32,
+        color: Color,
+    },
+    DrawTexture {
+        texture: TextureRef,
+        x: i32,
+        y: i32,
+        tint: Color,
+    },
+    DrawTextureV {
+        texture: TextureRef,
+        position: Vector2,
+        tint: Color,
+    },
+    DrawTextureEx {
+        texture: TextureRef,
+        position: Vector2,
+        rotation: f32,
+        scale: f32,
+        tint: Color,
+    },
+    DrawTextureRec {
+        texture: TextureRef,
+        source_rec: Rectangle,
+        position: Vector2,
+        tint: Color,
+    },
+    DrawTexturePro {
+        texture: TextureRef,
+        source_rec: Rectangle,
+        dest_rec: Rectangle,
+        origin: Vector2,
+        rotation: f32,
+        tint: Color,
+    },
+    DrawTextureNPatch {
+        texture: TextureRef,
+        n_patch_info: RecordedNPatchInfo,
+        dest_rec: Rectangle,
+        origin: Vector2,
+        rotation: f32,
+        tint: Color,
+    },
+    DrawFPS {
+        x: i32,
+        y: i32,
+    },
+    DrawText {
+        text: String,
+        x: i32,
+        y: i32,
+        font_size: i32,
+        color: Color,
+    },
+    DrawTextCodepoints {
+        font: FontRef,
+        text: String,
+        position: Vector2,
+        font_size: f32,
+        spacing: f32,
+        tint: Color,
+    },
+    DrawTextEx {
+        font: FontRef,
+        text: String,
+        position: Vector2,
+        font_size: f32,
+        spacing: f32,
+        tint: Color,
+    },
+    DrawTextPro {
+        font: FontRef,
+        text: String,
+        position: Vector2,
+        origin: Vector2,
+        rotation: f32,
+        font_size: f32,
+        spacing: f32,
+        tint: Color,
+    },
+    DrawTextCodepoint {
+        font: FontRef,
+        codepoint: i32,
+        position: Vector2,
+        scale: f32,
+        tint: Color,
+    },
+    DrawSplineLinear {
+        points: Vec<Vector2>,
+        thick: f32,
+        color: Color,
+    },
+    DrawSplineBasis {
+        points: Vec<Vector2>,
+        thick: f32,
+        color: Color,
+    },
+    DrawSplineCatmullRom {
+        points: Vec<Vector2>,
+        thick: f32,
+        color: Color,
+    },
+    DrawSplineBezierQuadratic {
+        points: Vec<Vector2>,
+        thick: f32,
+        color: Color,
+    },
+    DrawSplineBezierCubic {
+        points: Vec<Vector2>,
+        thick: f32,
+        color: Color,
+    },
+    DrawSplineSegmentLinear {
+        p1: Vector2,
+        p2: Vector2,
+        thick: f32,
+        color: Color,
+    },
+    DrawSplineSegmentBasis {
+        p1: Vector2,
+        p2: Vector2,
+        p3: Vector2,
+        p4: Vector2,
+        thick: f32,
+        color: Color,
+    },
+    DrawSplineSegmentCatmullRom {
+        p1: Vector2,
+        p2: Vector2,
+        p3: Vector2,
+        p4: Vector2,
+        thick: f32,
+        color: Color,
+    },
+    DrawSplineSegmentBezierQuadratic {
+        p1: Vector2,
+        c2: Vector2,
+        p3: Vector2,
+        thick: f32,
+        color: Color,
+    },
+    DrawSplineSegmentBezierCubic {
+        p1: Vector2,
+        c2: Vector2,
+        c3: Vector2,
+        p4: Vector2,
+        thick: f32,
+        color: Color,
+    },
+
+    DrawPoint3D {
+        position: Vector3,
+        color: Color,
+    },
+    DrawTriangle3D {
+        v1: Vector3,
+        v2: Vector3,
+        v3: Vector3,
+        color: Color,
+    },
+    DrawTriangleStrip3D {
+        points: Vec<Vector3>,
+        color: Color,
+    },
+    DrawLine3D {
+        start_pos: Vector3,
+        end_pos: Vector3,
+        color: Color,
+    },
+    DrawCircle3D {
+        center: Vector3,
+        radius: f32,
+        rotation_axis: Vector3,
+        rotation_angle: f32,
+        color: Color,
+    },
+    DrawCube {
+        position: Vector3,
+        width: f32,
+        height: f32,
+        length: f32,
+        color: Color,
+    },
+    DrawCubeV {
+        position: Vector3,
+        size: Vector3,
+        color: Color,
+    },
+    DrawCubeWires {
+        position: Vector3,
+        width: f32,
+        height: f32,
+        length: f32,
+        color: Color,
+    },
+    DrawCubeWiresV {
+        position: Vector3,
+        size: Vector3,
+        color: Color,
+    },
+    DrawMesh {
+        mesh: MeshRef,
+        material: MaterialRef,
+        transform: Matrix,
+    },
+    DrawMeshInstanced {
+        mesh: MeshRef,
+        material: MaterialRef,
+        transforms: Vec<Matrix>,
+    },
+    DrawSphere {
+        center_pos: Vector3,
+        radius: f32,
+        color: Color,
+    },
+    DrawSphereEx {
+        center_pos: Vector3,
+        radius: f32,
+        rings: i32,
+        slices: i32,
+        color: Color,
+    },
+    DrawSphereWires {
+        center_pos: Vector3,
+        radius: f32,
+        rings: i32,
+        slices: i32,
+        color: Color,
+    },
+    DrawCylinder {
+        position: Vector3,
+        radius_top: f32,
+        radius_bottom: f32,
+        height: f32,
+        slices: i32,
+        color: Color,
+    },
+    DrawCylinderEx {
+        start_position: Vector3,
+        end_position: Vector3,
+        radius_start: f32,
+        radius_end: f32,
+        slices: i32,
+        color: Color,
+    },
+    DrawCylinderWires {
+        position: Vector3,
+        radius_top: f32,
+        radius_bottom: f32,
+        height: f32,
+        slices: i32,
+        color: Color,
+    },
+    DrawCylinderWiresEx {
+        start_position: Vector3,
+        end_position: Vector3,
+        radius_start: f32,
+        radius_end: f32,
+        slices: i32,
+        color: Color,
+    },
+    DrawCapsule {
+        start_pos: Vector3,
+        end_pos: Vector3,
+        radius: f32,
+        slices: i32,
+        rings: i32,
+        color: Color,
+    },
+    DrawCapsuleWires {
+        start_pos: Vector3,
+        end_pos: Vector3,
+        radius: f32,
+        slices: i32,
+        rings: i32,
+        color: Color,
+    },
+    DrawPlane {
+        center_pos: Vector3,
+        size: Vector2,
+        color: Color,
+    },
+    DrawRay {
+        ray: Ray,
+        color: Color,
+    },
+    DrawGrid {
+        slices: i32,
+        spacing: f32,
+    },
+    DrawModel {
+        model: ModelRef,
+        position: Vector3,
+        scale: f32,
+        tint: Color,
+    },
+    DrawModelEx {
+        model: ModelRef,
+        position: Vector3,
+        rotation_axis: Vector3,
+        rotation_angle: f32,
+        scale: Vector3,
+        tint: Color,
+    },
+    DrawModelWires {
+        model: ModelRef,
+        position: Vector3,
+        scale: f32,
+        tint: Color,
+    },
+    DrawModelWiresEx {
+        model: ModelRef,
+        position: Vector3,
+        rotation_axis: Vector3,
+        rotation_angle: f32,
+        scale: Vector3,
+        tint: Color,
+    },
+    DrawModelPoints {
+        model: ModelRef,
+        position: Vector3,
+        scale: f32,
+        tint: Color,
+    },
+    DrawModelPointsEx {
+        model: ModelRef,
+        position: Vector3,
+        rotation_axis: Vector3,
+        angle: f32,
+        scale: Vector3,
+        tint: Color,
+    },
+    DrawBoundingBox {
+        bbox: BoundingBox,
+        color: Color,
+    },
+    DrawBillboard {
+        camera: RecordedCamera3D,
+        texture: TextureRef,
+        center: Vector3,
+        size: f32,
+        tint: Color,
+    },
+    DrawBillboardRec {
+        camera: RecordedCamera3D,
+        texture: TextureRef,
+        source_rec: Rectangle,
+        center: Vector3,
+        size: Vector2,
+        tint: Color,
+    },
+    DrawBillboardPro {
+        camera: RecordedCamera3D,
+        texture: TextureRef,
+        source: Rectangle,
+        position: Vector3,
+        up: Vector3,
+        size: Vector2,
+        origin: Vector2,
+        rotation: f32,
+        tint: Color,
+    },
+}
+
+/// The fonts, meshes, models and materials a [`DrawList`] refers to, looked up on replay.
+///
+/// Textures don't need to be registered, their GPU handle is stored in the command itself.
+#[derive(Default)]
+pub struct DrawResources<'a> {
+    fonts: HashMap<FontRef, ffi::Font>,
+    meshes: HashMap<MeshRef, ffi::Mesh>,
+    models: HashMap<ModelRef, ffi::Model>,
+    materials: HashMap<MaterialRef, ffi::Material>,
+    _borrow: PhantomData<&'a ()>,
+}
+
+impl<'a> DrawResources<'a> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add_font(&mut self, font: &'a impl AsRef<ffi::Font>) -> &mut Self {
+        let font = *font.as_ref();
+        self.fonts.insert(FontRef::of(&font), font);
+        self
+    }
+
+    pub fn add_mesh(&mut self, mesh: &'a impl AsRef<ffi::Mesh>) -> &mut Self {
+        let mesh = *mesh.as_ref();
+        self.meshes.insert(MeshRef::of(&mesh), mesh);
+        self
+    }
+
+    pub fn add_model(&mut self, model: &'a impl AsRef<ffi::Model>) -> &mut Self {
+        let model = *model.as_ref();
+        self.models.insert(ModelRef::of(&model), model);
+        self
+    }
+
+    pub fn add_material(&mut self, material: &'a impl AsRef<ffi::Material>) -> &mut Self {
+        let material = *material.as_ref();
+        self.materials.insert(MaterialRef::of(&material), material);
+        self
+    }
+
+    fn font(&self, font: FontRef) -> ffi::Font {
+        match self.fonts.get(&font) {
+            Some(f) => *f,
+            None => unsafe { ffi::GetFontDefault() },
+        }
+    }
+}
+
+/// A recorded sequence of draw calls. See the [module documentation](self).
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "with_serde", derive(Serialize, Deserialize))]
+pub struct DrawList {
+    commands: Vec<DrawCommand>,
+}
+
+impl DrawList {
+    pub fn new() -> DrawList {
+        DrawList::default()
+    }
+
+    pub fn with_capacity(capacity: usize) -> DrawList {
+        DrawList {
+            commands: Vec::with_capacity(capacity),
+        }
+    }
+
+    #[inline]
+    pub fn commands(&self) -> &[DrawCommand] {
+        &self.commands
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.commands.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.commands.is_empty()
+    }
+
+    #[inline]
+    pub fn clear(&mut self) {
+        self.commands.clear()
+    }
+
+    /// Appends a command as is. Begin/End pairs pushed this way should be balanced.
+    #[inline]
+    pub fn push(&mut self, command: DrawCommand) {
+        self.commands.push(command)
+    }
+
+    /// Appends all commands of `other`.
+    pub fn append(&mut self, other: &DrawList) {
+        self.commands.extend_from_slice(&other.commands)
+    }
+
+    pub fn into_commands(self) -> Vec<DrawCommand> {
+        self.commands
+    }
+
+    /// Records everything `func` draws inside `BeginMode2D`/`EndMode2D`.
+    #[allow(non_snake_case)]
+    pub fn mode2D(&mut self, camera: impl Into<Camera2D>, func: impl FnOnce(&mut DrawList)) {
+        self.push(DrawCommand::BeginMode2D {
+            camera: camera.into(),
+        });
+        func(self);
+        self.push(DrawCommand::EndMode2D);
+    }
+
+    /// Records everything `func` draws inside `BeginMode3D`/`EndMode3D`.
+    #[allow(non_snake_case)]
+    pub fn mode3D(&mut self, camera: impl Into<ffi::Camera3D>, func: impl FnOnce(&mut DrawList)) {
+        self.push(DrawCommand::BeginMode3D {
+            camera: to_camera3d(camera),
+        });
+        func(self);
+        self.push(DrawCommand::EndMode3D);
+    }
+
+    /// Records `BeginMode2D` and returns a guard that records `EndMode2D` when dropped.
+    /// Draws on the guard are recorded into this list.
+    #[allow(non_snake_case)]
+    #[must_use]
+    pub fn begin_mode2D(&mut self, camera: impl Into<Camera2D>) -> DrawListMode<'_> {
+        self.push(DrawCommand::BeginMode2D {
+            camera: camera.into(),
+        });
+        DrawListMode(self, DrawCommand::EndMode2D)
+    }
+
+    /// Records `BeginMode3D` and returns a guard that records `EndMode3D` when dropped.
+    /// Draws on the guard are recorded into this list.
+    #[allow(non_snake_case)]
+    #[must_use]
+    pub fn begin_mode3D(&mut self, camera: impl Into<ffi::Camera3D>) -> DrawListMode<'_> {
+        self.push(DrawCommand::BeginMode3D {
+            camera: to_camera3d(camera),
+        });
+        DrawListMode(self, DrawCommand::EndMode3D)
+    }
+
+    /// Records `BeginScissorMode` and returns a guard that records `EndScissorMode` when
+    /// dropped. Draws on the guard are recorded into this list.
+    #[must_use]
+    pub fn begin_scissor_mode(
+        &mut self,
+        x: i32,
+        y: i32,
+        width: i32,
+        height: i32,
+    ) -> DrawListMode<'_> {
+        self.push(DrawCommand::BeginScissorMode {
+            x,
+            y,
+            width,
+            height,
+        });
+        DrawListMode(self, DrawCommand::EndScissorMode)
+    }
+
+    /// Records everything `func` draws inside `BeginScissorMode`/`EndScissorMode`.
+    pub fn scissor_mode(
+        &mut self,
+        x: i32,
+        y: i32,
+        width: i32,
+        height: i32,
+        func: impl FnOnce(&mut DrawList),
+    ) {
+        self.push(DrawCommand::BeginScissorMode {
+            x,
+            y,
+            width,
+            height,
+        });
+        func(self);
+        self.push(DrawCommand::EndScissorMode);
+    }
+
+    /// Replays the list onto `d`. Text drawn with a custom font falls back to the default font
+    /// and commands using meshes, models or materials are skipped; use [`DrawList::replay_with`]
+    /// to provide those.
+    pub fn replay(&self, d: &mut impl RaylibDrawImmediate) -> usize {
+        self.replay_with(d, &DrawResources::default())
+    }
+
+    /// Replays the list onto `d`, looking up fonts, meshes, models and materials in `resources`.
+    ///
+    /// The commands go straight to raylib; `d` is what shows that a frame or texture mode is
+    /// being drawn. Fonts that aren't found fall back to the default font. Commands whose mesh,
+    /// model or material isn't found are skipped; the number of skipped commands is returned.
+    /// Modes left open by the list are closed at the end.
+    pub fn replay_with(
+        &self,
+        _d: &mut impl RaylibDrawImmediate,
+        resources: &DrawResources,
+    ) -> usize {
+        let mut skipped = 0;
+        let (mut mode2d, mut mode3d, mut scissor) = (0, 0, 0);
+        for command in &self.commands {
+            if !replay_command(command, resources) {
+                skipped += 1;
+                continue;
+            }
+            match command {
+                DrawCommand::BeginMode2D { .. } => mode2d += 1,
+                DrawCommand::EndMode2D => mode2d -= 1,
+                DrawCommand::BeginMode3D { .. } => mode3d += 1,
+                DrawCommand::EndMode3D => mode3d -= 1,
+                DrawCommand::BeginScissorMode { .. } => scissor += 1,
+                DrawCommand::EndScissorMode => scissor -= 1,
+                _ => {}
+            }
+        }
+        unsafe {
+            for _ in 0..scissor {
+                ffi::EndScissorMode();
+            }
+            for _ in 0..mode3d {
+                ffi::EndMode3D();
+                track_mode3d(false);
+            }
+            for _ in 0..mode2d {
+                ffi::EndMode2D();
+            }
+        }
+        skipped
+    }
+}
+
+/// A mode recorded into a [`DrawList`] by [`DrawList::begin_mode2D`] and friends. Records the
+/// end of the mode when dropped.
+#[derive(Debug)]
+pub struct DrawListMode<'a>(&'a mut DrawList, DrawCommand);
+
+impl<'a> Drop for DrawListMode<'a> {
+    fn drop(&mut self) {
+        let end = std::mem::replace(&mut self.1, DrawCommand::EndMode2D);
+        self.0.push(end);
+    }
+}
+
+impl<'a> std::ops::Deref for DrawListMode<'a> {
+    type Target = DrawList;
+
+    fn deref(&self) -> &DrawList {
+        self.0
+    }
+}
+
+impl<'a> std::ops::DerefMut for DrawListMode<'a> {
+    fn deref_mut(&mut self) -> &mut DrawList {
+        self.0
+    }
+}
+
+impl From<Vec<DrawCommand>> for DrawList {
+    fn from(commands: Vec<DrawCommand>) -> DrawList {
+        DrawList { commands }
+    }
+}
+
+impl Extend<DrawCommand> for DrawList {
+    fn extend<I: IntoIterator<Item = DrawCommand>>(&mut self, iter: I) {
+        self.commands.extend(iter)
+    }
+}
+
+impl<'a> IntoIterator for &'a DrawList {
+    type Item = &'a DrawCommand;
+    type IntoIter = std::slice::Iter<'a, DrawCommand>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.commands.iter()
+    }
+}
+
+fn to_color(color: impl Into<ffi::Color>) -> Color {
+    let color: ffi::Color = color.into();
+    color.into()
+}
+
+fn to_v2(v: impl Into<ffi::Vector2>) -> Vector2 {
+    let v: ffi::Vector2 = v.into();
+    v.into()
+}
+
+fn to_v3(v: impl Into<ffi::Vector3>) -> Vector3 {
+    let v: ffi::Vector3 = v.into();
+    v.into()
+}
+
+fn to_rect(r: impl Into<ffi::Rectangle>) -> Rectangle {
+    let r: ffi::Rectangle = r.into();
+    r.into()
+}
+
+fn to_camera3d(camera: impl Into<ffi::Camera3D>) -> RecordedCamera3D {
+    let camera: ffi::Camera3D = camera.into();
+    camera.into()
+}
+
+fn c_text(text: &str) -> CString {
+    // interior nul bytes would end the string early on the C side anyway
+    CString::new(text.split('\0').next().unwrap_or("")).unwrap()
+}
+
+/// Executes `command`, returns `false` if a resource it needs is missing.
+fn replay_command(command: &DrawCommand, res: &DrawResources) -> bool {
+    use DrawCommand::*;
+    unsafe {
+        match command {
+            BeginMode2D { camera } => ffi::BeginMode2D((*camera).into()),
+            EndMode2D => ffi::EndMode2D(),
+            BeginMode3D { camera } => {
+                ffi::BeginMode3D((*camera).into());
+                track_mode3d(true);
+            }
+            EndMode3D => {
+                ffi::EndMode3D();
+                track_mode3d(false);
+            }
+            BeginScissorMode {
+                x,
+                y,
+                width,
+                height,
+            } => ffi::BeginScissorMode(*x, *y, *width, *height),
+            EndScissorMode => ffi::EndScissorMode(),
+
+            ClearBackground { color } => ffi::ClearBackground(color.into()),
+            SetShapesTexture { texture, source } => {
+                ffi::SetShapesTexture((*texture).into(), source.into())
+            }
+            DrawPixel { x, y, color } => ffi::DrawPixel(*x, *y, color.into()),
+            DrawPixelV { position, color } => ffi::DrawPixelV((*position).into(), color.into()),
+            DrawLine {
+                start_pos_x,
+                start_pos_y,
+                end_pos_x,
+                end_pos_y,
+                color,
+            } => ffi::DrawLine(
+                *start_pos_x,
+                *start_pos_y,
+                *end_pos_x,
+                *end_pos_y,
+                color.into(),
+            ),
+            DrawLineV {
+                start_pos,
+                end_pos,
+                color,
+            } => ffi::DrawLineV((*start_pos).into(), (*end_pos).into(), color.into()),
+            DrawLineEx {
+                start_pos,
+                end_pos,
+                thick,
+                color,
+            } => ffi::DrawLineEx((*start_pos).into(), (*end_pos).into(), *thick, color.into()),
+            DrawLineBezier {
+                start_pos,
+                end_pos,
+                thick,
+                color,
+            } => ffi::DrawLineBezier((*start_pos).into(), (*end_pos).into(), *thick, color.into()),
+            DrawLineStrip { points, color } => ffi::DrawLineStrip(
+                points.as_ptr() as *mut ffi::Vector2,
+                points.len() as i32,
+                color.into(),
+            ),
+            DrawCircle {
+                center_x,
+                center_y,
+                radius,
+                color,
+            } => ffi::DrawCircle(*center_x, *center_y, *radius, color.into()),
+            DrawCircleSector {
+                center,
+                radius,
+                start_angle,
+                end_angle,
+                segments,
+                color,
+            } => ffi::DrawCircleSector(
+                (*center).into(),
+                *radius,
+                *start_angle,
+                *end_angle,
+                *segments,
+                color.into(),
+            ),
+            DrawCircleSectorLines {
+                center,
+                radius,
+                start_angle,
+                end_angle,
+                segments,
+                color,
+            } => ffi::DrawCircleSectorLines(
+                (*center).into(),
+                *radius,
+                *start_angle,
+                *end_angle,
+                *segments,
+                color.into(),
+            ),
+            DrawCircleGradient {
+                center_x,
+                center_y,
+                radius,
+                color1,
+                color2,
+            } => {
+                ffi::DrawCircleGradient(*center_x, *center_y, *radius, color1.into(), color2.into())
+            }
+            DrawCircleV {
+                center,
+                radius,
+                color,
+            } => ffi::DrawCircleV((*center).into(), *radius, color.into()),
+            DrawCircleLines {
+                center_x,
+                center_y,
+                radius,
+                color,
+            } => ffi::DrawCircleLines(*center_x, *center_y, *radius, color.into()),
+            DrawCircleLinesV {
+                center,
+                radius,
+                color,
+            } => ffi::DrawCircleLinesV((*center).into(), *radius, color.into()),
+            DrawEllipse {
+                center_x,
+                center_y,
+                radius_h,
+                radius_v,
+                color,
+            } => ffi::DrawEllipse(*center_x, *center_y, *radius_h, *radius_v, color.into()),
+            DrawEllipseLines {
+                center_x,
+                center_y,
+                radius_h,
+                radius_v,
+                color,
+            } => ffi::DrawEllipseLines(*center_x, *center_y, *radius_h, *radius_v, color.into()),
+            DrawRing {
+                center,
+                inner_radius,
+                outer_radius,
+                start_angle,
+                end_angle,
+                segments,
+                color,
+            } => ffi::DrawRing(
+                (*center).into(),
+                *inner_radius,
+                *outer_radius,
+                *start_angle,
+                *end_angle,
+                *segments,
+                color.into(),
+            ),
+            DrawRingLines {
+                center,
+                inner_radius,
+                outer_radius,
+                start_angle,
+                end_angle,
+                segments,
+                color,
+            } => ffi::DrawRingLines(
+                (*center).into(),
+                *inner_radius,
+                *outer_radius,
+                *start_angle,
+                *end_angle,
+                *segments,
+                color.into(),
+            ),
+            DrawRectangle {
+                x,
+                y,
+                width,
+                height,
+                color,
+            } => ffi::DrawRectangle(*x, *y, *width, *height, color.into()),
+            DrawRectangleV {
+                position,
+                size,
+                color,
+            } => ffi::DrawRectangleV((*position).into(), (*size).into(), color.into()),
+            DrawRectangleRec { rec, color } => ffi::DrawRectangleRec(rec.into(), color.into()),
+            DrawRectanglePro {
+                rec,
+                origin,
+                rotation,
+                color,
+            } => ffi::DrawRectanglePro(rec.into(), (*origin).into(), *rotation, color.into()),
+            DrawRectangleGradientV {
+                x,
+                y,
+                width,
+                height,
+                color1,
+                color2,
+            } => ffi::DrawRectangleGradientV(*x, *y, *width, *height, color1.into(), color2.into()),
+            DrawRectangleGradientH {
+                x,
+                y,
+                width,
+                height,
+                color1,
+                color2,
+            } => ffi::DrawRectangleGradientH(*x, *y, *width, *height, color1.into(), color2.into()),
+            DrawRectangleGradientEx {
+                rec,
+                col1,
+                col2,
+                col3,
+                col4,
+            } => ffi::DrawRectangleGradientEx(
+                rec.into(),
+                col1.into(),
+                col2.into(),
+                col3.into(),
+                col4.into(),
+            ),
+            DrawRectangleLines {
+                x,
+                y,
+                width,
+                height,
+                color,
+            } => ffi::DrawRectangleLines(*x, *y, *width, *height, color.into()),
+            DrawRectangleLinesEx {
+                rec,
+                line_thick,
+                color,
+            } => ffi::DrawRectangleLinesEx(rec.into(), *line_thick, color.into()),
+            DrawRectangleRounded {
+                rec,
+                roundness,
+                segments,
+                color,
+            } => ffi::DrawRectangleRounded(rec.into(), *roundness, *segments, color.into()),
+            DrawRectangleRoundedLines {
+                rec,
+                roundness,
+                segments,
+                color,
+            } => ffi::DrawRectangleRoundedLines(rec.into(), *roundness, *segments, color.into()),
+            DrawRectangleRoundedLinesEx {
+                rec,
+                roundness,
+                segments,
+                line_thickness,
+                color,
+            } => ffi::DrawRectangleRoundedLinesEx(
+                rec.into(),
+                *roundness,
+                *segments,
+                *line_thickness,
+                color.into(),
+            ),
+            DrawTriangle { v1, v2, v3, color } => {
+                ffi::DrawTriangle((*v1).into(), (*v2).into(), (*v3).into(), color.into())
+            }
+            DrawTriangleLines { v1, v2, v3, color } => {
+                ffi::DrawTriangleLines((*v1).into(), (*v2).into(), (*v3).into(), color.into())
+            }
+            DrawTriangleFan { points, color } => ffi::DrawTriangleFan(
+                points.as_ptr() as *mut ffi::Vector2,
+                points.len() as i32,
+                color.into(),
+            ),
+            DrawTriangleStrip { points, color } => ffi::DrawTriangleStrip(
+                points.as_ptr() as *mut ffi::Vector2,
+                points.len() as i32,
+                color.into(),
+            ),
+            DrawPoly {
+                center,
+                sides,
+                radius,
+                rotation,
+                color,
+            } => ffi::DrawPoly((*center).into(), *sides, *radius, *rotation, color.into()),
+            DrawPolyLines {
+                center,
+                sides,
+                radius,
+                rotation,
+                color,
+            } => ffi::DrawPolyLines((*center).into(), *sides, *radius, *rotation, color.into()),
+            DrawPolyLinesEx {
+                center,
+                sides,
+                radius,
+                rotation,
+                line_thick,
+                color,
+            } => ffi::DrawPolyLinesEx(
+                (*center).into(),
+                *sides,
+                *radius,
+                *rotation,
+                *line_thick,
+                color.into(),
+            ),
+            DrawTexture {
+                texture,
+                x,
+                y,
+                tint,
+            } => ffi::DrawTexture((*texture).into(), *x, *y, tint.into()),
+            DrawTextureV {
+                texture,
+                position,
+                tint,
+            } => ffi::DrawTextureV((*texture).into(), (*position).into(), tint.into()),
+            DrawTextureEx {
+                texture,
+                position,
+                rotation,
+                scale,
+                tint,
+            } => ffi::DrawTextureEx(
+                (*texture).into(),
+                (*position).into(),
+                *rotation,
+                *scale,
+                tint.into(),
+            ),
+            DrawTextureRec {
+                texture,
+                source_rec,
+                position,
+                tint,
+            } => ffi::DrawTextureRec(
+                (*texture).into(),
+                source_rec.into(),
+                (*position).into(),
+                tint.into(),
+            ),
+            DrawTexturePro {
+                texture,
+                source_rec,
+                dest_rec,
+                origin,
+                rotation,
+                tint,
+            } => ffi::DrawTexturePro(
+                (*texture).into(),
+                source_rec.into(),
+                dest_rec.into(),
+                (*origin).into(),
+                *rotation,
+                tint.into(),
+            ),
+            DrawTextureNPatch {
+                texture,
+                n_patch_info,
+                dest_rec,
+                origin,
+                rotation,
+                tint,
+            } => ffi::DrawTextureNPatch(
+                (*texture).into(),
+                (*n_patch_info).into(),
+                dest_rec.into(),
+                (*origin).into(),
+                *rotation,
+                tint.into(),
+            ),
+            DrawFPS { x, y } => ffi::DrawFPS(*x, *y),
+            DrawText {
+                text,
+                x,
+                y,
+                font_size,
+                color,
+            } => {
+                let text = c_text(text);
+                ffi::DrawText(text.as_ptr(), *x, *y, *font_size, color.into())
+            }
+            DrawTextCodepoints {
+                font,
+                text,
+                position,
+                font_size,
+                spacing,
+                tint,
+            } => {
+                let codepoints: Vec<i32> = text.chars().map(|c| c as i32).collect();
+                ffi::DrawTextCodepoints(
+                    res.font(*font),
+                    codepoints.as_ptr(),
+                    codepoints.len() as i32,
+                    (*position).into(),
+                    *font_size,
+                    *spacing,
+                    tint.into(),
+                )
+            }
+            DrawTextEx {
+                font,
+                text,
+                position,
+                font_size,
+                spacing,
+                tint,
+            } => {
+                let text = c_text(text);
+                ffi::DrawTextEx(
+                    res.font(*font),
+                    text.as_ptr(),
+                    (*position).into(),
+                    *font_size,
+                    *spacing,
+                    tint.into(),
+                )
+            }
+            DrawTextPro {
+                font,
+                text,
+                position,
+                origin,
+                rotation,
+                font_size,
+                spacing,
+                tint,
+            } => {
+                let text = c_text(text);
+                ffi::DrawTextPro(
+                    res.font(*font),
+                    text.as_ptr(),
+                    (*position).into(),
+                    (*origin).into(),
+                    *rotation,
+                    *font_size,
+                    *spacing,
+                    tint.into(),
+                )
+            }
+            DrawTextCodepoint {
+                font,
+                codepoint,
+                position,
+                scale,
+                tint,
+            } => ffi::DrawTextCodepoint(
+                res.font(*font),
+                *codepoint,
+                (*position).into(),
+                *scale,
+                tint.into(),
+            ),
+            DrawSplineLinear {
+                points,
+                thick,
+                color,
+            } => ffi::DrawSplineLinear(
+                points.as_ptr() as *mut ffi::Vector2,
+                points.len() as i32,
+                *thick,
+                color.into(),
+            ),
+            DrawSplineBasis {
+                points,
+                thick,
+                color,
+            } => ffi::DrawSplineBasis(
+                points.as_ptr() as *mut ffi::Vector2,
+                points.len() as i32,
+                *thick,
+                color.into(),
+            ),
+            DrawSplineCatmullRom {
+                points,
+                thick,
+                color,
+            } => ffi::DrawSplineCatmullRom(
+                points.as_ptr() as *mut ffi::Vector2,
+                points.len() as i32,
+                *thick,
+                color.into(),
+            ),
+            DrawSplineBezierQuadratic {
+                points,
+                thick,
+                color,
+            } => ffi::DrawSplineBezierQuadratic(
+                points.as_ptr() as *mut ffi::Vector2,
+                points.len() as i32,
+                *thick,
+                color.into(),
+            ),
+            DrawSplineBezierCubic {
+                points,
+                thick,
+                color,
+            } => ffi::DrawSplineBezierCubic(
+                points.as_ptr() as *mut ffi::Vector2,
+                points.len() as i32,
+                *thick,
+                color.into(),
+            ),
+            DrawSplineSegmentLinear {
+                p1,
+                p2,
+                thick,
+                color,
+            } => ffi::DrawSplineSegmentLinear((*p1).into(), (*p2).into(), *thick, color.into()),
+            DrawSplineSegmentBasis {
+                p1,
+                p2,
+                p3,
+                p4,
+                thick,
+                color,
+            } => ffi::DrawSplineSegmentBasis(
+                (*p1).into(),
+                (*p2).into(),
+                (*p3).into(),
+                (*p4).into(),
+                *thick,
+                color.into(),
+            ),
+            DrawSplineSegmentCatmullRom {
+                p1,
+                p2,
+                p3,
+                p4,
+                thick,
+                color,
+            } => ffi::DrawSplineSegmentCatmullRom(
+                (*p1).into(),
+                (*p2).into(),
+                (*p3).into(),
+                (*p4).into(),
+                *thick,
+                color.into(),
+            ),
+            DrawSplineSegmentBezierQuadratic {
+                p1,
+                c2,
+                p3,
+                thick,
+                color,
+            } => ffi::DrawSplineSegmentBezierQuadratic(
+                (*p1).into(),
+                (*c2).into(),
+                (*p3).into(),
+                *thick,
+                color.into(),
+            ),
+            DrawSplineSegmentBezierCubic {
+                p1,
+                c2,
+                c3,
+                p4,
+                thick,
+                color,
+            } => ffi::DrawSplineSegmentBezierCubic(
+                (*p1).into(),
+                (*c2).into(),
+                (*c3).into(),
+                (*p4).into(),
+                *thick,
+                color.into(),
+            ),
+
+            DrawPoint3D { position, color } => ffi::DrawPoint3D((*position).into(), color.into()),
+            DrawTriangle3D { v1, v2, v3, color } => {
+                ffi::DrawTriangle3D((*v1).into(), (*v2).into(), (*v3).into(), color.into())
+            }
+            DrawTriangleStrip3D { points, color } => ffi::DrawTriangleStrip3D(
+                points.as_ptr() as *mut ffi::Vector3,
+                points.len() as i32,
+                color.into(),
+            ),
+            DrawLine3D {
+                start_pos,
+                end_pos,
+                color,
+            } => ffi::DrawLine3D((*start_pos).into(), (*end_pos).into(), color.into()),
+            DrawCircle3D {
+                center,
+                radius,
+                rotation_axis,
+                rotation_angle,
+                color,
+            } => ffi::DrawCircle3D(
+                (*center).into(),
+                *radius,
+                (*rotation_axis).into(),
+                *rotation_angle,
+                color.into(),
+            ),
+            DrawCube {
+                position,
+                width,
+                height,
+                length,
+                color,
+            } => ffi::DrawCube((*position).into(), *width, *height, *length, color.into()),
+            DrawCubeV {
+                position,
+                size,
+                color,
+            } => ffi::DrawCubeV((*position).into(), (*size).into(), color.into()),
+            DrawCubeWires {
+                position,
+                width,
+                height,
+                length,
+                color,
+            } => ffi::DrawCubeWires((*position).into(), *width, *height, *length, color.into()),
+            DrawCubeWiresV {
+                position,
+                size,
+                color,
+            } => ffi::DrawCubeWiresV((*position).into(), (*size).into(), color.into()),
+            DrawMesh {
+                mesh,
+                material,
+                transform,
+            } => match (res.meshes.get(mesh), res.materials.get(material)) {
+                (Some(mesh), Some(material)) => {
+                    ffi::DrawMesh(*mesh, *material, (*transform).into())
+                }
+                _ => return false,
+            },
+            DrawMeshInstanced {
+                mesh,
+                material,
+                transforms,
+            } => match (res.meshes.get(mesh), res.materials.get(material)) {
+                (Some(mesh), Some(material)) => {
+                    let transforms: Vec<ffi::Matrix> =
+                        transforms.iter().map(|&m| m.into()).collect();
+                    ffi::DrawMeshInstanced(
+                        *mesh,
+                        *material,
+                        transforms.as_ptr(),
+                        transforms.len() as i32,
+                    )
+                }
+                _ => return false,
+            },
+            DrawSphere {
+                center_pos,
+                radius,
+                color,
+            } => ffi::DrawSphere((*center_pos).into(), *radius, color.into()),
+            DrawSphereEx {
+                center_pos,
+                radius,
+                rings,
+                slices,
+                color,
+            } => ffi::DrawSphereEx((*center_pos).into(), *radius, *rings, *slices, color.into()),
+            DrawSphereWires {
+                center_pos,
+                radius,
+                rings,
+                slices,
+                color,
+            } => ffi::DrawSphereWires((*center_pos).into(), *radius, *rings, *slices, color.into()),
+            DrawCylinder {
+                position,
+                radius_top,
+                radius_bottom,
+                height,
+                slices,
+                color,
+            } => ffi::DrawCylinder(
+                (*position).into(),
+                *radius_top,
+                *radius_bottom,
+                *height,
+                *slices,
+                color.into(),
+            ),
+            DrawCylinderEx {
+                start_position,
+                end_position,
+                radius_start,
+                radius_end,
+                slices,
+                color,
+            } => ffi::DrawCylinderEx(
+                (*start_position).into(),
+                (*end_position).into(),
+                *radius_start,
+                *radius_end,
+                *slices,
+                color.into(),
+            ),
+            DrawCylinderWires {
+                position,
+                radius_top,
+                radius_bottom,
+                height,
+                slices,
+                color,
+            } => ffi::DrawCylinderWires(
+                (*position).into(),
+                *radius_top,
+                *radius_bottom,
+                *height,
+                *slices,
+                color.into(),
+            ),
+            DrawCylinderWiresEx {
+                start_position,
+                end_position,
+                radius_start,
+                radius_end,
+                slices,
+                color,
+            } => ffi::DrawCylinderWiresEx(
+                (*start_position).into(),
+                (*end_position).into(),
+                *radius_start,
+                *radius_end,
+                *slices,
+                color.into(),
+            ),
+            DrawCapsule {
+                start_pos,
+                end_pos,
+                radius,
+                slices,
+                rings,
+                color,
+            } => ffi::DrawCapsule(
+                (*start_pos).into(),
+                (*end_pos).into(),
+                *radius,
+                *slices,
+                *rings,
+                color.into(),
+            ),
+            DrawCapsuleWires {
+                start_pos,
+                end_pos,
+                radius,
+                slices,
+                rings,
+                color,
+            } => ffi::DrawCapsuleWires(
+                (*start_pos).into(),
+                (*end_pos).into(),
+                *radius,
+                *slices,
+                *rings,
+                color.into(),
+            ),
+            DrawPlane {
+                center_pos,
+                size,
+                color,
+            } => ffi::DrawPlane((*center_pos).into(), (*size).into(), color.into()),
+            DrawRay { ray, color } => ffi::DrawRay((*ray).into(), color.into()),
+            DrawGrid { slices, spacing } => ffi::DrawGrid(*slices, *spacing),
+            DrawModel {
+                model,
+                position,
+                scale,
+                tint,
+            } => match res.models.get(model) {
+                Some(model) => ffi::DrawModel(*model, (*position).into(), *scale, tint.into()),
+                None => return false,
+            },
+            DrawModelEx {
+                model,
+                position,
+                rotation_axis,
+                rotation_angle,
+                scale,
+                tint,
+            } => match res.models.get(model) {
+                Some(model) => ffi::DrawModelEx(
+                    *model,
+                    (*position).into(),
+                    (*rotation_axis).into(),
+                    *rotation_angle,
+                    (*scale).into(),
+                    tint.into(),
+                ),
+                None => return false,
+            },
+            DrawModelWires {
+                model,
+                position,
+                scale,
+                tint,
+            } => match res.models.get(model) {
+                Some(model) => ffi::DrawModelWires(*model, (*position).into(), *scale, tint.into()),
+                None => return false,
+            },
+            DrawModelWiresEx {
+                model,
+                position,
+                rotation_axis,
+                rotation_angle,
+                scale,
+                tint,
+            } => match res.models.get(model) {
+                Some(model) => ffi::DrawModelWiresEx(
+                    *model,
+                    (*position).into(),
+                    (*rotation_axis).into(),
+                    *rotation_angle,
+                    (*scale).into(),
+                    tint.into(),
+                ),
+                None => return false,
+            },
+            DrawModelPoints {
+                model,
+                position,
+                scale,
+                tint,
+            } => match res.models.get(model) {
+                Some(model) => {
+                    ffi::DrawModelPoints(*model, (*position).into(), *scale, tint.into())
+                }
+                None => return false,
+            },
+            DrawModelPointsEx {
+                model,
+                position,
+                rotation_axis,
+                angle,
+                scale,
+                tint,
+            } => match res.models.get(model) {
+                Some(model) => ffi::DrawModelPointsEx(
+                    *model,
+                    (*position).into(),
+                    (*rotation_axis).into(),
+                    *angle,
+                    (*scale).into(),
+                    tint.into(),
+                ),
+                None => return false,
+            },
+            DrawBoundingBox { bbox, color } => ffi::DrawBoundingBox((*bbox).into(), color.into()),
+            DrawBillboard {
+                camera,
+                texture,
+                center,
+                size,
+                tint,
+            } => ffi::DrawBillboard(
+                (*camera).into(),
+                (*texture).into(),
+                (*center).into(),
+                *size,
+                tint.into(),
+            ),
+            DrawBillboardRec {
+                camera,
+                texture,
+                source_rec,
+                center,
+                size,
+                tint,
+            } => ffi::DrawBillboardRec(
+                (*camera).into(),
+                (*texture).into(),
+                source_rec.into(),
+                (*center).into(),
+                (*size).into(),
+                tint.into(),
+            ),
+            DrawBillboardPro {
+                camera,
+                texture,
+                source,
+                position,
+                up,
+                size,
+                origin,
+                rotation,
+                tint,
+            } => ffi::DrawBillboardPro(
+                (*camera).into(),
+                (*texture).into(),
+                source.into(),
+                (*position).into(),
+                (*up).into(),
+                (*size).into(),
+                (*origin).into(),
+                *rotation,
+                tint.into(),
+            ),
+        }
+    }
+    true
+}
+
+impl RaylibDraw for DrawList {
+    fn clear_background(&mut self, color: impl Into<ffi::Color>) {
+        self.push(DrawCommand::ClearBackground {
+            color: to_color(color),
+        });
+    }
+
+    fn set_shapes_texture(
+        &mut self,
+        texture: impl AsRef<ffi::Texture2D>,
+        source: impl Into<ffi::Rectangle>,
+    ) {
+        self.push(DrawCommand::SetShapesTexture {
+            texture: texture.as_ref().into(),
+            source: to_rect(source),
+        });
+    }
+
+    fn draw_pixel(&mut self, x: i32, y: i32, color: impl Into<ffi::Color>) {
+        self.push(DrawCommand::DrawPixel {
+            x,
+            y,
+            color: to_color(color),
+        });
+    }
+
+    fn draw_pixel_v(&mut self, position: impl Into<ffi::Vector2>, color: impl Into<ffi::Color>) {
+        self.push(DrawCommand::DrawPixelV {
+            position: to_v2(position),
+            color: to_color(color),
+        });
+    }
+
+    fn draw_line(
+        &mut self,
+        start_pos_x: i32,
+        start_pos_y: i32,
+        end_pos_x: i32,
+        end_pos_y: i32,
+        color: impl Into<ffi::Color>,
+    ) {
+        self.push(DrawCommand::DrawLine {
+            start_pos_x,
+            start_pos_y,
+            end_pos_x,
+            end_pos_y,
+            color: to_color(color),
+        });
+    }
+
+    fn draw_line_v(
+        &mut self,
+        start_pos: impl Into<ffi::Vector2>,
+        end_pos: impl Into<ffi::Vector2>,
+        color: impl Into<ffi::Color>,
+    ) {
+        self.push(DrawCommand::DrawLineV {
+            start_pos: to_v2(start_pos),
+            end_pos: to_v2(end_pos),
+            color: to_color(color),
+        });
+    }
+
+    fn draw_line_ex(
+        &mut self,
+        start_pos: impl Into<ffi::Vector2>,
+        end_pos: impl Into<ffi::Vector2>,
+        thick: f32,
+        color: impl Into<ffi::Color>,
+    ) {
+        self.push(DrawCommand::DrawLineEx {
+            start_pos: to_v2(start_pos),
+            end_pos: to_v2(end_pos),
+            thick,
+            color: to_color(color),
+        });
+    }
+
+    fn draw_line_bezier(
+        &mut self,
+        start_pos: impl Into<ffi::Vector2>,
+        end_pos: impl Into<ffi::Vector2>,
+        thick: f32,
+        color: impl Into<ffi::Color>,
+    ) {
+        self.push(DrawCommand::DrawLineBezier {
+            start_pos: to_v2(start_pos),
+            end_pos: to_v2(end_pos),
+            thick,
+            color: to_color(color),
+        });
+    }
+
+    fn draw_line_strip(&mut self, points: &[Vector2], color: impl Into<ffi::Color>) {
+        self.push(DrawCommand::DrawLineStrip {
+            points: points.to_vec(),
+            color: to_color(color),
+        });
+    }
+
+    fn draw_circle(
+        &mut self,
+        center_x: i32,
+        center_y: i32,
+        radius: f32,
+        color: impl Into<ffi::Color>,
+    ) {
+        self.push(DrawCommand::DrawCircle {
+            center_x,
+            center_y,
+            radius,
+            color: to_color(color),
+        });
+    }
+
+    fn draw_circle_sector(
+        &mut self,
+        center: impl Into<ffi::Vector2>,
+        radius: f32,
+        start_angle: f32,
+        end_angle: f32,
+        segments: i32,
+        color: impl Into<ffi::Color>,
+    ) {
+        self.push(DrawCommand::DrawCircleSector {
+            center: to_v2(center),
+            radius,
+            start_angle,
+            end_angle,
+            segments,
+            color: to_color(color),
+        });
+    }
+
+    fn draw_circle_sector_lines(
+        &mut self,
+        center: impl Into<ffi::Vector2>,
+        radius: f32,
+        start_angle: f32,
+        end_angle: f32,
+        segments: i32,
+        color: impl Into<ffi::Color>,
+    ) {
+        self.push(DrawCommand::DrawCircleSectorLines {
+            center: to_v2(center),
+            radius,
+            start_angle,
+            end_angle,
+            segments,
+            color: to_color(color),
+        });
+    }
+
+    fn draw_circle_gradient(
+        &mut self,
+        center_x: i32,
+        center_y: i32,
+        radius: f32,
+        color1: impl Into<ffi::Color>,
+        color2: impl Into<ffi::Color>,
+    ) {
+        self.push(DrawCommand::DrawCircleGradient {
+            center_x,
+            center_y,
+            radius,
+            color1: to_color(color1),
+            color2: to_color(color2),
+        });
+    }
+
+    fn draw_circle_v(
+        &mut self,
+        center: impl Into<ffi::Vector2>,
+        radius: f32,
+        color: impl Into<ffi::Color>,
+    ) {
+        self.push(DrawCommand::DrawCircleV {
+            center: to_v2(center),
+            radius,
+            color: to_color(color),
+        });
+    }
+
+    fn draw_circle_lines(
+        &mut self,
+        center_x: i32,
+        center_y: i32,
+        radius: f32,
+        color: impl Into<ffi::Color>,
+    ) {
+        self.push(DrawCommand::DrawCircleLines {
+            center_x,
+            center_y,
+            radius,
+            color: to_color(color),
+        });
+    }
+
+    fn draw_circle_lines_v(
+        &mut self,
+        center: impl Into<ffi::Vector2>,
+        radius: f32,
+        color: impl Into<ffi::Color>,
+    ) {
+        self.push(DrawCommand::DrawCircleLinesV {
+            center: to_v2(center),
+            radius,
+            color: to_color(color),
+        });
+    }
+
+    fn draw_ellipse(
+        &mut self,
+        center_x: i32,
+        center_y: i32,
+        radius_h: f32,
+        radius_v: f32,
+        color: impl Into<ffi::Color>,
+    ) {
+        self.push(DrawCommand::DrawEllipse {
+            center_x,
+            center_y,
+            radius_h,
+            radius_v,
+            color: to_color(color),
+        });
+    }
+
+    fn draw_ellipse_lines(
+        &mut self,
+        center_x: i32,
+        center_y: i32,
+        radius_h: f32,
+        radius_v: f32,
+        color: impl Into<ffi::Color>,
+    ) {
+        self.push(DrawCommand::DrawEllipseLines {
+            center_x,
+            center_y,
+            radius_h,
+            radius_v,
+            color: to_color(color),
+        });
+    }
+
+    fn draw_ring(
+        &mut self,
+        center: impl Into<ffi::Vector2>,
+        inner_radius: f32,
+        outer_radius: f32,
+        start_angle: f32,
+        end_angle: f32,
+        segments: i32,
+        color: impl Into<ffi::Color>,
+    ) {
+        self.push(DrawCommand::DrawRing {
+            center: to_v2(center),
+            inner_radius,
+            outer_radius,
+            start_angle,
+            end_angle,
+            segments,
+            color: to_color(color),
+        });
+    }
+
+    fn draw_ring_lines(
+        &mut self,
+        center: impl Into<ffi::Vector2>,
+        inner_radius: f32,
+        outer_radius: f32,
+        start_angle: f32,
+        end_angle: f32,
+        segments: i32,
+        color: impl Into<ffi::Color>,
+    ) {
+        self.push(DrawCommand::DrawRingLines {
+            center: to_v2(center),
+            inner_radius,
+            outer_radius,
+            start_angle,
+            end_angle,
+            segments,
+            color: to_color(color),
+        });
+    }
+
+    fn draw_rectangle(
+        &mut self,
+        x: i32,
+        y: i32,
+        width: i32,
+        height: i32,
+        color: impl Into<ffi::Color>,
+    ) {
+        self.push(DrawCommand::DrawRectangle {
+            x,
+            y,
+            width,
+            height,
+            color: to_color(color),
+        });
+    }
+
+    fn draw_rectangle_v(
+        &mut self,
+        position: impl Into<ffi::Vector2>,
+        size: impl Into<ffi::Vector2>,
+        color: impl Into<ffi::Color>,
+    ) {
+        self.push(DrawCommand::DrawRectangleV {
+            position: to_v2(position),
+            size: to_v2(size),
+            color: to_color(color),
+        });
+    }
+
+    fn draw_rectangle_rec(&mut self, rec: impl Into<ffi::Rectangle>, color: impl Into<ffi::Color>) {
+        self.push(DrawCommand::DrawRectangleRec {
+            rec: to_rect(rec),
+            color: to_color(color),
+        });
+    }
+
+    fn draw_rectangle_pro(
+        &mut self,
+        rec: impl Into<ffi::Rectangle>,
+        origin: impl Into<ffi::Vector2>,
+        rotation: f32,
+        color: impl Into<ffi::Color>,
+    ) {
+        self.push(DrawCommand::DrawRectanglePro {
+            rec: to_rect(rec),
+            origin: to_v2(origin),
+            rotation,
+            color: to_color(color),
+        });
+    }
+
+    fn draw_rectangle_gradient_v(
+        &mut self,
+        x: i32,
+        y: i32,
+        width: i32,
+        height: i32,
+        color1: impl Into<ffi::Color>,
+        color2: impl Into<ffi::Color>,
+    ) {
+        self.push(DrawCommand::DrawRectangleGradientV {
+            x,
+            y,
+            width,
+            height,
+            color1: to_color(color1),
+            color2: to_color(color2),
+        });
+    }
+
+    fn draw_rectangle_gradient_h(
+        &mut self,
+        x: i32,
+        y: i32,
+        width: i32,
+        height: i32,
+        color1: impl Into<ffi::Color>,
+        color2: impl Into<ffi::Color>,
+    ) {
+        self.push(DrawCommand::DrawRectangleGradientH {
+            x,
+            y,
+            width,
+            height,
+            color1: to_color(color1),
+            color2: to_color(color2),
+        });
+    }
+
+    fn draw_rectangle_gradient_ex(
+        &mut self,
+        rec: impl Into<ffi::Rectangle>,
+        col1: impl Into<ffi::Color>,
+        col2: impl Into<ffi::Color>,
+        col3: impl Into<ffi::Color>,
+        col4: impl Into<ffi::Color>,
+    ) {
+        self.push(DrawCommand::DrawRectangleGradientEx {
+            rec: to_rect(rec),
+            col1: to_color(col1),
+            col2: to_color(col2),
+            col3: to_color(col3),
+            col4: to_color(col4),
+        });
+    }
+
+    fn draw_rectangle_lines(
+        &mut self,
+        x: i32,
+        y: i32,
+        width: i32,
+        height: i32,
+        color: impl Into<ffi::Color>,
+    ) {
+        self.push(DrawCommand::DrawRectangleLines {
+            x,
+            y,
+            width,
+            height,
+            color: to_color(color),
+        });
+    }
+
+    fn draw_rectangle_lines_ex(
+        &mut self,
+        rec: impl Into<ffi::Rectangle>,
+        line_thick: f32,
+        color: impl Into<ffi::Color>,
+    ) {
+        self.push(DrawCommand::DrawRectangleLinesEx {
+            rec: to_rect(rec),
+            line_thick,
+            color: to_color(color),
+        });
+    }
+
+    fn draw_rectangle_rounded(
+        &mut self,
+        rec: impl Into<ffi::Rectangle>,
+        roundness: f32,
+        segments: i32,
+        color: impl Into<ffi::Color>,
+    ) {
+        self.push(DrawCommand::DrawRectangleRounded {
+            rec: to_rect(rec),
+            roundness,
+            segments,
+            color: to_color(color),
+        });
+    }
+
+    fn draw_rectangle_rounded_lines(
+        &mut self,
+        rec: impl Into<ffi::Rectangle>,
+        roundness: f32,
+        segments: i32,
+        color: impl Into<ffi::Color>,
+    ) {
+        self.push(DrawCommand::DrawRectangleRoundedLines {
+            rec: to_rect(rec),
+            roundness,
+            segments,
+            color: to_color(color),
+        });
+    }
+
+    fn draw_rectangle_rounded_lines_ex(
+        &mut self,
+        rec: impl Into<ffi::Rectangle>,
+        roundness: f32,
+        segments: i32,
+        line_thickness: f32,
+        color: impl Into<ffi::Color>,
+    ) {
+        self.push(DrawCommand::DrawRectangleRoundedLinesEx {
+            rec: to_rect(rec),
+            roundness,
+            segments,
+            line_thickness,
+            color: to_color(color),
+        });
+    }
+
+    fn draw_triangle(
+        &mut self,
+        v1: impl Into<ffi::Vector2>,
+        v2: impl Into<ffi::Vector2>,
+        v3: impl Into<ffi::Vector2>,
+        color: impl Into<ffi::Color>,
+    ) {
+        self.push(DrawCommand::DrawTriangle {
+            v1: to_v2(v1),
+            v2: to_v2(v2),
+            v3: to_v2(v3),
+            color: to_color(color),
+        });
+    }
+
+    fn draw_triangle_lines(
+        &mut self,
+        v1: impl Into<ffi::Vector2>,
+        v2: impl Into<ffi::Vector2>,
+        v3: impl Into<ffi::Vector2>,
+        color: impl Into<ffi::Color>,
+    ) {
+        self.push(DrawCommand::DrawTriangleLines {
+            v1: to_v2(v1),
+            v2: to_v2(v2),
+            v3: to_v2(v3),
+            color: to_color(color),
+        });
+    }
+
+    fn draw_triangle_fan(&mut self, points: &[Vector2], color: impl Into<ffi::Color>) {
+        self.push(DrawCommand::DrawTriangleFan {
+            points: points.to_vec(),
+            color: to_color(color),
+        });
+    }
+
+    fn draw_triangle_strip(&mut self, points: &[Vector2], color: impl Into<ffi::Color>) {
+        self.push(DrawCommand::DrawTriangleStrip {
+            points: points.to_vec(),
+            color: to_color(color),
+        });
+    }
+
+    fn draw_poly(
+        &mut self,
+        center: impl Into<ffi::Vector2>,
+        sides: i32,
+        radius: f32,
+        rotation: f32,
+        color: impl Into<ffi::Color>,
+    ) {
+        self.push(DrawCommand::DrawPoly {
+            center: to_v2(center),
+            sides,
+            radius,
+            rotation,
+            color: to_color(color),
+        });
+    }
+
+    fn draw_poly_lines(
+        &mut self,
+        center: impl Into<ffi::Vector2>,
+        sides: i32,
+        radius: f32,
+        rotation: f32,
+        color: impl Into<ffi::Color>,
+    ) {
+        self.push(DrawCommand::DrawPolyLines {
+            center: to_v2(center),
+            sides,
+            radius,
+            rotation,
+            color: to_color(color),
+        });
+    }
+
+    fn draw_poly_lines_ex(
+        &mut self,
+        center: Vector2,
+        sides: i32,
+        radius: f32,
+        rotation: f32,
+        line_thick: f32,
+        color: impl Into<ffi::Color>,
+    ) {
+        self.push(DrawCommand::DrawPolyLinesEx {
+            center,
+            sides,
+            radius,
+            rotation,
+            line_thick,
+            color: to_color(color),
+        });
+    }
+
+    fn draw_texture(
+        &mut self,
+        texture: impl AsRef<ffi::Texture2D>,
+        x: i32,
+        y: i32,
+        tint: impl Into<ffi::Color>,
+    ) {
+        self.push(DrawCommand::DrawTexture {
+            texture: texture.as_ref().into(),
+            x,
+            y,
+            tint: to_color(tint),
+        });
+    }
+
+    fn draw_texture_v(
+        &mut self,
+        texture: impl AsRef<ffi::Texture2D>,
+        position: impl Into<ffi::Vector2>,
+        tint: impl Into<ffi::Color>,
+    ) {
+        self.push(DrawCommand::DrawTextureV {
+            texture: texture.as_ref().into(),
+            position: to_v2(position),
+            tint: to_color(tint),
+        });
+    }
+
+    fn draw_texture_ex(
+        &mut self,
+        texture: impl AsRef<ffi::Texture2D>,
+        position: impl Into<ffi::Vector2>,
+        rotation: f32,
+        scale: f32,
+        tint: impl Into<ffi::Color>,
+    ) {
+        self.push(DrawCommand::DrawTextureEx {
+            texture: texture.as_ref().into(),
+            position: to_v2(position),
+            rotation,
+            scale,
+            tint: to_color(tint),
+        });
+    }
+
+    fn draw_texture_rec(
+        &mut self,
+        texture: impl AsRef<ffi::Texture2D>,
+        source_rec: impl Into<ffi::Rectangle>,
+        position: impl Into<ffi::Vector2>,
+        tint: impl Into<ffi::Color>,
+    ) {
+        self.push(DrawCommand::DrawTextureRec {
+            texture: texture.as_ref().into(),
+            source_rec: to_rect(source_rec),
+            position: to_v2(position),
+            tint: to_color(tint),
+        });
+    }
+
+    fn draw_texture_pro(
+        &mut self,
+        texture: impl AsRef<ffi::Texture2D>,
+        source_rec: impl Into<ffi::Rectangle>,
+        dest_rec: impl Into<ffi::Rectangle>,
+        origin: impl Into<ffi::Vector2>,
+        rotation: f32,
+        tint: impl Into<ffi::Color>,
+    ) {
+        self.push(DrawCommand::DrawTexturePro {
+            texture: texture.as_ref().into(),
+            source_rec: to_rect(source_rec),
+            dest_rec: to_rect(dest_rec),
+            origin: to_v2(origin),
+            rotation,
+            tint: to_color(tint),
+        });
+    }
+
+    fn draw_texture_n_patch(
+        &mut self,
+        texture: impl AsRef<ffi::Texture2D>,
+        n_patch_info: impl Into<ffi::NPatchInfo>,
+        dest_rec: impl Into<ffi::Rectangle>,
+        origin: impl Into<ffi::Vector2>,
+        rotation: f32,
+        tint: impl Into<ffi::Color>,
+    ) {
+        self.push(DrawCommand::DrawTextureNPatch {
+            texture: texture.as_ref().into(),
+            n_patch_info: {
+                let n_patch_info: ffi::NPatchInfo = n_patch_info.into();
+                n_patch_info.into()
+            },
+            dest_rec: to_rect(dest_rec),
+            origin: to_v2(origin),
+            rotation,
+            tint: to_color(tint),
+        });
+    }
+
+    fn draw_fps(&mut self, x: i32, y: i32) {
+        self.push(DrawCommand::DrawFPS { x, y });
+    }
+
+    fn draw_text(
+        &mut self,
+        text: &str,
+        x: i32,
+        y: i32,
+        font_size: i32,
+        color: impl Into<ffi::Color>,
+    ) {
+        self.push(DrawCommand::DrawText {
+            text: text.to_owned(),
+            x,
+            y,
+            font_size,
+            color: to_color(color),
+        });
+    }
+
+    fn draw_text_codepoints(
+        &mut self,
+        font: impl AsRef<ffi::Font>,
+        text: &str,
+        position: Vector2,
+        font_size: f32,
+        spacing: f32,
+        tint: impl Into<ffi::Color>,
+    ) {
+        self.push(DrawCommand::DrawTextCodepoints {
+            font: FontRef::of(font.as_ref()),
+            text: text.to_owned(),
+            position,
+            font_size,
+            spacing,
+            tint: to_color(tint),
+        });
+    }
+
+    fn draw_text_ex(
+        &mut self,
+        font: impl AsRef<ffi::Font>,
+        text: &str,
+        position: impl Into<ffi::Vector2>,
+        font_size: f32,
+        spacing: f32,
+        tint: impl Into<ffi::Color>,
+    ) {
+        self.push(DrawCommand::DrawTextEx {
+            font: FontRef::of(font.as_ref()),
+            text: text.to_owned(),
+            position: to_v2(position),
+            font_size,
+            spacing,
+            tint: to_color(tint),
+        });
+    }
+
+    fn draw_text_pro(
+        &mut self,
+        font: impl AsRef<ffi::Font>,
+        text: &str,
+        position: impl Into<ffi::Vector2>,
+        origin: impl Into<ffi::Vector2>,
+        rotation: f32,
+        font_size: f32,
+        spacing: f32,
+        tint: impl Into<ffi::Color>,
+    ) {
+        self.push(DrawCommand::DrawTextPro {
+            font: FontRef::of(font.as_ref()),
+            text: text.to_owned(),
+            position: to_v2(position),
+            origin: to_v2(origin),
+            rotation,
+            font_size,
+            spacing,
+            tint: to_color(tint),
+        });
+    }
+
+    fn draw_text_codepoint(
+        &mut self,
+        font: impl AsRef<ffi::Font>,
+        codepoint: i32,
+        position: impl Into<ffi::Vector2>,
+        scale: f32,
+        tint: impl Into<ffi::Color>,
+    ) {
+        self.push(DrawCommand::DrawTextCodepoint {
+            font: FontRef::of(font.as_ref()),
+            codepoint,
+            position: to_v2(position),
+            scale,
+            tint: to_color(tint),
+        });
+    }
+
+    fn draw_spline_linear(&mut self, points: &[Vector2], thick: f32, color: impl Into<ffi::Color>) {
+        self.push(DrawCommand::DrawSplineLinear {
+            points: points.to_vec(),
+            thick,
+            color: to_color(color),
+        });
+    }
+
+    fn draw_spline_basis(&mut self, points: &[Vector2], thick: f32, color: impl Into<ffi::Color>) {
+        self.push(DrawCommand::DrawSplineBasis {
+            points: points.to_vec(),
+            thick,
+            color: to_color(color),
+        });
+    }
+
+    fn draw_spline_catmull_rom(
+        &mut self,
+        points: &[Vector2],
+        thick: f32,
+        color: impl Into<ffi::Color>,
+    ) {
+        self.push(DrawCommand::DrawSplineCatmullRom {
+            points: points.to_vec(),
+            thick,
+            color: to_color(color),
+        });
+    }
+
+    fn draw_spline_bezier_quadratic(
+        &mut self,
+        points: &[Vector2],
+        thick: f32,
+        color: impl Into<ffi::Color>,
+    ) {
+        self.push(DrawCommand::DrawSplineBezierQuadratic {
+            points: points.to_vec(),
+            thick,
+            color: to_color(color),
+        });
+    }
+
+    fn draw_spline_bezier_cubic(
+        &mut self,
+        points: &[Vector2],
+        thick: f32,
+        color: impl Into<ffi::Color>,
+    ) {
+        self.push(DrawCommand::DrawSplineBezierCubic {
+            points: points.to_vec(),
+            thick,
+            color: to_color(color),
+        });
+    }
+
+    fn draw_spline_segment_linear(
+        &mut self,
+        p1: Vector2,
+        p2: Vector2,
+        thick: f32,
+        color: impl Into<ffi::Color>,
+    ) {
+        self.push(DrawCommand::DrawSplineSegmentLinear {
+            p1,
+            p2,
+            thick,
+            color: to_color(color),
+        });
+    }
+
+    fn draw_spline_segment_basis(
+        &mut self,
+        p1: Vector2,
+        p2: Vector2,
+        p3: Vector2,
+        p4: Vector2,
+        thick: f32,
+        color: impl Into<ffi::Color>,
+    ) {
+        self.push(DrawCommand::DrawSplineSegmentBasis {
+            p1,
+            p2,
+            p3,
+            p4,
+            thick,
+            color: to_color(color),
+        });
+    }
+
+    fn draw_spline_segment_catmull_rom(
+        &mut self,
+        p1: Vector2,
+        p2: Vector2,
+        p3: Vector2,
+        p4: Vector2,
+        thick: f32,
+        color: impl Into<ffi::Color>,
+    ) {
+        self.push(DrawCommand::DrawSplineSegmentCatmullRom {
+            p1,
+            p2,
+            p3,
+            p4,
+            thick,
+            color: to_color(color),
+        });
+    }
+
+    fn draw_spline_segment_bezier_quadratic(
+        &mut self,
+        p1: Vector2,
+        c2: Vector2,
+        p3: Vector2,
+        thick: f32,
+        color: impl Into<ffi::Color>,
+    ) {
+        self.push(DrawCommand::DrawSplineSegmentBezierQuadratic {
+            p1,
+            c2,
+            p3,
+            thick,
+            color: to_color(color),
+        });
+    }
+
+    fn draw_spline_segment_bezier_cubic(
+        &mut self,
+        p1: Vector2,
+        c2: Vector2,
+        c3: Vector2,
+        p4: Vector2,
+        thick: f32,
+        color: impl Into<ffi::Color>,
+    ) {
+        self.push(DrawCommand::DrawSplineSegmentBezierCubic {
+            p1,
+            c2,
+            c3,
+            p4,
+            thick,
+            color: to_color(color),
+        });
+    }
+}
+
+impl RaylibDraw3D for DrawList {
+    fn draw_point3D(&mut self, position: impl Into<ffi::Vector3>, color: impl Into<ffi::Color>) {
+        self.push(DrawCommand::DrawPoint3D {
+            position: to_v3(position),
+            color: to_color(color),
+        });
+    }
+
+    fn draw_triangle3D(
+        &mut self,
+        v1: impl Into<ffi::Vector3>,
+        v2: impl Into<ffi::Vector3>,
+        v3: impl Into<ffi::Vector3>,
+        color: impl Into<ffi::Color>,
+    ) {
+        self.push(DrawCommand::DrawTriangle3D {
+            v1: to_v3(v1),
+            v2: to_v3(v2),
+            v3: to_v3(v3),
+            color: to_color(color),
+        });
+    }
+
+    fn draw_triangle_strip3D(&mut self, points: &[Vector3], color: impl Into<ffi::Color>) {
+        self.push(DrawCommand::DrawTriangleStrip3D {
+            points: points.to_vec(),
+            color: to_color(color),
+        });
+    }
+
+    fn draw_line_3D(
+        &mut self,
+        start_pos: impl Into<ffi::Vector3>,
+        end_pos: impl Into<ffi::Vector3>,
+        color: impl Into<ffi::Color>,
+    ) {
+        self.push(DrawCommand::DrawLine3D {
+            start_pos: to_v3(start_pos),
+            end_pos: to_v3(end_pos),
+            color: to_color(color),
+        });
+    }
+
+    fn draw_circle_3D(
+        &mut self,
+        center: impl Into<ffi::Vector3>,
+        radius: f32,
+        rotation_axis: impl Into<ffi::Vector3>,
+        rotation_angle: f32,
+        color: impl Into<ffi::Color>,
+    ) {
+        self.push(DrawCommand::DrawCircle3D {
+            center: to_v3(center),
+            radius,
+            rotation_axis: to_v3(rotation_axis),
+            rotation_angle,
+            color: to_color(color),
+        });
+    }
+
+    fn draw_cube(
+        &mut self,
+        position: impl Into<ffi::Vector3>,
+        width: f32,
+        height: f32,
+        length: f32,
+        color: impl Into<ffi::Color>,
+    ) {
+        self.push(DrawCommand::DrawCube {
+            position: to_v3(position),
+            width,
+            height,
+            length,
+            color: to_color(color),
+        });
+    }
+
+    fn draw_cube_v(
+        &mut self,
+        position: impl Into<ffi::Vector3>,
+        size: impl Into<ffi::Vector3>,
+        color: impl Into<ffi::Color>,
+    ) {
+        self.push(DrawCommand::DrawCubeV {
+            position: to_v3(position),
+            size: to_v3(size),
+            color: to_color(color),
+        });
+    }
+
+    fn draw_cube_wires(
+        &mut self,
+        position: impl Into<ffi::Vector3>,
+        width: f32,
+        height: f32,
+        length: f32,
+        color: impl Into<ffi::Color>,
+    ) {
+        self.push(DrawCommand::DrawCubeWires {
+            position: to_v3(position),
+            width,
+            height,
+            length,
+            color: to_color(color),
+        });
+    }
+
+    fn draw_cube_wires_v(
+        &mut self,
+        position: impl Into<ffi::Vector3>,
+        size: impl Into<ffi::Vector3>,
+        color: impl Into<ffi::Color>,
+    ) {
+        self.push(DrawCommand::DrawCubeWiresV {
+            position: to_v3(position),
+            size: to_v3(size),
+            color: to_color(color),
+        });
+    }
+
+    fn draw_mesh(
+        &mut self,
+        mesh: impl AsRef<ffi::Mesh>,
+        material: WeakMaterial,
+        transform: Matrix,
+    ) {
+        self.push(DrawCommand::DrawMesh {
+            mesh: MeshRef::of(mesh.as_ref()),
+            material: MaterialRef::of(material.as_ref()),
+            transform,
+        });
+    }
+
+    fn draw_mesh_instanced(
+        &mut self,
+        mesh: impl AsRef<ffi::Mesh>,
+        material: WeakMaterial,
+        transforms: &[Matrix],
+    ) {
+        self.push(DrawCommand::DrawMeshInstanced {
+            mesh: MeshRef::of(mesh.as_ref()),
+            material: MaterialRef::of(material.as_ref()),
+            transforms: transforms.to_vec(),
+        });
+    }
+
+    fn draw_sphere(
+        &mut self,
+        center_pos: impl Into<ffi::Vector3>,
+        radius: f32,
+        color: impl Into<ffi::Color>,
+    ) {
+        self.push(DrawCommand::DrawSphere {
+            center_pos: to_v3(center_pos),
+            radius,
+            color: to_color(color),
+        });
+    }
+
+    fn draw_sphere_ex(
+        &mut self,
+        center_pos: impl Into<ffi::Vector3>,
+        radius: f32,
+        rings: i32,
+        slices: i32,
+        color: impl Into<ffi::Color>,
+    ) {
+        self.push(DrawCommand::DrawSphereEx {
+            center_pos: to_v3(center_pos),
+            radius,
+            rings,
+            slices,
+            color: to_color(color),
+        });
+    }
+
+    fn draw_sphere_wires(
+        &mut self,
+        center_pos: impl Into<ffi::Vector3>,
+        radius: f32,
+        rings: i32,
+        slices: i32,
+        color: impl Into<ffi::Color>,
+    ) {
+        self.push(DrawCommand::DrawSphereWires {
+            center_pos: to_v3(center_pos),
+            radius,
+            rings,
+            slices,
+            color: to_color(color),
+        });
+    }
+
+    fn draw_cylinder(
+        &mut self,
+        position: impl Into<ffi::Vector3>,
+        radius_top: f32,
+        radius_bottom: f32,
+        height: f32,
+        slices: i32,
+        color: impl Into<ffi::Color>,
+    ) {
+        self.push(DrawCommand::DrawCylinder {
+            position: to_v3(position),
+            radius_top,
+            radius_bottom,
+            height,
+            slices,
+            color: to_color(color),
+        });
+    }
+
+    fn draw_cylinder_ex(
+        &mut self,
+        start_position: impl Into<ffi::Vector3>,
+        end_position: impl Into<ffi::Vector3>,
+        radius_start: f32,
+        radius_end: f32,
+        slices: i32,
+        color: impl Into<ffi::Color>,
+    ) {
+        self.push(DrawCommand::DrawCylinderEx {
+            start_position: to_v3(start_position),
+            end_position: to_v3(end_position),
+            radius_start,
+            radius_end,
+            slices,
+            color: to_color(color),
+        });
+    }
+
+    fn draw_cylinder_wires(
+        &mut self,
+        position: impl Into<ffi::Vector3>,
+        radius_top: f32,
+        radius_bottom: f32,
+        height: f32,
+        slices: i32,
+        color: impl Into<ffi::Color>,
+    ) {
+        self.push(DrawCommand::DrawCylinderWires {
+            position: to_v3(position),
+            radius_top,
+            radius_bottom,
+            height,
+            slices,
+            color: to_color(color),
+        });
+    }
+
+    fn draw_cylinder_wires_ex(
+        &mut self,
+        start_position: impl Into<ffi::Vector3>,
+        end_position: impl Into<ffi::Vector3>,
+        radius_start: f32,
+        radius_end: f32,
+        slices: i32,
+        color: impl Into<ffi::Color>,
+    ) {
+        self.push(DrawCommand::DrawCylinderWiresEx {
+            start_position: to_v3(start_position),
+            end_position: to_v3(end_position),
+            radius_start,
+            radius_end,
+            slices,
+            color: to_color(color),
+        });
+    }
+
+    fn draw_capsule(
+        &mut self,
+        start_pos: impl Into<ffi::Vector3>,
+        end_pos: impl Into<ffi::Vector3>,
+        radius: f32,
+        slices: i32,
+        rings: i32,
+        color: impl Into<ffi::Color>,
+    ) {
+        self.push(DrawCommand::DrawCapsule {
+            start_pos: to_v3(start_pos),
+            end_pos: to_v3(end_pos),
+            radius,
+            slices,
+            rings,
+            color: to_color(color),
+        });
+    }
+
+    fn draw_capsule_wires(
+        &mut self,
+        start_pos: impl Into<ffi::Vector3>,
+        end_pos: impl Into<ffi::Vector3>,
+        radius: f32,
+        slices: i32,
+        rings: i32,
+        color: impl Into<ffi::Color>,
+    ) {
+        self.push(DrawCommand::DrawCapsuleWires {
+            start_pos: to_v3(start_pos),
+            end_pos: to_v3(end_pos),
+            radius,
+            slices,
+            rings,
+            color: to_color(color),
+        });
+    }
+
+    fn draw_plane(
+        &mut self,
+        center_pos: impl Into<ffi::Vector3>,
+        size: impl Into<ffi::Vector2>,
+        color: impl Into<ffi::Color>,
+    ) {
+        self.push(DrawCommand::DrawPlane {
+            center_pos: to_v3(center_pos),
+            size: to_v2(size),
+            color: to_color(color),
+        });
+    }
+
+    fn draw_ray(&mut self, ray: Ray, color: impl Into<ffi::Color>) {
+        self.push(DrawCommand::DrawRay {
+            ray,
+            color: to_color(color),
+        });
+    }
+
+    fn draw_grid(&mut self, slices: i32, spacing: f32) {
+        self.push(DrawCommand::DrawGrid { slices, spacing });
+    }
+
+    fn draw_model(
+        &mut self,
+        model: impl AsRef<ffi::Model>,
+        position: impl Into<ffi::Vector3>,
+        scale: f32,
+        tint: impl Into<ffi::Color>,
+    ) {
+        self.push(DrawCommand::DrawModel {
+            model: ModelRef::of(model.as_ref()),
+            position: to_v3(position),
+            scale,
+            tint: to_color(tint),
+        });
+    }
+
+    fn draw_model_ex(
+        &mut self,
+        model: impl AsRef<ffi::Model>,
+        position: impl Into<ffi::Vector3>,
+        rotation_axis: impl Into<ffi::Vector3>,
+        rotation_angle: f32,
+        scale: impl Into<ffi::Vector3>,
+        tint: impl Into<ffi::Color>,
+    ) {
+        self.push(DrawCommand::DrawModelEx {
+            model: ModelRef::of(model.as_ref()),
+            position: to_v3(position),
+            rotation_axis: to_v3(rotation_axis),
+            rotation_angle,
+            scale: to_v3(scale),
+            tint: to_color(tint),
+        });
+    }
+
+    fn draw_model_wires(
+        &mut self,
+        model: impl AsRef<ffi::Model>,
+        position: impl Into<ffi::Vector3>,
+        scale: f32,
+        tint: impl Into<ffi::Color>,
+    ) {
+        self.push(DrawCommand::DrawModelWires {
+            model: ModelRef::of(model.as_ref()),
+            position: to_v3(position),
+            scale,
+            tint: to_color(tint),
+        });
+    }
+
+    fn draw_model_wires_ex(
+        &mut self,
+        model: impl AsRef<ffi::Model>,
+        position: impl Into<ffi::Vector3>,
+        rotation_axis: impl Into<ffi::Vector3>,
+        rotation_angle: f32,
+        scale: impl Into<ffi::Vector3>,
+        tint: impl Into<ffi::Color>,
+    ) {
+        self.push(DrawCommand::DrawModelWiresEx {
+            model: ModelRef::of(model.as_ref()),
+            position: to_v3(position),
+            rotation_axis: to_v3(rotation_axis),
+            rotation_angle,
+            scale: to_v3(scale),
+            tint: to_color(tint),
+        });
+    }
+
+    fn draw_bounding_box(
+        &mut self,
+        bbox: impl Into<ffi::BoundingBox>,
+        color: impl Into<ffi::Color>,
+    ) {
+        self.push(DrawCommand::DrawBoundingBox {
+            bbox: {
+                let bbox: ffi::BoundingBox = bbox.into();
+                bbox.into()
+            },
+            color: to_color(color),
+        });
+    }
+
+    fn draw_billboard(
+        &mut self,
+        camera: impl Into<ffi::Camera3D>,
+        texture: &crate::core::texture::Texture2D,
+        center: impl Into<ffi::Vector3>,
+        size: f32,
+        tint: impl Into<ffi::Color>,
+    ) {
+        self.push(DrawCommand::DrawBillboard {
+            camera: to_camera3d(camera),
+            texture: texture.as_ref().into(),
+            center: to_v3(center),
+            size,
+            tint: to_color(tint),
+        });
+    }
+
+    fn draw_billboard_rec(
+        &mut self,
+        camera: Camera3D,
+        texture: &crate::core::texture::Texture2D,
+        source_rec: impl Into<ffi::Rectangle>,
+        center: impl Into<ffi::Vector3>,
+        size: impl Into<ffi::Vector2>,
+        tint: impl Into<ffi::Color>,
+    ) {
+        self.push(DrawCommand::DrawBillboardRec {
+            camera: to_camera3d(camera),
+            texture: texture.as_ref().into(),
+            source_rec: to_rect(source_rec),
+            center: to_v3(center),
+            size: to_v2(size),
+            tint: to_color(tint),
+        });
+    }
+
+    fn draw_billboard_pro(
+        &mut self,
+        camera: impl Into<ffi::Camera>,
+        texture: impl Into<ffi::Texture2D>,
+        source: impl Into<ffi::Rectangle>,
+        position: impl Into<ffi::Vector3>,
+        up: impl Into<ffi::Vector3>,
+        size: impl Into<ffi::Vector2>,
+        origin: impl Into<ffi::Vector2>,
+        rotation: f32,
+        tint: impl Into<ffi::Color>,
+    ) {
+        let texture: ffi::Texture2D = texture.into();
+        self.push(DrawCommand::DrawBillboardPro {
+            camera: to_camera3d(camera),
+            texture: (&texture).into(),
+            source: to_rect(source),
+            position: to_v3(position),
+            up: to_v3(up),
+            size: to_v2(size),
+            origin: to_v2(origin),
+            rotation,
+            tint: to_color(tint),
+        });
+    }
+
+    fn draw_model_points(
+        &mut self,
+        model: impl Into<ffi::Model>,
+        position: impl Into<ffi::Vector3>,
+        scale: f32,
+        tint: impl Into<ffi::Color>,
+    ) {
+        self.push(DrawCommand::DrawModelPoints {
+            model: ModelRef::of(&model.into()),
+            position: to_v3(position),
+            scale,
+            tint: to_color(tint),
+        });
+    }
+
+    fn draw_model_points_ex(
+        &mut self,
+        model: impl Into<ffi::Model>,
+        position: impl Into<ffi::Vector3>,
+        rotation_axis: impl Into<ffi::Vector3>,
+        angle: f32,
+        scale: impl Into<ffi::Vector3>,
+        tint: impl Into<ffi::Color>,
+    ) {
+        self.push(DrawCommand::DrawModelPointsEx {
+            model: ModelRef::of(&model.into()),
+            position: to_v3(position),
+            rotation_axis: to_v3(rotation_axis),
+            angle,
+            scale: to_v3(scale),
+            tint: to_color(tint),
+        });
+    }
+}
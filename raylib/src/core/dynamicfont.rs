@@ -0,0 +1,436 @@
+//! Fonts that rasterize glyphs the first time they are needed
+//!
+//! ```no_run
+//! use raylib::prelude::*;
+//!
+//! let (mut rl, thread) = raylib::init().size(800, 450).build();
+//! let mut font = rl.load_font_dynamic(&thread, "NotoSans.ttf", 24).unwrap();
+//! let message = "Grüße, Ölçü, Привет";
+//!
+//! // rasterize whatever the message needs before drawing it
+//! font.cache(&thread, message).unwrap();
+//! let mut d = rl.begin_drawing(&thread);
+//! d.draw_text_ex(&font, message, Vector2::new(20.0, 20.0), 24.0, 1.0, Color::BLACK);
+//! ```
+use crate::consts::PixelFormat;
+use crate::core::file::load_file_data;
+use crate::core::text::RaylibFont;
+use crate::core::texture::{RaylibTexture2D, Texture2D};
+use crate::core::{RaylibHandle, RaylibThread};
+use crate::error::{error, Error};
+use crate::ffi;
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Empty pixels kept around each glyph so filtering never samples a neighbour.
+const GLYPH_PADDING: i32 = 2;
+
+/// Largest side of the atlas, which is supported by every GPU raylib runs on.
+pub const DYNAMIC_FONT_MAX_ATLAS_SIZE: i32 = 4096;
+
+/// A font that keeps its TTF/OTF data in memory and rasterizes codepoints on demand.
+///
+/// Glyphs are packed in rows into a grayscale atlas. New glyphs are copied into the atlas
+/// texture with [`RaylibTexture2D::update_texture_rec`]; when the atlas is full it doubles in
+/// size and every glyph is packed again, up to [`DYNAMIC_FONT_MAX_ATLAS_SIZE`].
+///
+/// Call [`DynamicFont::cache`] with the text before drawing it. The font implements
+/// [`RaylibFont`], so it draws and measures like any other font; codepoints not cached yet
+/// are drawn with the `?` glyph.
+#[derive(Debug)]
+pub struct DynamicFont {
+    data: Vec<u8>,
+    font_size: i32,
+    raw: ffi::Font,
+    glyphs: Vec<ffi::GlyphInfo>,
+    recs: Vec<ffi::Rectangle>,
+    index: HashMap<char, usize>,
+    /// Gray and alpha bytes of the atlas, kept to repack and upload it.
+    pixels: Vec<u8>,
+    width: i32,
+    height: i32,
+    packer: RowPacker,
+    texture: Texture2D,
+}
+
+impl RaylibHandle {
+    /// Loads a font file for on demand rasterization at `font_size`, with the printable ASCII
+    /// characters already cached.
+    pub fn load_font_dynamic(
+        &mut self,
+        thread: &RaylibThread,
+        filename: impl AsRef<Path>,
+        font_size: i32,
+    ) -> Result<DynamicFont, Error> {
+        let filename = filename.as_ref();
+        let data = load_file_data(filename).ok_or_else(|| {
+            error!(
+                "Error loading font. Check if the file exists and if it's the right type",
+                filename,
+            )
+        })?;
+        self.load_font_dynamic_from_memory(thread, data, font_size)
+    }
+
+    /// Takes the contents of a .ttf or .otf file for on demand rasterization at `font_size`,
+    /// with the printable ASCII characters already cached.
+    pub fn load_font_dynamic_from_memory(
+        &mut self,
+        thread: &RaylibThread,
+        file_data: Vec<u8>,
+        font_size: i32,
+    ) -> Result<DynamicFont, Error> {
+        if font_size <= 0 {
+            return Err(error!(
+                "Error loading dynamic font: the font size must be positive."
+            ));
+        }
+        // room for the ASCII glyphs, the atlas grows for anything else
+        let side = ((font_size + 2 * GLYPH_PADDING) * 10)
+            .max(64)
+            .min(DYNAMIC_FONT_MAX_ATLAS_SIZE) as u32;
+        let side = side.next_power_of_two() as i32;
+        let pixels = vec![0; (side * side * 2) as usize];
+        let texture = load_atlas_texture(thread, &pixels, side, side)?;
+        let mut font = DynamicFont {
+            data: file_data,
+            font_size,
+            raw: unsafe { std::mem::zeroed() },
+            glyphs: Vec::new(),
+            recs: Vec::new(),
+            index: HashMap::new(),
+            pixels,
+            width: side,
+            height: side,
+            packer: RowPacker::default(),
+            texture,
+        };
+        let ascii: String = (32u8..127).map(char::from).collect();
+        font.cache(thread, &ascii)?;
+        Ok(font)
+    }
+}
+
+impl DynamicFont {
+    /// Rasterizes the codepoints of `text` that are not cached yet, and returns how many there
+    /// were.
+    pub fn cache(&mut self, thread: &RaylibThread, text: &str) -> Result<usize, Error> {
+        let mut missing: Vec<char> = text
+            .chars()
+            .filter(|c| !c.is_control() && !self.index.contains_key(c))
+            .collect();
+        missing.sort_unstable();
+        missing.dedup();
+        if missing.is_empty() {
+            return Ok(0);
+        }
+
+        let mut codepoints: Vec<i32> = missing.iter().map(|&c| c as i32).collect();
+        let glyphs = unsafe {
+            ffi::LoadFontData(
+                self.data.as_ptr(),
+                self.data.len() as i32,
+                self.font_size,
+                codepoints.as_mut_ptr(),
+                codepoints.len() as i32,
+                ffi::FontType::FONT_DEFAULT as i32,
+            )
+        };
+        if glyphs.is_null() {
+            return Err(error!(
+                "Error rasterizing glyphs. Check if the font data is a valid font"
+            ));
+        }
+        let new_glyphs = unsafe { std::slice::from_raw_parts(glyphs, codepoints.len()) }.to_vec();
+        // the glyph images now belong to this font, only the array goes back to raylib
+        unsafe { ffi::MemFree(glyphs as *mut std::os::raw::c_void) };
+
+        let first = self.glyphs.len();
+        for glyph in new_glyphs {
+            self.index.insert(
+                char::from_u32(glyph.value as u32).unwrap_or('?'),
+                self.glyphs.len(),
+            );
+            self.glyphs.push(glyph);
+            self.recs.push(ffi::Rectangle {
+                x: 0.0,
+                y: 0.0,
+                width: 0.0,
+                height: 0.0,
+            });
+        }
+
+        let packer = self.packer;
+        let result = if (first..self.glyphs.len()).all(|i| self.place(i)) {
+            (first..self.glyphs.len()).try_for_each(|i| self.upload(i))
+        } else if let Some((width, height)) = self.grown_size() {
+            self.repack(thread, width, height)
+        } else {
+            Err(error!(
+                "Error caching glyphs: the dynamic font atlas is full."
+            ))
+        };
+        if result.is_err() {
+            // give up on the new glyphs, the atlas keeps what it had
+            self.packer = packer;
+            for (glyph, rec) in self.glyphs.drain(first..).zip(self.recs.drain(first..)) {
+                if let Some(c) = char::from_u32(glyph.value as u32) {
+                    self.index.remove(&c);
+                }
+                clear_rec(&mut self.pixels, self.width, rec);
+                unsafe { ffi::UnloadImage(glyph.image) };
+            }
+        }
+        // the vectors may have moved, even on failure
+        self.sync_raw();
+        result.map(|_| self.glyphs.len() - first)
+    }
+
+    /// Whether `codepoint` has been rasterized.
+    pub fn contains(&self, codepoint: char) -> bool {
+        self.index.contains_key(&codepoint)
+    }
+
+    /// Number of cached glyphs.
+    pub fn glyph_count(&self) -> usize {
+        self.glyphs.len()
+    }
+
+    /// Width and height of the atlas texture.
+    pub fn atlas_size(&self) -> (i32, i32) {
+        (self.width, self.height)
+    }
+
+    /// Packs glyph `i` into the free space of the atlas and copies its pixels there.
+    fn place(&mut self, i: usize) -> bool {
+        match place_glyph(
+            self.glyphs[i].image,
+            &mut self.pixels,
+            self.width,
+            self.height,
+            &mut self.packer,
+        ) {
+            Some(rec) => {
+                self.recs[i] = rec;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Copies the atlas pixels of glyph `i` to the texture.
+    fn upload(&mut self, i: usize) -> Result<(), Error> {
+        let rec = self.recs[i];
+        if rec.width <= 0.0 || rec.height <= 0.0 {
+            return Ok(());
+        }
+        let (x, y) = (rec.x as usize, rec.y as usize);
+        let (width, height) = (rec.width as usize, rec.height as usize);
+        let mut pixels = Vec::with_capacity(width * height * 2);
+        for row in y..y + height {
+            let start = (row * self.width as usize + x) * 2;
+            pixels.extend_from_slice(&self.pixels[start..start + width * 2]);
+        }
+        self.texture.update_texture_rec(rec, &pixels)
+    }
+
+    /// Smallest doubling of the atlas that holds every glyph, if any is small enough.
+    fn grown_size(&self) -> Option<(i32, i32)> {
+        let mut sizes: Vec<(i32, i32)> = self
+            .glyphs
+            .iter()
+            .map(|glyph| {
+                (
+                    glyph.image.width + 2 * GLYPH_PADDING,
+                    glyph.image.height + 2 * GLYPH_PADDING,
+                )
+            })
+            .collect();
+        sizes.sort_by_key(|&(_, height)| std::cmp::Reverse(height));
+        let (mut width, mut height) = (self.width, self.height);
+        loop {
+            if width <= height {
+                width *= 2;
+            } else {
+                height *= 2;
+            }
+            if width > DYNAMIC_FONT_MAX_ATLAS_SIZE || height > DYNAMIC_FONT_MAX_ATLAS_SIZE {
+                return None;
+            }
+            let mut packer = RowPacker::default();
+            if sizes
+                .iter()
+                .all(|&(w, h)| packer.place(w, h, width, height).is_some())
+            {
+                return Some((width, height));
+            }
+        }
+    }
+
+    /// Packs every glyph again, tallest first, into a `width` by `height` atlas and replaces the
+    /// texture. The font is left as it was if the new texture can't be loaded.
+    fn repack(&mut self, thread: &RaylibThread, width: i32, height: i32) -> Result<(), Error> {
+        let mut order: Vec<usize> = (0..self.glyphs.len()).collect();
+        order.sort_by_key(|&i| std::cmp::Reverse(self.glyphs[i].image.height));
+        let mut pixels = vec![0; (width * height * 2) as usize];
+        let mut packer = RowPacker::default();
+        let mut recs = self.recs.clone();
+        for i in order {
+            if let Some(rec) = place_glyph(
+                self.glyphs[i].image,
+                &mut pixels,
+                width,
+                height,
+                &mut packer,
+            ) {
+                recs[i] = rec;
+            }
+        }
+        let texture = load_atlas_texture(thread, &pixels, width, height)?;
+        self.width = width;
+        self.height = height;
+        self.pixels = pixels;
+        self.packer = packer;
+        self.recs = recs;
+        self.texture = texture;
+        Ok(())
+    }
+
+    /// Points the raylib font at the current glyphs, rectangles and texture.
+    fn sync_raw(&mut self) {
+        self.raw.baseSize = self.font_size;
+        self.raw.glyphCount = self.glyphs.len() as i32;
+        self.raw.glyphPadding = GLYPH_PADDING;
+        self.raw.texture = self.texture.0;
+        self.raw.recs = self.recs.as_mut_ptr();
+        self.raw.glyphs = self.glyphs.as_mut_ptr();
+    }
+}
+
+impl AsRef<ffi::Font> for DynamicFont {
+    fn as_ref(&self) -> &ffi::Font {
+        &self.raw
+    }
+}
+
+impl AsMut<ffi::Font> for DynamicFont {
+    fn as_mut(&mut self) -> &mut ffi::Font {
+        &mut self.raw
+    }
+}
+
+impl RaylibFont for DynamicFont {}
+
+impl Drop for DynamicFont {
+    fn drop(&mut self) {
+        // the glyph arrays are Rust owned, so only the images go back to raylib
+        for glyph in &self.glyphs {
+            unsafe { ffi::UnloadImage(glyph.image) };
+        }
+    }
+}
+
+/// Fills an atlas row by row, starting a new row when a glyph does not fit in the current one.
+#[derive(Debug, Clone, Copy, Default)]
+struct RowPacker {
+    x: i32,
+    y: i32,
+    row_height: i32,
+}
+
+impl RowPacker {
+    fn place(
+        &mut self,
+        width: i32,
+        height: i32,
+        atlas_width: i32,
+        atlas_height: i32,
+    ) -> Option<(i32, i32)> {
+        if width > atlas_width {
+            return None;
+        }
+        if self.x + width > atlas_width {
+            self.x = 0;
+            self.y += self.row_height;
+            self.row_height = 0;
+        }
+        if self.y + height > atlas_height {
+            return None;
+        }
+        let position = (self.x, self.y);
+        self.x += width;
+        self.row_height = self.row_height.max(height);
+        Some(position)
+    }
+}
+
+/// Packs `image` into the free space of a `width` by `height` atlas and copies its pixels there,
+/// returning where it went.
+fn place_glyph(
+    image: ffi::Image,
+    pixels: &mut [u8],
+    width: i32,
+    height: i32,
+    packer: &mut RowPacker,
+) -> Option<ffi::Rectangle> {
+    let (x, y) = packer.place(
+        image.width + 2 * GLYPH_PADDING,
+        image.height + 2 * GLYPH_PADDING,
+        width,
+        height,
+    )?;
+    let rec = ffi::Rectangle {
+        x: (x + GLYPH_PADDING) as f32,
+        y: (y + GLYPH_PADDING) as f32,
+        width: image.width as f32,
+        height: image.height as f32,
+    };
+    if image.data.is_null() {
+        return Some(rec);
+    }
+    // glyph images are one coverage byte per pixel, the atlas is white with that alpha
+    let coverage = unsafe {
+        std::slice::from_raw_parts(
+            image.data as *const u8,
+            (image.width * image.height) as usize,
+        )
+    };
+    for row in 0..image.height {
+        for col in 0..image.width {
+            let offset = (((rec.y as i32 + row) * width + rec.x as i32 + col) * 2) as usize;
+            pixels[offset] = 255;
+            pixels[offset + 1] = coverage[(row * image.width + col) as usize];
+        }
+    }
+    Some(rec)
+}
+
+/// Clears the pixels under `rec` in an atlas `width` pixels wide.
+fn clear_rec(pixels: &mut [u8], width: i32, rec: ffi::Rectangle) {
+    let (x, y) = (rec.x as usize, rec.y as usize);
+    for row in y..y + rec.height as usize {
+        let start = (row * width as usize + x) * 2;
+        pixels[start..start + rec.width as usize * 2].fill(0);
+    }
+}
+
+fn load_atlas_texture(
+    _: &RaylibThread,
+    pixels: &[u8],
+    width: i32,
+    height: i32,
+) -> Result<Texture2D, Error> {
+    let image = ffi::Image {
+        data: pixels.as_ptr() as *mut std::os::raw::c_void,
+        width,
+        height,
+        mipmaps: 1,
+        format: PixelFormat::PIXELFORMAT_UNCOMPRESSED_GRAY_ALPHA as i32,
+    };
+    // raylib only reads the pixels, they stay owned by the font
+    let texture = unsafe { ffi::LoadTextureFromImage(image) };
+    if texture.id == 0 {
+        return Err(error!("Error loading the dynamic font atlas texture."));
+    }
+    Ok(Texture2D(texture))
+}
@@ -3,6 +3,7 @@ use crate::ffi;
 
 use crate::core::RaylibHandle;
 use std::ffi::{CStr, CString, OsString};
+use std::path::Path;
 
 make_thin_wrapper!(FilePathList, ffi::FilePathList, ffi::UnloadDirectoryFiles);
 make_thin_wrapper!(
@@ -137,3 +138,17 @@ impl RaylibHandle {
         unsafe { DroppedFilePathList(ffi::LoadDroppedFiles()) }
     }
 }
+
+/// Reads a whole file with raylib's `LoadFileData`, so custom file callbacks apply, or `None`
+/// if it can't be read.
+pub(crate) fn load_file_data(filename: &Path) -> Option<Vec<u8>> {
+    let c_filename = CString::new(filename.to_string_lossy().replace('\0', "")).ok()?;
+    let mut size = 0;
+    let data = unsafe { ffi::LoadFileData(c_filename.as_ptr(), &mut size) };
+    if data.is_null() {
+        return None;
+    }
+    let bytes = unsafe { std::slice::from_raw_parts(data, size.max(0) as usize) }.to_vec();
+    unsafe { ffi::UnloadFileData(data) };
+    Some(bytes)
+}
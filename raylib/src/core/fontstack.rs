@@ -0,0 +1,301 @@
+//! Font fallback chains, for text mixing scripts no single font covers
+//!
+//! Stacks are drawn with [`RaylibFontStackExt`] and measured with [`FontStack::measure_text`].
+//! [`TextLayout::with_font_stack`] lays text out with one, and
+//! [`FontSet::set_fallback`](crate::core::richtext::FontSet::set_fallback) makes one the
+//! fallback of rich text.
+//!
+//! ```no_run
+//! use raylib::prelude::*;
+//!
+//! let (mut rl, thread) = raylib::init().size(800, 450).build();
+//! let latin = rl.load_font_ex(&thread, "NotoSans.ttf", 32, None).unwrap();
+//! let cjk_chars = "日本語のテキスト";
+//! let cjk = rl
+//!     .load_font_ex(&thread, "NotoSansJP.ttf", 32, Some(cjk_chars))
+//!     .unwrap();
+//! let mut fonts = FontStack::new(&latin);
+//! fonts.push(&cjk).set_replacement('\u{FFFD}');
+//!
+//! let mut d = rl.begin_drawing(&thread);
+//! d.draw_text_stack(&fonts, "Text / テキスト", Vector2::new(20.0, 20.0), 32.0, 1.0, Color::BLACK);
+//! ```
+use crate::core::drawing::RaylibDraw;
+use crate::core::math::{Vector2, Vector3};
+use crate::core::rlgl::Rlgl;
+use crate::core::text::{RaylibFont, WeakFont, TEXT_LINE_SPACING};
+use crate::core::textlayout::TextLayout;
+use crate::ffi;
+use std::collections::HashMap;
+use std::marker::PhantomData;
+use std::sync::atomic::Ordering;
+
+/// Glyphs tried, in order, to find where the baseline of a font is.
+const BASELINE_GLYPHS: [char; 5] = ['H', '0', '\u{3042}', '\u{4E00}', '?'];
+
+#[derive(Debug, Clone)]
+struct StackEntry {
+    font: ffi::Font,
+    scale: f32,
+    /// Distance from the top of a line to the baseline, relative to the font size
+    baseline: Option<f32>,
+}
+
+/// An ordered list of fonts drawn and measured as one.
+///
+/// Each codepoint is drawn with the first font that has a glyph for it. Codepoints no font has are
+/// replaced by [`FontStack::replacement`], `?` by default. All fonts are scaled to the requested
+/// font size, whatever their base size, and moved vertically to share the baseline of the first font.
+/// The fonts are borrowed, so they must outlive the stack.
+#[derive(Debug, Clone)]
+pub struct FontStack<'a> {
+    fonts: Vec<StackEntry>,
+    replacement: char,
+    _fonts: PhantomData<&'a ffi::Font>,
+}
+
+impl<'a> FontStack<'a> {
+    /// Creates a stack starting with `primary`, whose baseline the other fonts line up with.
+    pub fn new(primary: &'a impl AsRef<ffi::Font>) -> Self {
+        let mut stack = FontStack {
+            fonts: Vec::new(),
+            replacement: '?',
+            _fonts: PhantomData,
+        };
+        stack.push(primary);
+        stack
+    }
+
+    /// Adds `font` as the last fallback.
+    pub fn push(&mut self, font: &'a impl AsRef<ffi::Font>) -> &mut Self {
+        self.push_scaled(font, 1.0)
+    }
+
+    /// Adds `font` as the last fallback, drawing its glyphs `scale` times bigger. Useful for fonts
+    /// that look smaller or bigger than the others at the same size.
+    pub fn push_scaled(&mut self, font: &'a impl AsRef<ffi::Font>, scale: f32) -> &mut Self {
+        let font = *font.as_ref();
+        self.fonts.push(StackEntry {
+            font,
+            scale,
+            baseline: baseline(&WeakFont(font)),
+        });
+        self
+    }
+
+    /// Sets the codepoint drawn for codepoints none of the fonts have.
+    pub fn set_replacement(&mut self, replacement: char) -> &mut Self {
+        self.replacement = replacement;
+        self
+    }
+
+    pub fn replacement(&self) -> char {
+        self.replacement
+    }
+
+    pub fn font_count(&self) -> usize {
+        self.fonts.len()
+    }
+
+    pub fn font(&self, index: usize) -> Option<WeakFont> {
+        self.fonts.get(index).map(|entry| WeakFont(entry.font))
+    }
+
+    /// Index of the font drawing `codepoint`, and the codepoint it draws:
+    /// `codepoint` itself, or the replacement if no font has it.
+    pub fn resolve(&self, codepoint: char) -> (usize, char) {
+        if let Some(index) = self.find(codepoint) {
+            return (index, codepoint);
+        }
+        // the first font draws its own fallback glyph if even the replacement is missing
+        (self.find(self.replacement).unwrap_or(0), self.replacement)
+    }
+
+    /// Measures `text` like [`RaylibFont::measure_text`] does for a single font.
+    pub fn measure_text(&self, text: &str, font_size: f32, spacing: f32) -> Vector2 {
+        let line_spacing = TEXT_LINE_SPACING.load(Ordering::Relaxed) as f32;
+        let mut width: f32 = 0.0;
+        let mut lines = 0;
+        for line in text.split('\n') {
+            let glyphs = line.chars().count();
+            let advances: f32 = line.chars().map(|c| self.advance(c, font_size)).sum();
+            width = width.max(advances + spacing * glyphs.saturating_sub(1) as f32);
+            lines += 1;
+        }
+        Vector2::new(
+            width,
+            font_size + (lines - 1) as f32 * (font_size + line_spacing),
+        )
+    }
+
+    /// Horizontal advance of `codepoint` at `font_size`, without spacing.
+    pub fn advance(&self, codepoint: char, font_size: f32) -> f32 {
+        let (index, codepoint) = self.resolve(codepoint);
+        let entry = &self.fonts[index];
+        glyph_advance(&WeakFont(entry.font), codepoint) * self.size_of(index, font_size)
+            / entry.font.baseSize as f32
+    }
+
+    /// Advance of every codepoint of every font for a font size of 1, and of the replacement.
+    pub(crate) fn advances(&self) -> (HashMap<char, f32>, f32) {
+        let mut advances = HashMap::new();
+        // later fonts first, so the earlier ones overwrite their glyphs
+        for (index, entry) in self.fonts.iter().enumerate().rev() {
+            let font = WeakFont(entry.font);
+            let size = self.size_of(index, 1.0) / entry.font.baseSize as f32;
+            for glyph in font.chars() {
+                if let Some(c) = char::from_u32(glyph.value as u32) {
+                    advances.insert(c, glyph_advance(&font, c) * size);
+                }
+            }
+        }
+        (advances, self.advance(self.replacement, 1.0))
+    }
+
+    /// Font size to draw the font at `index` with for text of `font_size`.
+    pub(crate) fn size_of(&self, index: usize, font_size: f32) -> f32 {
+        font_size * self.fonts[index].scale
+    }
+
+    /// Vertical offset moving glyphs of the font at `index` onto the baseline of the first font.
+    fn offset_of(&self, index: usize, font_size: f32) -> f32 {
+        self.offset_to(self.fonts[0].baseline, index, font_size)
+    }
+
+    /// Vertical offset moving glyphs of the font at `index` onto the `primary` baseline, given
+    /// relative to the font size.
+    pub(crate) fn offset_to(&self, primary: Option<f32>, index: usize, font_size: f32) -> f32 {
+        match (primary, self.fonts[index].baseline) {
+            (Some(primary), Some(baseline)) => {
+                primary * font_size - baseline * self.size_of(index, font_size)
+            }
+            _ => 0.0,
+        }
+    }
+
+    fn find(&self, codepoint: char) -> Option<usize> {
+        self.fonts
+            .iter()
+            .position(|entry| has_glyph(&WeakFont(entry.font), codepoint))
+    }
+}
+
+/// Drawing with [`FontStack`]s for anything that implements [`RaylibDraw`].
+pub trait RaylibFontStackExt: RaylibDraw
+where
+    Self: Sized,
+{
+    /// Draws `text` like
+    /// [`RaylibDraw::draw_text_ex`](crate::core::drawing::RaylibDraw::draw_text_ex), taking each
+    /// glyph from the first font in `fonts` that has it.
+    fn draw_text_stack(
+        &mut self,
+        fonts: &FontStack,
+        text: &str,
+        position: impl Into<Vector2>,
+        font_size: f32,
+        spacing: f32,
+        tint: impl Into<ffi::Color>,
+    ) {
+        let position = position.into();
+        let tint: ffi::Color = tint.into();
+        let line_spacing = TEXT_LINE_SPACING.load(Ordering::Relaxed) as f32;
+        let mut pen = position;
+        for c in text.chars() {
+            if c == '\n' {
+                pen = Vector2::new(position.x, pen.y + font_size + line_spacing);
+                continue;
+            }
+            let (index, codepoint) = fonts.resolve(c);
+            if !codepoint.is_whitespace() {
+                self.draw_text_codepoint(
+                    WeakFont(fonts.fonts[index].font),
+                    codepoint as i32,
+                    Vector2::new(pen.x, pen.y + fonts.offset_of(index, font_size)),
+                    fonts.size_of(index, font_size),
+                    tint,
+                );
+            }
+            pen.x += fonts.advance(c, font_size) + spacing;
+        }
+    }
+
+    /// Draws `text` like
+    /// [`RaylibDraw::draw_text_pro`](crate::core::drawing::RaylibDraw::draw_text_pro), rotated by
+    /// `rotation` degrees around `origin`, which is relative to `position`.
+    fn draw_text_stack_pro(
+        &mut self,
+        fonts: &FontStack,
+        text: &str,
+        position: impl Into<Vector2>,
+        origin: impl Into<Vector2>,
+        rotation: f32,
+        font_size: f32,
+        spacing: f32,
+        tint: impl Into<ffi::Color>,
+    ) {
+        let position = position.into();
+        let origin = origin.into();
+        let mut m = self.push_matrix();
+        m.translate(position.x, position.y, 0.0)
+            .rotate(rotation, Vector3::new(0.0, 0.0, 1.0))
+            .translate(-origin.x, -origin.y, 0.0);
+        m.draw_text_stack(fonts, text, Vector2::zero(), font_size, spacing, tint);
+    }
+
+    /// Draws a layout made with [`TextLayout::with_font_stack`] with its origin at `position`.
+    fn draw_text_layout_stack(
+        &mut self,
+        fonts: &FontStack,
+        layout: &TextLayout,
+        position: impl Into<Vector2>,
+        tint: impl Into<ffi::Color>,
+    ) {
+        let position = position.into();
+        let tint: ffi::Color = tint.into();
+        let font_size = layout.font_size();
+        for glyph in layout.glyphs() {
+            let (index, codepoint) = fonts.resolve(glyph.codepoint);
+            if !codepoint.is_whitespace() {
+                let offset = Vector2::new(0.0, fonts.offset_of(index, font_size));
+                self.draw_text_codepoint(
+                    WeakFont(fonts.fonts[index].font),
+                    codepoint as i32,
+                    position + glyph.position + offset,
+                    fonts.size_of(index, font_size),
+                    tint,
+                );
+            }
+        }
+    }
+}
+
+impl<D: RaylibDraw> RaylibFontStackExt for D {}
+
+/// Whether `font` has its own glyph for `codepoint`, rather than its fallback glyph.
+pub(crate) fn has_glyph(font: &WeakFont, codepoint: char) -> bool {
+    let index = font.get_glyph_index(codepoint);
+    font.chars()
+        .get(index as usize)
+        .map_or(false, |glyph| glyph.value == codepoint as i32)
+}
+
+/// Advance of `codepoint` at the base size of `font`, the way raylib computes it.
+fn glyph_advance(font: &WeakFont, codepoint: char) -> f32 {
+    let glyph = font.get_glyph_info(codepoint);
+    match glyph.advanceX {
+        0 => font.get_glyph_atlas_rec(codepoint).width,
+        advance => advance as f32,
+    }
+}
+
+/// Distance from the top of a line to the baseline of `font`, relative to the font size.
+pub(crate) fn baseline(font: &WeakFont) -> Option<f32> {
+    let c = BASELINE_GLYPHS
+        .iter()
+        .copied()
+        .find(|&c| has_glyph(font, c))?;
+    let glyph = font.get_glyph_info(c);
+    let height = font.get_glyph_atlas_rec(c).height;
+    Some((glyph.offsetY as f32 + height) / font.base_size() as f32)
+}
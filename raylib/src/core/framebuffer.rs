@@ -0,0 +1,561 @@
+//! Render textures with custom color formats, depth textures, stencil, MSAA and multiple
+//! color attachments, built on rlgl's framebuffer functions
+use crate::core::texture::{RaylibRenderTexture2D, WeakTexture2D};
+use crate::core::RaylibThread;
+use crate::error::{error, Error};
+use crate::ffi;
+use crate::ffi::PixelFormat;
+use std::borrow::Cow;
+
+/// The most color attachments [`RenderTextureBuilder`] accepts, matching rlgl's draw buffer limit.
+pub const MAX_COLOR_ATTACHMENTS: usize = 8;
+
+/// How a [`RenderTarget`] stores depth.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum DepthAttachment {
+    /// No depth buffer. Draws are never depth tested.
+    None,
+    /// A depth buffer only the GPU can read, as created by `load_render_texture`.
+    Renderbuffer,
+    /// A depth texture shaders can sample, for shadow maps, SSAO and deferred lighting.
+    Texture,
+}
+
+/// Describes the attachments of a [`RenderTarget`].
+///
+/// The defaults match `load_render_texture`: one RGBA8 color texture and a depth renderbuffer.
+///
+/// ```no_run
+/// use raylib::prelude::*;
+///
+/// let (mut rl, thread) = raylib::init().size(800, 450).build();
+/// // albedo, normals and positions plus a depth texture for a deferred renderer
+/// let gbuffer = RenderTextureBuilder::new(800, 450)
+///     .color_attachments(&[
+///         PixelFormat::PIXELFORMAT_UNCOMPRESSED_R8G8B8A8,
+///         PixelFormat::PIXELFORMAT_UNCOMPRESSED_R16G16B16A16,
+///         PixelFormat::PIXELFORMAT_UNCOMPRESSED_R32G32B32A32,
+///     ])
+///     .depth(DepthAttachment::Texture)
+///     .build(&thread)
+///     .unwrap();
+/// ```
+#[derive(Debug, Clone)]
+pub struct RenderTextureBuilder {
+    width: u32,
+    height: u32,
+    colors: Vec<PixelFormat>,
+    depth: DepthAttachment,
+    stencil: bool,
+    samples: u32,
+}
+
+impl RenderTextureBuilder {
+    pub fn new(width: u32, height: u32) -> RenderTextureBuilder {
+        RenderTextureBuilder {
+            width,
+            height,
+            colors: vec![PixelFormat::PIXELFORMAT_UNCOMPRESSED_R8G8B8A8],
+            depth: DepthAttachment::Renderbuffer,
+            stencil: false,
+            samples: 1,
+        }
+    }
+
+    /// Uses a single color attachment of `format`. Float formats give HDR targets.
+    pub fn color_format(&mut self, format: PixelFormat) -> &mut Self {
+        self.color_attachments(&[format])
+    }
+
+    /// Uses one color attachment per format, bound to `COLOR_ATTACHMENT0` onwards in order.
+    /// Fragment shaders write to them through their `out` variables.
+    pub fn color_attachments(&mut self, formats: &[PixelFormat]) -> &mut Self {
+        self.colors = formats.to_vec();
+        self
+    }
+
+    /// Removes every color attachment, leaving a depth-only target such as a shadow map.
+    pub fn no_color(&mut self) -> &mut Self {
+        self.colors.clear();
+        self
+    }
+
+    pub fn depth(&mut self, depth: DepthAttachment) -> &mut Self {
+        self.depth = depth;
+        self
+    }
+
+    /// Adds an 8 bit stencil buffer, stored together with a 24 bit depth renderbuffer.
+    ///
+    /// rlgl can't create stencil buffers, so this needs desktop OpenGL 3.3 and the raylib that
+    /// raylib-sys builds; [`build`](Self::build) fails with the `nobuild` feature.
+    pub fn stencil(&mut self) -> &mut Self {
+        self.stencil = true;
+        self
+    }
+
+    /// Renders with `samples` samples per pixel. Values of 0 and 1 turn multisampling off.
+    ///
+    /// Multisampled targets are drawn into separate storage; call [`RenderTarget::resolve`]
+    /// after drawing to update the color textures. Like [`stencil`](Self::stencil), this isn't
+    /// available with the `nobuild` feature.
+    pub fn msaa(&mut self, samples: u32) -> &mut Self {
+        self.samples = samples.max(1);
+        self
+    }
+
+    /// Creates the framebuffers and attachments on the GPU.
+    pub fn build(&self, _: &RaylibThread) -> Result<RenderTarget, Error> {
+        if self.width == 0 || self.height == 0 {
+            return Err(error!("render texture size must be greater than zero"));
+        }
+        if self.colors.len() > MAX_COLOR_ATTACHMENTS {
+            return Err(error!(Cow::Owned(format!(
+                "render textures support at most {} color attachments, got {}",
+                MAX_COLOR_ATTACHMENTS,
+                self.colors.len()
+            ))));
+        }
+        if let Some(format) = self.colors.iter().find(|&&f| is_compressed(f)) {
+            return Err(error!(Cow::Owned(format!(
+                "{:?} can't be rendered to",
+                format
+            ))));
+        }
+        if self.colors.is_empty() && self.depth != DepthAttachment::Texture {
+            return Err(error!(
+                "render texture needs a color attachment or a depth texture"
+            ));
+        }
+        if self.depth == DepthAttachment::Texture && (self.stencil || self.samples > 1) {
+            return Err(error!(
+                "depth textures can't be combined with a stencil buffer or multisampling"
+            ));
+        }
+        let gl = if self.stencil || self.samples > 1 {
+            Some(gl::load().ok_or_else(|| {
+                error!(
+                    "stencil and multisampled render textures require desktop OpenGL 3.3 and \
+                     raylib built by raylib-sys"
+                )
+            })?)
+        } else {
+            None
+        };
+
+        let (width, height) = (self.width as i32, self.height as i32);
+        let empty = ffi::Texture2D {
+            id: 0,
+            width,
+            height,
+            mipmaps: 1,
+            format: 0,
+        };
+        // Fill in as we go, so an error part way through unloads whatever was created.
+        let mut target = RenderTarget {
+            raw: ffi::RenderTexture2D {
+                id: 0,
+                texture: empty,
+                depth: empty,
+            },
+            framebuffer: 0,
+            multisample_framebuffer: 0,
+            renderbuffers: Vec::new(),
+            colors: Vec::with_capacity(self.colors.len()),
+            depth_texture: false,
+            stencil: self.stencil,
+            samples: self.samples,
+            gl,
+        };
+
+        target.framebuffer = unsafe { ffi::rlLoadFramebuffer() };
+        if target.framebuffer == 0 {
+            return Err(error!("failed to create framebuffer"));
+        }
+        for (i, &format) in self.colors.iter().enumerate() {
+            let id =
+                unsafe { ffi::rlLoadTexture(std::ptr::null(), width, height, format as i32, 1) };
+            if id == 0 {
+                return Err(error!(Cow::Owned(format!(
+                    "{:?} is not supported as a color attachment",
+                    format
+                ))));
+            }
+            target.colors.push(ffi::Texture2D {
+                id,
+                width,
+                height,
+                mipmaps: 1,
+                format: format as i32,
+            });
+            unsafe { attach(target.framebuffer, id, color_attachment(i), TEXTURE2D) };
+        }
+
+        let draw_framebuffer = match gl {
+            Some(gl) if self.samples > 1 => {
+                target.multisample_framebuffer = unsafe { ffi::rlLoadFramebuffer() };
+                if target.multisample_framebuffer == 0 {
+                    return Err(error!("failed to create multisampled framebuffer"));
+                }
+                for (i, &format) in self.colors.iter().enumerate() {
+                    let (mut internal, mut gl_format, mut gl_type) = (0, 0, 0);
+                    unsafe {
+                        ffi::rlGetGlTextureFormats(
+                            format as i32,
+                            &mut internal,
+                            &mut gl_format,
+                            &mut gl_type,
+                        )
+                    };
+                    let id = unsafe { gl.renderbuffer(internal, self.samples, width, height) };
+                    target.renderbuffers.push(id);
+                    unsafe {
+                        attach(
+                            target.multisample_framebuffer,
+                            id,
+                            color_attachment(i),
+                            RENDERBUFFER,
+                        )
+                    };
+                }
+                target.multisample_framebuffer
+            }
+            _ => target.framebuffer,
+        };
+
+        match (self.depth, gl) {
+            (DepthAttachment::Texture, _) => {
+                let id = unsafe { ffi::rlLoadTextureDepth(width, height, false) };
+                if id == 0 {
+                    return Err(error!("failed to create depth texture"));
+                }
+                unsafe { attach(draw_framebuffer, id, DEPTH, TEXTURE2D) };
+                target.depth_texture = true;
+                // Sampling a depth texture gives a single float channel
+                target.raw.depth = ffi::Texture2D {
+                    id,
+                    format: PixelFormat::PIXELFORMAT_UNCOMPRESSED_R32 as i32,
+                    ..empty
+                };
+            }
+            (DepthAttachment::None, _) if !self.stencil => {}
+            (_, Some(gl)) => {
+                let format = if self.stencil {
+                    gl::DEPTH24_STENCIL8
+                } else {
+                    gl::DEPTH_COMPONENT24
+                };
+                let id = unsafe { gl.renderbuffer(format, self.samples, width, height) };
+                unsafe { attach(draw_framebuffer, id, DEPTH, RENDERBUFFER) };
+                if self.stencil {
+                    unsafe { attach(draw_framebuffer, id, STENCIL, RENDERBUFFER) };
+                }
+                target.raw.depth.id = id;
+            }
+            (_, None) => {
+                let id = unsafe { ffi::rlLoadTextureDepth(width, height, true) };
+                if id == 0 {
+                    return Err(error!("failed to create depth renderbuffer"));
+                }
+                unsafe { attach(draw_framebuffer, id, DEPTH, RENDERBUFFER) };
+                target.raw.depth.id = id;
+            }
+        }
+
+        for &id in &[target.framebuffer, target.multisample_framebuffer] {
+            if id == 0 {
+                continue;
+            }
+            // Draw buffers are framebuffer state, so they only need setting once
+            if target.colors.len() > 1 {
+                unsafe {
+                    ffi::rlEnableFramebuffer(id);
+                    ffi::rlActiveDrawBuffers(target.colors.len() as i32);
+                    ffi::rlDisableFramebuffer();
+                }
+            }
+            if !unsafe { ffi::rlFramebufferComplete(id) } {
+                return Err(error!("render texture framebuffer is incomplete"));
+            }
+        }
+
+        target.raw.id = draw_framebuffer;
+        if let Some(&color) = target.colors.first() {
+            target.raw.texture = color;
+        }
+        Ok(target)
+    }
+}
+
+/// A render texture created by [`RenderTextureBuilder`].
+///
+/// Derefs to [`ffi::RenderTexture2D`], so it works with `begin_texture_mode` like a
+/// [`RenderTexture2D`](crate::core::texture::RenderTexture2D). Its texture is the first color
+/// attachment, or an empty texture of the right size for depth-only targets.
+/// Everything it created is unloaded when it is dropped.
+#[derive(Debug)]
+pub struct RenderTarget {
+    raw: ffi::RenderTexture2D,
+    framebuffer: u32,
+    multisample_framebuffer: u32,
+    renderbuffers: Vec<u32>,
+    colors: Vec<ffi::Texture2D>,
+    depth_texture: bool,
+    stencil: bool,
+    samples: u32,
+    gl: Option<gl::Renderbuffers>,
+}
+
+impl RenderTarget {
+    pub fn width(&self) -> i32 {
+        self.raw.texture.width
+    }
+
+    pub fn height(&self) -> i32 {
+        self.raw.texture.height
+    }
+
+    /// Samples per pixel, 1 when multisampling is off.
+    pub fn samples(&self) -> u32 {
+        self.samples
+    }
+
+    pub fn is_multisampled(&self) -> bool {
+        self.multisample_framebuffer != 0
+    }
+
+    pub fn has_stencil(&self) -> bool {
+        self.stencil
+    }
+
+    pub fn color_count(&self) -> usize {
+        self.colors.len()
+    }
+
+    /// The texture behind color attachment `index`.
+    pub fn color_texture(&self, index: usize) -> Option<&WeakTexture2D> {
+        self.colors
+            .get(index)
+            .map(|t| unsafe { &*(t as *const ffi::Texture2D as *const WeakTexture2D) })
+    }
+
+    /// The depth texture, if built with [`DepthAttachment::Texture`].
+    pub fn depth_texture(&self) -> Option<&WeakTexture2D> {
+        if self.depth_texture {
+            Some(unsafe { &*(&self.raw.depth as *const ffi::Texture2D as *const WeakTexture2D) })
+        } else {
+            None
+        }
+    }
+
+    /// Copies the multisampled color attachments into the color textures. Call it after the
+    /// texture mode ends and before sampling the textures. Does nothing without multisampling.
+    pub fn resolve(&mut self, _: &RaylibThread) {
+        let gl = match self.gl {
+            Some(gl) if self.is_multisampled() => gl,
+            _ => return,
+        };
+        let (width, height) = (self.width(), self.height());
+        unsafe {
+            ffi::rlDrawRenderBatchActive();
+            ffi::rlBindFramebuffer(ffi::RL_READ_FRAMEBUFFER, self.multisample_framebuffer);
+            ffi::rlBindFramebuffer(ffi::RL_DRAW_FRAMEBUFFER, self.framebuffer);
+            // A blit copies from one read buffer into every draw buffer, so go one at a time
+            for i in 0..self.colors.len() {
+                let mut buffers = [gl::NONE; MAX_COLOR_ATTACHMENTS];
+                buffers[i] = gl::COLOR_ATTACHMENT0 + i as u32;
+                (gl.read_buffer)(buffers[i]);
+                (gl.draw_buffers)(i as i32 + 1, buffers.as_ptr());
+                ffi::rlBlitFramebuffer(
+                    0,
+                    0,
+                    width,
+                    height,
+                    0,
+                    0,
+                    width,
+                    height,
+                    gl::COLOR_BUFFER_BIT,
+                );
+            }
+            (gl.read_buffer)(gl::COLOR_ATTACHMENT0);
+            ffi::rlActiveDrawBuffers(self.colors.len() as i32);
+            ffi::rlDisableFramebuffer();
+        }
+    }
+}
+
+impl Drop for RenderTarget {
+    fn drop(&mut self) {
+        unsafe {
+            for color in &self.colors {
+                ffi::rlUnloadTexture(color.id);
+            }
+            if let Some(gl) = self.gl {
+                if !self.renderbuffers.is_empty() {
+                    (gl.delete_renderbuffers)(
+                        self.renderbuffers.len() as i32,
+                        self.renderbuffers.as_ptr(),
+                    );
+                }
+            }
+            // rlUnloadFramebuffer also deletes whatever is attached as depth
+            if self.multisample_framebuffer != 0 {
+                ffi::rlUnloadFramebuffer(self.multisample_framebuffer);
+            }
+            if self.framebuffer != 0 {
+                ffi::rlUnloadFramebuffer(self.framebuffer);
+            }
+        }
+    }
+}
+
+impl std::ops::Deref for RenderTarget {
+    type Target = ffi::RenderTexture2D;
+
+    fn deref(&self) -> &Self::Target {
+        &self.raw
+    }
+}
+
+impl std::ops::DerefMut for RenderTarget {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.raw
+    }
+}
+
+impl AsRef<ffi::RenderTexture2D> for RenderTarget {
+    fn as_ref(&self) -> &ffi::RenderTexture2D {
+        &self.raw
+    }
+}
+
+impl AsMut<ffi::RenderTexture2D> for RenderTarget {
+    fn as_mut(&mut self) -> &mut ffi::RenderTexture2D {
+        &mut self.raw
+    }
+}
+
+impl AsRef<ffi::Texture2D> for RenderTarget {
+    fn as_ref(&self) -> &ffi::Texture2D {
+        &self.raw.texture
+    }
+}
+
+impl RaylibRenderTexture2D for RenderTarget {}
+
+const TEXTURE2D: i32 = ffi::rlFramebufferAttachTextureType::RL_ATTACHMENT_TEXTURE2D as i32;
+const RENDERBUFFER: i32 = ffi::rlFramebufferAttachTextureType::RL_ATTACHMENT_RENDERBUFFER as i32;
+const DEPTH: i32 = ffi::rlFramebufferAttachType::RL_ATTACHMENT_DEPTH as i32;
+const STENCIL: i32 = ffi::rlFramebufferAttachType::RL_ATTACHMENT_STENCIL as i32;
+
+fn color_attachment(index: usize) -> i32 {
+    ffi::rlFramebufferAttachType::RL_ATTACHMENT_COLOR_CHANNEL0 as i32 + index as i32
+}
+
+unsafe fn attach(framebuffer: u32, id: u32, attachment: i32, kind: i32) {
+    ffi::rlFramebufferAttach(framebuffer, id, attachment, kind, 0)
+}
+
+fn is_compressed(format: PixelFormat) -> bool {
+    format as i32 >= PixelFormat::PIXELFORMAT_COMPRESSED_DXT1_RGB as i32
+}
+
+/// The renderbuffer functions rlgl doesn't wrap, taken from the glad loader raylib already ran
+/// for its desktop OpenGL backends.
+///
+/// glad's function pointers are private to raylib, so they're only linked when raylib-sys
+/// builds raylib as a static library. With `nobuild` the raylib being linked may not have
+/// them, and stencil and MSAA targets fail to build instead.
+mod gl {
+    use std::os::raw::{c_int, c_uint};
+
+    pub const NONE: c_uint = 0;
+    pub const COLOR_ATTACHMENT0: c_uint = 0x8CE0;
+    pub const DEPTH_COMPONENT24: c_uint = 0x81A6;
+    pub const DEPTH24_STENCIL8: c_uint = 0x88F0;
+    pub const COLOR_BUFFER_BIT: c_int = 0x4000;
+    const RENDERBUFFER: c_uint = 0x8D41;
+
+    type GenRenderbuffers = unsafe extern "system" fn(c_int, *mut c_uint);
+    type BindRenderbuffer = unsafe extern "system" fn(c_uint, c_uint);
+    type RenderbufferStorageMultisample =
+        unsafe extern "system" fn(c_uint, c_int, c_uint, c_int, c_int);
+    type DeleteRenderbuffers = unsafe extern "system" fn(c_int, *const c_uint);
+    type ReadBuffer = unsafe extern "system" fn(c_uint);
+    type DrawBuffers = unsafe extern "system" fn(c_int, *const c_uint);
+
+    #[derive(Debug, Copy, Clone)]
+    pub struct Renderbuffers {
+        gen_renderbuffers: GenRenderbuffers,
+        bind_renderbuffer: BindRenderbuffer,
+        renderbuffer_storage_multisample: RenderbufferStorageMultisample,
+        pub delete_renderbuffers: DeleteRenderbuffers,
+        pub read_buffer: ReadBuffer,
+        pub draw_buffers: DrawBuffers,
+    }
+
+    impl Renderbuffers {
+        /// Creates a renderbuffer. A single sample gives a regular renderbuffer.
+        pub unsafe fn renderbuffer(
+            &self,
+            format: c_uint,
+            samples: u32,
+            width: c_int,
+            height: c_int,
+        ) -> c_uint {
+            let mut id = 0;
+            (self.gen_renderbuffers)(1, &mut id);
+            (self.bind_renderbuffer)(RENDERBUFFER, id);
+            let samples = if samples > 1 { samples as c_int } else { 0 };
+            (self.renderbuffer_storage_multisample)(RENDERBUFFER, samples, format, width, height);
+            (self.bind_renderbuffer)(RENDERBUFFER, 0);
+            id
+        }
+    }
+
+    #[cfg(not(any(
+        feature = "nobuild",
+        feature = "opengl_21",
+        feature = "opengl_es_20",
+        feature = "opengl_es_30",
+        target_os = "android",
+        target_os = "emscripten",
+        target_arch = "wasm32",
+        all(target_arch = "arm", target_os = "linux")
+    )))]
+    pub fn load() -> Option<Renderbuffers> {
+        extern "C" {
+            static glad_glGenRenderbuffers: Option<GenRenderbuffers>;
+            static glad_glBindRenderbuffer: Option<BindRenderbuffer>;
+            static glad_glRenderbufferStorageMultisample: Option<RenderbufferStorageMultisample>;
+            static glad_glDeleteRenderbuffers: Option<DeleteRenderbuffers>;
+            static glad_glReadBuffer: Option<ReadBuffer>;
+            static glad_glDrawBuffers: Option<DrawBuffers>;
+        }
+        unsafe {
+            Some(Renderbuffers {
+                gen_renderbuffers: glad_glGenRenderbuffers?,
+                bind_renderbuffer: glad_glBindRenderbuffer?,
+                renderbuffer_storage_multisample: glad_glRenderbufferStorageMultisample?,
+                delete_renderbuffers: glad_glDeleteRenderbuffers?,
+                read_buffer: glad_glReadBuffer?,
+                draw_buffers: glad_glDrawBuffers?,
+            })
+        }
+    }
+
+    #[cfg(any(
+        feature = "nobuild",
+        feature = "opengl_21",
+        feature = "opengl_es_20",
+        feature = "opengl_es_30",
+        target_os = "android",
+        target_os = "emscripten",
+        target_arch = "wasm32",
+        all(target_arch = "arm", target_os = "linux")
+    ))]
+    pub fn load() -> Option<Renderbuffers> {
+        None
+    }
+}
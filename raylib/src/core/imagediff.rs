@@ -0,0 +1,114 @@
+//! Pixel by pixel image comparison, for checking rendered output against reference images
+//!
+//! ```no_run
+//! use raylib::prelude::*;
+//!
+//! let expected = Image::load_image("expected.png").unwrap();
+//! let actual = Image::load_image("actual.png").unwrap();
+//! let diff = actual.diff_with_tolerance(&expected, 2).unwrap();
+//! if !diff.is_match() {
+//!     diff.heatmap.export_image("diff.png");
+//! }
+//! ```
+use crate::consts::PixelFormat;
+use crate::core::color::Color;
+use crate::core::imageops::{channels, luminance, max_channel_difference};
+use crate::core::texture::Image;
+use crate::error::{error, Error};
+use crate::ffi;
+use std::borrow::Cow;
+
+/// How two images differ, from [`Image::diff`].
+#[derive(Debug)]
+pub struct ImageDiff {
+    /// Largest difference of each channel, in RGBA order.
+    pub max_error: [u8; 4],
+    /// Average difference of each channel over all pixels, in RGBA order.
+    pub mean_error: [f32; 4],
+    /// Number of pixels with a channel that differs by more than the tolerance.
+    pub mismatched: usize,
+    /// Where the images differ. Matching pixels are a dim grayscale copy of the first image,
+    /// mismatched ones go from red to yellow as the difference grows.
+    pub heatmap: Image,
+}
+
+impl ImageDiff {
+    /// Whether every pixel is within the tolerance.
+    pub fn is_match(&self) -> bool {
+        self.mismatched == 0
+    }
+}
+
+impl Image {
+    /// Compares the image with `other` pixel by pixel. Any difference counts as a mismatch.
+    ///
+    /// Fails if the images have different sizes.
+    pub fn diff(&self, other: &Image) -> Result<ImageDiff, Error> {
+        self.diff_with_tolerance(other, 0)
+    }
+
+    /// Like [`Image::diff`], but pixels whose channels all differ by at most `tolerance`
+    /// match, which allows for small differences between GPUs and drivers.
+    pub fn diff_with_tolerance(&self, other: &Image, tolerance: u8) -> Result<ImageDiff, Error> {
+        if self.width() != other.width() || self.height() != other.height() {
+            return Err(error!(Cow::Owned(format!(
+                "can't diff a {}x{} image with a {}x{} image",
+                self.width(),
+                self.height(),
+                other.width(),
+                other.height()
+            ))));
+        }
+        if self.width() <= 0 || self.height() <= 0 {
+            // nothing to compare, and raylib can't make an empty heatmap
+            return Ok(ImageDiff {
+                max_error: [0; 4],
+                mean_error: [0.0; 4],
+                mismatched: 0,
+                heatmap: Image(ffi::Image {
+                    data: std::ptr::null_mut(),
+                    width: 0,
+                    height: 0,
+                    mipmaps: 1,
+                    format: PixelFormat::PIXELFORMAT_UNCOMPRESSED_R8G8B8A8 as i32,
+                }),
+            });
+        }
+        let (a, b) = (self.rgba(), other.rgba());
+        let mut max_error = [0u8; 4];
+        let mut totals = [0u64; 4];
+        let mut mismatched = 0;
+        let heatmap: Vec<Color> = a
+            .iter()
+            .zip(b.iter())
+            .map(|(&x, &y)| {
+                let (from, to) = (channels(x), channels(y));
+                let pairs = from.iter().zip(to.iter());
+                for ((max, total), (&p, &q)) in max_error.iter_mut().zip(&mut totals).zip(pairs) {
+                    let d = (p as i16 - q as i16).unsigned_abs() as u8;
+                    *max = (*max).max(d);
+                    *total += d as u64;
+                }
+                let worst = max_channel_difference(x, y);
+                if worst > tolerance {
+                    mismatched += 1;
+                    Color::new(255, worst, 0, 255)
+                } else {
+                    let l = luminance(x) / 4;
+                    Color::new(l, l, l, 255)
+                }
+            })
+            .collect();
+        let count = a.len().max(1) as f32;
+        let mut mean_error = [0.0; 4];
+        for (mean, &total) in mean_error.iter_mut().zip(&totals) {
+            *mean = total as f32 / count;
+        }
+        Ok(ImageDiff {
+            max_error,
+            mean_error,
+            mismatched,
+            heatmap: Image::from_pixel_slice(self.width(), self.height(), &heatmap)?,
+        })
+    }
+}
@@ -0,0 +1,752 @@
+//! Image processing on the CPU: flood fill, morphology, labelling, tone and palette operations
+//!
+//! These complement raylib's own image functions and are written in Rust on top of
+//! [`Image::pixels_mut`]. Operations that modify an image convert it to
+//! [`PIXELFORMAT_UNCOMPRESSED_R8G8B8A8`](PixelFormat::PIXELFORMAT_UNCOMPRESSED_R8G8B8A8) first,
+//! like [`Image::canvas`] does. Images that can't be converted, like compressed ones, are left
+//! unchanged. Tone operations change the color channels and leave alpha alone.
+//!
+//! ```no_run
+//! use raylib::prelude::*;
+//!
+//! let mut map = Image::load_image("level.png").unwrap();
+//! map.flood_fill(10, 10, Color::BLUE, 8);
+//! map.morph_close(2);
+//! let islands = map.connected_components(Connectivity::Four, |c| c.a > 0);
+//! let palette = map.quantize(16, QuantizeMethod::MedianCut);
+//! ```
+use crate::consts::PixelFormat;
+use crate::core::color::Color;
+use crate::core::math::{Rectangle, Vector2};
+use crate::core::pixels::Rgba8;
+use crate::core::texture::Image;
+use crate::ffi;
+use std::borrow::Cow;
+use std::collections::{HashMap, VecDeque};
+
+/// Which neighbours of a pixel count as connected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Connectivity {
+    /// Left, right, up and down.
+    Four,
+    /// The four sides and the four corners.
+    Eight,
+}
+
+/// One connected region found by [`Image::connected_components`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Component {
+    /// Label of the region's pixels in [`ComponentLabels::labels`], starting at 1.
+    pub label: u32,
+    /// Number of pixels.
+    pub area: usize,
+    /// Smallest rectangle containing the region, in pixels.
+    pub bounds: Rectangle,
+    /// Average position of the region's pixel centers.
+    pub centroid: Vector2,
+}
+
+/// The result of [`Image::connected_components`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ComponentLabels {
+    pub width: usize,
+    pub height: usize,
+    /// One label per pixel, row by row: 0 for background, otherwise the label of its region.
+    pub labels: Vec<u32>,
+    /// The regions, ordered by label.
+    pub components: Vec<Component>,
+}
+
+impl ComponentLabels {
+    /// Label of the pixel at `(x, y)`, 0 for background or outside the image.
+    pub fn label_at(&self, x: usize, y: usize) -> u32 {
+        if x < self.width && y < self.height {
+            self.labels[y * self.width + x]
+        } else {
+            0
+        }
+    }
+
+    /// The region with `label`.
+    pub fn component(&self, label: u32) -> Option<&Component> {
+        self.components.get((label as usize).checked_sub(1)?)
+    }
+}
+
+/// Pixel counts per channel value, from [`Image::histogram`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Histogram {
+    pub red: [u32; 256],
+    pub green: [u32; 256],
+    pub blue: [u32; 256],
+    pub alpha: [u32; 256],
+    /// Counts of the luminance of each pixel, weighted like raylib's grayscale conversion.
+    pub luminance: [u32; 256],
+}
+
+impl Histogram {
+    /// Number of pixels counted.
+    pub fn total(&self) -> u64 {
+        self.red.iter().map(|&n| n as u64).sum()
+    }
+}
+
+/// How [`Image::quantize`] chooses a palette.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuantizeMethod {
+    /// Splits the color space at the median of its widest channel until there are enough
+    /// boxes. Fast and deterministic.
+    MedianCut,
+    /// Refines a median cut palette with k-means, moving each color to the average of the
+    /// pixels nearest to it. Slower, usually closer to the original.
+    KMeans { iterations: usize },
+}
+
+pub(crate) fn luminance(c: Color) -> u8 {
+    (c.r as f32 * 0.299 + c.g as f32 * 0.587 + c.b as f32 * 0.114) as u8
+}
+
+pub(crate) fn max_channel_difference(a: Color, b: Color) -> u8 {
+    let d = |x: u8, y: u8| (x as i16 - y as i16).unsigned_abs() as u8;
+    d(a.r, b.r)
+        .max(d(a.g, b.g))
+        .max(d(a.b, b.b))
+        .max(d(a.a, b.a))
+}
+
+pub(crate) fn channels(c: Color) -> [u8; 4] {
+    [c.r, c.g, c.b, c.a]
+}
+
+fn from_channels(c: [u8; 4]) -> Color {
+    Color::new(c[0], c[1], c[2], c[3])
+}
+
+fn distance_squared(a: Color, b: Color) -> u32 {
+    channels(a)
+        .iter()
+        .zip(channels(b).iter())
+        .map(|(&x, &y)| {
+            let d = x as i32 - y as i32;
+            (d * d) as u32
+        })
+        .sum()
+}
+
+/// Index of the palette color nearest to `color`.
+pub(crate) fn nearest(palette: &[Color], color: Color) -> usize {
+    palette
+        .iter()
+        .enumerate()
+        .min_by_key(|(_, &p)| distance_squared(p, color))
+        .map_or(0, |(i, _)| i)
+}
+
+/// Replaces every channel with the minimum or maximum over a square of `radius` around it,
+/// as two separable passes.
+fn min_max_filter(pixels: &mut [Color], width: usize, height: usize, radius: usize, max: bool) {
+    if radius == 0 || pixels.is_empty() {
+        return;
+    }
+    let pick = |a: u8, b: u8| if max { a.max(b) } else { a.min(b) };
+    let pass = |pixels: &mut [Color], horizontal: bool| {
+        let source = pixels.to_vec();
+        for y in 0..height {
+            for x in 0..width {
+                let (pos, len) = if horizontal { (x, width) } else { (y, height) };
+                let (lo, hi) = (pos.saturating_sub(radius), (pos + radius).min(len - 1));
+                let mut out = channels(source[y * width + x]);
+                for i in lo..=hi {
+                    let (sx, sy) = if horizontal { (i, y) } else { (x, i) };
+                    let c = channels(source[sy * width + sx]);
+                    for channel in 0..4 {
+                        out[channel] = pick(out[channel], c[channel]);
+                    }
+                }
+                pixels[y * width + x] = from_channels(out);
+            }
+        }
+    };
+    pass(pixels, true);
+    pass(pixels, false);
+}
+
+/// Blurs the color channels with a separable Gaussian of standard deviation `sigma`.
+fn gaussian_blur(pixels: &[Color], width: usize, height: usize, sigma: f32) -> Vec<[f32; 3]> {
+    let mut out: Vec<[f32; 3]> = pixels
+        .iter()
+        .map(|c| [c.r as f32, c.g as f32, c.b as f32])
+        .collect();
+    if sigma <= 0.0 || pixels.is_empty() {
+        return out;
+    }
+    let radius = (sigma * 3.0).ceil() as isize;
+    let kernel: Vec<f32> = (-radius..=radius)
+        .map(|i| (-(i * i) as f32 / (2.0 * sigma * sigma)).exp())
+        .collect();
+    let total: f32 = kernel.iter().sum();
+    for horizontal in [true, false].iter().copied() {
+        let source = out.clone();
+        for y in 0..height {
+            for x in 0..width {
+                let mut sum = [0.0; 3];
+                for (k, weight) in kernel.iter().enumerate() {
+                    let offset = k as isize - radius;
+                    // clamp to the edge
+                    let (sx, sy) = if horizontal {
+                        (
+                            (x as isize + offset).clamp(0, width as isize - 1) as usize,
+                            y,
+                        )
+                    } else {
+                        (
+                            x,
+                            (y as isize + offset).clamp(0, height as isize - 1) as usize,
+                        )
+                    };
+                    let s = source[sy * width + sx];
+                    for channel in 0..3 {
+                        sum[channel] += s[channel] * weight;
+                    }
+                }
+                out[y * width + x] = [sum[0] / total, sum[1] / total, sum[2] / total];
+            }
+        }
+    }
+    out
+}
+
+/// Colors and how many pixels have each, sorted so results don't depend on hashing.
+pub(crate) fn color_counts(pixels: &[Color]) -> Vec<(Color, u32)> {
+    let mut counts: HashMap<[u8; 4], u32> = HashMap::new();
+    for &c in pixels {
+        *counts.entry(channels(c)).or_insert(0) += 1;
+    }
+    let mut counts: Vec<([u8; 4], u32)> = counts.into_iter().collect();
+    counts.sort_unstable();
+    counts
+        .into_iter()
+        .map(|(c, n)| (from_channels(c), n))
+        .collect()
+}
+
+/// Weighted average color of `colors`.
+fn average(colors: &[(Color, u32)]) -> Color {
+    let mut sum = [0u64; 4];
+    let mut total = 0u64;
+    for &(c, n) in colors {
+        for (s, v) in sum.iter_mut().zip(channels(c).iter()) {
+            *s += *v as u64 * n as u64;
+        }
+        total += n as u64;
+    }
+    let total = total.max(1);
+    from_channels([
+        ((sum[0] + total / 2) / total) as u8,
+        ((sum[1] + total / 2) / total) as u8,
+        ((sum[2] + total / 2) / total) as u8,
+        ((sum[3] + total / 2) / total) as u8,
+    ])
+}
+
+/// Channel with the widest range in `colors`, and that range.
+fn widest_channel(colors: &[(Color, u32)]) -> (usize, u8) {
+    let mut lo = [u8::MAX; 4];
+    let mut hi = [0u8; 4];
+    for &(c, _) in colors {
+        for (channel, v) in channels(c).iter().enumerate() {
+            lo[channel] = lo[channel].min(*v);
+            hi[channel] = hi[channel].max(*v);
+        }
+    }
+    (0..4)
+        .map(|channel| (channel, hi[channel].saturating_sub(lo[channel])))
+        .max_by_key(|&(channel, range)| (range, std::cmp::Reverse(channel)))
+        .unwrap_or((0, 0))
+}
+
+pub(crate) fn median_cut(mut colors: Vec<(Color, u32)>, max_colors: usize) -> Vec<Color> {
+    if colors.is_empty() || max_colors == 0 {
+        return Vec::new();
+    }
+    let mut boxes: Vec<Vec<(Color, u32)>> = vec![std::mem::take(&mut colors)];
+    while boxes.len() < max_colors {
+        // split the box with the widest channel range
+        let widest = boxes
+            .iter()
+            .enumerate()
+            .filter(|(_, b)| b.len() > 1)
+            .map(|(i, b)| (i, widest_channel(b)))
+            .max_by_key(|&(i, (_, range))| (range, std::cmp::Reverse(i)));
+        let (index, channel) = match widest {
+            Some((index, (channel, range))) if range > 0 => (index, channel),
+            _ => break,
+        };
+        let mut colors = boxes.swap_remove(index);
+        colors.sort_by_key(|&(c, _)| channels(c)[channel]);
+        // split at the weighted median, keeping both halves non-empty
+        let total: u64 = colors.iter().map(|&(_, n)| n as u64).sum();
+        let mut seen = 0u64;
+        let mut split = 1;
+        for (i, &(_, n)) in colors.iter().enumerate() {
+            seen += n as u64;
+            if seen * 2 >= total {
+                split = (i + 1).clamp(1, colors.len() - 1);
+                break;
+            }
+        }
+        let upper = colors.split_off(split);
+        boxes.push(colors);
+        boxes.push(upper);
+    }
+    boxes.iter().map(|b| average(b)).collect()
+}
+
+fn k_means(colors: &[(Color, u32)], mut palette: Vec<Color>, iterations: usize) -> Vec<Color> {
+    for _ in 0..iterations {
+        let mut clusters: Vec<Vec<(Color, u32)>> = vec![Vec::new(); palette.len()];
+        for &(c, n) in colors {
+            clusters[nearest(&palette, c)].push((c, n));
+        }
+        let mut moved = false;
+        for (entry, cluster) in palette.iter_mut().zip(clusters.iter()) {
+            if cluster.is_empty() {
+                continue;
+            }
+            let center = average(cluster);
+            moved |= center != *entry;
+            *entry = center;
+        }
+        if !moved {
+            break;
+        }
+    }
+    palette
+}
+
+/// Fritsch-Carlson monotone cubic through `points`, sampled at every channel value.
+fn curve_lut(points: &[(u8, u8)]) -> [u8; 256] {
+    let mut points: Vec<(f32, f32)> = points.iter().map(|&(x, y)| (x as f32, y as f32)).collect();
+    points.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+    points.dedup_by(|a, b| a.0 == b.0);
+    let mut lut = [0u8; 256];
+    match points.len() {
+        0 => {
+            for (i, v) in lut.iter_mut().enumerate() {
+                *v = i as u8;
+            }
+            return lut;
+        }
+        1 => return [points[0].1 as u8; 256],
+        _ => {}
+    }
+    let n = points.len();
+    let slopes: Vec<f32> = points
+        .windows(2)
+        .map(|w| (w[1].1 - w[0].1) / (w[1].0 - w[0].0))
+        .collect();
+    let mut tangents = vec![0.0f32; n];
+    tangents[0] = slopes[0];
+    tangents[n - 1] = slopes[n - 2];
+    for i in 1..n - 1 {
+        tangents[i] = if slopes[i - 1] * slopes[i] <= 0.0 {
+            0.0
+        } else {
+            (slopes[i - 1] + slopes[i]) / 2.0
+        };
+    }
+    for i in 0..n - 1 {
+        if slopes[i] == 0.0 {
+            tangents[i] = 0.0;
+            tangents[i + 1] = 0.0;
+            continue;
+        }
+        let (a, b) = (tangents[i] / slopes[i], tangents[i + 1] / slopes[i]);
+        let h = a * a + b * b;
+        if h > 9.0 {
+            let t = 3.0 / h.sqrt();
+            tangents[i] = t * a * slopes[i];
+            tangents[i + 1] = t * b * slopes[i];
+        }
+    }
+    for (x, v) in lut.iter_mut().enumerate() {
+        let x = x as f32;
+        let y = if x <= points[0].0 {
+            points[0].1
+        } else if x >= points[n - 1].0 {
+            points[n - 1].1
+        } else {
+            let i = points.windows(2).position(|w| x < w[1].0).unwrap_or(n - 2);
+            let ((x0, y0), (x1, y1)) = (points[i], points[i + 1]);
+            let h = x1 - x0;
+            let t = (x - x0) / h;
+            let (t2, t3) = (t * t, t * t * t);
+            (2.0 * t3 - 3.0 * t2 + 1.0) * y0
+                + (t3 - 2.0 * t2 + t) * h * tangents[i]
+                + (-2.0 * t3 + 3.0 * t2) * y1
+                + (t3 - t2) * h * tangents[i + 1]
+        };
+        *v = y.round().clamp(0.0, 255.0) as u8;
+    }
+    lut
+}
+
+impl Image {
+    /// The pixels as RGBA with the width and height, converting the image if needed.
+    ///
+    /// `None` if the image can't be converted, like compressed images, so callers leave it
+    /// alone.
+    pub(crate) fn rgba_mut(&mut self) -> Option<(&mut [Color], usize, usize)> {
+        if self.format() != PixelFormat::PIXELFORMAT_UNCOMPRESSED_R8G8B8A8 {
+            self.set_format(PixelFormat::PIXELFORMAT_UNCOMPRESSED_R8G8B8A8);
+        }
+        let (width, height) = (self.width().max(0) as usize, self.height().max(0) as usize);
+        match self.pixels_mut::<Rgba8>() {
+            Ok(pixels) if pixels.len() == width * height => Some((pixels, width, height)),
+            _ => None,
+        }
+    }
+
+    /// The pixels as RGBA, copied if the image is in another format.
+    pub(crate) fn rgba(&self) -> Cow<'_, [Color]> {
+        match self.pixels::<Rgba8>() {
+            Ok(pixels) => Cow::Borrowed(pixels),
+            Err(_) => Cow::Owned(self.get_image_data().to_vec()),
+        }
+    }
+
+    /// Fills the area connected to `(x, y)` with `color`, like a paint bucket. Pixels whose
+    /// channels all differ from the starting pixel by at most `tolerance` are part of the area.
+    ///
+    /// Returns the number of pixels filled, 0 if `(x, y)` is outside the image or the image
+    /// can't be converted to RGBA.
+    pub fn flood_fill(
+        &mut self,
+        x: i32,
+        y: i32,
+        color: impl Into<ffi::Color>,
+        tolerance: u8,
+    ) -> usize {
+        let color = Color::from(color.into());
+        let (pixels, width, height) = match self.rgba_mut() {
+            Some(rgba) => rgba,
+            None => return 0,
+        };
+        if x < 0 || y < 0 || x as usize >= width || y as usize >= height {
+            return 0;
+        }
+        let seed = pixels[y as usize * width + x as usize];
+        let matches = |c: Color| max_channel_difference(c, seed) <= tolerance;
+        let mut filled = vec![false; width * height];
+        let mut count = 0;
+        let mut stack = vec![(x as usize, y as usize)];
+        while let Some((x, y)) = stack.pop() {
+            let row = y * width;
+            if filled[row + x] || !matches(pixels[row + x]) {
+                continue;
+            }
+            // extend to the whole horizontal span, then queue the spans above and below
+            let (mut left, mut right) = (x, x);
+            while left > 0 && !filled[row + left - 1] && matches(pixels[row + left - 1]) {
+                left -= 1;
+            }
+            while right + 1 < width && !filled[row + right + 1] && matches(pixels[row + right + 1])
+            {
+                right += 1;
+            }
+            for f in &mut filled[row + left..=row + right] {
+                *f = true;
+            }
+            count += right - left + 1;
+            for ny in [y.wrapping_sub(1), y + 1].iter().copied() {
+                if ny >= height {
+                    continue;
+                }
+                let mut in_span = false;
+                for nx in left..=right {
+                    let fillable = !filled[ny * width + nx] && matches(pixels[ny * width + nx]);
+                    if fillable && !in_span {
+                        stack.push((nx, ny));
+                    }
+                    in_span = fillable;
+                }
+            }
+        }
+        for (pixel, _) in pixels.iter_mut().zip(filled).filter(|(_, f)| *f) {
+            *pixel = color;
+        }
+        count
+    }
+
+    /// Shrinks bright and opaque areas: every channel becomes the minimum in the square of
+    /// `radius` pixels around it.
+    pub fn erode(&mut self, radius: u32) {
+        let (pixels, width, height) = match self.rgba_mut() {
+            Some(rgba) => rgba,
+            None => return,
+        };
+        min_max_filter(pixels, width, height, radius as usize, false);
+    }
+
+    /// Grows bright and opaque areas: every channel becomes the maximum in the square of
+    /// `radius` pixels around it.
+    pub fn dilate(&mut self, radius: u32) {
+        let (pixels, width, height) = match self.rgba_mut() {
+            Some(rgba) => rgba,
+            None => return,
+        };
+        min_max_filter(pixels, width, height, radius as usize, true);
+    }
+
+    /// Erodes then dilates, removing bright specks smaller than the square.
+    pub fn morph_open(&mut self, radius: u32) {
+        self.erode(radius);
+        self.dilate(radius);
+    }
+
+    /// Dilates then erodes, filling dark holes and gaps smaller than the square.
+    pub fn morph_close(&mut self, radius: u32) {
+        self.dilate(radius);
+        self.erode(radius);
+    }
+
+    /// Labels the connected regions of pixels for which `foreground` is true.
+    pub fn connected_components(
+        &self,
+        connectivity: Connectivity,
+        foreground: impl Fn(Color) -> bool,
+    ) -> ComponentLabels {
+        let pixels = self.rgba();
+        let (width, height) = (self.width().max(0) as usize, self.height().max(0) as usize);
+        let mut labels = vec![0u32; width * height];
+        let mut components = Vec::new();
+        let mut queue = VecDeque::new();
+        let neighbours: &[(isize, isize)] = match connectivity {
+            Connectivity::Four => &[(-1, 0), (1, 0), (0, -1), (0, 1)],
+            Connectivity::Eight => &[
+                (-1, 0),
+                (1, 0),
+                (0, -1),
+                (0, 1),
+                (-1, -1),
+                (1, -1),
+                (-1, 1),
+                (1, 1),
+            ],
+        };
+        for start in 0..width * height {
+            if labels[start] != 0 || !foreground(pixels[start]) {
+                continue;
+            }
+            let label = components.len() as u32 + 1;
+            labels[start] = label;
+            queue.push_back(start);
+            let (mut min_x, mut min_y, mut max_x, mut max_y) = (width, height, 0, 0);
+            let (mut area, mut sum_x, mut sum_y) = (0usize, 0f64, 0f64);
+            while let Some(index) = queue.pop_front() {
+                let (x, y) = (index % width, index / width);
+                area += 1;
+                sum_x += x as f64;
+                sum_y += y as f64;
+                min_x = min_x.min(x);
+                min_y = min_y.min(y);
+                max_x = max_x.max(x);
+                max_y = max_y.max(y);
+                for &(dx, dy) in neighbours {
+                    let (nx, ny) = (x as isize + dx, y as isize + dy);
+                    if nx < 0 || ny < 0 || nx >= width as isize || ny >= height as isize {
+                        continue;
+                    }
+                    let n = ny as usize * width + nx as usize;
+                    if labels[n] == 0 && foreground(pixels[n]) {
+                        labels[n] = label;
+                        queue.push_back(n);
+                    }
+                }
+            }
+            components.push(Component {
+                label,
+                area,
+                bounds: Rectangle::new(
+                    min_x as f32,
+                    min_y as f32,
+                    (max_x - min_x + 1) as f32,
+                    (max_y - min_y + 1) as f32,
+                ),
+                centroid: Vector2::new(
+                    (sum_x / area as f64) as f32 + 0.5,
+                    (sum_y / area as f64) as f32 + 0.5,
+                ),
+            });
+        }
+        ComponentLabels {
+            width,
+            height,
+            labels,
+            components,
+        }
+    }
+
+    /// Counts the pixels with each value of each channel.
+    pub fn histogram(&self) -> Histogram {
+        let mut histogram = Histogram {
+            red: [0; 256],
+            green: [0; 256],
+            blue: [0; 256],
+            alpha: [0; 256],
+            luminance: [0; 256],
+        };
+        for &c in self.rgba().iter() {
+            histogram.red[c.r as usize] += 1;
+            histogram.green[c.g as usize] += 1;
+            histogram.blue[c.b as usize] += 1;
+            histogram.alpha[c.a as usize] += 1;
+            histogram.luminance[luminance(c) as usize] += 1;
+        }
+        histogram
+    }
+
+    /// Spreads each color channel over the full range, so its values are used about equally.
+    pub fn equalize_histogram(&mut self) {
+        let histogram = self.histogram();
+        let total = histogram.total();
+        let lut = |counts: &[u32; 256]| {
+            let mut lut = [0u8; 256];
+            let mut cumulative = 0u64;
+            let first = counts.iter().find(|&&n| n > 0).copied().unwrap_or(0) as u64;
+            for (value, &n) in counts.iter().enumerate() {
+                cumulative += n as u64;
+                lut[value] = if total > first {
+                    ((cumulative.saturating_sub(first)) * 255 / (total - first)) as u8
+                } else {
+                    value as u8
+                };
+            }
+            lut
+        };
+        let (red, green, blue) = (
+            lut(&histogram.red),
+            lut(&histogram.green),
+            lut(&histogram.blue),
+        );
+        let (pixels, _, _) = match self.rgba_mut() {
+            Some(rgba) => rgba,
+            None => return,
+        };
+        for c in pixels {
+            c.r = red[c.r as usize];
+            c.g = green[c.g as usize];
+            c.b = blue[c.b as usize];
+        }
+    }
+
+    /// Maps every color channel value `v` to `lut[v]`.
+    pub fn apply_lut(&mut self, lut: &[u8; 256]) {
+        let (pixels, _, _) = match self.rgba_mut() {
+            Some(rgba) => rgba,
+            None => return,
+        };
+        for c in pixels {
+            c.r = lut[c.r as usize];
+            c.g = lut[c.g as usize];
+            c.b = lut[c.b as usize];
+        }
+    }
+
+    /// Adjusts levels like an image editor: `input_black` and below become `output_black`,
+    /// `input_white` and above become `output_white`, and `gamma` above 1 brightens the
+    /// midtones in between.
+    pub fn levels(
+        &mut self,
+        input_black: u8,
+        input_white: u8,
+        gamma: f32,
+        output_black: u8,
+        output_white: u8,
+    ) {
+        let mut lut = [0u8; 256];
+        let range = (input_white as f32 - input_black as f32).max(1.0);
+        let gamma = if gamma > 0.0 { gamma } else { 1.0 };
+        for (value, out) in lut.iter_mut().enumerate() {
+            let t = ((value as f32 - input_black as f32) / range).clamp(0.0, 1.0);
+            let t = t.powf(1.0 / gamma);
+            let v = output_black as f32 + t * (output_white as f32 - output_black as f32);
+            *out = v.round().clamp(0.0, 255.0) as u8;
+        }
+        self.apply_lut(&lut);
+    }
+
+    /// Gamma correction; values above 1 brighten, below 1 darken.
+    pub fn gamma(&mut self, gamma: f32) {
+        self.levels(0, 255, gamma, 0, 255);
+    }
+
+    /// Remaps the color channels through a smooth curve passing through `points`, given as
+    /// `(input, output)`. The curve is monotone between points and flat beyond the first
+    /// and last.
+    pub fn curves(&mut self, points: &[(u8, u8)]) {
+        self.apply_lut(&curve_lut(points));
+    }
+
+    /// Chooses a palette of at most `max_colors` colors representing the image.
+    pub fn quantize_palette(&self, max_colors: usize, method: QuantizeMethod) -> Vec<Color> {
+        let colors = color_counts(&self.rgba());
+        let palette = median_cut(colors.clone(), max_colors);
+        match method {
+            QuantizeMethod::MedianCut => palette,
+            QuantizeMethod::KMeans { iterations } => k_means(&colors, palette, iterations),
+        }
+    }
+
+    /// Replaces every pixel with the nearest color of `palette`, alpha included.
+    pub fn remap_to_palette(&mut self, palette: &[Color]) {
+        if palette.is_empty() {
+            return;
+        }
+        let mut cache: HashMap<[u8; 4], Color> = HashMap::new();
+        let (pixels, _, _) = match self.rgba_mut() {
+            Some(rgba) => rgba,
+            None => return,
+        };
+        for c in pixels {
+            *c = *cache
+                .entry(channels(*c))
+                .or_insert_with(|| palette[nearest(palette, *c)]);
+        }
+    }
+
+    /// Reduces the image to at most `max_colors` colors and returns the palette used.
+    pub fn quantize(&mut self, max_colors: usize, method: QuantizeMethod) -> Vec<Color> {
+        let palette = self.quantize_palette(max_colors, method);
+        self.remap_to_palette(&palette);
+        palette
+    }
+
+    /// Sharpens by adding back `amount` times the difference from a Gaussian blur of
+    /// standard deviation `radius`. Channels that differ from the blur by `threshold` or
+    /// less are left alone, so smooth areas don't get noisy.
+    pub fn unsharp_mask(&mut self, radius: f32, amount: f32, threshold: u8) {
+        let (pixels, width, height) = match self.rgba_mut() {
+            Some(rgba) => rgba,
+            None => return,
+        };
+        let blurred = gaussian_blur(pixels, width, height, radius);
+        for (c, blur) in pixels.iter_mut().zip(blurred) {
+            let mut out = [c.r, c.g, c.b];
+            for (v, b) in out.iter_mut().zip(blur.iter()) {
+                let difference = *v as f32 - b;
+                if difference.abs() > threshold as f32 {
+                    *v = (*v as f32 + amount * difference).round().clamp(0.0, 255.0) as u8;
+                }
+            }
+            c.r = out[0];
+            c.g = out[1];
+            c.b = out[2];
+        }
+    }
+
+    /// Sharpens edges by `amount`, 1 being a moderate amount.
+    pub fn sharpen(&mut self, amount: f32) {
+        self.unsharp_mask(1.0, amount, 0);
+    }
+}
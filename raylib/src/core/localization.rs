@@ -0,0 +1,1167 @@
+//! Translated string tables, with placeholders, plurals and the glyphs each locale needs
+//!
+//! Tables are read from Fluent (`.ftl`) or gettext (`.po`) files. Only the parts of those
+//! formats that games use are supported: messages, terms, attributes, variables and plural
+//! selectors for Fluent; entries, contexts, plural forms and the `Plural-Forms` header for
+//! gettext. Placeholders in gettext strings are written `{name}`.
+//!
+//! ```no_run
+//! use raylib::prelude::*;
+//!
+//! let (mut rl, thread) = raylib::init().size(800, 450).build();
+//! let mut l10n = Localization::new("en");
+//! l10n.load("en", "locales/en.ftl").unwrap();
+//! l10n.load("ru", "locales/ru.ftl").unwrap();
+//! l10n.set_locale("ru").unwrap();
+//!
+//! // only the glyphs the Russian strings use, plus digits
+//! let glyphs = l10n.codepoints("ru").unwrap();
+//! let font = rl.load_font_ex(&thread, "NotoSans.ttf", 32, Some(&glyphs)).unwrap();
+//! let text = l10n.format("emails", &[("count", 5.into())]);
+//! ```
+use crate::error::{error, Error};
+use std::borrow::Cow;
+use std::collections::{BTreeSet, HashMap};
+use std::fmt;
+use std::path::Path;
+
+/// Nested message references resolved before giving up, which also stops reference cycles.
+const MAX_REFERENCE_DEPTH: usize = 8;
+
+/// Deepest `Plural-Forms` expression accepted, so parsing and evaluating never overflow the stack.
+const MAX_PLURAL_DEPTH: usize = 64;
+
+/// Deepest nesting of Fluent selects accepted, so parsing and formatting never overflow the
+/// stack.
+const MAX_SELECT_DEPTH: usize = 64;
+
+/// Characters numbers are formatted with, added to every set of codepoints.
+const NUMBER_CHARS: &str = "0123456789.-";
+
+/// A value substituted into a placeholder.
+#[derive(Debug, Clone, PartialEq)]
+pub enum LocalizationArg<'a> {
+    Str(Cow<'a, str>),
+    /// Numbers also choose plural variants.
+    Num(f64),
+}
+
+impl fmt::Display for LocalizationArg<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LocalizationArg::Str(text) => f.write_str(text),
+            LocalizationArg::Num(n) if n.fract() == 0.0 && n.abs() < 1e15 => {
+                write!(f, "{}", *n as i64)
+            }
+            LocalizationArg::Num(n) => write!(f, "{}", n),
+        }
+    }
+}
+
+impl<'a> From<&'a str> for LocalizationArg<'a> {
+    fn from(text: &'a str) -> Self {
+        LocalizationArg::Str(Cow::Borrowed(text))
+    }
+}
+
+impl From<String> for LocalizationArg<'_> {
+    fn from(text: String) -> Self {
+        LocalizationArg::Str(Cow::Owned(text))
+    }
+}
+
+macro_rules! impl_num_arg {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for LocalizationArg<'_> {
+                fn from(n: $t) -> Self {
+                    LocalizationArg::Num(n as f64)
+                }
+            }
+        )*
+    };
+}
+
+impl_num_arg!(i32, i64, u32, u64, usize, f32, f64);
+
+/// CLDR plural categories, which Fluent variants are keyed by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PluralCategory {
+    Zero,
+    One,
+    Two,
+    Few,
+    Many,
+    Other,
+}
+
+impl PluralCategory {
+    /// Category of `n` in the language of `locale`, like `en`, `pt-BR` or `ru_RU`.
+    ///
+    /// Covers the cardinal rules of the most common languages; others use the English rule.
+    pub fn of(locale: &str, n: f64) -> PluralCategory {
+        use PluralCategory::*;
+        let language = locale
+            .split(['-', '_'])
+            .next()
+            .unwrap_or("")
+            .to_ascii_lowercase();
+        if n.fract() != 0.0 {
+            return Other;
+        }
+        let i = n.abs() as u64;
+        let (mod10, mod100) = (i % 10, i % 100);
+        match language.as_str() {
+            "ja" | "zh" | "ko" | "th" | "vi" | "id" | "ms" | "lo" | "my" | "km" => Other,
+            "fr" | "pt" | "hy" | "kab" => match i {
+                0 | 1 => One,
+                _ => Other,
+            },
+            "ru" | "uk" | "be" | "sr" | "hr" | "bs" => match (mod10, mod100) {
+                (1, m) if m != 11 => One,
+                (2..=4, m) if !(12..=14).contains(&m) => Few,
+                _ => Many,
+            },
+            "pl" => match (i, mod10, mod100) {
+                (1, _, _) => One,
+                (_, 2..=4, m) if !(12..=14).contains(&m) => Few,
+                _ => Many,
+            },
+            "cs" | "sk" => match i {
+                1 => One,
+                2..=4 => Few,
+                _ => Other,
+            },
+            "ar" => match (i, mod100) {
+                (0, _) => Zero,
+                (1, _) => One,
+                (2, _) => Two,
+                (_, 3..=10) => Few,
+                (_, 11..=99) => Many,
+                _ => Other,
+            },
+            "he" | "iw" => match i {
+                1 => One,
+                2 => Two,
+                _ => Other,
+            },
+            "lt" => match (mod10, mod100) {
+                (1, m) if !(11..=19).contains(&m) => One,
+                (2..=9, m) if !(11..=19).contains(&m) => Few,
+                _ => Other,
+            },
+            _ => match i {
+                1 => One,
+                _ => Other,
+            },
+        }
+    }
+
+    /// The name Fluent uses for the category.
+    pub fn name(self) -> &'static str {
+        match self {
+            PluralCategory::Zero => "zero",
+            PluralCategory::One => "one",
+            PluralCategory::Two => "two",
+            PluralCategory::Few => "few",
+            PluralCategory::Many => "many",
+            PluralCategory::Other => "other",
+        }
+    }
+}
+
+/// A piece of a message.
+#[derive(Debug, Clone, PartialEq)]
+enum Element {
+    Text(String),
+    /// `{ $name }` in Fluent, `{name}` in gettext.
+    Variable(String),
+    /// `{ other-message }` or `{ -term }`.
+    Reference(String),
+    /// `{ $name -> [key] ... *[other] ... }`.
+    Select {
+        selector: String,
+        variants: Vec<(VariantKey, Vec<Element>)>,
+        default: usize,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum VariantKey {
+    Number(f64),
+    Name(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Message {
+    Pattern(Vec<Element>),
+    /// gettext plural forms, chosen by the `Plural-Forms` formula applied to `count`.
+    Plural(Vec<Vec<Element>>),
+}
+
+/// The strings of one locale.
+#[derive(Debug, Clone, Default)]
+struct StringTable {
+    messages: HashMap<String, Message>,
+    /// Plural formula of gettext tables.
+    plural_forms: Option<PluralExpr>,
+}
+
+/// String tables for any number of locales, one of which is current.
+///
+/// Lookups fall back to the fallback locale, the first one created by default, and then to
+/// the key itself, so a missing translation shows up as its key instead of nothing.
+#[derive(Debug, Clone)]
+pub struct Localization {
+    tables: HashMap<String, StringTable>,
+    locale: String,
+    fallback: Option<String>,
+}
+
+impl Localization {
+    /// Creates an empty localization whose current and fallback locale is `locale`.
+    pub fn new(locale: &str) -> Localization {
+        Localization {
+            tables: HashMap::new(),
+            locale: locale.to_owned(),
+            fallback: Some(locale.to_owned()),
+        }
+    }
+
+    /// Loads the `.ftl` or `.po` file at `filename` into the table of `locale`, adding to or
+    /// replacing the strings already there.
+    pub fn load(&mut self, locale: &str, filename: impl AsRef<Path>) -> Result<(), Error> {
+        let filename = filename.as_ref();
+        let source = std::fs::read_to_string(filename).map_err(|_| {
+            error!(
+                "Error loading string table. Check if the file exists",
+                filename
+            )
+        })?;
+        match filename.extension().and_then(|e| e.to_str()) {
+            Some("po") | Some("pot") => self.add_po(locale, &source),
+            Some("ftl") => self.add_ftl(locale, &source),
+            _ => Err(error!(
+                "Error loading string table: only .ftl and .po files are supported.",
+                filename
+            )),
+        }
+    }
+
+    /// Adds the messages and terms of a Fluent resource to the table of `locale`.
+    pub fn add_ftl(&mut self, locale: &str, source: &str) -> Result<(), Error> {
+        let messages = parse_ftl(source)?;
+        let table = self.tables.entry(locale.to_owned()).or_default();
+        table.messages.extend(messages);
+        Ok(())
+    }
+
+    /// Adds the entries of a gettext catalog to the table of `locale`. Entries are looked up
+    /// by `msgid`, or with [`Localization::format_in_context`] when they have a `msgctxt`.
+    pub fn add_po(&mut self, locale: &str, source: &str) -> Result<(), Error> {
+        let parsed = parse_po(source)?;
+        let table = self.tables.entry(locale.to_owned()).or_default();
+        table.messages.extend(parsed.messages);
+        if parsed.plural_forms.is_some() {
+            table.plural_forms = parsed.plural_forms;
+        }
+        Ok(())
+    }
+
+    /// Makes `locale` current. It needs a table.
+    pub fn set_locale(&mut self, locale: &str) -> Result<(), Error> {
+        if !self.tables.contains_key(locale) {
+            return Err(error!(Cow::Owned(format!(
+                "No string table for locale \"{}\".",
+                locale
+            ))));
+        }
+        self.locale = locale.to_owned();
+        Ok(())
+    }
+
+    pub fn locale(&self) -> &str {
+        &self.locale
+    }
+
+    /// Locale used for keys the current locale lacks, if any.
+    pub fn set_fallback(&mut self, locale: Option<&str>) {
+        self.fallback = locale.map(str::to_owned);
+    }
+
+    pub fn fallback(&self) -> Option<&str> {
+        self.fallback.as_deref()
+    }
+
+    /// Locales that have a table, sorted.
+    pub fn locales(&self) -> Vec<&str> {
+        let mut locales: Vec<&str> = self.tables.keys().map(String::as_str).collect();
+        locales.sort_unstable();
+        locales
+    }
+
+    /// Whether the current or fallback locale has `key`.
+    pub fn contains(&self, key: &str) -> bool {
+        self.find(key).is_some()
+    }
+
+    /// The string for `key` without arguments.
+    pub fn get(&self, key: &str) -> String {
+        self.format(key, &[])
+    }
+
+    /// The string for `key` with its placeholders replaced by `args`, or `key` itself if no
+    /// table has it. Numeric arguments choose plural variants.
+    pub fn format(&self, key: &str, args: &[(&str, LocalizationArg)]) -> String {
+        self.try_format(key, args).unwrap_or_else(|| key.to_owned())
+    }
+
+    /// Like [`Localization::format`], but `None` if no table has `key`.
+    pub fn try_format(&self, key: &str, args: &[(&str, LocalizationArg)]) -> Option<String> {
+        let (locale, table, message) = self.find(key)?;
+        let mut out = String::new();
+        let formatter = Formatter {
+            locale,
+            table,
+            args,
+        };
+        formatter.message(message, &mut out, 0);
+        Some(out)
+    }
+
+    /// Formats the gettext entry with `msgctxt` `context` and `msgid` `key`.
+    pub fn format_in_context(
+        &self,
+        context: &str,
+        key: &str,
+        args: &[(&str, LocalizationArg)],
+    ) -> String {
+        self.try_format(&context_key(context, key), args)
+            .unwrap_or_else(|| key.to_owned())
+    }
+
+    /// Every character the strings of `locale` can produce, sorted and without duplicates,
+    /// ready for [`RaylibHandle::load_font_ex`](crate::core::RaylibHandle::load_font_ex).
+    ///
+    /// Digits, `.` and `-` are included for numeric arguments. Text arguments come from
+    /// outside the tables, so add their characters yourself.
+    pub fn codepoints(&self, locale: &str) -> Option<String> {
+        let table = self.tables.get(locale)?;
+        let mut chars: BTreeSet<char> = NUMBER_CHARS.chars().collect();
+        for message in table.messages.values() {
+            match message {
+                Message::Pattern(pattern) => collect_chars(pattern, &mut chars),
+                Message::Plural(forms) => {
+                    for pattern in forms {
+                        collect_chars(pattern, &mut chars);
+                    }
+                }
+            }
+        }
+        Some(chars.into_iter().filter(|c| !c.is_control()).collect())
+    }
+
+    fn find(&self, key: &str) -> Option<(&str, &StringTable, &Message)> {
+        std::iter::once(self.locale.as_str())
+            .chain(self.fallback.as_deref())
+            .find_map(|locale| {
+                let table = self.tables.get(locale)?;
+                Some((locale, table, table.messages.get(key)?))
+            })
+    }
+}
+
+fn context_key(context: &str, key: &str) -> String {
+    // the separator gettext itself uses
+    format!("{}\u{4}{}", context, key)
+}
+
+fn collect_chars(pattern: &[Element], chars: &mut BTreeSet<char>) {
+    for element in pattern {
+        match element {
+            Element::Text(text) => chars.extend(text.chars()),
+            Element::Select { variants, .. } => {
+                for (_, pattern) in variants {
+                    collect_chars(pattern, chars);
+                }
+            }
+            Element::Variable(_) | Element::Reference(_) => {}
+        }
+    }
+}
+
+struct Formatter<'a> {
+    locale: &'a str,
+    table: &'a StringTable,
+    args: &'a [(&'a str, LocalizationArg<'a>)],
+}
+
+impl Formatter<'_> {
+    fn arg(&self, name: &str) -> Option<&LocalizationArg<'_>> {
+        self.args
+            .iter()
+            .find(|(key, _)| *key == name)
+            .map(|(_, value)| value)
+    }
+
+    fn message(&self, message: &Message, out: &mut String, depth: usize) {
+        match message {
+            Message::Pattern(pattern) => self.pattern(pattern, out, depth),
+            Message::Plural(forms) => {
+                let n = match self.arg("count").or_else(|| self.arg("n")) {
+                    Some(LocalizationArg::Num(n)) => *n,
+                    _ => 1.0,
+                };
+                let index = match &self.table.plural_forms {
+                    Some(expr) => expr.eval(n.abs() as u64) as usize,
+                    None => (n != 1.0) as usize,
+                };
+                if let Some(pattern) = forms.get(index).or_else(|| forms.last()) {
+                    self.pattern(pattern, out, depth);
+                }
+            }
+        }
+    }
+
+    fn pattern(&self, pattern: &[Element], out: &mut String, depth: usize) {
+        for element in pattern {
+            match element {
+                Element::Text(text) => out.push_str(text),
+                Element::Variable(name) => match self.arg(name) {
+                    Some(value) => out.push_str(&value.to_string()),
+                    // leave missing arguments visible
+                    None => {
+                        out.push('{');
+                        out.push_str(name);
+                        out.push('}');
+                    }
+                },
+                Element::Reference(key) => match self.table.messages.get(key) {
+                    Some(message) if depth < MAX_REFERENCE_DEPTH => {
+                        self.message(message, out, depth + 1)
+                    }
+                    _ => out.push_str(key),
+                },
+                Element::Select {
+                    selector,
+                    variants,
+                    default,
+                } => {
+                    let chosen = self.select(selector, variants).unwrap_or(*default);
+                    self.pattern(&variants[chosen].1, out, depth);
+                }
+            }
+        }
+    }
+
+    /// Exact numeric keys win over plural categories, which win over exact string keys.
+    fn select(&self, selector: &str, variants: &[(VariantKey, Vec<Element>)]) -> Option<usize> {
+        match self.arg(selector)? {
+            LocalizationArg::Num(n) => {
+                let exact = variants
+                    .iter()
+                    .position(|(key, _)| *key == VariantKey::Number(*n));
+                exact.or_else(|| {
+                    let category = PluralCategory::of(self.locale, *n).name();
+                    variants.iter().position(
+                        |(key, _)| matches!(key, VariantKey::Name(name) if name == category),
+                    )
+                })
+            }
+            LocalizationArg::Str(value) => variants
+                .iter()
+                .position(|(key, _)| matches!(key, VariantKey::Name(name) if name == value)),
+        }
+    }
+}
+
+fn ftl_error(line: usize, message: &str) -> Error {
+    error!(Cow::Owned(format!(
+        "Error parsing Fluent resource, line {}: {}",
+        line, message
+    )))
+}
+
+/// Parses the messages, terms and attributes of a Fluent resource. Attributes are keyed
+/// `message.attribute`, terms keep their leading `-`.
+fn parse_ftl(source: &str) -> Result<Vec<(String, Message)>, Error> {
+    let mut messages = Vec::new();
+    // key, first line number and lines of the entry being read
+    let mut entry: Option<(String, usize, Vec<&str>)> = None;
+    let finish = |entry: Option<(String, usize, Vec<&str>)>,
+                  messages: &mut Vec<(String, Message)>|
+     -> Result<(), Error> {
+        if let Some((key, line, lines)) = entry {
+            // attributes are indented lines starting with `.name =`
+            let mut value_lines = Vec::new();
+            let mut attributes: Vec<(String, Vec<&str>)> = Vec::new();
+            for text in lines {
+                let trimmed = text.trim_start();
+                let attribute = trimmed.strip_prefix('.').and_then(|rest| {
+                    let (name, value) = rest.split_once('=')?;
+                    is_identifier(name.trim()).then(|| (name.trim().to_owned(), value))
+                });
+                match (attribute, attributes.last_mut()) {
+                    (Some((name, value)), _) => attributes.push((name, vec![value])),
+                    (None, Some((_, attribute_lines))) => attribute_lines.push(text),
+                    (None, None) => value_lines.push(text),
+                }
+            }
+            let value = join_lines(&value_lines);
+            if !value.trim().is_empty() {
+                let pattern = PatternParser::new(&value, line).parse()?;
+                messages.push((key.clone(), Message::Pattern(pattern)));
+            }
+            for (name, lines) in attributes {
+                let pattern = PatternParser::new(&join_lines(&lines), line).parse()?;
+                messages.push((format!("{}.{}", key, name), Message::Pattern(pattern)));
+            }
+        }
+        Ok(())
+    };
+
+    for (number, line) in source.lines().enumerate() {
+        let number = number + 1;
+        if line.starts_with('#') {
+            finish(entry.take(), &mut messages)?;
+            continue;
+        }
+        // the closing brace of a multiline select may be unindented
+        if line.starts_with(|c: char| c.is_whitespace() || c == '}') || line.is_empty() {
+            if let Some((_, _, lines)) = entry.as_mut() {
+                lines.push(line);
+            }
+            continue;
+        }
+        // an unindented line starts a new entry
+        finish(entry.take(), &mut messages)?;
+        let (key, value) = line
+            .split_once('=')
+            .ok_or_else(|| ftl_error(number, "expected `key = value`"))?;
+        let key = key.trim();
+        if !is_identifier(key.strip_prefix('-').unwrap_or(key)) {
+            return Err(ftl_error(number, "invalid message identifier"));
+        }
+        entry = Some((key.to_owned(), number, vec![value]));
+    }
+    finish(entry.take(), &mut messages)?;
+    Ok(messages)
+}
+
+fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    matches!(chars.next(), Some(c) if c.is_ascii_alphabetic())
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+/// Joins the lines of a multiline Fluent value, removing their common indentation and the
+/// blank lines around them.
+fn join_lines(lines: &[&str]) -> String {
+    let first = lines.first().map_or("", |line| line.trim());
+    let rest = &lines[1.min(lines.len())..];
+    let indent = rest
+        .iter()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.len() - line.trim_start().len())
+        .min()
+        .unwrap_or(0);
+    let mut joined: Vec<&str> = Vec::new();
+    if !first.is_empty() {
+        joined.push(first);
+    }
+    for line in rest {
+        joined.push(line.get(indent..).unwrap_or("").trim_end());
+    }
+    while joined.last().is_some_and(|line| line.is_empty()) {
+        joined.pop();
+    }
+    while joined.first().is_some_and(|line| line.is_empty()) {
+        joined.remove(0);
+    }
+    joined.join("\n")
+}
+
+/// Parses the text and placeables of a Fluent pattern.
+struct PatternParser<'a> {
+    source: &'a str,
+    pos: usize,
+    line: usize,
+    /// Selects open around the current position, up to [`MAX_SELECT_DEPTH`].
+    depth: usize,
+}
+
+impl<'a> PatternParser<'a> {
+    fn new(source: &'a str, line: usize) -> Self {
+        PatternParser {
+            source,
+            pos: 0,
+            line,
+            depth: 0,
+        }
+    }
+
+    fn parse(mut self) -> Result<Vec<Element>, Error> {
+        let pattern = self.pattern(false)?;
+        if self.pos < self.source.len() {
+            return Err(ftl_error(self.line, "unexpected `}`"));
+        }
+        Ok(pattern)
+    }
+
+    fn rest(&self) -> &'a str {
+        &self.source[self.pos..]
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = self.rest();
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+
+    /// Reads until the end, a `}` closing a placeable or, inside a select, the next variant.
+    fn pattern(&mut self, in_variant: bool) -> Result<Vec<Element>, Error> {
+        let mut elements = Vec::new();
+        let mut text = String::new();
+        while let Some(c) = self.rest().chars().next() {
+            match c {
+                '{' => {
+                    self.pos += 1;
+                    if !text.is_empty() {
+                        elements.push(Element::Text(std::mem::take(&mut text)));
+                    }
+                    elements.push(self.placeable()?);
+                }
+                '}' => break,
+                '\n' if in_variant => {
+                    let next = self.rest()[1..].trim_start();
+                    if next.starts_with('[') || next.starts_with("*[") || next.starts_with('}') {
+                        break;
+                    }
+                    text.push(c);
+                    self.pos += 1;
+                }
+                _ => {
+                    text.push(c);
+                    self.pos += c.len_utf8();
+                }
+            }
+        }
+        if !text.is_empty() {
+            elements.push(Element::Text(text));
+        }
+        Ok(elements)
+    }
+
+    /// Reads a placeable after its `{`, up to and including its `}`.
+    fn placeable(&mut self) -> Result<Element, Error> {
+        self.skip_whitespace();
+        let element = if let Some(rest) = self.rest().strip_prefix('"') {
+            let end = rest
+                .find('"')
+                .ok_or_else(|| ftl_error(self.line, "unterminated string literal"))?;
+            self.pos += end + 2;
+            Element::Text(rest[..end].to_owned())
+        } else {
+            let variable = self.rest().starts_with('$');
+            if variable {
+                self.pos += 1;
+            }
+            let name_len = self
+                .rest()
+                .find(|c: char| !(c.is_ascii_alphanumeric() || c == '-' || c == '_'))
+                .unwrap_or(self.rest().len());
+            let name = self.rest()[..name_len].to_owned();
+            if name.is_empty() {
+                return Err(ftl_error(
+                    self.line,
+                    "expected a variable or message in `{ }`",
+                ));
+            }
+            self.pos += name_len;
+            self.skip_whitespace();
+            if variable && self.rest().starts_with("->") {
+                self.pos += 2;
+                self.select(name)?
+            } else if variable {
+                Element::Variable(name)
+            } else {
+                Element::Reference(name)
+            }
+        };
+        self.skip_whitespace();
+        if !self.rest().starts_with('}') {
+            return Err(ftl_error(self.line, "expected `}`"));
+        }
+        self.pos += 1;
+        Ok(element)
+    }
+
+    /// Reads the variants of a select expression, up to its closing `}`. A failed parse is
+    /// abandoned, so only successful selects need to come back up.
+    fn select(&mut self, selector: String) -> Result<Element, Error> {
+        self.depth += 1;
+        if self.depth > MAX_SELECT_DEPTH {
+            return Err(ftl_error(self.line, "selects are nested too deeply"));
+        }
+        let mut variants = Vec::new();
+        let mut default = None;
+        loop {
+            self.skip_whitespace();
+            if self.rest().starts_with('}') || self.rest().is_empty() {
+                break;
+            }
+            if self.rest().starts_with('*') {
+                default = Some(variants.len());
+                self.pos += 1;
+            }
+            let rest = self
+                .rest()
+                .strip_prefix('[')
+                .ok_or_else(|| ftl_error(self.line, "expected `[variant]`"))?;
+            let end = rest
+                .find(']')
+                .ok_or_else(|| ftl_error(self.line, "expected `]`"))?;
+            let key = rest[..end].trim();
+            let key = match key.parse::<f64>() {
+                Ok(n) => VariantKey::Number(n),
+                Err(_) => VariantKey::Name(key.to_owned()),
+            };
+            self.pos += end + 2;
+            self.skip_inline_whitespace();
+            variants.push((key, self.pattern(true)?));
+        }
+        let default =
+            default.ok_or_else(|| ftl_error(self.line, "a select needs a `*[default]` variant"))?;
+        self.depth -= 1;
+        Ok(Element::Select {
+            selector,
+            variants,
+            default,
+        })
+    }
+
+    fn skip_inline_whitespace(&mut self) {
+        let rest = self.rest();
+        self.pos += rest.len() - rest.trim_start_matches([' ', '\t']).len();
+    }
+}
+
+fn po_error(line: usize, message: &str) -> Error {
+    error!(Cow::Owned(format!(
+        "Error parsing gettext catalog, line {}: {}",
+        line, message
+    )))
+}
+
+/// Parses the translated entries of a gettext catalog and its plural formula. Untranslated
+/// and obsolete entries are skipped.
+fn parse_po(source: &str) -> Result<StringTable, Error> {
+    #[derive(Default)]
+    struct Entry {
+        context: Option<String>,
+        id: Option<String>,
+        plural: bool,
+        strings: Vec<(usize, String)>,
+    }
+    // the field each continuation string appends to
+    enum Field {
+        Context,
+        Id,
+        IdPlural,
+        Str(usize),
+    }
+
+    let mut messages = Vec::new();
+    let mut plural_forms = None;
+    let mut entry = Entry::default();
+    let mut field = None;
+    let finish = |entry: Entry,
+                  messages: &mut Vec<(String, Message)>,
+                  plural_forms: &mut Option<PluralExpr>,
+                  line: usize|
+     -> Result<(), Error> {
+        let id = match entry.id {
+            Some(id) => id,
+            None => return Ok(()),
+        };
+        let mut strings = entry.strings;
+        strings.sort_by_key(|(index, _)| *index);
+        if id.is_empty() && entry.context.is_none() {
+            // the header
+            let header = strings.first().map_or("", |(_, text)| text.as_str());
+            for header_line in header.lines() {
+                if let Some(value) = header_line.strip_prefix("Plural-Forms:") {
+                    let formula = value
+                        .split(';')
+                        .find_map(|part| part.trim().strip_prefix("plural="))
+                        .ok_or_else(|| po_error(line, "Plural-Forms without `plural=`"))?;
+                    *plural_forms = Some(PluralExpr::parse(formula).map_err(|message| {
+                        po_error(line, &format!("invalid plural formula: {}", message))
+                    })?);
+                }
+            }
+            return Ok(());
+        }
+        if strings.iter().all(|(_, text)| text.is_empty()) {
+            return Ok(());
+        }
+        let key = match &entry.context {
+            Some(context) => context_key(context, &id),
+            None => id,
+        };
+        let message = if entry.plural {
+            Message::Plural(strings.iter().map(|(_, text)| po_pattern(text)).collect())
+        } else {
+            Message::Pattern(po_pattern(&strings[0].1))
+        };
+        messages.push((key, message));
+        Ok(())
+    };
+
+    let mut last_line = 0;
+    for (number, line) in source.lines().enumerate() {
+        let number = number + 1;
+        last_line = number;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        if line.starts_with('"') {
+            let text = po_string(line).ok_or_else(|| po_error(number, "invalid string"))?;
+            match field {
+                Some(Field::Context) => entry
+                    .context
+                    .get_or_insert_with(String::new)
+                    .push_str(&text),
+                Some(Field::Id) => entry.id.get_or_insert_with(String::new).push_str(&text),
+                Some(Field::IdPlural) => {}
+                Some(Field::Str(index)) => {
+                    if let Some((_, string)) = entry.strings.iter_mut().find(|(i, _)| *i == index) {
+                        string.push_str(&text);
+                    }
+                }
+                None => return Err(po_error(number, "string outside of an entry")),
+            }
+            continue;
+        }
+        let (keyword, rest) = line.split_at(line.find(char::is_whitespace).unwrap_or(line.len()));
+        let text = po_string(rest.trim()).ok_or_else(|| po_error(number, "invalid string"))?;
+        match keyword {
+            "msgctxt" => {
+                finish(
+                    std::mem::take(&mut entry),
+                    &mut messages,
+                    &mut plural_forms,
+                    number,
+                )?;
+                entry.context = Some(text);
+                field = Some(Field::Context);
+            }
+            "msgid" => {
+                if entry.id.is_some() {
+                    finish(
+                        std::mem::take(&mut entry),
+                        &mut messages,
+                        &mut plural_forms,
+                        number,
+                    )?;
+                }
+                entry.id = Some(text);
+                field = Some(Field::Id);
+            }
+            "msgid_plural" => {
+                entry.plural = true;
+                field = Some(Field::IdPlural);
+            }
+            "msgstr" => {
+                entry.strings.push((0, text));
+                field = Some(Field::Str(0));
+            }
+            _ => {
+                let index = keyword
+                    .strip_prefix("msgstr[")
+                    .and_then(|rest| rest.strip_suffix(']'))
+                    .and_then(|index| index.parse().ok())
+                    .ok_or_else(|| po_error(number, "unknown keyword"))?;
+                entry.strings.push((index, text));
+                field = Some(Field::Str(index));
+            }
+        }
+    }
+    finish(entry, &mut messages, &mut plural_forms, last_line)?;
+    Ok(StringTable {
+        messages: messages.into_iter().collect(),
+        plural_forms,
+    })
+}
+
+/// Unquotes and unescapes a gettext string.
+fn po_string(quoted: &str) -> Option<String> {
+    let inner = quoted.strip_prefix('"')?.strip_suffix('"')?;
+    let mut out = String::with_capacity(inner.len());
+    let mut chars = inner.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next()? {
+            'n' => out.push('\n'),
+            't' => out.push('\t'),
+            'r' => out.push('\r'),
+            other => out.push(other),
+        }
+    }
+    Some(out)
+}
+
+/// Splits a gettext string into text and `{name}` placeholders. `{{` and `}}` are literal
+/// braces.
+fn po_pattern(text: &str) -> Vec<Element> {
+    let mut elements = Vec::new();
+    let mut literal = String::new();
+    let mut rest = text;
+    while let Some(c) = rest.chars().next() {
+        if rest.starts_with("{{") || rest.starts_with("}}") {
+            literal.push(c);
+            rest = &rest[2..];
+            continue;
+        }
+        if c == '{' {
+            if let Some(end) = rest.find('}') {
+                let name = rest[1..end].trim();
+                if !name.is_empty() && !name.contains('{') {
+                    if !literal.is_empty() {
+                        elements.push(Element::Text(std::mem::take(&mut literal)));
+                    }
+                    elements.push(Element::Variable(name.to_owned()));
+                    rest = &rest[end + 1..];
+                    continue;
+                }
+            }
+        }
+        literal.push(c);
+        rest = &rest[c.len_utf8()..];
+    }
+    if !literal.is_empty() {
+        elements.push(Element::Text(literal));
+    }
+    elements
+}
+
+/// The C expression of a `Plural-Forms` header, like `(n != 1)` or
+/// `(n%10==1 && n%100!=11 ? 0 : n%10>=2 && n%10<=4 && (n%100<10 || n%100>=20) ? 1 : 2)`.
+#[derive(Debug, Clone, PartialEq)]
+enum PluralExpr {
+    N,
+    Number(u64),
+    Not(Box<PluralExpr>),
+    Binary(BinaryOp, Box<PluralExpr>, Box<PluralExpr>),
+    Ternary(Box<PluralExpr>, Box<PluralExpr>, Box<PluralExpr>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum BinaryOp {
+    Or,
+    And,
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+}
+
+impl PluralExpr {
+    fn parse(source: &str) -> Result<PluralExpr, &'static str> {
+        let tokens = tokenize(source)?;
+        let mut parser = ExprParser {
+            tokens,
+            pos: 0,
+            depth: 0,
+        };
+        let expr = parser.ternary()?;
+        if parser.pos != parser.tokens.len() {
+            return Err("unexpected token");
+        }
+        Ok(expr)
+    }
+
+    fn eval(&self, n: u64) -> u64 {
+        match self {
+            PluralExpr::N => n,
+            PluralExpr::Number(value) => *value,
+            PluralExpr::Not(expr) => (expr.eval(n) == 0) as u64,
+            PluralExpr::Ternary(condition, then, otherwise) => {
+                if condition.eval(n) != 0 {
+                    then.eval(n)
+                } else {
+                    otherwise.eval(n)
+                }
+            }
+            PluralExpr::Binary(op, left, right) => {
+                let (a, b) = (left.eval(n), right.eval(n));
+                match op {
+                    BinaryOp::Or => (a != 0 || b != 0) as u64,
+                    BinaryOp::And => (a != 0 && b != 0) as u64,
+                    BinaryOp::Eq => (a == b) as u64,
+                    BinaryOp::Ne => (a != b) as u64,
+                    BinaryOp::Lt => (a < b) as u64,
+                    BinaryOp::Le => (a <= b) as u64,
+                    BinaryOp::Gt => (a > b) as u64,
+                    BinaryOp::Ge => (a >= b) as u64,
+                    BinaryOp::Add => a.wrapping_add(b),
+                    BinaryOp::Sub => a.wrapping_sub(b),
+                    BinaryOp::Mul => a.wrapping_mul(b),
+                    BinaryOp::Div => a.checked_div(b).unwrap_or(0),
+                    BinaryOp::Rem => a.checked_rem(b).unwrap_or(0),
+                }
+            }
+        }
+    }
+}
+
+fn tokenize(source: &str) -> Result<Vec<String>, &'static str> {
+    let mut tokens = Vec::new();
+    let mut chars = source.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+        } else if c.is_ascii_digit() {
+            let mut number = String::new();
+            while let Some(&d) = chars.peek().filter(|d| d.is_ascii_digit()) {
+                number.push(d);
+                chars.next();
+            }
+            tokens.push(number);
+        } else {
+            chars.next();
+            let two = chars.peek().map(|&next| format!("{}{}", c, next));
+            match two.as_deref() {
+                Some("==") | Some("!=") | Some("<=") | Some(">=") | Some("&&") | Some("||") => {
+                    chars.next();
+                    tokens.push(two.unwrap());
+                }
+                _ if "n?:!<>+-*/%()".contains(c) => tokens.push(c.to_string()),
+                _ => return Err("unexpected character"),
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+/// Recursive descent over the C operator precedence levels the formulas use.
+struct ExprParser {
+    tokens: Vec<String>,
+    pos: usize,
+    /// Depth of the expression being built, up to [`MAX_PLURAL_DEPTH`].
+    depth: usize,
+}
+
+impl ExprParser {
+    fn peek(&self) -> Option<&str> {
+        self.tokens.get(self.pos).map(String::as_str)
+    }
+
+    fn expect(&mut self, token: &str) -> Result<(), &'static str> {
+        if self.peek() == Some(token) {
+            self.pos += 1;
+            Ok(())
+        } else {
+            Err("unbalanced expression")
+        }
+    }
+
+    /// Goes one level deeper, failing past [`MAX_PLURAL_DEPTH`]. A failed parse is abandoned,
+    /// so only successful levels need to come back up.
+    fn enter(&mut self) -> Result<(), &'static str> {
+        self.depth += 1;
+        if self.depth > MAX_PLURAL_DEPTH {
+            return Err("expression is nested too deeply");
+        }
+        Ok(())
+    }
+
+    fn ternary(&mut self) -> Result<PluralExpr, &'static str> {
+        self.enter()?;
+        let condition = self.binary(0)?;
+        if self.peek() != Some("?") {
+            self.depth -= 1;
+            return Ok(condition);
+        }
+        self.pos += 1;
+        let then = self.ternary()?;
+        self.expect(":")?;
+        let otherwise = self.ternary()?;
+        self.depth -= 1;
+        Ok(PluralExpr::Ternary(
+            Box::new(condition),
+            Box::new(then),
+            Box::new(otherwise),
+        ))
+    }
+
+    fn binary(&mut self, level: usize) -> Result<PluralExpr, &'static str> {
+        const LEVELS: [&[(&str, BinaryOp)]; 6] = [
+            &[("||", BinaryOp::Or)],
+            &[("&&", BinaryOp::And)],
+            &[("==", BinaryOp::Eq), ("!=", BinaryOp::Ne)],
+            &[
+                ("<", BinaryOp::Lt),
+                ("<=", BinaryOp::Le),
+                (">", BinaryOp::Gt),
+                (">=", BinaryOp::Ge),
+            ],
+            &[("+", BinaryOp::Add), ("-", BinaryOp::Sub)],
+            &[
+                ("*", BinaryOp::Mul),
+                ("/", BinaryOp::Div),
+                ("%", BinaryOp::Rem),
+            ],
+        ];
+        if level == LEVELS.len() {
+            return self.unary();
+        }
+        let mut left = self.binary(level + 1)?;
+        // every operator in a chain nests the expression so far one level deeper
+        let depth = self.depth;
+        while let Some(&(_, op)) = LEVELS[level]
+            .iter()
+            .find(|(token, _)| self.peek() == Some(token))
+        {
+            self.pos += 1;
+            self.enter()?;
+            let right = self.binary(level + 1)?;
+            left = PluralExpr::Binary(op, Box::new(left), Box::new(right));
+        }
+        self.depth = depth;
+        Ok(left)
+    }
+
+    fn unary(&mut self) -> Result<PluralExpr, &'static str> {
+        let token = self.peek().ok_or("unexpected end")?.to_owned();
+        self.pos += 1;
+        match token.as_str() {
+            "!" => {
+                self.enter()?;
+                let expr = self.unary()?;
+                self.depth -= 1;
+                Ok(PluralExpr::Not(Box::new(expr)))
+            }
+            "n" => Ok(PluralExpr::N),
+            "(" => {
+                let expr = self.ternary()?;
+                self.expect(")")?;
+                Ok(expr)
+            }
+            number => number
+                .parse()
+                .map(PluralExpr::Number)
+                .map_err(|_| "unexpected token"),
+        }
+    }
+}
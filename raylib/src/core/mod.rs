@@ -1,24 +1,45 @@
 #[macro_use]
 mod macros;
 
+pub mod animimage;
+pub mod assets;
 pub mod audio;
 pub mod automation;
+pub mod bmfont;
 pub mod callbacks;
 pub mod camera;
+pub mod canvas;
 pub mod collision;
 pub mod color;
+pub mod compositing;
+pub mod cubemap;
 pub mod data;
 pub mod drawing;
+pub mod drawlist;
+pub mod dynamicfont;
 pub mod error;
 pub mod file;
+pub mod fontstack;
+pub mod framebuffer;
+pub mod imagediff;
+pub mod imageops;
 
 pub mod input;
+pub mod localization;
 pub mod logging;
 pub mod math;
 pub mod misc;
 pub mod models;
+pub mod noise;
+pub mod pixels;
+pub mod postprocess;
+pub mod richtext;
+pub mod rlgl;
+pub mod sdffont;
 pub mod shaders;
+pub mod terrain;
 pub mod text;
+pub mod textlayout;
 pub mod texture;
 pub mod vr;
 pub mod window;
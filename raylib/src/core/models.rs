@@ -1,10 +1,13 @@
 //! 3D Model, Mesh, and Animation
 
-use crate::core::math::{BoundingBox, Vector3};
+use crate::core::color::Color;
+use crate::core::math::{BoundingBox, Vector2, Vector3, Vector4};
 use crate::core::texture::Image;
 use crate::core::{RaylibHandle, RaylibThread};
 use crate::error::{error, Error};
 use crate::{consts, ffi};
+use std::borrow::Cow;
+use std::convert::TryFrom;
 use std::ffi::CString;
 use std::os::raw::c_void;
 
@@ -260,8 +263,11 @@ impl Mesh {
     }
 }
 pub trait RaylibMesh: AsRef<ffi::Mesh> + AsMut<ffi::Mesh> {
-    unsafe fn upload(&mut self, dynamic: bool) {
-        ffi::UploadMesh(self.as_mut(), dynamic);
+    /// Uploads the mesh data to the GPU. Meshes that will change through
+    /// [`RaylibMesh::update_positions`] and friends should be `dynamic`.
+    /// Uploading a mesh again does nothing.
+    fn upload(&mut self, _: &RaylibThread, dynamic: bool) {
+        unsafe { ffi::UploadMesh(self.as_mut(), dynamic) }
     }
     unsafe fn update_buffer<A>(&mut self, index: i32, data: &[u8], offset: i32) {
         ffi::UpdateMeshBuffer(
@@ -353,6 +359,76 @@ pub trait RaylibMesh: AsRef<ffi::Mesh> + AsMut<ffi::Mesh> {
         }
     }
 
+    /// Replaces the positions of the vertices from `start` on, in the mesh and on the GPU.
+    fn update_positions(
+        &mut self,
+        _: &RaylibThread,
+        start: usize,
+        positions: &[Vector3],
+    ) -> Result<(), Error> {
+        let cpu = self.as_ref().vertices as *mut Vector3;
+        update_attribute(
+            self.as_ref(),
+            ffi::RL_DEFAULT_SHADER_ATTRIB_LOCATION_POSITION,
+            cpu,
+            start,
+            positions,
+        )
+    }
+
+    /// Replaces the normals of the vertices from `start` on, in the mesh and on the GPU.
+    fn update_normals(
+        &mut self,
+        _: &RaylibThread,
+        start: usize,
+        normals: &[Vector3],
+    ) -> Result<(), Error> {
+        let cpu = self.as_ref().normals as *mut Vector3;
+        update_attribute(
+            self.as_ref(),
+            ffi::RL_DEFAULT_SHADER_ATTRIB_LOCATION_NORMAL,
+            cpu,
+            start,
+            normals,
+        )
+    }
+
+    /// Replaces the texture coordinates of the vertices from `start` on, in the mesh and on
+    /// the GPU.
+    fn update_texcoords(
+        &mut self,
+        _: &RaylibThread,
+        start: usize,
+        texcoords: &[Vector2],
+    ) -> Result<(), Error> {
+        let cpu = self.as_ref().texcoords as *mut Vector2;
+        update_attribute(
+            self.as_ref(),
+            ffi::RL_DEFAULT_SHADER_ATTRIB_LOCATION_TEXCOORD,
+            cpu,
+            start,
+            texcoords,
+        )
+    }
+
+    /// Replaces the colors of the vertices from `start` on, in the mesh and on the GPU.
+    /// The mesh must have been uploaded with colors.
+    fn update_colors(
+        &mut self,
+        _: &RaylibThread,
+        start: usize,
+        colors: &[Color],
+    ) -> Result<(), Error> {
+        let cpu = self.as_ref().colors as *mut Color;
+        update_attribute(
+            self.as_ref(),
+            ffi::RL_DEFAULT_SHADER_ATTRIB_LOCATION_COLOR,
+            cpu,
+            start,
+            colors,
+        )
+    }
+
     /// Generate polygonal mesh
     #[inline]
     fn gen_mesh_poly(_: &RaylibThread, sides: i32, radius: f32) -> Mesh {
@@ -460,6 +536,233 @@ pub trait RaylibMesh: AsRef<ffi::Mesh> + AsMut<ffi::Mesh> {
     }
 }
 
+/// Writes `values` over the vertices from `start` on, into the CPU copy at `cpu` if there is
+/// one and into vertex buffer `buffer`.
+fn update_attribute<T: Copy>(
+    mesh: &ffi::Mesh,
+    buffer: u32,
+    cpu: *mut T,
+    start: usize,
+    values: &[T],
+) -> Result<(), Error> {
+    let count = mesh.vertexCount.max(0) as usize;
+    let end = start
+        .checked_add(values.len())
+        .filter(|&end| end <= count)
+        .ok_or(error!("Mesh update is out of the mesh's vertex range."))?;
+    if mesh.vboId.is_null() || unsafe { *mesh.vboId.add(buffer as usize) } == 0 {
+        return Err(error!("Mesh has no vertex buffer for this attribute."));
+    }
+    let size = i32::try_from(std::mem::size_of_val(values))
+        .map_err(|_| error!("Mesh update is too large."))?;
+    let offset = i32::try_from(start * std::mem::size_of::<T>())
+        .map_err(|_| error!("Mesh update is too large."))?;
+    unsafe {
+        if !cpu.is_null() {
+            std::slice::from_raw_parts_mut(cpu, count)[start..end].copy_from_slice(values);
+        }
+        ffi::UpdateMeshBuffer(
+            *mesh,
+            buffer as i32,
+            values.as_ptr() as *const c_void,
+            size,
+            offset,
+        );
+    }
+    Ok(())
+}
+
+/// Copies `values` into memory allocated by raylib, so raylib can free it.
+pub(crate) unsafe fn copy_to_raylib<T: Copy>(values: &[T]) -> Result<*mut T, Error> {
+    let size = u32::try_from(std::mem::size_of_val(values))
+        .map_err(|_| error!("Mesh data is too large to allocate."))?;
+    let buffer = ffi::MemAlloc(size) as *mut T;
+    if buffer.is_null() {
+        return Err(error!("Could not allocate mesh data."));
+    }
+    std::ptr::copy_nonoverlapping(values.as_ptr(), buffer, values.len());
+    Ok(buffer)
+}
+
+/// Creates a [`Mesh`] from vertex attributes in Rust slices.
+///
+/// Every attribute has one value per position. Without indices, each three positions make a
+/// triangle; with them, each three indices do. The data is copied into memory raylib owns,
+/// so [`Mesh`] frees it as usual, and uploaded to the GPU by [`MeshBuilder::build`].
+///
+/// ```no_run
+/// use raylib::prelude::*;
+///
+/// let (mut rl, thread) = raylib::init().build();
+/// let positions = [
+///     Vector3::new(0.0, 0.0, 0.0),
+///     Vector3::new(1.0, 0.0, 0.0),
+///     Vector3::new(1.0, 0.0, -1.0),
+///     Vector3::new(0.0, 0.0, -1.0),
+/// ];
+/// let mut quad = MeshBuilder::new(&positions)
+///     .normals(&[Vector3::up(); 4])
+///     .colors(&[Color::RED, Color::GREEN, Color::BLUE, Color::WHITE])
+///     .indices(&[0, 1, 2, 0, 2, 3])
+///     .dynamic()
+///     .build(&thread)
+///     .unwrap();
+/// quad.update_positions(&thread, 2, &[Vector3::new(1.0, 0.5, -1.0)])
+///     .unwrap();
+/// ```
+#[derive(Debug, Clone)]
+pub struct MeshBuilder<'a> {
+    positions: &'a [Vector3],
+    normals: Option<&'a [Vector3]>,
+    texcoords: Option<&'a [Vector2]>,
+    texcoords2: Option<&'a [Vector2]>,
+    colors: Option<&'a [Color]>,
+    tangents: Option<&'a [Vector4]>,
+    indices: Option<&'a [u16]>,
+    bone_ids: Option<&'a [[u8; 4]]>,
+    bone_weights: Option<&'a [[f32; 4]]>,
+    dynamic: bool,
+}
+
+impl<'a> MeshBuilder<'a> {
+    pub fn new(positions: &'a [Vector3]) -> MeshBuilder<'a> {
+        MeshBuilder {
+            positions,
+            normals: None,
+            texcoords: None,
+            texcoords2: None,
+            colors: None,
+            tangents: None,
+            indices: None,
+            bone_ids: None,
+            bone_weights: None,
+            dynamic: false,
+        }
+    }
+
+    pub fn normals(&mut self, normals: &'a [Vector3]) -> &mut Self {
+        self.normals = Some(normals);
+        self
+    }
+
+    pub fn texcoords(&mut self, texcoords: &'a [Vector2]) -> &mut Self {
+        self.texcoords = Some(texcoords);
+        self
+    }
+
+    /// A second set of texture coordinates, often used for lightmaps.
+    pub fn texcoords2(&mut self, texcoords: &'a [Vector2]) -> &mut Self {
+        self.texcoords2 = Some(texcoords);
+        self
+    }
+
+    pub fn colors(&mut self, colors: &'a [Color]) -> &mut Self {
+        self.colors = Some(colors);
+        self
+    }
+
+    /// Tangents with the handedness of the bitangent in `w`, as raylib's shaders expect.
+    pub fn tangents(&mut self, tangents: &'a [Vector4]) -> &mut Self {
+        self.tangents = Some(tangents);
+        self
+    }
+
+    pub fn indices(&mut self, indices: &'a [u16]) -> &mut Self {
+        self.indices = Some(indices);
+        self
+    }
+
+    /// Up to four bones per vertex and how much each one moves it, for skinning.
+    pub fn bones(&mut self, ids: &'a [[u8; 4]], weights: &'a [[f32; 4]]) -> &mut Self {
+        self.bone_ids = Some(ids);
+        self.bone_weights = Some(weights);
+        self
+    }
+
+    /// Uploads the mesh for frequent updates through [`RaylibMesh::update_positions`] and
+    /// friends.
+    pub fn dynamic(&mut self) -> &mut Self {
+        self.dynamic = true;
+        self
+    }
+
+    /// Checks the attributes, copies them into a new mesh and uploads it.
+    pub fn build(&self, thread: &RaylibThread) -> Result<Mesh, Error> {
+        let count = self.positions.len();
+        let vertex_count =
+            i32::try_from(count).map_err(|_| error!("Mesh has too many vertices."))?;
+        if count == 0 {
+            return Err(error!("Mesh needs at least one triangle."));
+        }
+        let lengths = [
+            ("normals", self.normals.map(<[_]>::len)),
+            ("texcoords", self.texcoords.map(<[_]>::len)),
+            ("texcoords2", self.texcoords2.map(<[_]>::len)),
+            ("colors", self.colors.map(<[_]>::len)),
+            ("tangents", self.tangents.map(<[_]>::len)),
+            ("bone ids", self.bone_ids.map(<[_]>::len)),
+            ("bone weights", self.bone_weights.map(<[_]>::len)),
+        ];
+        for &(name, len) in &lengths {
+            match len {
+                Some(len) if len != count => {
+                    return Err(error!(Cow::Owned(format!(
+                        "Mesh has {} positions but {} {}.",
+                        count, len, name
+                    ))))
+                }
+                _ => {}
+            }
+        }
+        let corners = match self.indices {
+            Some(indices) => {
+                if indices.iter().any(|&i| i as usize >= count) {
+                    return Err(error!("Mesh index is out of the vertex range."));
+                }
+                indices.len()
+            }
+            None => count,
+        };
+        if corners == 0 || !corners.is_multiple_of(3) {
+            return Err(error!("Mesh needs whole triangles."));
+        }
+
+        unsafe {
+            let mut mesh = Mesh(std::mem::zeroed());
+            mesh.0.vertexCount = vertex_count;
+            mesh.0.triangleCount = (corners / 3) as i32;
+            // raylib frees these in UnloadMesh, also when a later allocation fails
+            mesh.0.vertices = copy_to_raylib(self.positions)? as *mut f32;
+            if let Some(normals) = self.normals {
+                mesh.0.normals = copy_to_raylib(normals)? as *mut f32;
+            }
+            if let Some(texcoords) = self.texcoords {
+                mesh.0.texcoords = copy_to_raylib(texcoords)? as *mut f32;
+            }
+            if let Some(texcoords) = self.texcoords2 {
+                mesh.0.texcoords2 = copy_to_raylib(texcoords)? as *mut f32;
+            }
+            if let Some(colors) = self.colors {
+                mesh.0.colors = copy_to_raylib(colors)? as *mut u8;
+            }
+            if let Some(tangents) = self.tangents {
+                mesh.0.tangents = copy_to_raylib(tangents)? as *mut f32;
+            }
+            if let Some(indices) = self.indices {
+                mesh.0.indices = copy_to_raylib(indices)?;
+            }
+            if let Some(ids) = self.bone_ids {
+                mesh.0.boneIds = copy_to_raylib(ids)? as *mut u8;
+            }
+            if let Some(weights) = self.bone_weights {
+                mesh.0.boneWeights = copy_to_raylib(weights)? as *mut f32;
+            }
+            mesh.upload(thread, self.dynamic);
+            Ok(mesh)
+        }
+    }
+}
+
 impl Material {
     pub unsafe fn make_weak(self) -> WeakMaterial {
         let m = WeakMaterial(self.0);
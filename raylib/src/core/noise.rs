@@ -0,0 +1,735 @@
+//! Seeded procedural noise: simplex, value and Worley noise, fractal octaves and domain warping
+//!
+//! [`Image::gen_image_perlin_noise`] and [`Image::gen_image_cellular`] only hand back finished
+//! images. The generators here are plain Rust and can be sampled at any point in 2D, 3D or 4D,
+//! layered with [`Noise::fbm`], [`Noise::ridged`] and [`Noise::billow`], and bent with
+//! [`Noise::warped`]. [`Noise::sample_tiled`] wraps a 2D pattern around a torus in 4D so that
+//! it repeats seamlessly.
+//!
+//! A [`Heightmap`] holds a grid of samples. It can be normalized and turned into a grayscale
+//! image, an image colored by a [`ColorRamp`], or a float image. The grayscale image is what
+//! [`Mesh::gen_mesh_heightmap`](crate::core::models::RaylibMesh::gen_mesh_heightmap)
+//! expects.
+//!
+//! ```no_run
+//! use raylib::prelude::*;
+//!
+//! let hills = SimplexNoise::new(7).fbm(5).warped(SimplexNoise::new(8), 0.6);
+//! let mut heights = Heightmap::from_noise(&hills, 256, 256, 1.0 / 64.0);
+//! heights.normalize();
+//! let ramp = ColorRamp::new(&[
+//!     (0.0, Color::DARKBLUE),
+//!     (0.4, Color::BEIGE),
+//!     (0.6, Color::DARKGREEN),
+//!     (1.0, Color::WHITE),
+//! ]);
+//! heights.to_image_ramp(&ramp).unwrap().export_image("map.png");
+//! ```
+use crate::core::color::Color;
+use crate::core::pixels::{Gray8, R32f};
+use crate::core::texture::Image;
+use crate::error::{error, Error};
+use std::convert::TryInto;
+use std::f32::consts::TAU;
+
+/// A noise function that can be sampled anywhere in 2D, 3D or 4D.
+///
+/// Simplex and value noise, and the fractal and warped layers built on them, return values
+/// roughly between -1 and 1. Worley noise returns distances, from 0 up to about 1.
+pub trait Noise {
+    fn sample2(&self, x: f32, y: f32) -> f32;
+    fn sample3(&self, x: f32, y: f32, z: f32) -> f32;
+    fn sample4(&self, x: f32, y: f32, z: f32, w: f32) -> f32;
+
+    /// Samples a 2D pattern that repeats every `period_x` along x and every `period_y` along y.
+    /// Each axis is wrapped around a circle in 4D, so features keep roughly the same scale.
+    fn sample_tiled(&self, x: f32, y: f32, period_x: f32, period_y: f32) -> f32 {
+        let (ax, ay) = (x / period_x * TAU, y / period_y * TAU);
+        let (rx, ry) = (period_x / TAU, period_y / TAU);
+        self.sample4(ax.cos() * rx, ax.sin() * rx, ay.cos() * ry, ay.sin() * ry)
+    }
+
+    /// Fractal Brownian motion: `octaves` layers, each at twice the frequency and half the
+    /// amplitude of the last.
+    fn fbm(self, octaves: u32) -> Fractal<Self>
+    where
+        Self: Sized,
+    {
+        Fractal::new(self, FractalMode::Fbm, octaves)
+    }
+
+    /// Octaves folded into sharp ridges, for mountain ranges.
+    fn ridged(self, octaves: u32) -> Fractal<Self>
+    where
+        Self: Sized,
+    {
+        Fractal::new(self, FractalMode::Ridged, octaves)
+    }
+
+    /// Octaves folded into rounded bumps, for clouds and rolling hills.
+    fn billow(self, octaves: u32) -> Fractal<Self>
+    where
+        Self: Sized,
+    {
+        Fractal::new(self, FractalMode::Billow, octaves)
+    }
+
+    /// Moves each sample point by up to `strength` along the values of `warp` before sampling.
+    fn warped<W: Noise>(self, warp: W, strength: f32) -> DomainWarp<Self, W>
+    where
+        Self: Sized,
+    {
+        DomainWarp {
+            source: self,
+            warp,
+            strength,
+        }
+    }
+}
+
+impl<N: Noise + ?Sized> Noise for &N {
+    fn sample2(&self, x: f32, y: f32) -> f32 {
+        (**self).sample2(x, y)
+    }
+
+    fn sample3(&self, x: f32, y: f32, z: f32) -> f32 {
+        (**self).sample3(x, y, z)
+    }
+
+    fn sample4(&self, x: f32, y: f32, z: f32, w: f32) -> f32 {
+        (**self).sample4(x, y, z, w)
+    }
+}
+
+impl<N: Noise + ?Sized> Noise for Box<N> {
+    fn sample2(&self, x: f32, y: f32) -> f32 {
+        (**self).sample2(x, y)
+    }
+
+    fn sample3(&self, x: f32, y: f32, z: f32) -> f32 {
+        (**self).sample3(x, y, z)
+    }
+
+    fn sample4(&self, x: f32, y: f32, z: f32, w: f32) -> f32 {
+        (**self).sample4(x, y, z, w)
+    }
+}
+
+/// Hashes lattice coordinates together with a seed.
+fn hash(seed: u32, cell: [i32; 4]) -> u32 {
+    let mut h = seed.wrapping_mul(0x27d4_eb2d) ^ 0x1656_67b1;
+    for &c in &cell {
+        h = (h ^ c as u32).wrapping_mul(0x85eb_ca6b);
+        h ^= h >> 13;
+        h = h.wrapping_mul(0xc2b2_ae35);
+        h ^= h >> 16;
+    }
+    h
+}
+
+/// Maps a hash to `[0, 1)`.
+fn unit(h: u32) -> f32 {
+    (h >> 8) as f32 / (1u32 << 24) as f32
+}
+
+fn fast_floor(x: f32) -> i32 {
+    x.floor() as i32
+}
+
+const GRAD3: [[f32; 3]; 12] = [
+    [1.0, 1.0, 0.0],
+    [-1.0, 1.0, 0.0],
+    [1.0, -1.0, 0.0],
+    [-1.0, -1.0, 0.0],
+    [1.0, 0.0, 1.0],
+    [-1.0, 0.0, 1.0],
+    [1.0, 0.0, -1.0],
+    [-1.0, 0.0, -1.0],
+    [0.0, 1.0, 1.0],
+    [0.0, -1.0, 1.0],
+    [0.0, 1.0, -1.0],
+    [0.0, -1.0, -1.0],
+];
+
+/// The 32 edge midpoints of a tesseract.
+fn grad4(h: u32) -> [f32; 4] {
+    let h = h % 32;
+    let (a, b, c) = (
+        if h & 1 == 0 { 1.0 } else { -1.0 },
+        if h & 2 == 0 { 1.0 } else { -1.0 },
+        if h & 4 == 0 { 1.0 } else { -1.0 },
+    );
+    match h >> 3 {
+        0 => [0.0, a, b, c],
+        1 => [a, 0.0, b, c],
+        2 => [a, b, 0.0, c],
+        _ => [a, b, c, 0.0],
+    }
+}
+
+/// Simplex noise, after Ken Perlin's improvement of his original gradient noise.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SimplexNoise {
+    pub seed: u32,
+}
+
+impl SimplexNoise {
+    pub fn new(seed: u32) -> Self {
+        SimplexNoise { seed }
+    }
+}
+
+impl Noise for SimplexNoise {
+    fn sample2(&self, x: f32, y: f32) -> f32 {
+        let f2 = 0.5 * (3f32.sqrt() - 1.0);
+        let g2 = (3.0 - 3f32.sqrt()) / 6.0;
+        let s = (x + y) * f2;
+        let (i, j) = (fast_floor(x + s), fast_floor(y + s));
+        let t = (i + j) as f32 * g2;
+        let (x0, y0) = (x - (i as f32 - t), y - (j as f32 - t));
+        let (i1, j1) = if x0 > y0 { (1, 0) } else { (0, 1) };
+        let corners = [
+            (0, 0, x0, y0),
+            (i1, j1, x0 - i1 as f32 + g2, y0 - j1 as f32 + g2),
+            (1, 1, x0 - 1.0 + 2.0 * g2, y0 - 1.0 + 2.0 * g2),
+        ];
+        let mut total = 0.0;
+        for &(di, dj, cx, cy) in &corners {
+            let t = 0.5 - cx * cx - cy * cy;
+            if t > 0.0 {
+                let g = GRAD3[(hash(self.seed, [i + di, j + dj, 0, 0]) % 12) as usize];
+                total += t * t * t * t * (g[0] * cx + g[1] * cy);
+            }
+        }
+        70.0 * total
+    }
+
+    fn sample3(&self, x: f32, y: f32, z: f32) -> f32 {
+        let (f3, g3) = (1.0 / 3.0, 1.0 / 6.0);
+        let s = (x + y + z) * f3;
+        let (i, j, k) = (fast_floor(x + s), fast_floor(y + s), fast_floor(z + s));
+        let t = (i + j + k) as f32 * g3;
+        let p0 = [x - (i as f32 - t), y - (j as f32 - t), z - (k as f32 - t)];
+        // rank the axes to find which simplex of the skewed cube the point is in
+        let mut order = [0, 1, 2];
+        order.sort_by(|&a, &b| {
+            p0[b]
+                .partial_cmp(&p0[a])
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        let mut offset = [0; 3];
+        let mut total = 0.0;
+        for corner in 0..4 {
+            if corner > 0 {
+                offset[order[corner - 1]] = 1;
+            }
+            let g = corner as f32 * g3;
+            let d = [
+                p0[0] - offset[0] as f32 + g,
+                p0[1] - offset[1] as f32 + g,
+                p0[2] - offset[2] as f32 + g,
+            ];
+            let t = 0.6 - d[0] * d[0] - d[1] * d[1] - d[2] * d[2];
+            if t > 0.0 {
+                let cell = [i + offset[0], j + offset[1], k + offset[2], 0];
+                let g = GRAD3[(hash(self.seed, cell) % 12) as usize];
+                total += t * t * t * t * (g[0] * d[0] + g[1] * d[1] + g[2] * d[2]);
+            }
+        }
+        32.0 * total
+    }
+
+    fn sample4(&self, x: f32, y: f32, z: f32, w: f32) -> f32 {
+        let f4 = (5f32.sqrt() - 1.0) / 4.0;
+        let g4 = (5.0 - 5f32.sqrt()) / 20.0;
+        let s = (x + y + z + w) * f4;
+        let cell = [
+            fast_floor(x + s),
+            fast_floor(y + s),
+            fast_floor(z + s),
+            fast_floor(w + s),
+        ];
+        let t = cell.iter().sum::<i32>() as f32 * g4;
+        let p = [x, y, z, w];
+        let mut p0 = [0.0; 4];
+        for axis in 0..4 {
+            p0[axis] = p[axis] - (cell[axis] as f32 - t);
+        }
+        let mut order = [0, 1, 2, 3];
+        order.sort_by(|&a, &b| {
+            p0[b]
+                .partial_cmp(&p0[a])
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        let mut offset = [0; 4];
+        let mut total = 0.0;
+        for corner in 0..5 {
+            if corner > 0 {
+                offset[order[corner - 1]] = 1;
+            }
+            let g = corner as f32 * g4;
+            let mut d = [0.0; 4];
+            let mut corner_cell = [0; 4];
+            for axis in 0..4 {
+                d[axis] = p0[axis] - offset[axis] as f32 + g;
+                corner_cell[axis] = cell[axis] + offset[axis];
+            }
+            let t = 0.6 - d.iter().map(|v| v * v).sum::<f32>();
+            if t > 0.0 {
+                let g = grad4(hash(self.seed, corner_cell));
+                let dot: f32 = g.iter().zip(&d).map(|(g, d)| g * d).sum();
+                total += t * t * t * t * dot;
+            }
+        }
+        27.0 * total
+    }
+}
+
+/// Value noise: random values on an integer lattice, smoothly interpolated. Blockier than
+/// simplex noise, and cheap.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ValueNoise {
+    pub seed: u32,
+}
+
+impl ValueNoise {
+    pub fn new(seed: u32) -> Self {
+        ValueNoise { seed }
+    }
+
+    fn sample(&self, p: [f32; 4], dims: usize) -> f32 {
+        let mut base = [0; 4];
+        let mut fade = [0.0; 4];
+        for axis in 0..dims {
+            base[axis] = fast_floor(p[axis]);
+            let f = p[axis] - base[axis] as f32;
+            fade[axis] = f * f * f * (f * (f * 6.0 - 15.0) + 10.0);
+        }
+        let mut total = 0.0;
+        for corner in 0..1usize << dims {
+            let mut cell = base;
+            let mut weight = 1.0;
+            for axis in 0..dims {
+                if corner & (1 << axis) != 0 {
+                    cell[axis] += 1;
+                    weight *= fade[axis];
+                } else {
+                    weight *= 1.0 - fade[axis];
+                }
+            }
+            total += weight * (unit(hash(self.seed, cell)) * 2.0 - 1.0);
+        }
+        total
+    }
+}
+
+impl Noise for ValueNoise {
+    fn sample2(&self, x: f32, y: f32) -> f32 {
+        self.sample([x, y, 0.0, 0.0], 2)
+    }
+
+    fn sample3(&self, x: f32, y: f32, z: f32) -> f32 {
+        self.sample([x, y, z, 0.0], 3)
+    }
+
+    fn sample4(&self, x: f32, y: f32, z: f32, w: f32) -> f32 {
+        self.sample([x, y, z, w], 4)
+    }
+}
+
+/// Which distance [`WorleyNoise`] returns.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WorleyDistance {
+    /// Distance to the nearest feature point: round cells.
+    F1,
+    /// Distance to the second nearest feature point.
+    F2,
+    /// Gap between the two: zero along cell borders, like cracks or veins.
+    F2MinusF1,
+}
+
+/// Worley (cellular) noise: distances to feature points scattered one per lattice cell.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WorleyNoise {
+    pub seed: u32,
+    pub distance: WorleyDistance,
+    /// How far feature points stray from their cell's center, from 0 (a regular grid) to 1.
+    pub jitter: f32,
+}
+
+impl WorleyNoise {
+    /// F1 Worley noise with fully jittered feature points.
+    pub fn new(seed: u32) -> Self {
+        WorleyNoise {
+            seed,
+            distance: WorleyDistance::F1,
+            jitter: 1.0,
+        }
+    }
+
+    pub fn with_distance(mut self, distance: WorleyDistance) -> Self {
+        self.distance = distance;
+        self
+    }
+
+    pub fn with_jitter(mut self, jitter: f32) -> Self {
+        self.jitter = jitter.clamp(0.0, 1.0);
+        self
+    }
+
+    fn sample(&self, p: [f32; 4], dims: usize) -> f32 {
+        let mut base = [0; 4];
+        for axis in 0..dims {
+            base[axis] = fast_floor(p[axis]);
+        }
+        let (mut f1, mut f2) = (f32::INFINITY, f32::INFINITY);
+        for neighbor in 0..3usize.pow(dims as u32) {
+            let mut cell = base;
+            let mut rest = neighbor;
+            for axis in cell.iter_mut().take(dims) {
+                *axis += (rest % 3) as i32 - 1;
+                rest /= 3;
+            }
+            let mut h = hash(self.seed, cell);
+            let mut d2 = 0.0;
+            for axis in 0..dims {
+                let feature = cell[axis] as f32 + 0.5 + (unit(h) - 0.5) * self.jitter;
+                h = hash(h, [axis as i32, 0, 0, 0]);
+                let d = feature - p[axis];
+                d2 += d * d;
+            }
+            if d2 < f1 {
+                f2 = f1;
+                f1 = d2;
+            } else if d2 < f2 {
+                f2 = d2;
+            }
+        }
+        match self.distance {
+            WorleyDistance::F1 => f1.sqrt(),
+            WorleyDistance::F2 => f2.sqrt(),
+            WorleyDistance::F2MinusF1 => f2.sqrt() - f1.sqrt(),
+        }
+    }
+}
+
+impl Noise for WorleyNoise {
+    fn sample2(&self, x: f32, y: f32) -> f32 {
+        self.sample([x, y, 0.0, 0.0], 2)
+    }
+
+    fn sample3(&self, x: f32, y: f32, z: f32) -> f32 {
+        self.sample([x, y, z, 0.0], 3)
+    }
+
+    fn sample4(&self, x: f32, y: f32, z: f32, w: f32) -> f32 {
+        self.sample([x, y, z, w], 4)
+    }
+}
+
+/// How [`Fractal`] shapes each octave before adding it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FractalMode {
+    /// Octaves added as they are.
+    Fbm,
+    /// `1 - |n|`, squared: sharp crests where the noise crosses zero.
+    Ridged,
+    /// `|n|`: rounded bumps with creases between them.
+    Billow,
+}
+
+/// Several octaves of a noise function added together. The result is scaled back to about
+/// -1 to 1 whatever the number of octaves.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Fractal<N> {
+    pub source: N,
+    pub mode: FractalMode,
+    pub octaves: u32,
+    /// Frequency multiplier between octaves.
+    pub lacunarity: f32,
+    /// Amplitude multiplier between octaves.
+    pub gain: f32,
+}
+
+impl<N: Noise> Fractal<N> {
+    pub fn new(source: N, mode: FractalMode, octaves: u32) -> Self {
+        Fractal {
+            source,
+            mode,
+            octaves: octaves.max(1),
+            lacunarity: 2.0,
+            gain: 0.5,
+        }
+    }
+
+    pub fn with_lacunarity(mut self, lacunarity: f32) -> Self {
+        self.lacunarity = lacunarity;
+        self
+    }
+
+    pub fn with_gain(mut self, gain: f32) -> Self {
+        self.gain = gain;
+        self
+    }
+
+    fn sum(&self, mut octave: impl FnMut(f32, f32) -> f32) -> f32 {
+        let (mut frequency, mut amplitude) = (1.0, 1.0);
+        let (mut total, mut range) = (0.0, 0.0);
+        for i in 0..self.octaves {
+            // shifting each octave keeps their lattices from lining up at the origin
+            let n = octave(frequency, i as f32 * 19.19);
+            let n = match self.mode {
+                FractalMode::Fbm => n,
+                FractalMode::Ridged => {
+                    let r = 1.0 - n.abs();
+                    r * r * 2.0 - 1.0
+                }
+                FractalMode::Billow => n.abs() * 2.0 - 1.0,
+            };
+            total += n * amplitude;
+            range += amplitude;
+            frequency *= self.lacunarity;
+            amplitude *= self.gain;
+        }
+        total / range
+    }
+}
+
+impl<N: Noise> Noise for Fractal<N> {
+    fn sample2(&self, x: f32, y: f32) -> f32 {
+        self.sum(|f, o| self.source.sample2(x * f + o, y * f + o))
+    }
+
+    fn sample3(&self, x: f32, y: f32, z: f32) -> f32 {
+        self.sum(|f, o| self.source.sample3(x * f + o, y * f + o, z * f + o))
+    }
+
+    fn sample4(&self, x: f32, y: f32, z: f32, w: f32) -> f32 {
+        self.sum(|f, o| {
+            self.source
+                .sample4(x * f + o, y * f + o, z * f + o, w * f + o)
+        })
+    }
+}
+
+/// A noise function sampled at points displaced by another, see [`Noise::warped`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DomainWarp<N, W> {
+    pub source: N,
+    pub warp: W,
+    pub strength: f32,
+}
+
+// each axis reads the warp noise somewhere else so the offsets aren't correlated
+const WARP_OFFSETS: [f32; 4] = [0.0, 31.7, 57.3, 83.9];
+
+impl<N: Noise, W: Noise> Noise for DomainWarp<N, W> {
+    fn sample2(&self, x: f32, y: f32) -> f32 {
+        let offset = |o: f32| self.warp.sample2(x + o, y + o) * self.strength;
+        self.source
+            .sample2(x + offset(WARP_OFFSETS[0]), y + offset(WARP_OFFSETS[1]))
+    }
+
+    fn sample3(&self, x: f32, y: f32, z: f32) -> f32 {
+        let offset = |o: f32| self.warp.sample3(x + o, y + o, z + o) * self.strength;
+        self.source.sample3(
+            x + offset(WARP_OFFSETS[0]),
+            y + offset(WARP_OFFSETS[1]),
+            z + offset(WARP_OFFSETS[2]),
+        )
+    }
+
+    fn sample4(&self, x: f32, y: f32, z: f32, w: f32) -> f32 {
+        let offset = |o: f32| self.warp.sample4(x + o, y + o, z + o, w + o) * self.strength;
+        self.source.sample4(
+            x + offset(WARP_OFFSETS[0]),
+            y + offset(WARP_OFFSETS[1]),
+            z + offset(WARP_OFFSETS[2]),
+            w + offset(WARP_OFFSETS[3]),
+        )
+    }
+}
+
+/// Colors picked along a line of stops, for mapping heights or other values to colors.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ColorRamp {
+    stops: Vec<(f32, Color)>,
+}
+
+impl ColorRamp {
+    /// A ramp through `stops`, given as `(position, color)` in any order.
+    pub fn new(stops: &[(f32, Color)]) -> Self {
+        stops
+            .iter()
+            .fold(ColorRamp::default(), |ramp, &(t, color)| {
+                ramp.stop(t, color)
+            })
+    }
+
+    /// Adds a stop at `t`.
+    pub fn stop(mut self, t: f32, color: Color) -> Self {
+        let at = self.stops.iter().take_while(|s| s.0 <= t).count();
+        self.stops.insert(at, (t, color));
+        self
+    }
+
+    pub fn stops(&self) -> &[(f32, Color)] {
+        &self.stops
+    }
+
+    /// The color at `t`, blended between the stops on either side. Values past the ends get
+    /// the end colors; an empty ramp is [`Color::BLANK`].
+    pub fn sample(&self, t: f32) -> Color {
+        let after = self.stops.iter().position(|s| s.0 > t);
+        match after {
+            _ if self.stops.is_empty() => Color::BLANK,
+            Some(0) => self.stops[0].1,
+            None => self.stops[self.stops.len() - 1].1,
+            Some(i) => {
+                let ((t0, a), (t1, b)) = (self.stops[i - 1], self.stops[i]);
+                let f = (t - t0) / (t1 - t0);
+                let l = |x: u8, y: u8| (x as f32 + (y as f32 - x as f32) * f).round() as u8;
+                Color::new(l(a.r, b.r), l(a.g, b.g), l(a.b, b.b), l(a.a, b.a))
+            }
+        }
+    }
+}
+
+/// A grid of float samples, row by row.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Heightmap {
+    width: usize,
+    height: usize,
+    data: Vec<f32>,
+}
+
+impl Heightmap {
+    /// A flat heightmap of zeros.
+    pub fn new(width: usize, height: usize) -> Self {
+        Heightmap {
+            width,
+            height,
+            data: vec![0.0; width * height],
+        }
+    }
+
+    /// Fills a heightmap with `f(x, y)`.
+    pub fn from_fn(width: usize, height: usize, mut f: impl FnMut(usize, usize) -> f32) -> Self {
+        let mut data = Vec::with_capacity(width * height);
+        for y in 0..height {
+            for x in 0..width {
+                data.push(f(x, y));
+            }
+        }
+        Heightmap {
+            width,
+            height,
+            data,
+        }
+    }
+
+    /// Samples `noise` in 2D, at `(x * frequency, y * frequency)` for each cell.
+    pub fn from_noise(noise: &impl Noise, width: usize, height: usize, frequency: f32) -> Self {
+        Heightmap::from_fn(width, height, |x, y| {
+            noise.sample2(x as f32 * frequency, y as f32 * frequency)
+        })
+    }
+
+    /// Like [`Heightmap::from_noise`], but the edges wrap around so the map tiles seamlessly.
+    pub fn from_noise_tiled(
+        noise: &impl Noise,
+        width: usize,
+        height: usize,
+        frequency: f32,
+    ) -> Self {
+        let (period_x, period_y) = (width as f32 * frequency, height as f32 * frequency);
+        Heightmap::from_fn(width, height, |x, y| {
+            noise.sample_tiled(
+                x as f32 * frequency,
+                y as f32 * frequency,
+                period_x,
+                period_y,
+            )
+        })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn data(&self) -> &[f32] {
+        &self.data
+    }
+
+    pub fn data_mut(&mut self) -> &mut [f32] {
+        &mut self.data
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Option<f32> {
+        if x < self.width && y < self.height {
+            Some(self.data[y * self.width + x])
+        } else {
+            None
+        }
+    }
+
+    pub fn set(&mut self, x: usize, y: usize, value: f32) {
+        if x < self.width && y < self.height {
+            self.data[y * self.width + x] = value;
+        }
+    }
+
+    /// Lowest and highest value, or `None` if the map is empty.
+    pub fn range(&self) -> Option<(f32, f32)> {
+        self.data.iter().fold(None, |range, &v| match range {
+            None => Some((v, v)),
+            Some((lo, hi)) => Some((lo.min(v), hi.max(v))),
+        })
+    }
+
+    /// Stretches the values to fill 0 to 1. A flat map becomes all zeros.
+    pub fn normalize(&mut self) {
+        if let Some((lo, hi)) = self.range() {
+            for v in &mut self.data {
+                *v = if hi > lo { (*v - lo) / (hi - lo) } else { 0.0 };
+            }
+        }
+    }
+
+    fn image_size(&self) -> Result<(i32, i32), Error> {
+        match (self.width.try_into(), self.height.try_into()) {
+            (Ok(width), Ok(height)) => Ok((width, height)),
+            _ => Err(error!("Heightmap is too large for an image.")),
+        }
+    }
+
+    /// A grayscale image with 0 as black and 1 as white, clamping anything outside. Suitable
+    /// for `gen_mesh_heightmap`.
+    pub fn to_image(&self) -> Result<Image, Error> {
+        let (width, height) = self.image_size()?;
+        let pixels: Vec<Gray8> = self
+            .data
+            .iter()
+            .map(|v| Gray8((v.clamp(0.0, 1.0) * 255.0).round() as u8))
+            .collect();
+        Image::from_pixel_slice(width, height, &pixels)
+    }
+
+    /// An image colored by looking each value up in `ramp`.
+    pub fn to_image_ramp(&self, ramp: &ColorRamp) -> Result<Image, Error> {
+        let (width, height) = self.image_size()?;
+        let pixels: Vec<Color> = self.data.iter().map(|&v| ramp.sample(v)).collect();
+        Image::from_pixel_slice(width, height, &pixels)
+    }
+
+    /// A single channel float image holding the values unchanged.
+    pub fn to_image_f32(&self) -> Result<Image, Error> {
+        let (width, height) = self.image_size()?;
+        let pixels: Vec<R32f> = self.data.iter().map(|&v| R32f(v)).collect();
+        Image::from_pixel_slice(width, height, &pixels)
+    }
+}
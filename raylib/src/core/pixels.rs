@@ -0,0 +1,1025 @@
+//! Typed, zero-copy access to the pixels of an [`Image`]
+//!
+//! Every uncompressed [`PixelFormat`] has a [`Pixel`] type with the same memory layout, so
+//! [`Image::pixels`] and [`Image::pixels_mut`] can return the image data as a slice without
+//! copying it or calling into raylib once per pixel. [`ImageView`] and [`ImageViewMut`] do the
+//! same for a rectangle inside the image, whose rows are not contiguous.
+//!
+//! [`Image::from_pixels`] goes the other way, copying a Rust buffer into memory raylib
+//! allocated, so the image can be passed to any raylib function that reallocates or frees
+//! it. With the `image` feature, images convert to and from the `image` crate's types.
+//!
+//! ```no_run
+//! use raylib::prelude::*;
+//!
+//! let mut image = Image::gen_image_color(64, 64, Color::SKYBLUE);
+//! for pixel in image.pixels_mut::<Rgba8>().unwrap() {
+//!     pixel.r = 255 - pixel.r;
+//! }
+//!
+//! let mut corner = image
+//!     .view_mut::<Rgba8>(Rectangle::new(0.0, 0.0, 16.0, 16.0))
+//!     .unwrap();
+//! corner.fill(Color::RED);
+//! ```
+use crate::consts::PixelFormat;
+use crate::core::color::Color;
+use crate::core::texture::Image;
+use crate::error::{error, Error};
+use crate::ffi;
+use std::borrow::Cow;
+#[cfg(feature = "image")]
+use std::convert::TryFrom;
+use std::convert::TryInto;
+use std::ops::Range;
+use std::os::raw::c_void;
+
+/// A pixel of one uncompressed [`PixelFormat`].
+///
+/// # Safety
+///
+/// The type must have exactly the size and layout of one pixel of [`Pixel::FORMAT`], with
+/// an alignment raylib's allocations satisfy and no invalid bit patterns.
+pub unsafe trait Pixel: Copy + 'static {
+    const FORMAT: PixelFormat;
+
+    fn to_color(self) -> Color;
+    fn from_color(color: Color) -> Self;
+}
+
+/// [`PixelFormat::PIXELFORMAT_UNCOMPRESSED_R8G8B8A8`], the format of [`Color`].
+pub type Rgba8 = Color;
+
+/// [`PixelFormat::PIXELFORMAT_UNCOMPRESSED_GRAYSCALE`]
+#[repr(transparent)]
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub struct Gray8(pub u8);
+
+/// [`PixelFormat::PIXELFORMAT_UNCOMPRESSED_GRAY_ALPHA`]
+#[repr(C)]
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub struct GrayAlpha8 {
+    pub gray: u8,
+    pub alpha: u8,
+}
+
+/// [`PixelFormat::PIXELFORMAT_UNCOMPRESSED_R5G6B5`], red in the high bits.
+#[repr(transparent)]
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub struct Rgb565(pub u16);
+
+/// [`PixelFormat::PIXELFORMAT_UNCOMPRESSED_R8G8B8`]
+#[repr(C)]
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub struct Rgb8 {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+}
+
+/// [`PixelFormat::PIXELFORMAT_UNCOMPRESSED_R5G5B5A1`], red in the high bits.
+#[repr(transparent)]
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub struct Rgba5551(pub u16);
+
+/// [`PixelFormat::PIXELFORMAT_UNCOMPRESSED_R4G4B4A4`], red in the high bits.
+#[repr(transparent)]
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub struct Rgba4444(pub u16);
+
+/// [`PixelFormat::PIXELFORMAT_UNCOMPRESSED_R32`]
+#[repr(transparent)]
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+pub struct R32f(pub f32);
+
+/// [`PixelFormat::PIXELFORMAT_UNCOMPRESSED_R32G32B32`]
+#[repr(C)]
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+pub struct Rgb32f {
+    pub r: f32,
+    pub g: f32,
+    pub b: f32,
+}
+
+/// [`PixelFormat::PIXELFORMAT_UNCOMPRESSED_R32G32B32A32`]
+#[repr(C)]
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+pub struct Rgba32f {
+    pub r: f32,
+    pub g: f32,
+    pub b: f32,
+    pub a: f32,
+}
+
+/// [`PixelFormat::PIXELFORMAT_UNCOMPRESSED_R16`], a half float stored as its bits.
+#[repr(transparent)]
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub struct R16f(pub u16);
+
+/// [`PixelFormat::PIXELFORMAT_UNCOMPRESSED_R16G16B16`], half floats stored as their bits.
+#[repr(C)]
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub struct Rgb16f {
+    pub r: u16,
+    pub g: u16,
+    pub b: u16,
+}
+
+/// [`PixelFormat::PIXELFORMAT_UNCOMPRESSED_R16G16B16A16`], half floats stored as their bits.
+#[repr(C)]
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub struct Rgba16f {
+    pub r: u16,
+    pub g: u16,
+    pub b: u16,
+    pub a: u16,
+}
+
+impl R16f {
+    pub fn new(value: f32) -> Self {
+        R16f(f32_to_half(value))
+    }
+
+    pub fn value(self) -> f32 {
+        half_to_f32(self.0)
+    }
+}
+
+impl Rgb16f {
+    pub fn new(r: f32, g: f32, b: f32) -> Self {
+        Rgb16f {
+            r: f32_to_half(r),
+            g: f32_to_half(g),
+            b: f32_to_half(b),
+        }
+    }
+
+    pub fn to_array(self) -> [f32; 3] {
+        [
+            half_to_f32(self.r),
+            half_to_f32(self.g),
+            half_to_f32(self.b),
+        ]
+    }
+}
+
+impl Rgba16f {
+    pub fn new(r: f32, g: f32, b: f32, a: f32) -> Self {
+        Rgba16f {
+            r: f32_to_half(r),
+            g: f32_to_half(g),
+            b: f32_to_half(b),
+            a: f32_to_half(a),
+        }
+    }
+
+    pub fn to_array(self) -> [f32; 4] {
+        [
+            half_to_f32(self.r),
+            half_to_f32(self.g),
+            half_to_f32(self.b),
+            half_to_f32(self.a),
+        ]
+    }
+}
+
+/// Scales a channel of `bits` bits to 8 bits.
+fn expand(value: u16, bits: u32) -> u8 {
+    let max = (1u32 << bits) - 1;
+    ((value as u32 & max) * 255 / max) as u8
+}
+
+/// Scales an 8 bit channel to `bits` bits, rounding to nearest.
+fn shrink(value: u8, bits: u32) -> u16 {
+    let max = (1u32 << bits) - 1;
+    ((value as u32 * max + 127) / 255) as u16
+}
+
+fn unit_to_u8(value: f32) -> u8 {
+    (value.clamp(0.0, 1.0) * 255.0).round() as u8
+}
+
+fn u8_to_unit(value: u8) -> f32 {
+    value as f32 / 255.0
+}
+
+/// Luminance with the weights raylib uses for grayscale conversion.
+fn luminance(color: Color) -> u8 {
+    (color.r as f32 * 0.299 + color.g as f32 * 0.587 + color.b as f32 * 0.114) as u8
+}
+
+fn half_to_f32(bits: u16) -> f32 {
+    let sign = ((bits as u32) & 0x8000) << 16;
+    let exponent = (bits as u32 >> 10) & 0x1f;
+    let mantissa = bits as u32 & 0x3ff;
+    let magnitude = match exponent {
+        0 => {
+            // subnormal
+            let value = mantissa as f32 * 2f32.powi(-24);
+            return if sign != 0 { -value } else { value };
+        }
+        0x1f => 0x7f80_0000 | (mantissa << 13),
+        _ => ((exponent + 127 - 15) << 23) | (mantissa << 13),
+    };
+    f32::from_bits(sign | magnitude)
+}
+
+fn f32_to_half(value: f32) -> u16 {
+    let bits = value.to_bits();
+    let sign = ((bits >> 16) & 0x8000) as u16;
+    let exponent = ((bits >> 23) & 0xff) as i32;
+    let mantissa = bits & 0x7f_ffff;
+    if exponent == 0xff {
+        // infinity stays infinity, NaN stays NaN
+        return sign | 0x7c00 | if mantissa != 0 { 0x200 } else { 0 };
+    }
+    let exponent = exponent - 127 + 15;
+    if exponent >= 0x1f {
+        return sign | 0x7c00;
+    }
+    if exponent <= 0 {
+        if exponent < -10 {
+            return sign;
+        }
+        // subnormal, with the implicit leading bit made explicit
+        let mantissa = mantissa | 0x80_0000;
+        let shift = (14 - exponent) as u32;
+        let rounded = (mantissa + (1 << (shift - 1))) >> shift;
+        return sign | rounded as u16;
+    }
+    let rounded = ((exponent as u32) << 10 | mantissa >> 13) + ((mantissa >> 12) & 1);
+    sign | rounded as u16
+}
+
+unsafe impl Pixel for Color {
+    const FORMAT: PixelFormat = PixelFormat::PIXELFORMAT_UNCOMPRESSED_R8G8B8A8;
+
+    fn to_color(self) -> Color {
+        self
+    }
+
+    fn from_color(color: Color) -> Self {
+        color
+    }
+}
+
+unsafe impl Pixel for Gray8 {
+    const FORMAT: PixelFormat = PixelFormat::PIXELFORMAT_UNCOMPRESSED_GRAYSCALE;
+
+    fn to_color(self) -> Color {
+        Color::new(self.0, self.0, self.0, 255)
+    }
+
+    fn from_color(color: Color) -> Self {
+        Gray8(luminance(color))
+    }
+}
+
+unsafe impl Pixel for GrayAlpha8 {
+    const FORMAT: PixelFormat = PixelFormat::PIXELFORMAT_UNCOMPRESSED_GRAY_ALPHA;
+
+    fn to_color(self) -> Color {
+        Color::new(self.gray, self.gray, self.gray, self.alpha)
+    }
+
+    fn from_color(color: Color) -> Self {
+        GrayAlpha8 {
+            gray: luminance(color),
+            alpha: color.a,
+        }
+    }
+}
+
+unsafe impl Pixel for Rgb565 {
+    const FORMAT: PixelFormat = PixelFormat::PIXELFORMAT_UNCOMPRESSED_R5G6B5;
+
+    fn to_color(self) -> Color {
+        Color::new(
+            expand(self.0 >> 11, 5),
+            expand(self.0 >> 5, 6),
+            expand(self.0, 5),
+            255,
+        )
+    }
+
+    fn from_color(color: Color) -> Self {
+        Rgb565(shrink(color.r, 5) << 11 | shrink(color.g, 6) << 5 | shrink(color.b, 5))
+    }
+}
+
+unsafe impl Pixel for Rgb8 {
+    const FORMAT: PixelFormat = PixelFormat::PIXELFORMAT_UNCOMPRESSED_R8G8B8;
+
+    fn to_color(self) -> Color {
+        Color::new(self.r, self.g, self.b, 255)
+    }
+
+    fn from_color(color: Color) -> Self {
+        Rgb8 {
+            r: color.r,
+            g: color.g,
+            b: color.b,
+        }
+    }
+}
+
+unsafe impl Pixel for Rgba5551 {
+    const FORMAT: PixelFormat = PixelFormat::PIXELFORMAT_UNCOMPRESSED_R5G5B5A1;
+
+    fn to_color(self) -> Color {
+        Color::new(
+            expand(self.0 >> 11, 5),
+            expand(self.0 >> 6, 5),
+            expand(self.0 >> 1, 5),
+            expand(self.0, 1),
+        )
+    }
+
+    /// Alpha is opaque from 50 up, the threshold raylib uses.
+    fn from_color(color: Color) -> Self {
+        let alpha = (color.a > 50) as u16;
+        Rgba5551(
+            shrink(color.r, 5) << 11 | shrink(color.g, 5) << 6 | shrink(color.b, 5) << 1 | alpha,
+        )
+    }
+}
+
+unsafe impl Pixel for Rgba4444 {
+    const FORMAT: PixelFormat = PixelFormat::PIXELFORMAT_UNCOMPRESSED_R4G4B4A4;
+
+    fn to_color(self) -> Color {
+        Color::new(
+            expand(self.0 >> 12, 4),
+            expand(self.0 >> 8, 4),
+            expand(self.0 >> 4, 4),
+            expand(self.0, 4),
+        )
+    }
+
+    fn from_color(color: Color) -> Self {
+        Rgba4444(
+            shrink(color.r, 4) << 12
+                | shrink(color.g, 4) << 8
+                | shrink(color.b, 4) << 4
+                | shrink(color.a, 4),
+        )
+    }
+}
+
+unsafe impl Pixel for R32f {
+    const FORMAT: PixelFormat = PixelFormat::PIXELFORMAT_UNCOMPRESSED_R32;
+
+    fn to_color(self) -> Color {
+        let value = unit_to_u8(self.0);
+        Color::new(value, value, value, 255)
+    }
+
+    fn from_color(color: Color) -> Self {
+        R32f(u8_to_unit(luminance(color)))
+    }
+}
+
+unsafe impl Pixel for Rgb32f {
+    const FORMAT: PixelFormat = PixelFormat::PIXELFORMAT_UNCOMPRESSED_R32G32B32;
+
+    fn to_color(self) -> Color {
+        Color::new(
+            unit_to_u8(self.r),
+            unit_to_u8(self.g),
+            unit_to_u8(self.b),
+            255,
+        )
+    }
+
+    fn from_color(color: Color) -> Self {
+        Rgb32f {
+            r: u8_to_unit(color.r),
+            g: u8_to_unit(color.g),
+            b: u8_to_unit(color.b),
+        }
+    }
+}
+
+unsafe impl Pixel for Rgba32f {
+    const FORMAT: PixelFormat = PixelFormat::PIXELFORMAT_UNCOMPRESSED_R32G32B32A32;
+
+    fn to_color(self) -> Color {
+        Color::new(
+            unit_to_u8(self.r),
+            unit_to_u8(self.g),
+            unit_to_u8(self.b),
+            unit_to_u8(self.a),
+        )
+    }
+
+    fn from_color(color: Color) -> Self {
+        Rgba32f {
+            r: u8_to_unit(color.r),
+            g: u8_to_unit(color.g),
+            b: u8_to_unit(color.b),
+            a: u8_to_unit(color.a),
+        }
+    }
+}
+
+unsafe impl Pixel for R16f {
+    const FORMAT: PixelFormat = PixelFormat::PIXELFORMAT_UNCOMPRESSED_R16;
+
+    fn to_color(self) -> Color {
+        R32f(self.value()).to_color()
+    }
+
+    fn from_color(color: Color) -> Self {
+        R16f::new(R32f::from_color(color).0)
+    }
+}
+
+unsafe impl Pixel for Rgb16f {
+    const FORMAT: PixelFormat = PixelFormat::PIXELFORMAT_UNCOMPRESSED_R16G16B16;
+
+    fn to_color(self) -> Color {
+        let [r, g, b] = self.to_array();
+        Rgb32f { r, g, b }.to_color()
+    }
+
+    fn from_color(color: Color) -> Self {
+        let Rgb32f { r, g, b } = Rgb32f::from_color(color);
+        Rgb16f::new(r, g, b)
+    }
+}
+
+unsafe impl Pixel for Rgba16f {
+    const FORMAT: PixelFormat = PixelFormat::PIXELFORMAT_UNCOMPRESSED_R16G16B16A16;
+
+    fn to_color(self) -> Color {
+        let [r, g, b, a] = self.to_array();
+        Rgba32f { r, g, b, a }.to_color()
+    }
+
+    fn from_color(color: Color) -> Self {
+        let Rgba32f { r, g, b, a } = Rgba32f::from_color(color);
+        Rgba16f::new(r, g, b, a)
+    }
+}
+
+/// Pixel bounds of `rec` inside a `width` x `height` area.
+fn view_bounds(
+    rec: ffi::Rectangle,
+    width: usize,
+    height: usize,
+) -> Result<(usize, usize, usize, usize), Error> {
+    let (x, y, w, h) = (
+        rec.x as i64,
+        rec.y as i64,
+        rec.width as i64,
+        rec.height as i64,
+    );
+    if x < 0 || y < 0 || w < 0 || h < 0 || x + w > width as i64 || y + h > height as i64 {
+        return Err(error!(Cow::Owned(format!(
+            "View rectangle {}x{} at ({}, {}) is outside the {}x{} image.",
+            w, h, x, y, width, height
+        ))));
+    }
+    Ok((x as usize, y as usize, w as usize, h as usize))
+}
+
+/// Number of elements from the first pixel of a view to its last.
+fn view_len(width: usize, height: usize, stride: usize) -> usize {
+    if width == 0 || height == 0 {
+        0
+    } else {
+        (height - 1) * stride + width
+    }
+}
+
+/// Elements of a `stride` wide buffer covered by the `width` x `height` rectangle at `(x, y)`.
+fn view_range(x: usize, y: usize, width: usize, height: usize, stride: usize) -> Range<usize> {
+    let len = view_len(width, height, stride);
+    if len == 0 {
+        return 0..0;
+    }
+    let start = y * stride + x;
+    start..start + len
+}
+
+/// A rectangle of pixels borrowed from an [`Image`], read-only.
+#[derive(Debug, Clone, Copy)]
+pub struct ImageView<'a, P: Pixel> {
+    data: &'a [P],
+    width: usize,
+    height: usize,
+    stride: usize,
+}
+
+/// A rectangle of pixels borrowed mutably from an [`Image`].
+#[derive(Debug)]
+pub struct ImageViewMut<'a, P: Pixel> {
+    data: &'a mut [P],
+    width: usize,
+    height: usize,
+    stride: usize,
+}
+
+impl<'a, P: Pixel> ImageView<'a, P> {
+    /// A view of a `width` x `height` rectangle in `data`, whose rows start `stride` pixels
+    /// apart.
+    pub fn new(data: &'a [P], width: usize, height: usize, stride: usize) -> Result<Self, Error> {
+        if width > stride || data.len() < view_len(width, height, stride) {
+            return Err(error!("Pixel data is too short for the view."));
+        }
+        Ok(ImageView {
+            data: &data[..view_len(width, height, stride)],
+            width,
+            height,
+            stride,
+        })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Distance between the starts of two rows, in pixels.
+    pub fn stride(&self) -> usize {
+        self.stride
+    }
+
+    /// Pixel at `(x, y)`, or `None` outside the view.
+    pub fn get(&self, x: usize, y: usize) -> Option<P> {
+        if x < self.width && y < self.height {
+            Some(self.data[y * self.stride + x])
+        } else {
+            None
+        }
+    }
+
+    /// Row `y`.
+    ///
+    /// # Panics
+    ///
+    /// If `y` is not less than the height.
+    pub fn row(&self, y: usize) -> &'a [P] {
+        assert!(y < self.height, "row {} of a view {} high", y, self.height);
+        let start = y * self.stride;
+        &self.data[start..start + self.width]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &'a [P]> + '_ {
+        (0..self.height).map(move |y| self.row(y))
+    }
+
+    /// A view of `rec`, relative to this view.
+    pub fn view(&self, rec: impl Into<ffi::Rectangle>) -> Result<ImageView<'a, P>, Error> {
+        let (x, y, width, height) = view_bounds(rec.into(), self.width, self.height)?;
+        Ok(ImageView {
+            data: &self.data[view_range(x, y, width, height, self.stride)],
+            width,
+            height,
+            stride: self.stride,
+        })
+    }
+
+    /// Copies the pixels into a new, tightly packed `Vec`.
+    pub fn to_vec(&self) -> Vec<P> {
+        let mut pixels = Vec::with_capacity(self.width * self.height);
+        for row in self.rows() {
+            pixels.extend_from_slice(row);
+        }
+        pixels
+    }
+}
+
+impl<'a, P: Pixel> ImageViewMut<'a, P> {
+    /// A mutable view of a `width` x `height` rectangle in `data`, whose rows start `stride`
+    /// pixels apart.
+    pub fn new(
+        data: &'a mut [P],
+        width: usize,
+        height: usize,
+        stride: usize,
+    ) -> Result<Self, Error> {
+        let len = view_len(width, height, stride);
+        if width > stride || data.len() < len {
+            return Err(error!("Pixel data is too short for the view."));
+        }
+        Ok(ImageViewMut {
+            data: &mut data[..len],
+            width,
+            height,
+            stride,
+        })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Distance between the starts of two rows, in pixels.
+    pub fn stride(&self) -> usize {
+        self.stride
+    }
+
+    /// Reborrows as a read-only view.
+    pub fn as_view(&self) -> ImageView<'_, P> {
+        ImageView {
+            data: self.data,
+            width: self.width,
+            height: self.height,
+            stride: self.stride,
+        }
+    }
+
+    /// Pixel at `(x, y)`, or `None` outside the view.
+    pub fn get(&self, x: usize, y: usize) -> Option<P> {
+        self.as_view().get(x, y)
+    }
+
+    pub fn get_mut(&mut self, x: usize, y: usize) -> Option<&mut P> {
+        if x < self.width && y < self.height {
+            Some(&mut self.data[y * self.stride + x])
+        } else {
+            None
+        }
+    }
+
+    /// Sets the pixel at `(x, y)`, if it is inside the view.
+    pub fn set(&mut self, x: usize, y: usize, pixel: P) {
+        if let Some(p) = self.get_mut(x, y) {
+            *p = pixel;
+        }
+    }
+
+    /// Row `y`.
+    ///
+    /// # Panics
+    ///
+    /// If `y` is not less than the height.
+    pub fn row(&self, y: usize) -> &[P] {
+        assert!(y < self.height, "row {} of a view {} high", y, self.height);
+        let start = y * self.stride;
+        &self.data[start..start + self.width]
+    }
+
+    /// Row `y`, mutably.
+    ///
+    /// # Panics
+    ///
+    /// If `y` is not less than the height.
+    pub fn row_mut(&mut self, y: usize) -> &mut [P] {
+        assert!(y < self.height, "row {} of a view {} high", y, self.height);
+        let start = y * self.stride;
+        &mut self.data[start..start + self.width]
+    }
+
+    pub fn rows_mut(&mut self) -> impl Iterator<Item = &mut [P]> + '_ {
+        let width = self.width;
+        self.data
+            .chunks_mut(self.stride.max(1))
+            .map(move |row| &mut row[..width])
+    }
+
+    /// Sets every pixel of the view to `pixel`.
+    pub fn fill(&mut self, pixel: P) {
+        for row in self.rows_mut() {
+            row.fill(pixel);
+        }
+    }
+
+    /// Copies `source`, which must be the same size, into the view.
+    pub fn copy_from(&mut self, source: &ImageView<'_, P>) -> Result<(), Error> {
+        if source.width != self.width || source.height != self.height {
+            return Err(error!("Views passed to copy_from differ in size."));
+        }
+        for (y, row) in self.rows_mut().enumerate() {
+            row.copy_from_slice(source.row(y));
+        }
+        Ok(())
+    }
+
+    /// A mutable view of `rec`, relative to this view.
+    pub fn view_mut(
+        &mut self,
+        rec: impl Into<ffi::Rectangle>,
+    ) -> Result<ImageViewMut<'_, P>, Error> {
+        let (x, y, width, height) = view_bounds(rec.into(), self.width, self.height)?;
+        Ok(ImageViewMut {
+            data: &mut self.data[view_range(x, y, width, height, self.stride)],
+            width,
+            height,
+            stride: self.stride,
+        })
+    }
+
+    /// Turns into a mutable view of `rec`, keeping the original borrow.
+    pub fn into_view_mut(
+        self,
+        rec: impl Into<ffi::Rectangle>,
+    ) -> Result<ImageViewMut<'a, P>, Error> {
+        let (x, y, width, height) = view_bounds(rec.into(), self.width, self.height)?;
+        Ok(ImageViewMut {
+            data: &mut self.data[view_range(x, y, width, height, self.stride)],
+            width,
+            height,
+            stride: self.stride,
+        })
+    }
+}
+
+impl Image {
+    /// Checks that the image holds `P` pixels and returns where they start and how many
+    /// there are, not counting mipmaps.
+    fn pixel_data<P: Pixel>(&self) -> Result<(*mut P, usize), Error> {
+        if self.0.format != P::FORMAT as i32 {
+            return Err(error!(Cow::Owned(format!(
+                "Image format is {:?}, not {:?}.",
+                self.format(),
+                P::FORMAT
+            ))));
+        }
+        let len = self.0.width.max(0) as usize * self.0.height.max(0) as usize;
+        let data = self.0.data as *mut P;
+        if len == 0 {
+            return Ok((std::ptr::NonNull::dangling().as_ptr(), 0));
+        }
+        if data.is_null() || data.align_offset(std::mem::align_of::<P>()) != 0 {
+            return Err(error!("Image data is missing or misaligned."));
+        }
+        Ok((data, len))
+    }
+
+    /// The pixels of the image, row by row, without copying them.
+    ///
+    /// Fails if the image is not in `P`'s [`PixelFormat`]; convert it first with
+    /// [`Image::set_format`].
+    pub fn pixels<P: Pixel>(&self) -> Result<&[P], Error> {
+        let (data, len) = self.pixel_data::<P>()?;
+        Ok(unsafe { std::slice::from_raw_parts(data, len) })
+    }
+
+    /// The pixels of the image, row by row, for modifying in place.
+    ///
+    /// Fails if the image is not in `P`'s [`PixelFormat`]; convert it first with
+    /// [`Image::set_format`].
+    pub fn pixels_mut<P: Pixel>(&mut self) -> Result<&mut [P], Error> {
+        let (data, len) = self.pixel_data::<P>()?;
+        Ok(unsafe { std::slice::from_raw_parts_mut(data, len) })
+    }
+
+    /// A view of the pixels in `rec`.
+    pub fn view<P: Pixel>(
+        &self,
+        rec: impl Into<ffi::Rectangle>,
+    ) -> Result<ImageView<'_, P>, Error> {
+        let width = self.0.width.max(0) as usize;
+        ImageView::new(self.pixels()?, width, self.0.height.max(0) as usize, width)?.view(rec)
+    }
+
+    /// A mutable view of the pixels in `rec`.
+    pub fn view_mut<P: Pixel>(
+        &mut self,
+        rec: impl Into<ffi::Rectangle>,
+    ) -> Result<ImageViewMut<'_, P>, Error> {
+        let (width, height) = (self.0.width.max(0) as usize, self.0.height.max(0) as usize);
+        ImageViewMut::new(self.pixels_mut()?, width, height, width)?.into_view_mut(rec)
+    }
+}
+
+impl Image {
+    /// Creates an image by copying `data`, which must hold exactly one `width` x `height`
+    /// image in `format`, into memory allocated by raylib, so raylib can resize and free it.
+    pub fn from_pixels(
+        width: i32,
+        height: i32,
+        format: PixelFormat,
+        data: &[u8],
+    ) -> Result<Image, Error> {
+        if width <= 0 || height <= 0 {
+            return Err(error!("Image size must be positive."));
+        }
+        let size = unsafe { ffi::GetPixelDataSize(width, height, format as i32) } as usize;
+        if data.len() != size {
+            return Err(error!(Cow::Owned(format!(
+                "A {}x{} {:?} image takes {} bytes, not {}.",
+                width,
+                height,
+                format,
+                size,
+                data.len()
+            ))));
+        }
+        let alloc_size = size
+            .try_into()
+            .map_err(|_| error!("Image data is too large to allocate."))?;
+        unsafe {
+            let buffer = ffi::MemAlloc(alloc_size) as *mut u8;
+            if buffer.is_null() {
+                return Err(error!("Could not allocate image data."));
+            }
+            std::ptr::copy_nonoverlapping(data.as_ptr(), buffer, size);
+            Ok(Image(ffi::Image {
+                data: buffer as *mut c_void,
+                width,
+                height,
+                mipmaps: 1,
+                format: format as i32,
+            }))
+        }
+    }
+
+    /// Creates an image in `P`'s format by copying `pixels`, row by row.
+    pub fn from_pixel_slice<P: Pixel>(
+        width: i32,
+        height: i32,
+        pixels: &[P],
+    ) -> Result<Image, Error> {
+        // `Pixel` types have no padding, so their bytes are all initialized
+        let bytes = unsafe {
+            std::slice::from_raw_parts(pixels.as_ptr() as *const u8, std::mem::size_of_val(pixels))
+        };
+        Image::from_pixels(width, height, P::FORMAT, bytes)
+    }
+
+    /// Size of the image data in bytes, mipmaps included.
+    fn data_size(&self) -> usize {
+        if self.0.data.is_null() {
+            return 0;
+        }
+        (0..self.0.mipmaps.max(1))
+            .map(|level| unsafe {
+                ffi::GetPixelDataSize(
+                    (self.0.width >> level).max(1),
+                    (self.0.height >> level).max(1),
+                    self.0.format,
+                ) as usize
+            })
+            .sum()
+    }
+
+    /// The raw image data without copying it, followed by its mipmaps, if any.
+    pub fn as_bytes(&self) -> &[u8] {
+        match self.data_size() {
+            0 => &[],
+            size => unsafe { std::slice::from_raw_parts(self.0.data as *const u8, size) },
+        }
+    }
+
+    /// The raw image data, followed by its mipmaps, for modifying in place.
+    pub fn as_bytes_mut(&mut self) -> &mut [u8] {
+        match self.data_size() {
+            0 => &mut [],
+            size => unsafe { std::slice::from_raw_parts_mut(self.0.data as *mut u8, size) },
+        }
+    }
+
+    /// The image in `format`, without converting if it already is.
+    #[cfg(feature = "image")]
+    fn in_format(&self, format: PixelFormat) -> Result<Cow<'_, Image>, Error> {
+        if self.format() == format {
+            return Ok(Cow::Borrowed(self));
+        }
+        if self.format() as i32 >= PixelFormat::PIXELFORMAT_COMPRESSED_DXT1_RGB as i32 {
+            return Err(error!("Compressed images can't be converted."));
+        }
+        let mut converted = self.clone();
+        converted.set_format(format);
+        Ok(Cow::Owned(converted))
+    }
+}
+
+#[cfg(feature = "image")]
+impl TryFrom<&image::RgbaImage> for Image {
+    type Error = Error;
+
+    fn try_from(rgba: &image::RgbaImage) -> Result<Self, Error> {
+        Image::from_pixels(
+            rgba.width() as i32,
+            rgba.height() as i32,
+            PixelFormat::PIXELFORMAT_UNCOMPRESSED_R8G8B8A8,
+            rgba.as_raw(),
+        )
+    }
+}
+
+/// Keeps the channels and precision of the image where raylib has a matching format, and
+/// converts the rest to 8 or 32 bit RGBA.
+#[cfg(feature = "image")]
+impl TryFrom<&image::DynamicImage> for Image {
+    type Error = Error;
+
+    fn try_from(dynamic: &image::DynamicImage) -> Result<Self, Error> {
+        use image::DynamicImage;
+        use PixelFormat::*;
+        let (width, height) = (dynamic.width() as i32, dynamic.height() as i32);
+        let floats =
+            |data: &[f32]| -> Vec<u8> { data.iter().flat_map(|f| f.to_ne_bytes()).collect() };
+        match dynamic {
+            DynamicImage::ImageLuma8(buffer) => {
+                Image::from_pixels(width, height, PIXELFORMAT_UNCOMPRESSED_GRAYSCALE, buffer)
+            }
+            DynamicImage::ImageLumaA8(buffer) => {
+                Image::from_pixels(width, height, PIXELFORMAT_UNCOMPRESSED_GRAY_ALPHA, buffer)
+            }
+            DynamicImage::ImageRgb8(buffer) => {
+                Image::from_pixels(width, height, PIXELFORMAT_UNCOMPRESSED_R8G8B8, buffer)
+            }
+            DynamicImage::ImageRgba8(buffer) => {
+                Image::from_pixels(width, height, PIXELFORMAT_UNCOMPRESSED_R8G8B8A8, buffer)
+            }
+            DynamicImage::ImageRgb32F(buffer) => Image::from_pixels(
+                width,
+                height,
+                PIXELFORMAT_UNCOMPRESSED_R32G32B32,
+                &floats(buffer),
+            ),
+            DynamicImage::ImageRgba32F(buffer) => Image::from_pixels(
+                width,
+                height,
+                PIXELFORMAT_UNCOMPRESSED_R32G32B32A32,
+                &floats(buffer),
+            ),
+            // 16 bit channels would lose precision as 8 bit
+            DynamicImage::ImageLuma16(_)
+            | DynamicImage::ImageLumaA16(_)
+            | DynamicImage::ImageRgb16(_)
+            | DynamicImage::ImageRgba16(_) => Image::from_pixels(
+                width,
+                height,
+                PIXELFORMAT_UNCOMPRESSED_R32G32B32A32,
+                &floats(&dynamic.to_rgba32f()),
+            ),
+            _ => Image::try_from(&dynamic.to_rgba8()),
+        }
+    }
+}
+
+/// Converts any uncompressed image to 8 bit RGBA.
+#[cfg(feature = "image")]
+impl TryFrom<&Image> for image::RgbaImage {
+    type Error = Error;
+
+    fn try_from(image: &Image) -> Result<Self, Error> {
+        let rgba = image.in_format(PixelFormat::PIXELFORMAT_UNCOMPRESSED_R8G8B8A8)?;
+        let bytes = &rgba.as_bytes()[..rgba.pixels::<Rgba8>()?.len() * 4];
+        image::RgbaImage::from_raw(image.width() as u32, image.height() as u32, bytes.to_vec())
+            .ok_or(error!("Image data doesn't match its size."))
+    }
+}
+
+/// Keeps the channels and precision of 8 bit gray, RGB and RGBA and of 32 bit float RGB and
+/// RGBA images, and converts other uncompressed formats to 8 bit RGBA, or 32 bit float RGBA
+/// for float formats.
+#[cfg(feature = "image")]
+impl TryFrom<&Image> for image::DynamicImage {
+    type Error = Error;
+
+    fn try_from(source: &Image) -> Result<Self, Error> {
+        use image::{DynamicImage, ImageBuffer};
+        use PixelFormat::*;
+        let (width, height) = (source.width() as u32, source.height() as u32);
+        let invalid = || error!("Image data doesn't match its size.");
+        let bytes = |image: &Image, bytes_per_pixel: usize| {
+            image.as_bytes()[..width as usize * height as usize * bytes_per_pixel].to_vec()
+        };
+        let floats = |image: &Image| -> Result<Vec<f32>, Error> {
+            let pixels = image.pixels::<Rgba32f>()?;
+            Ok(pixels.iter().flat_map(|p| [p.r, p.g, p.b, p.a]).collect())
+        };
+        Ok(match source.format() {
+            PIXELFORMAT_UNCOMPRESSED_GRAYSCALE => DynamicImage::ImageLuma8(
+                ImageBuffer::from_raw(width, height, bytes(source, 1)).ok_or_else(invalid)?,
+            ),
+            PIXELFORMAT_UNCOMPRESSED_GRAY_ALPHA => DynamicImage::ImageLumaA8(
+                ImageBuffer::from_raw(width, height, bytes(source, 2)).ok_or_else(invalid)?,
+            ),
+            PIXELFORMAT_UNCOMPRESSED_R8G8B8 => DynamicImage::ImageRgb8(
+                ImageBuffer::from_raw(width, height, bytes(source, 3)).ok_or_else(invalid)?,
+            ),
+            PIXELFORMAT_UNCOMPRESSED_R32G32B32 => {
+                let pixels = source.pixels::<Rgb32f>()?;
+                let data = pixels.iter().flat_map(|p| [p.r, p.g, p.b]).collect();
+                DynamicImage::ImageRgb32F(
+                    ImageBuffer::from_raw(width, height, data).ok_or_else(invalid)?,
+                )
+            }
+            PIXELFORMAT_UNCOMPRESSED_R32
+            | PIXELFORMAT_UNCOMPRESSED_R32G32B32A32
+            | PIXELFORMAT_UNCOMPRESSED_R16
+            | PIXELFORMAT_UNCOMPRESSED_R16G16B16
+            | PIXELFORMAT_UNCOMPRESSED_R16G16B16A16 => {
+                let rgba = source.in_format(PIXELFORMAT_UNCOMPRESSED_R32G32B32A32)?;
+                DynamicImage::ImageRgba32F(
+                    ImageBuffer::from_raw(width, height, floats(&rgba)?).ok_or_else(invalid)?,
+                )
+            }
+            _ => DynamicImage::ImageRgba8(image::RgbaImage::try_from(source)?),
+        })
+    }
+}
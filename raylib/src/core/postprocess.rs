@@ -0,0 +1,314 @@
+//! Chain full-screen [`Shader`] passes over a rendered scene
+use crate::core::color::Color;
+use crate::core::drawing::{
+    RaylibDraw, RaylibDrawHandle, RaylibShaderModeExt, RaylibTextureModeExt,
+};
+use crate::core::math::{Rectangle, Vector2};
+use crate::core::shaders::{RaylibShader, Shader};
+use crate::core::texture::RenderTexture2D;
+use crate::core::{RaylibHandle, RaylibThread};
+use crate::error::Error;
+use crate::ffi;
+
+/// A single shader pass inside a [`PostProcessStack`].
+#[derive(Debug)]
+pub struct PostProcessPass {
+    name: String,
+    shader: Shader,
+    enabled: bool,
+    resolution_loc: i32,
+    time_loc: i32,
+    previous_loc: i32,
+    scene_loc: i32,
+}
+
+impl PostProcessPass {
+    fn new(name: &str, shader: Shader) -> PostProcessPass {
+        PostProcessPass {
+            name: name.to_owned(),
+            resolution_loc: shader.get_shader_location(PostProcessStack::RESOLUTION_UNIFORM),
+            time_loc: shader.get_shader_location(PostProcessStack::TIME_UNIFORM),
+            previous_loc: shader.get_shader_location(PostProcessStack::PREVIOUS_PASS_UNIFORM),
+            scene_loc: shader.get_shader_location(PostProcessStack::SCENE_UNIFORM),
+            shader,
+            enabled: true,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    /// Flips the pass on or off and returns the new state.
+    pub fn toggle(&mut self) -> bool {
+        self.enabled = !self.enabled;
+        self.enabled
+    }
+
+    pub fn shader(&self) -> &Shader {
+        &self.shader
+    }
+
+    /// Access the shader to set custom uniforms. Values persist between frames.
+    pub fn shader_mut(&mut self) -> &mut Shader {
+        &mut self.shader
+    }
+}
+
+/// Runs a scene through any number of full-screen [`Shader`] passes.
+///
+/// Draw the scene into [`PostProcessStack::scene_mut`], call [`PostProcessStack::apply`] and then
+/// present the result with [`PostProcessStack::present`] or [`PostProcessStack::present_to`].
+/// Passes ping-pong between two internal render textures that follow the window size
+/// (see [`PostProcessStack::sync_with_window`]).
+///
+/// Every pass gets the `*_UNIFORM` values below if its shader declares them.
+///
+/// ```no_run
+/// use raylib::prelude::*;
+///
+/// let (mut rl, thread) = raylib::init().size(800, 450).resizable().build();
+/// let mut stack = PostProcessStack::new(&mut rl, &thread, 800, 450).unwrap();
+/// let grayscale = rl.load_shader(&thread, None, Some("grayscale.fs"));
+/// let bloom = rl.load_shader(&thread, None, Some("bloom.fs"));
+/// stack.add_pass("grayscale", grayscale);
+/// stack.add_pass("bloom", bloom);
+///
+/// while !rl.window_should_close() {
+///     if rl.is_key_pressed(KeyboardKey::KEY_G) {
+///         stack.pass_mut("grayscale").unwrap().toggle();
+///     }
+///     stack.sync_with_window(&mut rl, &thread).unwrap();
+///
+///     let mut d = rl.begin_drawing(&thread);
+///     {
+///         let mut s = d.begin_texture_mode(&thread, stack.scene_mut());
+///         s.clear_background(Color::RAYWHITE);
+///         s.draw_circle(400, 225, 100.0, Color::RED);
+///     }
+///     stack.apply(&mut d, &thread);
+///     stack.present(&mut d);
+/// }
+/// ```
+#[derive(Debug)]
+pub struct PostProcessStack {
+    passes: Vec<PostProcessPass>,
+    scene: RenderTexture2D,
+    targets: [RenderTexture2D; 2],
+    /// Which texture holds the result of the last `apply`. `None` means the scene itself.
+    output: Option<usize>,
+    width: u32,
+    height: u32,
+}
+
+impl PostProcessStack {
+    /// `vec2` size of the render targets in pixels.
+    pub const RESOLUTION_UNIFORM: &'static str = "resolution";
+    /// `float` seconds since the window was opened.
+    pub const TIME_UNIFORM: &'static str = "time";
+    /// `sampler2D` output of the previous pass. Same texture as `texture0`.
+    pub const PREVIOUS_PASS_UNIFORM: &'static str = "previousPass";
+    /// `sampler2D` the scene as it was drawn, before any pass ran.
+    pub const SCENE_UNIFORM: &'static str = "sceneTexture";
+
+    /// Creates a stack with render targets of `width` x `height` pixels.
+    pub fn new(
+        rl: &mut RaylibHandle,
+        thread: &RaylibThread,
+        width: u32,
+        height: u32,
+    ) -> Result<PostProcessStack, Error> {
+        Ok(PostProcessStack {
+            passes: Vec::new(),
+            scene: rl.load_render_texture(thread, width, height)?,
+            targets: [
+                rl.load_render_texture(thread, width, height)?,
+                rl.load_render_texture(thread, width, height)?,
+            ],
+            output: None,
+            width,
+            height,
+        })
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Recreates the render targets at a new size. Their contents are lost.
+    pub fn resize(
+        &mut self,
+        rl: &mut RaylibHandle,
+        thread: &RaylibThread,
+        width: u32,
+        height: u32,
+    ) -> Result<(), Error> {
+        if width == self.width && height == self.height {
+            return Ok(());
+        }
+        self.scene = rl.load_render_texture(thread, width, height)?;
+        self.targets = [
+            rl.load_render_texture(thread, width, height)?,
+            rl.load_render_texture(thread, width, height)?,
+        ];
+        self.output = None;
+        self.width = width;
+        self.height = height;
+        Ok(())
+    }
+
+    /// Resizes the render targets to the current render size of the window, if it changed.
+    pub fn sync_with_window(
+        &mut self,
+        rl: &mut RaylibHandle,
+        thread: &RaylibThread,
+    ) -> Result<(), Error> {
+        let width = rl.get_render_width().max(1) as u32;
+        let height = rl.get_render_height().max(1) as u32;
+        self.resize(rl, thread, width, height)
+    }
+
+    /// Appends a pass to the end of the chain. Passes start enabled.
+    pub fn add_pass(&mut self, name: &str, shader: Shader) -> &mut PostProcessPass {
+        self.passes.push(PostProcessPass::new(name, shader));
+        self.passes.last_mut().unwrap()
+    }
+
+    /// Removes the first pass called `name` and hands back its shader.
+    pub fn remove_pass(&mut self, name: &str) -> Option<Shader> {
+        let index = self.passes.iter().position(|p| p.name == name)?;
+        Some(self.passes.remove(index).shader)
+    }
+
+    pub fn pass(&self, name: &str) -> Option<&PostProcessPass> {
+        self.passes.iter().find(|p| p.name == name)
+    }
+
+    pub fn pass_mut(&mut self, name: &str) -> Option<&mut PostProcessPass> {
+        self.passes.iter_mut().find(|p| p.name == name)
+    }
+
+    /// All passes in the order they run.
+    pub fn passes(&self) -> &[PostProcessPass] {
+        &self.passes
+    }
+
+    pub fn passes_mut(&mut self) -> &mut [PostProcessPass] {
+        &mut self.passes
+    }
+
+    /// The render texture the scene should be drawn into before calling [`PostProcessStack::apply`].
+    pub fn scene_mut(&mut self) -> &mut RenderTexture2D {
+        &mut self.scene
+    }
+
+    /// The texture holding the result of the last [`PostProcessStack::apply`].
+    pub fn output(&self) -> &RenderTexture2D {
+        match self.output {
+            Some(i) => &self.targets[i],
+            None => &self.scene,
+        }
+    }
+
+    /// Runs every enabled pass over the scene texture.
+    pub fn apply(&mut self, d: &mut RaylibDrawHandle, thread: &RaylibThread) {
+        let time = d.get_time() as f32;
+        let resolution = Vector2::new(self.width as f32, self.height as f32);
+        let source_rec = flipped_rec(self.width, self.height);
+
+        let scene = &self.scene;
+        let targets = &mut self.targets;
+        let mut output: Option<usize> = None;
+
+        for pass in self.passes.iter_mut().filter(|p| p.enabled) {
+            let dst_index = match output {
+                Some(i) => 1 - i,
+                None => 0,
+            };
+            let (first, second) = targets.split_at_mut(1);
+            let (src, dst) = match (output, dst_index) {
+                (None, _) => (scene, &mut first[0]),
+                (Some(_), 0) => (&second[0], &mut first[0]),
+                (Some(_), _) => (&first[0], &mut second[0]),
+            };
+
+            if pass.resolution_loc >= 0 {
+                pass.shader
+                    .set_shader_value(pass.resolution_loc, resolution);
+            }
+            if pass.time_loc >= 0 {
+                pass.shader.set_shader_value(pass.time_loc, time);
+            }
+            if pass.previous_loc >= 0 {
+                pass.shader.set_shader_value_texture(pass.previous_loc, src);
+            }
+            if pass.scene_loc >= 0 {
+                pass.shader.set_shader_value_texture(pass.scene_loc, scene);
+            }
+
+            {
+                let mut t = d.begin_texture_mode(thread, dst);
+                t.clear_background(Color::BLANK);
+                let mut s = t.begin_shader_mode(&mut pass.shader);
+                s.draw_texture_rec(src, source_rec, Vector2::zero(), Color::WHITE);
+            }
+
+            output = Some(dst_index);
+        }
+
+        self.output = output;
+    }
+
+    /// Draws the final result over the whole screen.
+    pub fn present(&self, d: &mut impl RaylibDraw) {
+        let width = unsafe { ffi::GetScreenWidth() } as f32;
+        let height = unsafe { ffi::GetScreenHeight() } as f32;
+        self.present_rec(d, Rectangle::new(0.0, 0.0, width, height));
+    }
+
+    /// Draws the final result stretched into `dest` (screen space).
+    pub fn present_rec(&self, d: &mut impl RaylibDraw, dest: Rectangle) {
+        d.draw_texture_pro(
+            self.output(),
+            flipped_rec(self.width, self.height),
+            dest,
+            Vector2::zero(),
+            0.0,
+            Color::WHITE,
+        );
+    }
+
+    /// Draws the final result into a caller-owned render texture, stretched to fill it.
+    pub fn present_to(
+        &self,
+        d: &mut RaylibDrawHandle,
+        thread: &RaylibThread,
+        target: &mut RenderTexture2D,
+    ) {
+        let dest = Rectangle::new(
+            0.0,
+            0.0,
+            target.texture.width as f32,
+            target.texture.height as f32,
+        );
+        let mut t = d.begin_texture_mode(thread, target);
+        self.present_rec(&mut t, dest);
+    }
+}
+
+/// Render textures are stored upside down, so sample them with a negative height.
+fn flipped_rec(width: u32, height: u32) -> Rectangle {
+    Rectangle::new(0.0, 0.0, width as f32, -(height as f32))
+}
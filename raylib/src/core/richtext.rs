@@ -0,0 +1,660 @@
+//! Styled text from lightweight inline markup
+//!
+//! Tags are written in square brackets and closed with `[/tag]`:
+//!
+//! | Tag | Effect |
+//! |-----|--------|
+//! | `[color=red]`, `[color=#ff8800]`, `[color=#ff880080]` | text color |
+//! | `[size=24]` | font size |
+//! | `[b]`, `[i]` | bold and italic fonts of the [`FontSet`] |
+//! | `[wave]`, `[wave=6]` | glyphs bob up and down, optionally by that many pixels |
+//! | `[shake]`, `[shake=2]` | glyphs jitter, optionally by that many pixels |
+//! | `[icon=coin]` | an icon from the [`FontSet`] atlas, sized to the text. Has no closing tag |
+//!
+//! `[[` writes a literal `[`. Tags that don't parse, or close a tag that isn't open, are kept as text.
+//!
+//! ```no_run
+//! use raylib::prelude::*;
+//!
+//! let (mut rl, thread) = raylib::init().size(800, 450).build();
+//! let font = rl.get_font_default();
+//! let icons = rl.load_texture(&thread, "icons.png").unwrap();
+//! let mut fonts = FontSet::new(&font);
+//! fonts.set_icons(&icons).add_icon("coin", Rectangle::new(0.0, 0.0, 16.0, 16.0));
+//! fonts.size = 20.0;
+//!
+//! let mut d = rl.begin_drawing(&thread);
+//! d.draw_text_rich(
+//!     &fonts,
+//!     "You found [color=gold][wave]12 [icon=coin][/wave][/color]! [size=10](press E)[/size]",
+//!     Rectangle::new(20.0, 20.0, 300.0, 100.0),
+//! );
+//! ```
+use crate::core::color::Color;
+use crate::core::drawing::RaylibDraw;
+use crate::core::fontstack::{baseline, has_glyph, FontStack};
+use crate::core::math::{Rectangle, Vector2};
+use crate::core::text::{TextAlign, WeakFont};
+use crate::core::texture::WeakTexture2D;
+use crate::ffi;
+use std::collections::HashMap;
+use std::marker::PhantomData;
+
+/// Default [`TextEffect::Wave`] amplitude in pixels.
+const WAVE_AMPLITUDE: f32 = 3.0;
+/// Default [`TextEffect::Shake`] intensity in pixels.
+const SHAKE_INTENSITY: f32 = 1.0;
+
+/// Animation applied to glyphs when they are drawn.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum TextEffect {
+    None,
+    /// Glyphs follow a sine wave moving along the text.
+    Wave {
+        amplitude: f32,
+    },
+    /// Glyphs jump around their position randomly.
+    Shake {
+        intensity: f32,
+    },
+}
+
+/// Style of a piece of rich text.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct RichStyle {
+    pub color: Color,
+    pub size: f32,
+    pub bold: bool,
+    pub italic: bool,
+    pub effect: TextEffect,
+}
+
+/// A piece of parsed markup with a single style.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RichSpan {
+    Text { text: String, style: RichStyle },
+    Icon { name: String, style: RichStyle },
+}
+
+/// The fonts, icons and defaults used to lay out and draw rich text.
+///
+/// Missing bold or italic fonts fall back to the regular one (and bold italic to bold, then italic).
+/// The fonts and icon atlas are borrowed, so they must outlive the set.
+#[derive(Clone)]
+pub struct FontSet<'a> {
+    regular: ffi::Font,
+    bold: Option<ffi::Font>,
+    italic: Option<ffi::Font>,
+    bold_italic: Option<ffi::Font>,
+    icons: Option<ffi::Texture2D>,
+    icon_recs: HashMap<String, Rectangle>,
+    fallback: Option<FontStack<'a>>,
+    /// Font size of text outside `[size]` tags
+    pub size: f32,
+    /// Space added after every glyph
+    pub spacing: f32,
+    /// Space added between lines
+    pub line_spacing: f32,
+    /// Color of text outside `[color]` tags
+    pub color: Color,
+    /// Horizontal alignment, [`TextAlign::Justify`] is treated as [`TextAlign::Left`]
+    pub align: TextAlign,
+    _borrows: PhantomData<&'a ffi::Font>,
+}
+
+impl<'a> FontSet<'a> {
+    /// Creates a set drawing with `regular` at its base size, in black and aligned left.
+    pub fn new(regular: &'a impl AsRef<ffi::Font>) -> Self {
+        let regular = *regular.as_ref();
+        FontSet {
+            regular,
+            bold: None,
+            italic: None,
+            bold_italic: None,
+            icons: None,
+            icon_recs: HashMap::new(),
+            fallback: None,
+            size: regular.baseSize as f32,
+            spacing: 1.0,
+            line_spacing: 2.0,
+            color: Color::BLACK,
+            align: TextAlign::Left,
+            _borrows: PhantomData,
+        }
+    }
+
+    pub fn set_bold(&mut self, font: &'a impl AsRef<ffi::Font>) -> &mut Self {
+        self.bold = Some(*font.as_ref());
+        self
+    }
+
+    pub fn set_italic(&mut self, font: &'a impl AsRef<ffi::Font>) -> &mut Self {
+        self.italic = Some(*font.as_ref());
+        self
+    }
+
+    pub fn set_bold_italic(&mut self, font: &'a impl AsRef<ffi::Font>) -> &mut Self {
+        self.bold_italic = Some(*font.as_ref());
+        self
+    }
+
+    /// Sets the texture atlas `[icon]` tags take their icons from.
+    pub fn set_icons(&mut self, atlas: &'a impl AsRef<ffi::Texture2D>) -> &mut Self {
+        self.icons = Some(*atlas.as_ref());
+        self
+    }
+
+    /// Names the part of the icon atlas at `source`. Unknown icons take no space and draw nothing.
+    pub fn add_icon(&mut self, name: impl Into<String>, source: impl Into<Rectangle>) -> &mut Self {
+        self.icon_recs.insert(name.into(), source.into());
+        self
+    }
+
+    /// Draws codepoints the font of their style has no glyph for with the first font in
+    /// `fonts` that has one, lined up with the baseline of the style's font.
+    pub fn set_fallback(&mut self, fonts: &FontStack<'a>) -> &mut Self {
+        self.fallback = Some(fonts.clone());
+        self
+    }
+
+    /// Style of text outside any tag.
+    pub fn base_style(&self) -> RichStyle {
+        RichStyle {
+            color: self.color,
+            size: self.size,
+            bold: false,
+            italic: false,
+            effect: TextEffect::None,
+        }
+    }
+
+    /// Font used for text in `style`.
+    pub fn font(&self, style: &RichStyle) -> WeakFont {
+        let font = match (style.bold, style.italic) {
+            (true, true) => self.bold_italic.or(self.bold).or(self.italic),
+            (true, false) => self.bold,
+            (false, true) => self.italic,
+            (false, false) => None,
+        };
+        WeakFont(font.unwrap_or(self.regular))
+    }
+
+    /// Font, codepoint, font size and vertical offset `codepoint` is drawn with in `style`.
+    fn glyph(&self, style: &RichStyle, codepoint: char) -> (WeakFont, char, f32, f32) {
+        let font = self.font(style);
+        match &self.fallback {
+            Some(stack) if !has_glyph(&font, codepoint) => {
+                let (index, codepoint) = stack.resolve(codepoint);
+                (
+                    stack.font(index).unwrap_or(font),
+                    codepoint,
+                    stack.size_of(index, style.size),
+                    stack.offset_to(baseline(&font), index, style.size),
+                )
+            }
+            _ => (font, codepoint, style.size, 0.0),
+        }
+    }
+
+    fn advance(&self, style: &RichStyle, codepoint: char) -> f32 {
+        if let Some(stack) = &self.fallback {
+            if !has_glyph(&self.font(style), codepoint) {
+                return stack.advance(codepoint, style.size) + self.spacing;
+            }
+        }
+        let font = self.font(style).0;
+        let index = unsafe { ffi::GetGlyphIndex(font, codepoint as i32) } as usize;
+        if font.glyphs.is_null() || index >= font.glyphCount as usize {
+            return 0.0;
+        }
+        let (glyph, rec) = unsafe { (*font.glyphs.add(index), *font.recs.add(index)) };
+        let advance = if glyph.advanceX == 0 {
+            rec.width
+        } else {
+            glyph.advanceX as f32
+        };
+        advance * style.size / font.baseSize as f32 + self.spacing
+    }
+}
+
+/// Parsed markup, ready to be laid out.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct RichText {
+    spans: Vec<RichSpan>,
+}
+
+impl RichText {
+    /// Parses `markup`, starting from the `base` style.
+    pub fn parse(markup: &str, base: RichStyle) -> RichText {
+        let mut spans = Vec::new();
+        let mut text = String::new();
+        let mut style = base;
+        // open tags and the style from before each of them
+        let mut open: Vec<(&str, RichStyle)> = Vec::new();
+
+        let mut rest = markup;
+        while let Some(start) = rest.find('[') {
+            text.push_str(&rest[..start]);
+            rest = &rest[start..];
+            if rest.starts_with("[[") {
+                text.push('[');
+                rest = &rest[2..];
+                continue;
+            }
+            let end = match rest.find(']') {
+                Some(end) => end,
+                None => break,
+            };
+            let tag = &rest[1..end];
+
+            if let Some(name) = tag.strip_prefix('/') {
+                match open
+                    .iter()
+                    .rposition(|(open_name, _)| *open_name == name.trim())
+                {
+                    Some(i) => {
+                        flush(&mut spans, &mut text, style);
+                        style = open[i].1;
+                        open.truncate(i);
+                    }
+                    None => text.push_str(&rest[..=end]),
+                }
+            } else {
+                let (name, value) = match tag.split_once('=') {
+                    Some((name, value)) => (name.trim(), Some(value.trim())),
+                    None => (tag.trim(), None),
+                };
+                if let ("icon", Some(icon)) = (name, value) {
+                    flush(&mut spans, &mut text, style);
+                    spans.push(RichSpan::Icon {
+                        name: icon.to_owned(),
+                        style,
+                    });
+                } else {
+                    match apply_tag(style, name, value) {
+                        Some(tagged) => {
+                            flush(&mut spans, &mut text, style);
+                            open.push((name, style));
+                            style = tagged;
+                        }
+                        None => text.push_str(&rest[..=end]),
+                    }
+                }
+            }
+            rest = &rest[end + 1..];
+        }
+        text.push_str(rest);
+        flush(&mut spans, &mut text, style);
+
+        RichText { spans }
+    }
+
+    pub fn spans(&self) -> &[RichSpan] {
+        &self.spans
+    }
+
+    /// The text without any markup or icons.
+    pub fn plain_text(&self) -> String {
+        self.spans
+            .iter()
+            .filter_map(|span| match span {
+                RichSpan::Text { text, .. } => Some(text.as_str()),
+                RichSpan::Icon { .. } => None,
+            })
+            .collect()
+    }
+
+    /// Wraps the text at word boundaries to fit the width of `bounds` and places every glyph.
+    /// Lines that would go past the bottom of `bounds` are left out, except for the first one.
+    pub fn layout(&self, fonts: &FontSet, bounds: impl Into<Rectangle>) -> RichLayout {
+        let bounds = bounds.into();
+        let atoms = self.atoms(fonts);
+        let mut layout = RichLayout {
+            glyphs: Vec::new(),
+            lines: 0,
+            size: Vector2::zero(),
+            truncated: false,
+        };
+        let mut lines = LineBreaker {
+            fonts,
+            bounds,
+            atoms: &atoms,
+            layout: &mut layout,
+            y: 0.0,
+            line: Vec::new(),
+            width: 0.0,
+        };
+
+        let mut i = 0;
+        while i < atoms.len() && !lines.layout.truncated {
+            match atoms[i].kind {
+                AtomKind::Newline => {
+                    lines.finish();
+                    i += 1;
+                }
+                AtomKind::Space => i += 1,
+                _ => {
+                    // a word, and the spaces before it if it isn't at the start of a line
+                    let spaces_start = (0..i)
+                        .rev()
+                        .take_while(|&j| atoms[j].kind == AtomKind::Space)
+                        .last()
+                        .unwrap_or(i);
+                    let end = (i..atoms.len())
+                        .find(|&j| matches!(atoms[j].kind, AtomKind::Space | AtomKind::Newline))
+                        .unwrap_or(atoms.len());
+                    let spaces: f32 = atoms[spaces_start..i].iter().map(|a| a.advance).sum();
+                    let word: f32 = atoms[i..end].iter().map(|a| a.advance).sum();
+
+                    if lines.line.is_empty() {
+                        lines.push_word(i..end);
+                    } else if lines.width + spaces + word <= bounds.width {
+                        lines.push_word(spaces_start..end);
+                    } else {
+                        lines.finish();
+                        lines.push_word(i..end);
+                    }
+                    i = end;
+                }
+            }
+        }
+        if !lines.line.is_empty() {
+            lines.finish();
+        }
+        layout
+    }
+
+    fn atoms(&self, fonts: &FontSet) -> Vec<Atom> {
+        let mut atoms = Vec::new();
+        for span in &self.spans {
+            match span {
+                RichSpan::Text { text, style } => {
+                    for c in text.chars() {
+                        let (kind, advance) = match c {
+                            '\n' => (AtomKind::Newline, 0.0),
+                            '\t' => (AtomKind::Space, 4.0 * fonts.advance(style, ' ')),
+                            c if c.is_whitespace() => (AtomKind::Space, fonts.advance(style, c)),
+                            c => (AtomKind::Char(c), fonts.advance(style, c)),
+                        };
+                        atoms.push(Atom {
+                            kind,
+                            style: *style,
+                            advance,
+                            height: style.size,
+                        });
+                    }
+                }
+                RichSpan::Icon { name, style } => {
+                    if let Some(source) = fonts.icon_recs.get(name) {
+                        let width = source.width * style.size / source.height;
+                        atoms.push(Atom {
+                            kind: AtomKind::Icon(*source),
+                            style: *style,
+                            advance: width + fonts.spacing,
+                            height: style.size,
+                        });
+                    }
+                }
+            }
+        }
+        atoms
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+enum AtomKind {
+    Char(char),
+    Icon(Rectangle),
+    Space,
+    Newline,
+}
+
+#[derive(Debug, Copy, Clone)]
+struct Atom {
+    kind: AtomKind,
+    style: RichStyle,
+    advance: f32,
+    height: f32,
+}
+
+struct LineBreaker<'l, 'f> {
+    fonts: &'l FontSet<'f>,
+    bounds: Rectangle,
+    atoms: &'l [Atom],
+    layout: &'l mut RichLayout,
+    y: f32,
+    line: Vec<usize>,
+    width: f32,
+}
+
+impl<'l, 'f> LineBreaker<'l, 'f> {
+    fn push_word(&mut self, range: std::ops::Range<usize>) {
+        for i in range {
+            let advance = self.atoms[i].advance;
+            // words longer than a whole line are broken anywhere
+            if !self.line.is_empty() && self.width + advance > self.bounds.width {
+                self.finish();
+                if self.atoms[i].kind == AtomKind::Space {
+                    continue;
+                }
+            }
+            self.line.push(i);
+            self.width += advance;
+        }
+    }
+
+    fn finish(&mut self) {
+        let line = std::mem::take(&mut self.line);
+        let width = std::mem::replace(&mut self.width, 0.0);
+        let height = if line.is_empty() {
+            self.fonts.size
+        } else {
+            line.iter()
+                .map(|&i| self.atoms[i].height)
+                .fold(0.0, f32::max)
+        };
+        if self.layout.lines > 0 && self.y + height > self.bounds.height {
+            self.layout.truncated = true;
+            return;
+        }
+
+        let mut x = match self.fonts.align {
+            TextAlign::Left | TextAlign::Justify => 0.0,
+            TextAlign::Center => (self.bounds.width - width) * 0.5,
+            TextAlign::Right => self.bounds.width - width,
+        };
+        for &i in &line {
+            let atom = &self.atoms[i];
+            let position = Vector2::new(
+                self.bounds.x + x,
+                // glyphs of different sizes share the bottom of the line
+                self.bounds.y + self.y + height - atom.height,
+            );
+            let kind = match atom.kind {
+                AtomKind::Char(c) => Some(RichGlyphKind::Char(c)),
+                AtomKind::Icon(source) => Some(RichGlyphKind::Icon(source)),
+                AtomKind::Space | AtomKind::Newline => None,
+            };
+            if let Some(kind) = kind {
+                self.layout.glyphs.push(RichGlyph {
+                    kind,
+                    position,
+                    size: Vector2::new(atom.advance - self.fonts.spacing, atom.height),
+                    style: atom.style,
+                });
+            }
+            x += atom.advance;
+        }
+
+        self.layout.lines += 1;
+        self.layout.size.x = self.layout.size.x.max(width);
+        self.layout.size.y = self.y + height;
+        self.y += height + self.fonts.line_spacing;
+    }
+}
+
+/// What a [`RichGlyph`] draws.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum RichGlyphKind {
+    Char(char),
+    /// The source rectangle of an icon in the [`FontSet`] atlas
+    Icon(Rectangle),
+}
+
+/// A glyph or icon placed by [`RichText::layout`].
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct RichGlyph {
+    pub kind: RichGlyphKind,
+    /// Top-left corner, without effects
+    pub position: Vector2,
+    pub size: Vector2,
+    pub style: RichStyle,
+}
+
+/// Laid out rich text. Only depends on the text and the bounds, so it can be kept between frames.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct RichLayout {
+    pub glyphs: Vec<RichGlyph>,
+    /// Number of lines placed
+    pub lines: usize,
+    /// Size of the placed text, starting at the top-left corner of the bounds
+    pub size: Vector2,
+    /// Whether lines were dropped because they didn't fit the bounds
+    pub truncated: bool,
+}
+
+/// Rich text drawing for anything that implements [`RaylibDraw`].
+pub trait RaylibRichTextExt: RaylibDraw
+where
+    Self: Sized,
+{
+    /// Parses `markup` (see the [module docs](crate::core::richtext)), wraps it inside `bounds`
+    /// and draws it.
+    fn draw_text_rich(&mut self, fonts: &FontSet, markup: &str, bounds: impl Into<Rectangle>) {
+        let layout = RichText::parse(markup, fonts.base_style()).layout(fonts, bounds);
+        self.draw_rich_layout(fonts, &layout);
+    }
+
+    /// Draws text laid out with [`RichText::layout`] using the same `fonts`.
+    fn draw_rich_layout(&mut self, fonts: &FontSet, layout: &RichLayout) {
+        let time = unsafe { ffi::GetTime() } as f32;
+        for (i, glyph) in layout.glyphs.iter().enumerate() {
+            let position = glyph.position + effect_offset(glyph.style.effect, time, i);
+            match glyph.kind {
+                RichGlyphKind::Char(c) => {
+                    let (font, codepoint, size, offset) = fonts.glyph(&glyph.style, c);
+                    self.draw_text_codepoint(
+                        font,
+                        codepoint as i32,
+                        position + Vector2::new(0.0, offset),
+                        size,
+                        glyph.style.color,
+                    )
+                }
+                RichGlyphKind::Icon(source) => {
+                    if let Some(atlas) = fonts.icons {
+                        self.draw_texture_pro(
+                            WeakTexture2D(atlas),
+                            source,
+                            Rectangle::new(position.x, position.y, glyph.size.x, glyph.size.y),
+                            Vector2::zero(),
+                            0.0,
+                            Color::new(255, 255, 255, glyph.style.color.a),
+                        );
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl<D: RaylibDraw> RaylibRichTextExt for D {}
+
+fn flush(spans: &mut Vec<RichSpan>, text: &mut String, style: RichStyle) {
+    if !text.is_empty() {
+        spans.push(RichSpan::Text {
+            text: std::mem::take(text),
+            style,
+        });
+    }
+}
+
+/// Returns `style` changed by the tag `name`, or `None` if it isn't a valid style tag.
+fn apply_tag(mut style: RichStyle, name: &str, value: Option<&str>) -> Option<RichStyle> {
+    match (name, value) {
+        ("color", Some(value)) => style.color = parse_color(value)?,
+        ("size", Some(value)) => {
+            style.size = value.parse().ok().filter(|&size: &f32| size > 0.0)?;
+        }
+        ("b", None) => style.bold = true,
+        ("i", None) => style.italic = true,
+        ("wave", value) => {
+            let amplitude = value.map_or(Some(WAVE_AMPLITUDE), |v| v.parse().ok())?;
+            style.effect = TextEffect::Wave { amplitude };
+        }
+        ("shake", value) => {
+            let intensity = value.map_or(Some(SHAKE_INTENSITY), |v| v.parse().ok())?;
+            style.effect = TextEffect::Shake { intensity };
+        }
+        _ => return None,
+    }
+    Some(style)
+}
+
+/// Parses `#rrggbb`, `#rrggbbaa` or one of a few color names.
+fn parse_color(value: &str) -> Option<Color> {
+    if let Some(hex) = value.strip_prefix('#') {
+        let channel = |i: usize| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok();
+        return match hex.len() {
+            6 => Some(Color::new(channel(0)?, channel(2)?, channel(4)?, 255)),
+            8 => Some(Color::new(
+                channel(0)?,
+                channel(2)?,
+                channel(4)?,
+                channel(6)?,
+            )),
+            _ => None,
+        };
+    }
+    Some(match value.to_ascii_lowercase().as_str() {
+        "white" => Color::WHITE,
+        "black" => Color::BLACK,
+        "gray" | "grey" => Color::GRAY,
+        "red" => Color::RED,
+        "green" => Color::GREEN,
+        "blue" => Color::BLUE,
+        "yellow" => Color::YELLOW,
+        "orange" => Color::ORANGE,
+        "gold" => Color::GOLD,
+        "pink" => Color::PINK,
+        "purple" => Color::PURPLE,
+        "brown" => Color::BROWN,
+        "skyblue" => Color::SKYBLUE,
+        "lime" => Color::LIME,
+        "maroon" => Color::MAROON,
+        _ => return None,
+    })
+}
+
+fn effect_offset(effect: TextEffect, time: f32, index: usize) -> Vector2 {
+    match effect {
+        TextEffect::None => Vector2::zero(),
+        TextEffect::Wave { amplitude } => {
+            Vector2::new(0.0, (time * 6.0 - index as f32 * 0.6).sin() * amplitude)
+        }
+        TextEffect::Shake { intensity } => {
+            // a new random offset per glyph, 20 times a second
+            let seed = ((time * 20.0) as u32).wrapping_mul(7919) ^ (index as u32).wrapping_mul(2);
+            Vector2::new(jitter(seed), jitter(seed.wrapping_add(1))) * intensity
+        }
+    }
+}
+
+/// Hashes `seed` to a value in `[-1, 1]`.
+fn jitter(seed: u32) -> f32 {
+    let mut x = seed.wrapping_mul(0x9E37_79B9);
+    x ^= x >> 16;
+    x = x.wrapping_mul(0x85EB_CA6B);
+    x ^= x >> 13;
+    (x & 0xFFFF) as f32 / 32767.5 - 1.0
+}
@@ -0,0 +1,340 @@
+//! Safe wrappers around rlgl's immediate-mode and matrix-stack functions
+//!
+//! Every guard in this module ends what it started when dropped (`rlPopMatrix`, `rlEnd`, ...),
+//! so the rlgl state can't leak out of the scope that changed it.
+use crate::core::color::Color;
+use crate::core::drawing::{sealed, RaylibDraw, RaylibDraw3D};
+use crate::core::math::{Matrix, Vector2, Vector3};
+use crate::ffi;
+
+/// Primitive type assembled from the vertices of a [`RaylibRlglPrimitive`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum PrimitiveMode {
+    Lines,
+    Triangles,
+    Quads,
+}
+
+impl PrimitiveMode {
+    fn to_rl(self) -> i32 {
+        (match self {
+            PrimitiveMode::Lines => ffi::RL_LINES,
+            PrimitiveMode::Triangles => ffi::RL_TRIANGLES,
+            PrimitiveMode::Quads => ffi::RL_QUADS,
+        }) as i32
+    }
+}
+
+macro_rules! rlgl_guard_deref {
+    ($name:ident) => {
+        impl<'a, T> std::ops::Deref for $name<'a, T> {
+            type Target = T;
+
+            fn deref(&self) -> &Self::Target {
+                &self.0
+            }
+        }
+        impl<'a, T> std::ops::DerefMut for $name<'a, T> {
+            fn deref_mut(&mut self) -> &mut T {
+                self.0
+            }
+        }
+        impl<'a, T> RaylibDraw for $name<'a, T> {}
+        impl<'a, T> sealed::Sealed for $name<'a, T> {}
+        impl<'a, T: RaylibDraw3D> RaylibDraw3D for $name<'a, T> {}
+    };
+}
+
+/// A pushed matrix on the rlgl matrix stack. Transforms apply to everything drawn through
+/// the guard and are popped when it is dropped.
+pub struct RaylibRlglMatrix<'a, T>(&'a mut T);
+
+impl<'a, T> Drop for RaylibRlglMatrix<'a, T> {
+    fn drop(&mut self) {
+        unsafe { ffi::rlPopMatrix() }
+    }
+}
+rlgl_guard_deref!(RaylibRlglMatrix);
+
+impl<'a, T> RaylibRlglMatrix<'a, T> {
+    /// Replaces the current matrix with the identity matrix.
+    #[inline]
+    pub fn load_identity(&mut self) -> &mut Self {
+        unsafe { ffi::rlLoadIdentity() }
+        self
+    }
+
+    /// Multiplies the current matrix by a translation matrix.
+    #[inline]
+    pub fn translate(&mut self, x: f32, y: f32, z: f32) -> &mut Self {
+        unsafe { ffi::rlTranslatef(x, y, z) }
+        self
+    }
+
+    /// Multiplies the current matrix by a translation matrix (Vector version).
+    #[inline]
+    pub fn translate_v(&mut self, v: impl Into<Vector3>) -> &mut Self {
+        let v = v.into();
+        self.translate(v.x, v.y, v.z)
+    }
+
+    /// Multiplies the current matrix by a rotation of `angle` degrees around `axis`.
+    #[inline]
+    pub fn rotate(&mut self, angle: f32, axis: impl Into<Vector3>) -> &mut Self {
+        let axis = axis.into();
+        unsafe { ffi::rlRotatef(angle, axis.x, axis.y, axis.z) }
+        self
+    }
+
+    /// Multiplies the current matrix by a scaling matrix.
+    #[inline]
+    pub fn scale(&mut self, x: f32, y: f32, z: f32) -> &mut Self {
+        unsafe { ffi::rlScalef(x, y, z) }
+        self
+    }
+
+    /// Multiplies the current matrix by `mat`.
+    #[inline]
+    pub fn mult_matrix(&mut self, mat: Matrix) -> &mut Self {
+        let m = mat.to_array();
+        unsafe { ffi::rlMultMatrixf(m.as_ptr()) }
+        self
+    }
+}
+
+/// Vertex builder for a single `rlBegin`/`rlEnd` block.
+///
+/// Attributes set with [`RaylibRlglPrimitive::color`], [`RaylibRlglPrimitive::texcoord`] and
+/// [`RaylibRlglPrimitive::normal`] apply to every following vertex.
+/// Drawing anything else is impossible while the builder is alive, since it holds the draw handle.
+pub struct RaylibRlglPrimitive<'a, T>(&'a mut T);
+
+impl<'a, T> Drop for RaylibRlglPrimitive<'a, T> {
+    fn drop(&mut self) {
+        unsafe { ffi::rlEnd() }
+    }
+}
+
+impl<'a, T> RaylibRlglPrimitive<'a, T> {
+    /// Emits a 2D vertex.
+    #[inline]
+    pub fn vertex2(&mut self, x: f32, y: f32) -> &mut Self {
+        unsafe { ffi::rlVertex2f(x, y) }
+        self
+    }
+
+    /// Emits a 2D vertex (Vector version).
+    #[inline]
+    pub fn vertex2_v(&mut self, v: impl Into<Vector2>) -> &mut Self {
+        let v = v.into();
+        self.vertex2(v.x, v.y)
+    }
+
+    /// Emits a 3D vertex.
+    #[inline]
+    pub fn vertex3(&mut self, x: f32, y: f32, z: f32) -> &mut Self {
+        unsafe { ffi::rlVertex3f(x, y, z) }
+        self
+    }
+
+    /// Emits a 3D vertex (Vector version).
+    #[inline]
+    pub fn vertex3_v(&mut self, v: impl Into<Vector3>) -> &mut Self {
+        let v = v.into();
+        self.vertex3(v.x, v.y, v.z)
+    }
+
+    /// Sets the texture coordinate for the following vertices.
+    #[inline]
+    pub fn texcoord(&mut self, u: f32, v: f32) -> &mut Self {
+        unsafe { ffi::rlTexCoord2f(u, v) }
+        self
+    }
+
+    /// Sets the normal for the following vertices.
+    #[inline]
+    pub fn normal(&mut self, n: impl Into<Vector3>) -> &mut Self {
+        let n = n.into();
+        unsafe { ffi::rlNormal3f(n.x, n.y, n.z) }
+        self
+    }
+
+    /// Sets the color for the following vertices.
+    #[inline]
+    pub fn color(&mut self, color: impl Into<Color>) -> &mut Self {
+        let c = color.into();
+        unsafe { ffi::rlColor4ub(c.r, c.g, c.b, c.a) }
+        self
+    }
+}
+
+/// A texture bound with `rlSetTexture` for the primitives drawn through the guard.
+pub struct RaylibRlglTexture<'a, T>(&'a mut T);
+
+impl<'a, T> Drop for RaylibRlglTexture<'a, T> {
+    fn drop(&mut self) {
+        unsafe { ffi::rlSetTexture(0) }
+    }
+}
+rlgl_guard_deref!(RaylibRlglTexture);
+
+/// A piece of render state switched by the [`Rlgl`] state guards.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum RenderState {
+    BackfaceCulling,
+    DepthTest,
+    DepthMask,
+    WireMode,
+}
+
+thread_local! {
+    // rlgl can't be queried for these, so the guards keep track of them. raylib starts with
+    // backface culling and depth writes enabled, Mode3D enables the depth test until it ends.
+    static RENDER_STATE: std::cell::Cell<[bool; 4]> =
+        const { std::cell::Cell::new([true, false, true, false]) };
+}
+
+impl RenderState {
+    /// Updates the tracked state without touching rlgl, returns what it was before.
+    fn record(self, enabled: bool) -> bool {
+        RENDER_STATE.with(|s| {
+            let mut states = s.get();
+            let previous = std::mem::replace(&mut states[self as usize], enabled);
+            s.set(states);
+            previous
+        })
+    }
+
+    /// Flushes the batch and switches the state, returns what it was before.
+    fn set(self, enabled: bool) -> bool {
+        unsafe {
+            ffi::rlDrawRenderBatchActive();
+            match (self, enabled) {
+                (RenderState::BackfaceCulling, true) => ffi::rlEnableBackfaceCulling(),
+                (RenderState::BackfaceCulling, false) => ffi::rlDisableBackfaceCulling(),
+                (RenderState::DepthTest, true) => ffi::rlEnableDepthTest(),
+                (RenderState::DepthTest, false) => ffi::rlDisableDepthTest(),
+                (RenderState::DepthMask, true) => ffi::rlEnableDepthMask(),
+                (RenderState::DepthMask, false) => ffi::rlDisableDepthMask(),
+                (RenderState::WireMode, true) => ffi::rlEnableWireMode(),
+                (RenderState::WireMode, false) => ffi::rlDisableWireMode(),
+            }
+        }
+        self.record(enabled)
+    }
+}
+
+/// Records that `BeginMode3D` (`true`) or `EndMode3D` (`false`) switched the depth test.
+pub(crate) fn track_mode3d(active: bool) {
+    RenderState::DepthTest.record(active);
+}
+
+/// A temporarily switched piece of render state (culling, depth test, wireframe).
+/// The pending batch is flushed on both ends so only draws inside the guard see the change,
+/// and the state from before the guard is restored when it is dropped.
+pub struct RaylibRlglState<'a, T>(&'a mut T, RenderState, bool);
+
+impl<'a, T> Drop for RaylibRlglState<'a, T> {
+    fn drop(&mut self) {
+        self.1.set(self.2);
+    }
+}
+rlgl_guard_deref!(RaylibRlglState);
+
+/// Immediate-mode drawing and matrix-stack access through rlgl.
+///
+/// ```no_run
+/// use raylib::prelude::*;
+///
+/// let (mut rl, thread) = raylib::init().size(800, 450).build();
+/// let mut d = rl.begin_drawing(&thread);
+/// d.clear_background(Color::RAYWHITE);
+/// {
+///     let mut m = d.push_matrix();
+///     m.translate(400.0, 225.0, 0.0).rotate(45.0, Vector3::new(0.0, 0.0, 1.0));
+///     {
+///         let mut tri = m.begin(PrimitiveMode::Triangles);
+///         tri.color(Color::RED)
+///             .vertex2(0.0, -50.0)
+///             .vertex2(-50.0, 50.0)
+///             .vertex2(50.0, 50.0);
+///     }
+///     m.draw_circle(0, 0, 5.0, Color::BLACK);
+/// }
+/// ```
+pub trait Rlgl
+where
+    Self: Sized,
+{
+    /// Pushes a copy of the current matrix. The guard pops it again when dropped.
+    #[must_use]
+    fn push_matrix(&mut self) -> RaylibRlglMatrix<Self> {
+        unsafe { ffi::rlPushMatrix() }
+        RaylibRlglMatrix(self)
+    }
+
+    /// Starts emitting vertices for `mode` primitives. `rlEnd` is called when the builder is dropped.
+    #[must_use]
+    fn begin(&mut self, mode: PrimitiveMode) -> RaylibRlglPrimitive<Self> {
+        unsafe { ffi::rlBegin(mode.to_rl()) }
+        RaylibRlglPrimitive(self)
+    }
+
+    /// Binds `texture` for the following primitives. Set texture coordinates on the vertices
+    /// to sample it. The default texture is restored when the guard is dropped.
+    #[must_use]
+    fn bind_texture(&mut self, texture: impl AsRef<ffi::Texture2D>) -> RaylibRlglTexture<Self> {
+        unsafe { ffi::rlSetTexture(texture.as_ref().id) }
+        RaylibRlglTexture(self)
+    }
+
+    /// Enables or disables backface culling until the guard is dropped, which restores the previous setting.
+    #[must_use]
+    fn begin_backface_culling(&mut self, enabled: bool) -> RaylibRlglState<Self> {
+        toggle(self, RenderState::BackfaceCulling, enabled)
+    }
+
+    /// Enables or disables the depth test until the guard is dropped, which restores the previous setting.
+    #[must_use]
+    fn begin_depth_test(&mut self, enabled: bool) -> RaylibRlglState<Self> {
+        toggle(self, RenderState::DepthTest, enabled)
+    }
+
+    /// Enables or disables depth buffer writes until the guard is dropped, which restores the previous setting.
+    #[must_use]
+    fn begin_depth_mask(&mut self, enabled: bool) -> RaylibRlglState<Self> {
+        toggle(self, RenderState::DepthMask, enabled)
+    }
+
+    /// Enables or disables wireframe rendering until the guard is dropped, which restores the previous setting.
+    #[must_use]
+    fn begin_wire_mode(&mut self, enabled: bool) -> RaylibRlglState<Self> {
+        toggle(self, RenderState::WireMode, enabled)
+    }
+
+    /// Sets the line width used by line primitives.
+    #[inline]
+    fn set_line_width(&mut self, width: f32) {
+        unsafe { ffi::rlSetLineWidth(width) }
+    }
+
+    /// Sends everything batched so far to the GPU.
+    #[inline]
+    fn draw_render_batch_active(&mut self) {
+        unsafe { ffi::rlDrawRenderBatchActive() }
+    }
+
+    /// Flushes the batch if `vertex_count` more vertices would not fit. Call it before [`Rlgl::begin`],
+    /// not inside a primitive. Returns `true` if the batch was flushed.
+    #[inline]
+    fn check_render_batch_limit(&mut self, vertex_count: i32) -> bool {
+        unsafe { ffi::rlCheckRenderBatchLimit(vertex_count) }
+    }
+}
+
+fn toggle<T>(d: &mut T, state: RenderState, enabled: bool) -> RaylibRlglState<T> {
+    let previous = state.set(enabled);
+    RaylibRlglState(d, state, previous)
+}
+
+impl<D: RaylibDraw> Rlgl for D {}
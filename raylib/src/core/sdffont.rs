@@ -0,0 +1,428 @@
+//! Signed distance field fonts, which stay sharp at any scale
+//!
+//! ```no_run
+//! use raylib::prelude::*;
+//!
+//! let (mut rl, thread) = raylib::init().size(800, 450).build();
+//! let font = rl.load_font_sdf(&thread, "NotoSans.ttf", 48, None).unwrap();
+//! let style = SdfTextStyle {
+//!     outline_width: 2.0,
+//!     outline_color: Color::BLACK,
+//!     shadow: Some(SdfShadow::new(Vector2::new(3.0, 3.0), Color::new(0, 0, 0, 128))),
+//!     ..Default::default()
+//! };
+//!
+//! let mut d = rl.begin_drawing(&thread);
+//! d.clear_background(Color::RAYWHITE);
+//! d.draw_text_sdf(&font, "Zoom me", Vector2::new(20.0, 20.0), 120.0, 0.0, Color::WHITE, &style);
+//! ```
+use crate::core::color::Color;
+use crate::core::drawing::RaylibDraw;
+use crate::core::math::Vector2;
+use crate::core::shaders::{RaylibShader, Shader, ShaderV};
+use crate::core::text::{Font, RaylibFont};
+use crate::core::texture::RaylibTexture2D;
+use crate::core::{RaylibHandle, RaylibThread};
+use crate::error::{error, Error};
+use crate::ffi;
+use std::ffi::CString;
+use std::os::raw::c_char;
+
+/// Distance, in pixels at the base size of the font, that raylib encodes around each glyph edge.
+/// Outlines, glows and shadow blur reach at most this far.
+pub const SDF_RANGE: f32 = 2.0;
+
+/// Padding raylib adds around each glyph of an SDF font.
+const SDF_PADDING: i32 = 4;
+
+/// Uniform names of [`SdfFont::shader`], so custom shaders can reuse them.
+pub const SDF_SOFTNESS_UNIFORM: &str = "softness";
+pub const SDF_OUTLINE_WIDTH_UNIFORM: &str = "outlineWidth";
+pub const SDF_OUTLINE_COLOR_UNIFORM: &str = "outlineColor";
+pub const SDF_GLOW_WIDTH_UNIFORM: &str = "glowWidth";
+pub const SDF_GLOW_COLOR_UNIFORM: &str = "glowColor";
+
+// Every variant decodes distances the way raylib encodes them, 128 + 64 * pixels, and draws from
+// back to front: glow, outline, then the glyph itself.
+macro_rules! sdf_fragment_shader {
+    ($header:expr, $input:expr, $texture:expr, $output:expr, $main_output:expr) => {
+        concat!(
+            $header,
+            $input,
+            " vec2 fragTexCoord;\n",
+            $input,
+            " vec4 fragColor;\n",
+            "uniform sampler2D texture0;\n",
+            "uniform vec4 colDiffuse;\n",
+            "uniform float softness;\n",
+            "uniform float outlineWidth;\n",
+            "uniform vec4 outlineColor;\n",
+            "uniform float glowWidth;\n",
+            "uniform vec4 glowColor;\n",
+            $output,
+            "vec4 over(vec4 top, vec4 bottom) {\n",
+            "    float a = top.a + bottom.a*(1.0 - top.a);\n",
+            "    vec3 rgb = (top.rgb*top.a + bottom.rgb*bottom.a*(1.0 - top.a))/max(a, 0.0001);\n",
+            "    return vec4(rgb, a);\n",
+            "}\n",
+            "void main() {\n",
+            "    float dist = (",
+            $texture,
+            "(texture0, fragTexCoord).a - 0.5)*255.0/64.0;\n",
+            "    float aa = max(length(vec2(dFdx(dist), dFdy(dist))), 0.0001) + softness;\n",
+            "    vec4 color = vec4(0.0);\n",
+            "    if (glowWidth > 0.0) {\n",
+            "        float glow = smoothstep(-outlineWidth - glowWidth, -outlineWidth, dist);\n",
+            "        color = vec4(glowColor.rgb, glowColor.a*glow*glow);\n",
+            "    }\n",
+            "    if (outlineWidth > 0.0) {\n",
+            "        float outline = smoothstep(-aa, aa, dist + outlineWidth);\n",
+            "        color = over(vec4(outlineColor.rgb, outlineColor.a*outline), color);\n",
+            "    }\n",
+            "    color = over(vec4(fragColor.rgb, fragColor.a*smoothstep(-aa, aa, dist)), color);\n",
+            "    ",
+            $main_output,
+            " = color*colDiffuse;\n",
+            "}\n",
+        )
+    };
+}
+
+#[cfg(not(any(
+    feature = "opengl_21",
+    feature = "opengl_es_20",
+    feature = "opengl_es_30",
+    target_os = "android",
+    target_os = "emscripten"
+)))]
+const SDF_FRAGMENT_SHADER: &str = sdf_fragment_shader!(
+    "#version 330\n",
+    "in",
+    "texture",
+    "out vec4 finalColor;\n",
+    "finalColor"
+);
+
+#[cfg(feature = "opengl_21")]
+const SDF_FRAGMENT_SHADER: &str =
+    sdf_fragment_shader!("#version 120\n", "varying", "texture2D", "", "gl_FragColor");
+
+#[cfg(feature = "opengl_es_30")]
+const SDF_FRAGMENT_SHADER: &str = sdf_fragment_shader!(
+    "#version 300 es\nprecision mediump float;\n",
+    "in",
+    "texture",
+    "out vec4 finalColor;\n",
+    "finalColor"
+);
+
+#[cfg(all(
+    not(any(feature = "opengl_21", feature = "opengl_es_30")),
+    any(
+        feature = "opengl_es_20",
+        target_os = "android",
+        target_os = "emscripten"
+    )
+))]
+const SDF_FRAGMENT_SHADER: &str = sdf_fragment_shader!(
+    "#version 100\n#extension GL_OES_standard_derivatives : enable\nprecision mediump float;\n",
+    "varying",
+    "texture2D",
+    "",
+    "gl_FragColor"
+);
+
+/// A [`Font`] whose atlas stores distances to glyph edges instead of coverage, bundled with the
+/// shader that draws it.
+///
+/// Draw it with [`RaylibSdfTextExt::draw_text_sdf`]. It implements [`RaylibFont`], so it can be
+/// measured and laid out like any other font.
+#[derive(Debug)]
+pub struct SdfFont {
+    font: Font,
+    shader: Shader,
+    locations: SdfLocations,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct SdfLocations {
+    softness: i32,
+    outline_width: i32,
+    outline_color: i32,
+    glow_width: i32,
+    glow_color: i32,
+}
+
+impl SdfFont {
+    /// Font with the distance field atlas, for measuring or custom drawing.
+    pub fn font(&self) -> &Font {
+        &self.font
+    }
+
+    /// Shader turning distances back into sharp glyphs. Draw [`SdfFont::font`] in its shader mode
+    /// to use it with the regular text functions.
+    pub fn shader(&self) -> &Shader {
+        &self.shader
+    }
+
+    pub fn shader_mut(&mut self) -> &mut Shader {
+        &mut self.shader
+    }
+
+    /// Splits the font from its shader.
+    pub fn into_parts(self) -> (Font, Shader) {
+        (self.font, self.shader)
+    }
+
+    fn set_uniforms(&self, style: &SdfTextStyle, font_size: f32) {
+        // effects are given in screen pixels, the shader works in pixels at the base size
+        let scale = self.font.0.baseSize as f32 / font_size;
+        let outline_width = (style.outline_width * scale).clamp(0.0, SDF_RANGE);
+        let glow_width = (style.glow_width * scale).clamp(0.0, SDF_RANGE - outline_width);
+        let softness = (style.softness * scale).max(0.0);
+        let shader = self.shader.0;
+        set_value(shader, self.locations.softness, softness);
+        set_value(shader, self.locations.outline_width, outline_width);
+        set_value(
+            shader,
+            self.locations.outline_color,
+            style.outline_color.color_normalize(),
+        );
+        set_value(shader, self.locations.glow_width, glow_width);
+        set_value(
+            shader,
+            self.locations.glow_color,
+            style.glow_color.color_normalize(),
+        );
+    }
+}
+
+impl AsRef<ffi::Font> for SdfFont {
+    fn as_ref(&self) -> &ffi::Font {
+        self.font.as_ref()
+    }
+}
+
+impl AsMut<ffi::Font> for SdfFont {
+    fn as_mut(&mut self) -> &mut ffi::Font {
+        self.font.as_mut()
+    }
+}
+
+impl RaylibFont for SdfFont {}
+
+fn set_value<S: ShaderV>(shader: ffi::Shader, location: i32, value: S) {
+    if location >= 0 {
+        unsafe {
+            ffi::SetShaderValue(
+                shader,
+                location,
+                value.value(),
+                (S::UNIFORM_TYPE as u32) as i32,
+            );
+        }
+    }
+}
+
+/// A drop shadow drawn under SDF text.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SdfShadow {
+    /// Offset of the shadow from the text, in screen pixels.
+    pub offset: Vector2,
+    pub color: Color,
+    /// Blur radius in screen pixels, limited by [`SDF_RANGE`].
+    pub blur: f32,
+}
+
+impl SdfShadow {
+    /// A hard shadow at `offset`.
+    pub fn new(offset: impl Into<Vector2>, color: impl Into<Color>) -> SdfShadow {
+        SdfShadow {
+            offset: offset.into(),
+            color: color.into(),
+            blur: 0.0,
+        }
+    }
+}
+
+/// Effects for [`RaylibSdfTextExt::draw_text_sdf`]. Widths are in screen pixels; outline and glow
+/// together reach at most [`SDF_RANGE`] pixels at the base size of the font, so load fonts bigger
+/// for wider effects.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SdfTextStyle {
+    pub outline_width: f32,
+    pub outline_color: Color,
+    /// Width of the glow fading out around the outline, or the glyphs without one.
+    pub glow_width: f32,
+    pub glow_color: Color,
+    pub shadow: Option<SdfShadow>,
+    /// Extra edge blur in screen pixels.
+    pub softness: f32,
+}
+
+impl Default for SdfTextStyle {
+    fn default() -> Self {
+        SdfTextStyle {
+            outline_width: 0.0,
+            outline_color: Color::BLACK,
+            glow_width: 0.0,
+            glow_color: Color::WHITE,
+            shadow: None,
+            softness: 0.0,
+        }
+    }
+}
+
+impl RaylibHandle {
+    /// Loads an SDF font from a font file, with the shader to draw it.
+    /// Supplying None for `chars` loads the 95 printable ASCII characters.
+    pub fn load_font_sdf(
+        &mut self,
+        thread: &RaylibThread,
+        filename: &str,
+        font_size: i32,
+        chars: Option<&str>,
+    ) -> Result<SdfFont, Error> {
+        let data = std::fs::read(filename)
+            .map_err(|_| error!("Error loading SDF font. Check if the file exists", filename))?;
+        self.load_font_sdf_from_memory(thread, &data, font_size, chars)
+    }
+
+    /// Loads an SDF font from the contents of a .ttf or .otf file, with the shader to draw it.
+    /// Supplying None for `chars` loads the 95 printable ASCII characters.
+    pub fn load_font_sdf_from_memory(
+        &mut self,
+        thread: &RaylibThread,
+        file_data: &[u8],
+        font_size: i32,
+        chars: Option<&str>,
+    ) -> Result<SdfFont, Error> {
+        let mut codepoints: Vec<i32> = match chars {
+            Some(chars) => chars.chars().map(|c| c as i32).collect(),
+            None => (32..127).collect(),
+        };
+        codepoints.sort_unstable();
+        codepoints.dedup();
+
+        let glyphs = unsafe {
+            ffi::LoadFontData(
+                file_data.as_ptr(),
+                file_data.len() as i32,
+                font_size,
+                codepoints.as_mut_ptr(),
+                codepoints.len() as i32,
+                ffi::FontType::FONT_SDF as i32,
+            )
+        };
+        if glyphs.is_null() {
+            return Err(error!(
+                "Error loading SDF font. Check if the data is a valid font"
+            ));
+        }
+
+        let font = unsafe {
+            let mut font = std::mem::zeroed::<ffi::Font>();
+            font.baseSize = font_size;
+            font.glyphCount = codepoints.len() as i32;
+            font.glyphs = glyphs;
+            let atlas = ffi::GenImageFontAtlas(
+                font.glyphs,
+                &mut font.recs,
+                font.glyphCount,
+                font.baseSize,
+                SDF_PADDING,
+                1,
+            );
+            font.texture = ffi::LoadTextureFromImage(atlas);
+            ffi::UnloadImage(atlas);
+            // raylib frees the glyphs, recs and texture together
+            Font(font)
+        };
+        if font.0.texture.id == 0 {
+            return Err(error!("Error loading SDF font atlas."));
+        }
+        font.texture().set_texture_filter(
+            thread,
+            crate::consts::TextureFilter::TEXTURE_FILTER_BILINEAR,
+        );
+
+        let shader = self.load_shader_from_memory(thread, None, Some(SDF_FRAGMENT_SHADER));
+        if shader.id == unsafe { ffi::rlGetShaderIdDefault() } {
+            return Err(error!("Error compiling the SDF font shader."));
+        }
+        let locations = SdfLocations {
+            softness: shader.get_shader_location(SDF_SOFTNESS_UNIFORM),
+            outline_width: shader.get_shader_location(SDF_OUTLINE_WIDTH_UNIFORM),
+            outline_color: shader.get_shader_location(SDF_OUTLINE_COLOR_UNIFORM),
+            glow_width: shader.get_shader_location(SDF_GLOW_WIDTH_UNIFORM),
+            glow_color: shader.get_shader_location(SDF_GLOW_COLOR_UNIFORM),
+        };
+
+        Ok(SdfFont {
+            font,
+            shader,
+            locations,
+        })
+    }
+}
+
+/// Drawing [`SdfFont`]s for anything that implements [`RaylibDraw`].
+pub trait RaylibSdfTextExt: RaylibDraw
+where
+    Self: Sized,
+{
+    /// Draws `text` like [`RaylibDraw::draw_text_ex`], sharp at any `font_size`, with the
+    /// outline, glow and shadow of `style`. NUL characters in `text` are skipped.
+    fn draw_text_sdf(
+        &mut self,
+        font: &SdfFont,
+        text: &str,
+        position: impl Into<Vector2>,
+        font_size: f32,
+        spacing: f32,
+        tint: impl Into<ffi::Color>,
+        style: &SdfTextStyle,
+    ) {
+        let position = position.into();
+        let tint: ffi::Color = tint.into();
+        let c_text = CString::new(text.replace('\0', "")).unwrap();
+        unsafe { ffi::BeginShaderMode(font.shader.0) }
+        if let Some(shadow) = style.shadow {
+            // the shadow covers the outline too, blurred but without glow
+            let shadow_style = SdfTextStyle {
+                outline_width: style.outline_width,
+                outline_color: shadow.color,
+                softness: shadow.blur,
+                ..SdfTextStyle::default()
+            };
+            font.set_uniforms(&shadow_style, font_size);
+            let shadow_color: ffi::Color = shadow.color.into();
+            unsafe {
+                ffi::DrawTextEx(
+                    font.font.0,
+                    c_text.as_ptr() as *const c_char,
+                    (position + shadow.offset).into(),
+                    font_size,
+                    spacing,
+                    shadow_color,
+                );
+                // uniforms apply to the whole batch, draw the shadow before they change
+                ffi::rlDrawRenderBatchActive();
+            }
+        }
+        font.set_uniforms(style, font_size);
+        unsafe {
+            ffi::DrawTextEx(
+                font.font.0,
+                c_text.as_ptr() as *const c_char,
+                position.into(),
+                font_size,
+                spacing,
+                tint,
+            );
+            ffi::EndShaderMode();
+        }
+    }
+}
+
+impl<D: RaylibDraw> RaylibSdfTextExt for D {}
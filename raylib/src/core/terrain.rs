@@ -0,0 +1,922 @@
+//! Chunked heightmap terrain with levels of detail, culling, height queries and picking
+//!
+//! [`RaylibMesh::gen_mesh_heightmap`](crate::core::models::RaylibMesh::gen_mesh_heightmap) turns
+//! a whole heightmap into one full-detail mesh. A [`Terrain`] cuts it into square chunks
+//! instead, each with a few levels of detail that halve the resolution from one level to the
+//! next. [`Terrain::draw`] skips chunks outside the camera's [`Frustum`] and picks a level for
+//! the rest from their distance to the camera. Every chunk hangs a skirt below its edges so
+//! seams between levels don't open into gaps.
+//!
+//! Heights, normals and ray picking are answered on the CPU from the full-detail surface, so
+//! they work before the chunks are uploaded and match what objects should stand on.
+//!
+//! Chunk meshes use texture coordinates that run from 0 to 1 across the whole terrain. A
+//! [`TerrainSplatMaterial`] blends four tiled layer textures with the weights of a splat map
+//! stretched over the terrain, which [`Terrain::splat_map`] can paint from heights and slopes.
+//!
+//! ```no_run
+//! use raylib::prelude::*;
+//!
+//! let (mut rl, thread) = raylib::init().size(800, 450).build();
+//! let mut heights = Heightmap::from_noise(&SimplexNoise::new(3).fbm(6), 513, 513, 1.0 / 128.0);
+//! heights.normalize();
+//! let mut terrain = Terrain::new(heights, Vector3::new(512.0, 60.0, 512.0))
+//!     .unwrap()
+//!     .with_position(Vector3::new(-256.0, 0.0, -256.0));
+//! terrain.load_meshes(&thread).unwrap();
+//! let material = rl.load_material_default(&thread);
+//!
+//! let camera = Camera3D::perspective(
+//!     Vector3::new(0.0, 80.0, 200.0),
+//!     Vector3::zero(),
+//!     Vector3::up(),
+//!     60.0,
+//! );
+//! let mut d = rl.begin_drawing(&thread);
+//! let mut d3 = d.begin_mode3D(camera);
+//! terrain.draw(&mut d3, &camera, 800.0 / 450.0, &material);
+//! ```
+use crate::consts::{CameraProjection, MaterialMapIndex, ShaderLocationIndex};
+use crate::core::assets::MeshData;
+use crate::core::camera::Camera3D;
+use crate::core::color::Color;
+use crate::core::drawing::RaylibDraw3D;
+use crate::core::math::{BoundingBox, Matrix, Ray, RayCollision, Vector2, Vector3, Vector4};
+use crate::core::models::{Mesh, RaylibMaterial, WeakMaterial};
+use crate::core::noise::Heightmap;
+use crate::core::shaders::{RaylibShader, Shader};
+use crate::core::texture::{Image, Texture2D};
+use crate::core::{RaylibHandle, RaylibThread};
+use crate::error::{error, Error};
+use crate::ffi;
+use std::convert::TryInto;
+use std::marker::PhantomData;
+
+/// The six planes of a camera's view volume, for culling.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Frustum {
+    /// Planes as `(a, b, c, d)` with `a*x + b*y + c*z + d >= 0` on the inside.
+    planes: [Vector4; 6],
+}
+
+impl Frustum {
+    /// Extracts the planes of a combined view-projection matrix, `view * projection`.
+    pub fn from_matrix(view_projection: Matrix) -> Frustum {
+        let m = view_projection;
+        let rows = [
+            [m.m0, m.m4, m.m8, m.m12],
+            [m.m1, m.m5, m.m9, m.m13],
+            [m.m2, m.m6, m.m10, m.m14],
+        ];
+        let w = [m.m3, m.m7, m.m11, m.m15];
+        let mut planes = [Vector4::new(0.0, 0.0, 0.0, 0.0); 6];
+        for (axis, row) in rows.iter().enumerate() {
+            for (side, sign) in [1.0, -1.0].iter().enumerate() {
+                let p = [
+                    w[0] + sign * row[0],
+                    w[1] + sign * row[1],
+                    w[2] + sign * row[2],
+                    w[3] + sign * row[3],
+                ];
+                let length = (p[0] * p[0] + p[1] * p[1] + p[2] * p[2]).sqrt().max(1e-12);
+                planes[axis * 2 + side] =
+                    Vector4::new(p[0] / length, p[1] / length, p[2] / length, p[3] / length);
+            }
+        }
+        Frustum { planes }
+    }
+
+    /// The view volume `camera` sees through a viewport of the given aspect ratio, with the same
+    /// projection and clip distances as `begin_mode3D`.
+    pub fn from_camera(camera: &Camera3D, aspect: f32) -> Frustum {
+        // RL_CULL_DISTANCE_NEAR and RL_CULL_DISTANCE_FAR
+        let (near, far) = (0.01, 1000.0);
+        let view = Matrix::look_at(camera.position, camera.target, camera.up);
+        let projection = match camera.camera_type() {
+            CameraProjection::CAMERA_ORTHOGRAPHIC => {
+                let top = camera.fovy / 2.0;
+                let right = top * aspect;
+                Matrix::ortho(-right, right, -top, top, near, far)
+            }
+            _ => Matrix::perspective(camera.fovy.to_radians(), aspect, near, far),
+        };
+        Frustum::from_matrix(view * projection)
+    }
+
+    pub fn contains_point(&self, point: Vector3) -> bool {
+        self.planes
+            .iter()
+            .all(|p| p.x * point.x + p.y * point.y + p.z * point.z + p.w >= 0.0)
+    }
+
+    /// Whether any part of `bounds` may be inside. Boxes near a corner of the frustum can pass
+    /// without being visible, but no visible box fails.
+    pub fn intersects_box(&self, bounds: &BoundingBox) -> bool {
+        self.planes.iter().all(|p| {
+            // the corner furthest along the plane normal
+            let x = if p.x >= 0.0 {
+                bounds.max.x
+            } else {
+                bounds.min.x
+            };
+            let y = if p.y >= 0.0 {
+                bounds.max.y
+            } else {
+                bounds.min.y
+            };
+            let z = if p.z >= 0.0 {
+                bounds.max.z
+            } else {
+                bounds.min.z
+            };
+            p.x * x + p.y * y + p.z * z + p.w >= 0.0
+        })
+    }
+}
+
+/// One square piece of a [`Terrain`].
+#[derive(Debug)]
+pub struct TerrainChunk {
+    column: usize,
+    row: usize,
+    /// Heightmap samples covered, first and last inclusive.
+    x: (usize, usize),
+    z: (usize, usize),
+    bounds: BoundingBox,
+    meshes: Vec<Mesh>,
+}
+
+impl TerrainChunk {
+    pub fn column(&self) -> usize {
+        self.column
+    }
+
+    pub fn row(&self) -> usize {
+        self.row
+    }
+
+    /// World space bounds, skirts included.
+    pub fn bounds(&self) -> BoundingBox {
+        self.bounds
+    }
+
+    /// Uploaded meshes, one per level of detail. Empty until [`Terrain::load_meshes`].
+    pub fn meshes(&self) -> &[Mesh] {
+        &self.meshes
+    }
+}
+
+/// A heightmap terrain split into chunks with levels of detail.
+///
+/// Heightmap values are scaled by the height of `size`, so values from 0 to 1 span the full
+/// height. The terrain starts at [`Terrain::position`] and reaches along +X and +Z.
+#[derive(Debug)]
+pub struct Terrain {
+    heights: Heightmap,
+    position: Vector3,
+    size: Vector3,
+    chunk_cells: usize,
+    lod_levels: usize,
+    lod_distance: Option<f32>,
+    skirt_depth: f32,
+    chunks: Vec<TerrainChunk>,
+}
+
+impl Terrain {
+    /// A terrain over `heights`, `size` wide, high and deep, with 32 cell chunks, four levels of
+    /// detail and skirts a tenth of the height deep.
+    pub fn new(heights: Heightmap, size: Vector3) -> Result<Terrain, Error> {
+        if heights.width() < 2 || heights.height() < 2 {
+            return Err(error!("Terrain heightmap needs at least 2x2 samples."));
+        }
+        let mut terrain = Terrain {
+            heights,
+            position: Vector3::zero(),
+            size,
+            chunk_cells: 32,
+            lod_levels: 4,
+            lod_distance: None,
+            skirt_depth: size.y * 0.1,
+            chunks: Vec::new(),
+        };
+        terrain.build_chunks();
+        Ok(terrain)
+    }
+
+    /// A terrain over the gray values of `image`, with black at the bottom and white at the top,
+    /// read the same way as `gen_mesh_heightmap` reads them.
+    pub fn from_image(image: &Image, size: Vector3) -> Result<Terrain, Error> {
+        let (width, height) = (
+            image.width().max(0) as usize,
+            image.height().max(0) as usize,
+        );
+        let pixels = image.rgba();
+        let heights = Heightmap::from_fn(width, height, |x, y| {
+            let c = pixels[y * width + x];
+            (c.r as f32 + c.g as f32 + c.b as f32) / 3.0 / 255.0
+        });
+        Terrain::new(heights, size)
+    }
+
+    /// Moves the terrain's corner. Uploaded meshes are dropped.
+    pub fn with_position(mut self, position: Vector3) -> Self {
+        self.position = position;
+        self.build_chunks();
+        self
+    }
+
+    /// Sets the number of heightmap cells along each side of a chunk at full detail. Uploaded
+    /// meshes are dropped.
+    pub fn with_chunk_cells(mut self, cells: usize) -> Self {
+        self.chunk_cells = cells.max(1);
+        self.build_chunks();
+        self
+    }
+
+    /// Uses `levels` levels of detail. Chunks switch to level 1 at `distance` from the camera,
+    /// and to each next level at twice the distance of the last. Uploaded meshes are dropped.
+    pub fn with_lod(mut self, levels: usize, distance: f32) -> Self {
+        self.lod_levels = levels.max(1);
+        self.lod_distance = Some(distance);
+        self.build_chunks();
+        self
+    }
+
+    /// Sets how far skirts reach below chunk edges. Uploaded meshes are dropped.
+    pub fn with_skirt_depth(mut self, depth: f32) -> Self {
+        self.skirt_depth = depth.max(0.0);
+        self.build_chunks();
+        self
+    }
+
+    pub fn heights(&self) -> &Heightmap {
+        &self.heights
+    }
+
+    pub fn position(&self) -> Vector3 {
+        self.position
+    }
+
+    pub fn size(&self) -> Vector3 {
+        self.size
+    }
+
+    pub fn chunk_cells(&self) -> usize {
+        self.chunk_cells
+    }
+
+    pub fn lod_levels(&self) -> usize {
+        self.lod_levels
+    }
+
+    /// Distance where level 1 starts. Unless set with [`Terrain::with_lod`], twice the width of
+    /// a chunk.
+    pub fn lod_distance(&self) -> f32 {
+        self.lod_distance
+            .unwrap_or_else(|| self.spacing().0 * self.chunk_cells as f32 * 2.0)
+    }
+
+    pub fn skirt_depth(&self) -> f32 {
+        self.skirt_depth
+    }
+
+    pub fn chunks(&self) -> &[TerrainChunk] {
+        &self.chunks
+    }
+
+    /// World space bounds of the surface, without skirts.
+    pub fn bounds(&self) -> BoundingBox {
+        let (lo, hi) = self.heights.range().unwrap_or((0.0, 0.0));
+        BoundingBox::new(
+            self.position + Vector3::new(0.0, lo * self.size.y, 0.0),
+            self.position + Vector3::new(self.size.x, hi * self.size.y, self.size.z),
+        )
+    }
+
+    /// World distance between neighboring heightmap samples along X and Z.
+    fn spacing(&self) -> (f32, f32) {
+        (
+            self.size.x / (self.heights.width() - 1) as f32,
+            self.size.z / (self.heights.height() - 1) as f32,
+        )
+    }
+
+    fn sample(&self, x: usize, z: usize) -> f32 {
+        self.heights.data()[z * self.heights.width() + x]
+    }
+
+    fn vertex(&self, x: usize, z: usize) -> Vector3 {
+        let (dx, dz) = self.spacing();
+        self.position
+            + Vector3::new(
+                x as f32 * dx,
+                self.sample(x, z) * self.size.y,
+                z as f32 * dz,
+            )
+    }
+
+    /// Smooth normal at a heightmap sample, from the slope to its neighbors.
+    fn vertex_normal(&self, x: usize, z: usize) -> Vector3 {
+        let (dx, dz) = self.spacing();
+        let (last_x, last_z) = (self.heights.width() - 1, self.heights.height() - 1);
+        let (x0, x1) = (x.saturating_sub(1), (x + 1).min(last_x));
+        let (z0, z1) = (z.saturating_sub(1), (z + 1).min(last_z));
+        let slope_x =
+            (self.sample(x1, z) - self.sample(x0, z)) * self.size.y / ((x1 - x0) as f32 * dx);
+        let slope_z =
+            (self.sample(x, z1) - self.sample(x, z0)) * self.size.y / ((z1 - z0) as f32 * dz);
+        Vector3::new(-slope_x, 1.0, -slope_z).normalized()
+    }
+
+    fn texcoord(&self, x: usize, z: usize) -> Vector2 {
+        Vector2::new(
+            x as f32 / (self.heights.width() - 1) as f32,
+            z as f32 / (self.heights.height() - 1) as f32,
+        )
+    }
+
+    fn build_chunks(&mut self) {
+        let (last_x, last_z) = (self.heights.width() - 1, self.heights.height() - 1);
+        let mut chunks = Vec::new();
+        for row in 0..last_z.div_ceil(self.chunk_cells) {
+            for column in 0..last_x.div_ceil(self.chunk_cells) {
+                let x = (
+                    column * self.chunk_cells,
+                    ((column + 1) * self.chunk_cells).min(last_x),
+                );
+                let z = (
+                    row * self.chunk_cells,
+                    ((row + 1) * self.chunk_cells).min(last_z),
+                );
+                let (mut lo, mut hi) = (f32::MAX, f32::MIN);
+                for sz in z.0..=z.1 {
+                    for sx in x.0..=x.1 {
+                        let y = self.vertex(sx, sz).y;
+                        lo = lo.min(y);
+                        hi = hi.max(y);
+                    }
+                }
+                let (min, max) = (self.vertex(x.0, z.0), self.vertex(x.1, z.1));
+                chunks.push(TerrainChunk {
+                    column,
+                    row,
+                    x,
+                    z,
+                    bounds: BoundingBox::new(
+                        Vector3::new(min.x, lo - self.skirt_depth, min.z),
+                        Vector3::new(max.x, hi, max.z),
+                    ),
+                    meshes: Vec::new(),
+                });
+            }
+        }
+        self.chunks = chunks;
+    }
+
+    /// Triangles of a chunk at a level of detail, in world space, skirts included. Level 0
+    /// uses every heightmap sample, each next level every other sample of the last. The
+    /// chunk's edges always end on its last samples.
+    pub fn chunk_mesh(&self, chunk: usize, lod: usize) -> Option<MeshData> {
+        let chunk = self.chunks.get(chunk)?;
+        if lod >= self.lod_levels {
+            return None;
+        }
+        let step = 1usize << lod.min(usize::BITS as usize - 1);
+        let steps = |(first, last): (usize, usize)| {
+            let mut samples: Vec<usize> = (first..last).step_by(step).collect();
+            samples.push(last);
+            samples
+        };
+        let (xs, zs) = (steps(chunk.x), steps(chunk.z));
+
+        let mut data = MeshData::default();
+        let push = |data: &mut MeshData, (x, z): (usize, usize), drop: f32| {
+            data.vertices
+                .push(self.vertex(x, z) - Vector3::new(0.0, drop, 0.0));
+            data.normals.push(self.vertex_normal(x, z));
+            data.texcoords.push(self.texcoord(x, z));
+        };
+        for j in 0..zs.len() - 1 {
+            for i in 0..xs.len() - 1 {
+                let (tl, tr) = ((xs[i], zs[j]), (xs[i + 1], zs[j]));
+                let (bl, br) = ((xs[i], zs[j + 1]), (xs[i + 1], zs[j + 1]));
+                // counter-clockwise seen from above, split along the same diagonal as height_at
+                for &corner in &[tl, bl, br, tl, br, tr] {
+                    push(&mut data, corner, 0.0);
+                }
+            }
+        }
+
+        let first_x = xs[0];
+        let last_x = xs[xs.len() - 1];
+        let first_z = zs[0];
+        let last_z = zs[zs.len() - 1];
+        let edges: [(Vec<(usize, usize)>, Vector3); 4] = [
+            (
+                xs.iter().map(|&x| (x, first_z)).collect(),
+                Vector3::new(0.0, 0.0, -1.0),
+            ),
+            (
+                xs.iter().map(|&x| (x, last_z)).collect(),
+                Vector3::new(0.0, 0.0, 1.0),
+            ),
+            (
+                zs.iter().map(|&z| (first_x, z)).collect(),
+                Vector3::new(-1.0, 0.0, 0.0),
+            ),
+            (
+                zs.iter().map(|&z| (last_x, z)).collect(),
+                Vector3::new(1.0, 0.0, 0.0),
+            ),
+        ];
+        for (edge, outward) in &edges {
+            for pair in edge.windows(2) {
+                let (a, b) = (pair[0], pair[1]);
+                let (va, vb) = (self.vertex(a.0, a.1), self.vertex(b.0, b.1));
+                let below = Vector3::new(0.0, self.skirt_depth, 0.0);
+                let facing = (vb - va).cross((vb - below) - va).dot(*outward);
+                let corners = if facing >= 0.0 {
+                    [(a, 0.0), (b, 0.0), (b, 1.0), (a, 0.0), (b, 1.0), (a, 1.0)]
+                } else {
+                    [(a, 0.0), (b, 1.0), (b, 0.0), (a, 0.0), (a, 1.0), (b, 1.0)]
+                };
+                for &(corner, down) in &corners {
+                    push(&mut data, corner, down * self.skirt_depth);
+                }
+            }
+        }
+        Some(data)
+    }
+
+    /// Uploads every level of detail of every chunk.
+    pub fn load_meshes(&mut self, thread: &RaylibThread) -> Result<(), Error> {
+        for index in 0..self.chunks.len() {
+            let meshes = (0..self.lod_levels)
+                .map(|lod| {
+                    self.chunk_mesh(index, lod)
+                        .ok_or(error!("Terrain chunk is missing."))?
+                        .load_mesh(thread)
+                })
+                .collect::<Result<Vec<_>, Error>>()?;
+            self.chunks[index].meshes = meshes;
+        }
+        Ok(())
+    }
+
+    /// Level of detail for a chunk `distance` away from the camera.
+    pub fn lod_for_distance(&self, distance: f32) -> usize {
+        let base = self.lod_distance();
+        if base <= 0.0 || distance < base {
+            return 0;
+        }
+        let level = 1 + (distance / base).log2().floor() as usize;
+        level.min(self.lod_levels - 1)
+    }
+
+    /// Chunks that may be seen through `frustum`, as `(chunk index, level of detail)` for a
+    /// camera at `eye`.
+    pub fn visible_chunks(&self, frustum: &Frustum, eye: Vector3) -> Vec<(usize, usize)> {
+        self.chunks
+            .iter()
+            .enumerate()
+            .filter(|(_, chunk)| frustum.intersects_box(&chunk.bounds))
+            .map(|(index, chunk)| {
+                let b = chunk.bounds;
+                let closest = Vector3::new(
+                    eye.x.clamp(b.min.x, b.max.x),
+                    eye.y.clamp(b.min.y, b.max.y),
+                    eye.z.clamp(b.min.z, b.max.z),
+                );
+                (index, self.lod_for_distance(eye.distance_to(closest)))
+            })
+            .collect()
+    }
+
+    /// Draws the uploaded chunks `camera` can see with `material`, and returns how many it
+    /// drew. `aspect` is the viewport's width over its height.
+    pub fn draw(
+        &self,
+        d: &mut impl RaylibDraw3D,
+        camera: &Camera3D,
+        aspect: f32,
+        material: impl AsRef<ffi::Material>,
+    ) -> usize {
+        let material = WeakMaterial(*material.as_ref());
+        let frustum = Frustum::from_camera(camera, aspect);
+        let mut drawn = 0;
+        for (index, lod) in self.visible_chunks(&frustum, camera.position) {
+            if let Some(mesh) = self.chunks[index].meshes.get(lod) {
+                d.draw_mesh(mesh, material.clone(), Matrix::identity());
+                drawn += 1;
+            }
+        }
+        drawn
+    }
+
+    /// Heightmap cell under world `(x, z)`, and the position inside it from 0 to 1.
+    fn cell_at(&self, x: f32, z: f32) -> Option<(usize, usize, f32, f32)> {
+        let (dx, dz) = self.spacing();
+        let (last_x, last_z) = (self.heights.width() - 1, self.heights.height() - 1);
+        let gx = (x - self.position.x) / dx;
+        let gz = (z - self.position.z) / dz;
+        if !(gx >= 0.0 && gx <= last_x as f32 && gz >= 0.0 && gz <= last_z as f32) {
+            return None;
+        }
+        let (i, j) = ((gx as usize).min(last_x - 1), (gz as usize).min(last_z - 1));
+        Some((i, j, gx - i as f32, gz - j as f32))
+    }
+
+    /// World height of the full-detail surface at world `(x, z)`, or `None` off the terrain.
+    pub fn height_at(&self, x: f32, z: f32) -> Option<f32> {
+        let (i, j, fx, fz) = self.cell_at(x, z)?;
+        let (h00, h10) = (self.sample(i, j), self.sample(i + 1, j));
+        let (h01, h11) = (self.sample(i, j + 1), self.sample(i + 1, j + 1));
+        let h = if fz >= fx {
+            h00 + (h01 - h00) * fz + (h11 - h01) * fx
+        } else {
+            h00 + (h10 - h00) * fx + (h11 - h10) * fz
+        };
+        Some(self.position.y + h * self.size.y)
+    }
+
+    /// Smooth surface normal at world `(x, z)`, or `None` off the terrain.
+    pub fn normal_at(&self, x: f32, z: f32) -> Option<Vector3> {
+        let (i, j, fx, fz) = self.cell_at(x, z)?;
+        let top = self
+            .vertex_normal(i, j)
+            .lerp(self.vertex_normal(i + 1, j), fx);
+        let bottom = self
+            .vertex_normal(i, j + 1)
+            .lerp(self.vertex_normal(i + 1, j + 1), fx);
+        Some(top.lerp(bottom, fz).normalized())
+    }
+
+    /// The first point where `ray` hits the full-detail surface, from either side.
+    pub fn get_ray_collision(&self, ray: Ray) -> RayCollision {
+        let miss = RayCollision::default();
+        let mut bounds = self.bounds();
+        // keep a flat terrain's bounds from having no thickness
+        bounds.min.y -= 1e-3;
+        bounds.max.y += 1e-3;
+        let (enter, exit) = match ray_box_span(&ray, &bounds) {
+            Some(span) => span,
+            None => return miss,
+        };
+
+        let (dx, dz) = self.spacing();
+        let (last_x, last_z) = (self.heights.width() - 1, self.heights.height() - 1);
+        let start = ray.position + ray.direction * enter;
+        let clamp_cell = |g: f32, last: usize| (g.floor().max(0.0) as usize).min(last - 1);
+        let mut i = clamp_cell((start.x - self.position.x) / dx, last_x);
+        let mut j = clamp_cell((start.z - self.position.z) / dz, last_z);
+        // walk the cells under the ray in order, the first hit is the nearest
+        let crossing = |origin: f32, dir: f32, cell: usize, spacing: f32| {
+            if dir > 0.0 {
+                ((cell + 1) as f32 * spacing - origin) / dir
+            } else if dir < 0.0 {
+                (cell as f32 * spacing - origin) / dir
+            } else {
+                f32::INFINITY
+            }
+        };
+        let local = ray.position - self.position;
+        let (mut next_x, mut next_z) = (
+            crossing(local.x, ray.direction.x, i, dx),
+            crossing(local.z, ray.direction.z, j, dz),
+        );
+        let (step_x, step_z) = ((dx / ray.direction.x).abs(), (dz / ray.direction.z).abs());
+        loop {
+            let (a, b, c, d) = (
+                self.vertex(i, j),
+                self.vertex(i, j + 1),
+                self.vertex(i + 1, j + 1),
+                self.vertex(i + 1, j),
+            );
+            let hit = [(a, b, c), (a, c, d)]
+                .iter()
+                .filter_map(|&(a, b, c)| ray_triangle(&ray, a, b, c).map(|t| (t, a, b, c)))
+                .min_by(|x, y| x.0.partial_cmp(&y.0).unwrap_or(std::cmp::Ordering::Equal));
+            if let Some((t, a, b, c)) = hit {
+                let point = ray.position + ray.direction * t;
+                return RayCollision {
+                    hit: true,
+                    distance: ray.position.distance_to(point),
+                    point,
+                    normal: (b - a).cross(c - a).normalized(),
+                };
+            }
+            if next_x.min(next_z) > exit {
+                return miss;
+            }
+            if next_x < next_z {
+                match if ray.direction.x > 0.0 {
+                    i.checked_add(1).filter(|&i| i < last_x)
+                } else {
+                    i.checked_sub(1)
+                } {
+                    Some(next) => i = next,
+                    None => return miss,
+                }
+                next_x += step_x;
+            } else {
+                match if ray.direction.z > 0.0 {
+                    j.checked_add(1).filter(|&j| j < last_z)
+                } else {
+                    j.checked_sub(1)
+                } {
+                    Some(next) => j = next,
+                    None => return miss,
+                }
+                next_z += step_z;
+            }
+        }
+    }
+
+    /// Paints a splat map for [`TerrainSplatMaterial`] covering the whole terrain. `weights`
+    /// gets the height, from 0 at the bottom of `size` to 1 at the top, and the normal at each
+    /// pixel, and returns how much of each of the four layers to use there. Weights are scaled
+    /// to add up to 1.
+    pub fn splat_map(
+        &self,
+        width: usize,
+        height: usize,
+        mut weights: impl FnMut(f32, Vector3) -> [f32; 4],
+    ) -> Result<Image, Error> {
+        let (w, h): (i32, i32) = match (width.try_into(), height.try_into()) {
+            (Ok(w), Ok(h)) => (w, h),
+            _ => return Err(error!("Splat map is too large.")),
+        };
+        let mut pixels = Vec::with_capacity(width * height);
+        for py in 0..height {
+            for px in 0..width {
+                let x = self.position.x + (px as f32 + 0.5) / width as f32 * self.size.x;
+                let z = self.position.z + (py as f32 + 0.5) / height as f32 * self.size.z;
+                let y = self.height_at(x, z).unwrap_or(self.position.y);
+                let normal = self.normal_at(x, z).unwrap_or_else(Vector3::up);
+                let level = if self.size.y != 0.0 {
+                    (y - self.position.y) / self.size.y
+                } else {
+                    0.0
+                };
+                let layers = weights(level, normal).map(|v| v.max(0.0));
+                let total: f32 = layers.iter().sum();
+                let scale = if total > 0.0 { 255.0 / total } else { 0.0 };
+                let [r, g, b, a] = layers.map(|v| (v * scale).round() as u8);
+                pixels.push(Color::new(r, g, b, a));
+            }
+        }
+        Image::from_pixel_slice(w, h, &pixels)
+    }
+}
+
+/// Where `ray` enters and leaves `bounds`, as multiples of its direction, clipped to start at 0.
+fn ray_box_span(ray: &Ray, bounds: &BoundingBox) -> Option<(f32, f32)> {
+    let (mut enter, mut exit) = (0.0f32, f32::INFINITY);
+    let axes = [
+        (ray.position.x, ray.direction.x, bounds.min.x, bounds.max.x),
+        (ray.position.y, ray.direction.y, bounds.min.y, bounds.max.y),
+        (ray.position.z, ray.direction.z, bounds.min.z, bounds.max.z),
+    ];
+    for &(origin, dir, min, max) in &axes {
+        if dir == 0.0 {
+            if origin < min || origin > max {
+                return None;
+            }
+            continue;
+        }
+        let (a, b) = ((min - origin) / dir, (max - origin) / dir);
+        enter = enter.max(a.min(b));
+        exit = exit.min(a.max(b));
+    }
+    if enter <= exit {
+        Some((enter, exit))
+    } else {
+        None
+    }
+}
+
+/// Möller–Trumbore intersection, as a multiple of the ray's direction.
+fn ray_triangle(ray: &Ray, a: Vector3, b: Vector3, c: Vector3) -> Option<f32> {
+    let (edge1, edge2) = (b - a, c - a);
+    let p = ray.direction.cross(edge2);
+    let det = edge1.dot(p);
+    if det.abs() < 1e-12 {
+        return None;
+    }
+    let inv = 1.0 / det;
+    let to_origin = ray.position - a;
+    let u = to_origin.dot(p) * inv;
+    if !(-1e-6..=1.0 + 1e-6).contains(&u) {
+        return None;
+    }
+    let q = to_origin.cross(edge1);
+    let v = ray.direction.dot(q) * inv;
+    if v < -1e-6 || u + v > 1.0 + 1e-6 {
+        return None;
+    }
+    let t = edge2.dot(q) * inv;
+    if t >= 0.0 {
+        Some(t)
+    } else {
+        None
+    }
+}
+
+/// Uniform name of the layer texture tiling of [`TerrainSplatMaterial`].
+pub const TERRAIN_TILING_UNIFORM: &str = "layerTiling";
+
+// texture0 holds the splat weights across the whole terrain, texture1 to texture4 the layers
+macro_rules! splat_fragment_shader {
+    ($header:expr, $input:expr, $texture:expr, $output:expr, $main_output:expr) => {
+        concat!(
+            $header,
+            $input,
+            " vec2 fragTexCoord;\n",
+            $input,
+            " vec4 fragColor;\n",
+            "uniform sampler2D texture0;\n",
+            "uniform sampler2D texture1;\n",
+            "uniform sampler2D texture2;\n",
+            "uniform sampler2D texture3;\n",
+            "uniform sampler2D texture4;\n",
+            "uniform vec4 colDiffuse;\n",
+            "uniform float layerTiling;\n",
+            $output,
+            "void main() {\n",
+            "    vec4 weights = ",
+            $texture,
+            "(texture0, fragTexCoord);\n",
+            "    weights /= max(weights.r + weights.g + weights.b + weights.a, 0.0001);\n",
+            "    vec2 uv = fragTexCoord*layerTiling;\n",
+            "    vec4 color = ",
+            $texture,
+            "(texture1, uv)*weights.r + ",
+            $texture,
+            "(texture2, uv)*weights.g\n",
+            "        + ",
+            $texture,
+            "(texture3, uv)*weights.b + ",
+            $texture,
+            "(texture4, uv)*weights.a;\n",
+            "    ",
+            $main_output,
+            " = color*fragColor*colDiffuse;\n",
+            "}\n",
+        )
+    };
+}
+
+#[cfg(not(any(
+    feature = "opengl_21",
+    feature = "opengl_es_20",
+    feature = "opengl_es_30",
+    target_os = "android",
+    target_os = "emscripten"
+)))]
+const SPLAT_FRAGMENT_SHADER: &str = splat_fragment_shader!(
+    "#version 330\n",
+    "in",
+    "texture",
+    "out vec4 finalColor;\n",
+    "finalColor"
+);
+
+#[cfg(feature = "opengl_21")]
+const SPLAT_FRAGMENT_SHADER: &str =
+    splat_fragment_shader!("#version 120\n", "varying", "texture2D", "", "gl_FragColor");
+
+#[cfg(feature = "opengl_es_30")]
+const SPLAT_FRAGMENT_SHADER: &str = splat_fragment_shader!(
+    "#version 300 es\nprecision mediump float;\n",
+    "in",
+    "texture",
+    "out vec4 finalColor;\n",
+    "finalColor"
+);
+
+#[cfg(all(
+    not(any(feature = "opengl_21", feature = "opengl_es_30")),
+    any(
+        feature = "opengl_es_20",
+        target_os = "android",
+        target_os = "emscripten"
+    )
+))]
+const SPLAT_FRAGMENT_SHADER: &str = splat_fragment_shader!(
+    "#version 100\nprecision mediump float;\n",
+    "varying",
+    "texture2D",
+    "",
+    "gl_FragColor"
+);
+
+/// A material that blends four layer textures with the weights of a splat map, for
+/// [`Terrain::draw`].
+///
+/// The material borrows the textures it was made with, so they outlive it:
+///
+/// ```compile_fail
+/// use raylib::prelude::*;
+///
+/// let (mut rl, thread) = raylib::init().size(800, 450).build();
+/// let image = Image::gen_image_color(8, 8, Color::GREEN);
+/// let texture = rl.load_texture_from_image(&thread, &image).unwrap();
+/// let layers = [&texture, &texture, &texture, &texture];
+/// let mut material = rl
+///     .load_terrain_splat_material(&thread, &texture, layers, 8.0)
+///     .unwrap();
+/// drop(texture);
+/// material.set_tiling(4.0);
+/// ```
+#[derive(Debug)]
+pub struct TerrainSplatMaterial<'a> {
+    material: WeakMaterial,
+    shader: Shader,
+    tiling_loc: i32,
+    _textures: PhantomData<&'a Texture2D>,
+}
+
+impl<'a> TerrainSplatMaterial<'a> {
+    /// How many times the layer textures repeat across the terrain.
+    pub fn set_tiling(&mut self, tiling: f32) {
+        self.shader.set_shader_value(self.tiling_loc, tiling);
+    }
+
+    /// Access the shader to set custom uniforms.
+    pub fn shader_mut(&mut self) -> &mut Shader {
+        &mut self.shader
+    }
+}
+
+impl<'a> AsRef<ffi::Material> for TerrainSplatMaterial<'a> {
+    fn as_ref(&self) -> &ffi::Material {
+        self.material.as_ref()
+    }
+}
+
+impl<'a> Drop for TerrainSplatMaterial<'a> {
+    fn drop(&mut self) {
+        // the shader drops on its own and the textures belong to the caller
+        unsafe { ffi::MemFree(self.material.0.maps as *mut std::os::raw::c_void) }
+    }
+}
+
+impl RaylibHandle {
+    /// Loads a [`TerrainSplatMaterial`] that blends `layers` with the red, green, blue and
+    /// alpha weights of `splat_map`, repeating the layers `tiling` times across the terrain.
+    pub fn load_terrain_splat_material<'a>(
+        &mut self,
+        thread: &RaylibThread,
+        splat_map: &'a Texture2D,
+        layers: [&'a Texture2D; 4],
+        tiling: f32,
+    ) -> Result<TerrainSplatMaterial<'a>, Error> {
+        let mut shader = self.load_shader_from_memory(thread, None, Some(SPLAT_FRAGMENT_SHADER));
+        if shader.id == unsafe { ffi::rlGetShaderIdDefault() } {
+            return Err(error!("Error compiling the terrain splat shader."));
+        }
+        // raylib binds material map N to the sampler at shader location MAP_ALBEDO + N
+        let maps = [
+            (
+                MaterialMapIndex::MATERIAL_MAP_ALBEDO,
+                ShaderLocationIndex::SHADER_LOC_MAP_ALBEDO,
+            ),
+            (
+                MaterialMapIndex::MATERIAL_MAP_METALNESS,
+                ShaderLocationIndex::SHADER_LOC_MAP_METALNESS,
+            ),
+            (
+                MaterialMapIndex::MATERIAL_MAP_NORMAL,
+                ShaderLocationIndex::SHADER_LOC_MAP_NORMAL,
+            ),
+            (
+                MaterialMapIndex::MATERIAL_MAP_ROUGHNESS,
+                ShaderLocationIndex::SHADER_LOC_MAP_ROUGHNESS,
+            ),
+            (
+                MaterialMapIndex::MATERIAL_MAP_OCCLUSION,
+                ShaderLocationIndex::SHADER_LOC_MAP_OCCLUSION,
+            ),
+        ];
+        for (slot, &(_, location)) in maps.iter().enumerate() {
+            let loc = shader.get_shader_location(&format!("texture{}", slot));
+            shader.locs_mut()[location as usize] = loc;
+        }
+        let tiling_loc = shader.get_shader_location(TERRAIN_TILING_UNIFORM);
+
+        let mut material = self.load_material_default(thread);
+        material.0.shader = shader.0;
+        let textures = [splat_map, layers[0], layers[1], layers[2], layers[3]];
+        for (&(map, _), texture) in maps.iter().zip(textures.iter()) {
+            material.set_material_texture(map, *texture);
+        }
+        let mut splat = TerrainSplatMaterial {
+            material,
+            shader,
+            tiling_loc,
+            _textures: PhantomData,
+        };
+        splat.set_tiling(tiling);
+        Ok(splat)
+    }
+}
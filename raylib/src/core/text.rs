@@ -2,6 +2,7 @@
 //! Text manipulation functions are super unsafe so use rust String functions
 use raylib_sys::LoadUTF8;
 
+use crate::core::bmfont::BmFontFormat;
 use crate::core::math::Vector2;
 use crate::core::texture::{Image, Texture2D};
 use crate::core::{RaylibHandle, RaylibThread};
@@ -13,8 +14,13 @@ use std::convert::{AsMut, AsRef, TryInto};
 use std::ffi::{CString, OsString};
 use std::mem::ManuallyDrop;
 use std::ops::Deref;
+use std::sync::atomic::{AtomicI32, Ordering};
 
 fn no_drop<T>(_thing: T) {}
+
+/// Last value given to [`RaylibHandle::set_text_line_spacing`], raylib doesn't expose it.
+pub(crate) static TEXT_LINE_SPACING: AtomicI32 = AtomicI32::new(2);
+
 make_thin_wrapper!(Font, ffi::Font, ffi::UnloadFont);
 make_thin_wrapper!(WeakFont, ffi::Font, no_drop);
 make_thin_wrapper!(GlyphInfo, ffi::GlyphInfo, no_drop);
@@ -103,7 +109,7 @@ impl RaylibHandle {
 
         unsafe {
             Codepoints(std::mem::ManuallyDrop::new(Box::from_raw(
-                std::slice::from_raw_parts_mut(u, text.len()),
+                std::slice::from_raw_parts_mut(u, len as usize),
             )))
         }
     }
@@ -150,7 +156,7 @@ impl RaylibHandle {
                         c_filename.as_ptr(),
                         font_size,
                         co.0.as_mut_ptr(),
-                        c.len() as i32,
+                        co.0.len() as i32,
                     )
                 }
                 None => ffi::LoadFontEx(c_filename.as_ptr(), font_size, std::ptr::null_mut(), 0),
@@ -203,7 +209,7 @@ impl RaylibHandle {
                         file_data.len() as i32,
                         font_size,
                         co.0.as_mut_ptr(),
-                        c.len() as i32,
+                        co.0.len() as i32,
                     )
                 }
                 None => ffi::LoadFontFromMemory(
@@ -242,7 +248,7 @@ impl RaylibHandle {
                         data.len() as i32,
                         font_size,
                         co.0.as_mut_ptr(),
-                        c.len() as i32,
+                        co.0.len() as i32,
                         sdf,
                     )
                 }
@@ -255,7 +261,7 @@ impl RaylibHandle {
                     sdf,
                 ),
             };
-            let ci_size = if let Some(c) = chars { c.len() } else { 95 }; // raylib assumes 95 if none given
+            let ci_size = if let Some(c) = chars { c.chars().count() } else { 95 }; // raylib assumes 95 if none given
             if ci_arr_ptr.is_null() {
                 None
             } else {
@@ -299,6 +305,15 @@ pub trait RaylibFont: AsRef<ffi::Font> + AsMut<ffi::Font> {
         unsafe { ffi::IsFontValid(*self.as_ref()) }
     }
 
+    /// Exports font as a BMFont descriptor in `format`, with its atlas as a PNG next to it.
+    fn export_bmfont(
+        &self,
+        filename: impl AsRef<std::path::Path>,
+        format: BmFontFormat,
+    ) -> Result<(), Error> {
+        crate::core::bmfont::export_font(self, filename.as_ref(), format)
+    }
+
     /// Export font as code file, returns true on success
     fn export_font_as_code<A>(&self, filename: A) -> bool
     where
@@ -367,7 +382,7 @@ impl Font {
     }
 
     /// Sets the character data on the current Font.
-    fn set_chars(&mut self, chars: &[ffi::GlyphInfo]) {
+    pub(crate) fn set_chars(&mut self, chars: &[ffi::GlyphInfo]) {
         unsafe {
             self.glyphCount = chars.len() as i32;
             let data_size = self.glyphCount as usize * std::mem::size_of::<ffi::GlyphInfo>();
@@ -381,8 +396,18 @@ impl Font {
         }
     }
 
+    /// Sets the atlas rectangles of the glyphs on the current Font.
+    pub(crate) fn set_recs(&mut self, recs: &[ffi::Rectangle]) {
+        unsafe {
+            let data_size = recs.len() * std::mem::size_of::<ffi::Rectangle>();
+            let recs_ptr = ffi::MemAlloc(data_size.try_into().unwrap()); // raylib frees this data in UnloadFont
+            std::ptr::copy(recs.as_ptr(), recs_ptr as *mut ffi::Rectangle, recs.len());
+            self.recs = recs_ptr as *mut ffi::Rectangle;
+        }
+    }
+
     /// Sets the texture on the current Font, and takes ownership of `tex`.
-    fn set_texture(&mut self, tex: Texture2D) {
+    pub(crate) fn set_texture(&mut self, tex: Texture2D) {
         self.texture = tex.0;
         std::mem::forget(tex); // UnloadFont will also unload the texture
     }
@@ -434,6 +459,19 @@ impl RaylibHandle {
     }
 
     pub fn set_text_line_spacing(&self, spacing: i32) {
+        TEXT_LINE_SPACING.store(spacing, Ordering::Relaxed);
         unsafe { ffi::SetTextLineSpacing(spacing) }
     }
 }
+
+/// Horizontal alignment of each line of text inside its bounds.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+pub enum TextAlign {
+    #[default]
+    Left,
+    Center,
+    Right,
+    /// Stretches the spaces of wrapped lines to fill the whole width. The last line of each
+    /// paragraph is aligned left.
+    Justify,
+}
@@ -0,0 +1,597 @@
+//! Line breaking, alignment and hit testing for text in a single font
+//!
+//! [`TextLayout`] only reads the glyph metrics of the font (see [`RaylibFont::chars`]) when it is
+//! created, so laying out and hit testing text works on the CPU, without the font afterwards.
+//!
+//! ```no_run
+//! use raylib::prelude::*;
+//!
+//! let (mut rl, thread) = raylib::init().size(800, 450).build();
+//! let font = rl.get_font_default();
+//! let mut layout = TextLayout::new(&font, "A long tooltip that wraps", 20.0, 2.0, Some(160.0));
+//! layout.set_align(TextAlign::Center).set_max_lines(Some(2));
+//!
+//! let caret = layout.caret_from_point(rl.get_mouse_position() - Vector2::new(20.0, 20.0));
+//! let mut d = rl.begin_drawing(&thread);
+//! d.draw_text_layout(&font, &layout, Vector2::new(20.0, 20.0), Color::BLACK);
+//! let p = layout.point_from_caret(caret) + Vector2::new(20.0, 20.0);
+//! d.draw_line_v(p, p + Vector2::new(0.0, layout.font_size()), Color::RED);
+//! ```
+use crate::core::drawing::RaylibDraw;
+use crate::core::fontstack::FontStack;
+use crate::core::math::Vector2;
+use crate::core::text::{RaylibFont, TextAlign, TEXT_LINE_SPACING};
+use crate::ffi;
+use std::collections::HashMap;
+use std::ops::Range;
+use std::sync::atomic::Ordering;
+
+/// A glyph placed by a [`TextLayout`].
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct LayoutGlyph {
+    pub codepoint: char,
+    /// Byte offset of the glyph in the text. Ellipsis glyphs get the offset of the first hidden character.
+    pub offset: usize,
+    /// Top-left corner, relative to the layout origin
+    pub position: Vector2,
+    /// Width of the glyph, without spacing
+    pub advance: f32,
+}
+
+/// A line of a [`TextLayout`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct LayoutLine {
+    /// Indices of the line's glyphs in [`TextLayout::glyphs`]
+    pub glyphs: Range<usize>,
+    /// Byte range of the line in the text, without the line break
+    pub text: Range<usize>,
+    /// Top-left corner, relative to the layout origin
+    pub position: Vector2,
+    /// Width without trailing whitespace
+    pub width: f32,
+    pub height: f32,
+    /// Whether the line ends a paragraph, rather than being wrapped
+    pub ends_paragraph: bool,
+}
+
+/// Glyph advances of a font at its base size.
+#[derive(Debug, Clone)]
+struct Advances {
+    advances: HashMap<char, f32>,
+    base_size: f32,
+    /// Advance of `?`, which raylib draws for missing glyphs
+    fallback: f32,
+}
+
+impl Advances {
+    fn from_font(font: &impl RaylibFont) -> Self {
+        let advances: HashMap<char, f32> = font
+            .chars()
+            .iter()
+            .filter_map(|glyph| {
+                let c = char::from_u32(glyph.value as u32)?;
+                let advance = match glyph.advanceX {
+                    0 => glyph.image.width as f32,
+                    advance => advance as f32,
+                };
+                Some((c, advance))
+            })
+            .collect();
+        Advances {
+            fallback: advances.get(&'?').copied().unwrap_or(0.0),
+            advances,
+            base_size: font.base_size() as f32,
+        }
+    }
+
+    fn contains(&self, c: char) -> bool {
+        self.advances.contains_key(&c)
+    }
+
+    fn get(&self, c: char, font_size: f32) -> f32 {
+        let advance = match c {
+            '\u{200B}' => 0.0,
+            c => self.advances.get(&c).copied().unwrap_or(self.fallback),
+        };
+        advance * font_size / self.base_size
+    }
+}
+
+/// A line before placement: a range of indices into the characters of the text.
+struct RawLine {
+    chars: Range<usize>,
+    ends_paragraph: bool,
+}
+
+/// Text wrapped to a width and aligned, with the position of every glyph.
+///
+/// Lines break at spaces, after hyphens and between CJK characters, without splitting
+/// closing punctuation from the text before it. Words wider than the maximum width are split
+/// anywhere. `\n`, `\r\n`, U+2028 and U+2029 always start a new line.
+#[derive(Debug, Clone)]
+pub struct TextLayout {
+    text: String,
+    advances: Advances,
+    font_size: f32,
+    spacing: f32,
+    max_width: Option<f32>,
+    max_lines: Option<usize>,
+    line_spacing: f32,
+    align: TextAlign,
+    glyphs: Vec<LayoutGlyph>,
+    lines: Vec<LayoutLine>,
+    size: Vector2,
+    truncated: bool,
+}
+
+impl TextLayout {
+    /// Lays out `text`, wrapping lines longer than `max_width` if it is set.
+    /// Lines are aligned left and the line spacing is the one last given to
+    /// [`RaylibHandle::set_text_line_spacing`](crate::core::RaylibHandle::set_text_line_spacing).
+    pub fn new(
+        font: &impl RaylibFont,
+        text: &str,
+        font_size: f32,
+        spacing: f32,
+        max_width: Option<f32>,
+    ) -> TextLayout {
+        Self::with_advances(
+            Advances::from_font(font),
+            text,
+            font_size,
+            spacing,
+            max_width,
+        )
+    }
+
+    /// Like [`TextLayout::new`], with glyphs from the fonts of `fonts`.
+    /// Draw it with [`RaylibFontStackExt::draw_text_layout_stack`](crate::core::fontstack::RaylibFontStackExt::draw_text_layout_stack).
+    pub fn with_font_stack(
+        fonts: &FontStack,
+        text: &str,
+        font_size: f32,
+        spacing: f32,
+        max_width: Option<f32>,
+    ) -> TextLayout {
+        let (advances, fallback) = fonts.advances();
+        let advances = Advances {
+            advances,
+            base_size: 1.0,
+            fallback,
+        };
+        Self::with_advances(advances, text, font_size, spacing, max_width)
+    }
+
+    fn with_advances(
+        advances: Advances,
+        text: &str,
+        font_size: f32,
+        spacing: f32,
+        max_width: Option<f32>,
+    ) -> TextLayout {
+        let mut layout = TextLayout {
+            text: text.to_owned(),
+            advances,
+            font_size,
+            spacing,
+            max_width,
+            max_lines: None,
+            line_spacing: TEXT_LINE_SPACING.load(Ordering::Relaxed) as f32,
+            align: TextAlign::Left,
+            glyphs: Vec::new(),
+            lines: Vec::new(),
+            size: Vector2::zero(),
+            truncated: false,
+        };
+        layout.relayout();
+        layout
+    }
+
+    pub fn set_text(&mut self, text: &str) -> &mut Self {
+        self.text = text.to_owned();
+        self.relayout();
+        self
+    }
+
+    pub fn set_max_width(&mut self, max_width: Option<f32>) -> &mut Self {
+        self.max_width = max_width;
+        self.relayout();
+        self
+    }
+
+    /// Keeps at most `max_lines` lines, ending the last one with an ellipsis if text was cut off.
+    pub fn set_max_lines(&mut self, max_lines: Option<usize>) -> &mut Self {
+        self.max_lines = max_lines;
+        self.relayout();
+        self
+    }
+
+    /// Sets the space between lines, in pixels.
+    pub fn set_line_spacing(&mut self, line_spacing: f32) -> &mut Self {
+        self.line_spacing = line_spacing;
+        self.relayout();
+        self
+    }
+
+    /// Aligns lines inside the maximum width, or the widest line if there is none.
+    pub fn set_align(&mut self, align: TextAlign) -> &mut Self {
+        self.align = align;
+        self.relayout();
+        self
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn font_size(&self) -> f32 {
+        self.font_size
+    }
+
+    pub fn spacing(&self) -> f32 {
+        self.spacing
+    }
+
+    pub fn line_spacing(&self) -> f32 {
+        self.line_spacing
+    }
+
+    pub fn glyphs(&self) -> &[LayoutGlyph] {
+        &self.glyphs
+    }
+
+    pub fn lines(&self) -> &[LayoutLine] {
+        &self.lines
+    }
+
+    /// Size of the laid out text.
+    pub fn size(&self) -> Vector2 {
+        self.size
+    }
+
+    /// Whether lines were dropped by [`TextLayout::set_max_lines`].
+    pub fn is_truncated(&self) -> bool {
+        self.truncated
+    }
+
+    /// Byte offset in the text of the caret position closest to `point`, relative to the layout origin.
+    pub fn caret_from_point(&self, point: impl Into<Vector2>) -> usize {
+        let point = point.into();
+        let line_index = ((point.y / (self.font_size + self.line_spacing))
+            .floor()
+            .max(0.0) as usize)
+            .min(self.lines.len() - 1);
+        let line = &self.lines[line_index];
+        for glyph in &self.glyphs[line.glyphs.clone()] {
+            if glyph.offset >= line.text.end {
+                break;
+            }
+            if point.x < glyph.position.x + glyph.advance * 0.5 {
+                return glyph.offset;
+            }
+        }
+        match self.glyphs[line.glyphs.clone()].last() {
+            // stay before the space a wrapped line ends with, the end is the start of the next line
+            Some(last) if !line.ends_paragraph && last.offset < line.text.end => last.offset,
+            _ => line.text.end,
+        }
+    }
+
+    /// Top-left corner of the caret at byte offset `caret`, relative to the layout origin.
+    /// The caret is as tall as [`TextLayout::font_size`].
+    pub fn point_from_caret(&self, caret: usize) -> Vector2 {
+        let line = self.line_of_caret(caret);
+        let line = &self.lines[line];
+        let glyphs = &self.glyphs[line.glyphs.clone()];
+        match glyphs.iter().find(|glyph| glyph.offset >= caret) {
+            Some(glyph) if glyph.offset == caret && caret < line.text.end => glyph.position,
+            _ => match glyphs.last() {
+                Some(last) => Vector2::new(last.position.x + last.advance, line.position.y),
+                None => line.position,
+            },
+        }
+    }
+
+    /// Index in [`TextLayout::lines`] of the line the caret at byte offset `caret` is on.
+    pub fn line_of_caret(&self, caret: usize) -> usize {
+        self.lines
+            .iter()
+            .rposition(|line| line.text.start <= caret)
+            .unwrap_or(0)
+    }
+
+    fn relayout(&mut self) {
+        let chars: Vec<(usize, char)> = self.text.char_indices().collect();
+        let raw_lines = self.break_lines(&chars);
+        let shown = self
+            .max_lines
+            .map_or(raw_lines.len(), |max| max.max(1).min(raw_lines.len()));
+        self.truncated = shown < raw_lines.len();
+
+        // glyphs of each line and its width, before alignment
+        let mut placed: Vec<(Vec<LayoutGlyph>, f32, Range<usize>)> = Vec::with_capacity(shown);
+        for (i, raw) in raw_lines[..shown].iter().enumerate() {
+            let byte = |c: usize| chars.get(c).map_or(self.text.len(), |&(offset, _)| offset);
+            let mut glyphs: Vec<LayoutGlyph> = chars[raw.chars.clone()]
+                .iter()
+                .map(|&(offset, codepoint)| LayoutGlyph {
+                    codepoint,
+                    offset,
+                    position: Vector2::zero(),
+                    advance: self.advances.get(codepoint, self.font_size),
+                })
+                .collect();
+            let mut text = byte(raw.chars.start)..byte(raw.chars.end);
+            if self.truncated && i == shown - 1 {
+                let (cut, ellipsis) = self.ellipsize(&mut glyphs, text.end);
+                text.end = text.end.min(cut);
+                glyphs.extend(ellipsis);
+            }
+            let width = self.width(&glyphs);
+            placed.push((glyphs, width, text));
+        }
+
+        let box_width = self.max_width.unwrap_or_else(|| {
+            placed
+                .iter()
+                .map(|(_, width, _)| *width)
+                .fold(0.0, f32::max)
+        });
+        self.glyphs.clear();
+        self.lines.clear();
+        self.size = Vector2::zero();
+        for (i, (mut glyphs, width, text)) in placed.into_iter().enumerate() {
+            let ends_paragraph = raw_lines[i].ends_paragraph;
+            let y = i as f32 * (self.font_size + self.line_spacing);
+            let x = match self.align {
+                TextAlign::Left | TextAlign::Justify => 0.0,
+                TextAlign::Center => (box_width - width) * 0.5,
+                TextAlign::Right => box_width - width,
+            };
+            // extra space after each space between words, when justifying
+            let inner_spaces =
+                glyphs
+                    .iter()
+                    .rposition(|g| !is_space(g.codepoint))
+                    .map_or(0, |last| {
+                        glyphs[..last]
+                            .iter()
+                            .filter(|g| is_space(g.codepoint))
+                            .count()
+                    });
+            let stretch = if self.align == TextAlign::Justify
+                && !ends_paragraph
+                && inner_spaces > 0
+                && self.max_width.is_some()
+            {
+                (box_width - width).max(0.0) / inner_spaces as f32
+            } else {
+                0.0
+            };
+
+            let mut pen = x;
+            for glyph in &mut glyphs {
+                glyph.position = Vector2::new(pen, y);
+                pen += glyph.advance + self.spacing;
+                if is_space(glyph.codepoint) {
+                    pen += stretch;
+                }
+            }
+            let line_width = if stretch > 0.0 { box_width } else { width };
+            let start = self.glyphs.len();
+            self.glyphs.extend(glyphs);
+            self.lines.push(LayoutLine {
+                glyphs: start..self.glyphs.len(),
+                text,
+                position: Vector2::new(x, y),
+                width: line_width,
+                height: self.font_size,
+                ends_paragraph,
+            });
+            self.size = Vector2::new(self.size.x.max(line_width), y + self.font_size);
+        }
+    }
+
+    /// Splits the text into paragraphs and wraps them.
+    fn break_lines(&self, chars: &[(usize, char)]) -> Vec<RawLine> {
+        let mut lines = Vec::new();
+        let mut start = 0;
+        let mut i = 0;
+        while i < chars.len() {
+            let c = chars[i].1;
+            if matches!(c, '\n' | '\r' | '\u{2028}' | '\u{2029}') {
+                self.wrap(chars, start..i, &mut lines);
+                i += 1;
+                if c == '\r' && chars.get(i).map(|&(_, c)| c) == Some('\n') {
+                    i += 1;
+                }
+                start = i;
+            } else {
+                i += 1;
+            }
+        }
+        self.wrap(chars, start..chars.len(), &mut lines);
+        lines
+    }
+
+    /// Wraps one paragraph, the characters in `range`.
+    fn wrap(&self, chars: &[(usize, char)], range: Range<usize>, lines: &mut Vec<RawLine>) {
+        let advance = |i: usize| self.advances.get(chars[i].1, self.font_size);
+        let mut line_start = range.start;
+        // width of line_start..i, with the spacing after each glyph
+        let mut width = 0.0;
+        let mut last_break = None;
+        for i in range.clone() {
+            if i > line_start && can_break_before(chars[i - 1].1, chars[i].1) {
+                last_break = Some(i);
+            }
+            let c = chars[i].1;
+            if let Some(max_width) = self.max_width {
+                if !is_space(c) && i > line_start && width + advance(i) > max_width {
+                    let at = last_break.unwrap_or(i);
+                    lines.push(RawLine {
+                        chars: line_start..at,
+                        ends_paragraph: false,
+                    });
+                    line_start = at;
+                    last_break = None;
+                    width = (at..i).map(|j| advance(j) + self.spacing).sum();
+                }
+            }
+            width += advance(i) + self.spacing;
+        }
+        lines.push(RawLine {
+            chars: line_start..range.end,
+            ends_paragraph: true,
+        });
+    }
+
+    /// Width of `glyphs` without trailing whitespace.
+    fn width(&self, glyphs: &[LayoutGlyph]) -> f32 {
+        match glyphs.iter().rposition(|g| !is_space(g.codepoint)) {
+            Some(last) => {
+                glyphs[..=last]
+                    .iter()
+                    .map(|g| g.advance + self.spacing)
+                    .sum::<f32>()
+                    - self.spacing
+            }
+            None => 0.0,
+        }
+    }
+
+    /// Removes glyphs from the end of the line until an ellipsis fits after them, and returns
+    /// the byte offset the text was cut at along with the ellipsis glyphs.
+    fn ellipsize(&self, glyphs: &mut Vec<LayoutGlyph>, end: usize) -> (usize, Vec<LayoutGlyph>) {
+        let dots: &[char] = if self.advances.contains('\u{2026}') {
+            &['\u{2026}']
+        } else {
+            &['.', '.', '.']
+        };
+        let dots_width = dots
+            .iter()
+            .map(|&c| self.advances.get(c, self.font_size) + self.spacing)
+            .sum::<f32>()
+            - self.spacing;
+        let max_width = self.max_width.unwrap_or(f32::INFINITY);
+        let mut cut = end;
+        while let Some(last) = glyphs.last() {
+            let width = self.width(glyphs);
+            if !is_space(last.codepoint) && width + self.spacing + dots_width <= max_width {
+                break;
+            }
+            cut = last.offset;
+            glyphs.pop();
+        }
+        let ellipsis = dots
+            .iter()
+            .map(|&codepoint| LayoutGlyph {
+                codepoint,
+                offset: cut,
+                position: Vector2::zero(),
+                advance: self.advances.get(codepoint, self.font_size),
+            })
+            .collect();
+        (cut, ellipsis)
+    }
+}
+
+/// Drawing of [`TextLayout`]s for anything that implements [`RaylibDraw`].
+pub trait RaylibTextLayoutExt: RaylibDraw
+where
+    Self: Sized,
+{
+    /// Draws `layout` with its origin at `position`. `font` should be the font it was created with.
+    fn draw_text_layout(
+        &mut self,
+        font: &impl AsRef<ffi::Font>,
+        layout: &TextLayout,
+        position: impl Into<Vector2>,
+        tint: impl Into<ffi::Color>,
+    ) {
+        let position = position.into();
+        let tint: ffi::Color = tint.into();
+        for glyph in layout.glyphs() {
+            if !glyph.codepoint.is_whitespace() {
+                self.draw_text_codepoint(
+                    font,
+                    glyph.codepoint as i32,
+                    position + glyph.position,
+                    layout.font_size(),
+                    tint,
+                );
+            }
+        }
+    }
+}
+
+impl<D: RaylibDraw> RaylibTextLayoutExt for D {}
+
+/// Whitespace lines can break after, which hangs at the end of the line.
+fn is_space(c: char) -> bool {
+    (c.is_whitespace() && !matches!(c, '\u{A0}' | '\u{2007}' | '\u{202F}')) || c == '\u{200B}'
+}
+
+fn is_cjk(c: char) -> bool {
+    matches!(c as u32,
+        0x2E80..=0x9FFF | 0xAC00..=0xD7AF | 0xF900..=0xFAFF | 0xFF00..=0xFFEF | 0x20000..=0x2FFFF)
+}
+
+/// Closing punctuation, which stays on the line of the text before it.
+fn is_closing(c: char) -> bool {
+    matches!(
+        c,
+        ')' | ']'
+            | '}'
+            | '!'
+            | '?'
+            | ','
+            | '.'
+            | ':'
+            | ';'
+            | '\u{2026}'
+            | '\u{3001}'
+            | '\u{3002}'
+            | '\u{300D}'
+            | '\u{300F}'
+            | '\u{3011}'
+            | '\u{3009}'
+            | '\u{300B}'
+            | '\u{30FC}'
+            | '\u{FF01}'
+            | '\u{FF09}'
+            | '\u{FF0C}'
+            | '\u{FF0E}'
+            | '\u{FF1A}'
+            | '\u{FF1B}'
+            | '\u{FF1F}'
+    )
+}
+
+/// Opening punctuation, which stays on the line of the text after it.
+fn is_opening(c: char) -> bool {
+    matches!(
+        c,
+        '(' | '['
+            | '{'
+            | '\u{3008}'
+            | '\u{300A}'
+            | '\u{300C}'
+            | '\u{300E}'
+            | '\u{3010}'
+            | '\u{FF08}'
+    )
+}
+
+/// Whether a line may start with `c` when it follows `prev`.
+fn can_break_before(prev: char, c: char) -> bool {
+    if is_space(c) || is_closing(c) {
+        false
+    } else if is_space(prev) {
+        true
+    } else if is_opening(prev) {
+        false
+    } else {
+        (matches!(prev, '-' | '\u{2010}') && c.is_alphanumeric()) || is_cjk(prev) || is_cjk(c)
+    }
+}
@@ -63,6 +63,10 @@ make_thin_wrapper!(
 );
 make_thin_wrapper!(WeakRenderTexture2D, ffi::RenderTexture2D, no_drop);
 
+// Images own their pixels, allocated and freed with raylib's allocator, which any thread can
+// use. Textures stay !Send, since only the thread with the GL context may unload them.
+unsafe impl Send for Image {}
+
 // Weak things can be clone
 impl Clone for WeakTexture2D {
     fn clone(&self) -> WeakTexture2D {
@@ -1084,16 +1088,17 @@ pub trait RaylibTexture2D: AsRef<ffi::Texture2D> + AsMut<ffi::Texture2D> {
         rec: impl Into<ffi::Rectangle>,
         pixels: &[u8],
     ) -> Result<(), Error> {
+        let rec = rec.into();
         let expected_len = unsafe {
             get_pixel_data_size(
-                self.as_ref().width,
-                self.as_ref().height,
+                rec.width as i32,
+                rec.height as i32,
                 std::mem::transmute::<i32, ffi::PixelFormat>(self.as_ref().format),
             ) as usize
         };
         if pixels.len() != expected_len {
             return Err(error!(std::borrow::Cow::Owned(format!(
-                "update_texture: Data is wrong size. Expected {}, got {}",
+                "update_texture_rec: Data is wrong size. Expected {}, got {}",
                 expected_len,
                 pixels.len()
             ))));
@@ -1101,7 +1106,7 @@ pub trait RaylibTexture2D: AsRef<ffi::Texture2D> + AsMut<ffi::Texture2D> {
         unsafe {
             ffi::UpdateTextureRec(
                 *self.as_ref(),
-                rec.into(),
+                rec,
                 pixels.as_ptr() as *const std::os::raw::c_void,
             )
         }
@@ -26,21 +26,42 @@ Permission is granted to anyone to use this software for any purpose, including
 
 pub use crate::callbacks::*;
 pub use crate::consts::*;
+pub use crate::core::animimage::*;
+pub use crate::core::assets::*;
 pub use crate::core::audio::*;
 pub use crate::core::automation::*;
+pub use crate::core::bmfont::*;
 pub use crate::core::camera::*;
+pub use crate::core::canvas::*;
 pub use crate::core::collision::*;
 pub use crate::core::color::*;
+pub use crate::core::compositing::*;
+pub use crate::core::cubemap::*;
 pub use crate::core::data::*;
 pub use crate::core::drawing::*;
+pub use crate::core::drawlist::*;
+pub use crate::core::dynamicfont::*;
 pub use crate::core::file::*;
+pub use crate::core::fontstack::*;
+pub use crate::core::framebuffer::*;
+pub use crate::core::imagediff::*;
+pub use crate::core::imageops::*;
 pub use crate::core::input::*;
+pub use crate::core::localization::*;
 pub use crate::core::logging::*;
 pub use crate::core::math::*;
 pub use crate::core::misc::*;
 pub use crate::core::models::*;
+pub use crate::core::noise::*;
+pub use crate::core::pixels::*;
+pub use crate::core::postprocess::*;
+pub use crate::core::richtext::*;
+pub use crate::core::rlgl::*;
+pub use crate::core::sdffont::*;
 pub use crate::core::shaders::*;
+pub use crate::core::terrain::*;
 pub use crate::core::text::*;
+pub use crate::core::textlayout::*;
 pub use crate::core::texture::*;
 pub use crate::core::vr::*;
 pub use crate::core::window::*;